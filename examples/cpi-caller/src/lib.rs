@@ -0,0 +1,123 @@
+// example program wagering on behalf of its users through a CPI into roshambo `NewGame`
+//
+// The program owns a wager authority PDA (seed `b"wager"`), funded with SOL and holding a token
+// account of the config mint. `Wager` creates a game with the PDA as game creator, so every
+// payout of the game comes back to the PDA's token account.
+
+use roshambo_sol::{
+    instruction::{new_game, with_mint},
+    pda::find_player_stats_address,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Seed of the PDA wagering on behalf of the program's users
+pub const WAGER_AUTHORITY_SEED: &[u8] = b"wager";
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// `Wager { amount: u64, public_seed_commitment: [u8; 32], seed_slot: u64 }`, the only
+/// instruction
+///
+///
+/// Accounts expected:
+///
+/// 0. `[writable]` Wager authority PDA - game creator of the roshambo game
+/// 1. `[writable]` Wager authority token account
+/// 2. `[writable, signer]` The roshambo game account, a fresh keypair created beforehand and
+///    owned by roshambo
+/// 3. `[writable]` Roshambo house token account
+/// 4. `[writable]` Roshambo config
+/// 5. `[]` The token program
+/// 6. `[writable]` Roshambo player stats PDA of the wager authority
+/// 7. `[]` The system program
+/// 8. `[]` The roshambo program
+/// 9. `[]` The mint of the roshambo config
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (amount, public_seed_commitment, seed_slot) = unpack_wager(instruction_data)?;
+
+    let account_info_iter = &mut accounts.iter();
+    let wager_authority = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let game_account = next_account_info(account_info_iter)?;
+    let house_token_account = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let player_stats_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let roshambo_program = next_account_info(account_info_iter)?;
+    let mint_account = next_account_info(account_info_iter)?;
+
+    let (authority, bump) = Pubkey::find_program_address(&[WAGER_AUTHORITY_SEED], program_id);
+    if *wager_authority.key != authority {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !roshambo_sol::check_id(roshambo_program.key) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (player_stats, _) =
+        find_player_stats_address(config_account.key, &authority, &roshambo_sol::id());
+
+    let instruction = with_mint(
+        new_game(
+            &roshambo_sol::id(),
+            &authority,
+            token_account.key,
+            game_account.key,
+            house_token_account.key,
+            config_account.key,
+            &player_stats,
+            amount,
+            public_seed_commitment,
+            seed_slot,
+        ),
+        mint_account.key,
+    );
+    invoke_signed(
+        &instruction,
+        &[
+            wager_authority.clone(),
+            token_account.clone(),
+            game_account.clone(),
+            house_token_account.clone(),
+            config_account.clone(),
+            token_program.clone(),
+            player_stats_account.clone(),
+            system_program.clone(),
+            roshambo_program.clone(),
+            mint_account.clone(),
+        ],
+        &[&[WAGER_AUTHORITY_SEED, &[bump]]],
+    )
+}
+
+fn unpack_wager(input: &[u8]) -> Result<(u64, [u8; 32], u64), ProgramError> {
+    let amount = input
+        .get(..8)
+        .and_then(|slice| slice.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let public_seed_commitment = input
+        .get(8..40)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let seed_slot = input
+        .get(40..48)
+        .and_then(|slice| slice.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    Ok((amount, public_seed_commitment, seed_slot))
+}
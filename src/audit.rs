@@ -0,0 +1,328 @@
+// fairness audit of the settled games of a config, only built with the "audit" feature
+
+use std::{collections::HashMap, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+use crate::{
+    client::ClientResult,
+    event::RoshamboEvent,
+    instruction::RoshamboInstruction,
+    outcome::{outcome, public_seed_commitment, Outcome, HANDS},
+};
+
+/// Chi-square value over the three outcomes (2 degrees of freedom) above which the outcome
+/// distribution fails the audit, a 0.1% significance level
+pub const CHI_SQUARE_CRITICAL: f64 = 13.816;
+
+/// Signatures fetched per `getSignaturesForAddress` page
+const SIGNATURES_PAGE: usize = 1_000;
+
+/// A settled game, re-derived from the seeds revealed by its claim
+pub struct AuditedGame {
+    pub signature: Signature,
+    pub slot: u64,
+    pub game: Pubkey,
+    pub game_id: u64,
+    /// Result recorded by the program in the `GameSettled` event
+    pub recorded: u8,
+    pub derived: Outcome,
+    /// Whether the revealed public seed matches the commitment of the opening instruction,
+    /// `None` if the game was opened before the audited history or through a CPI
+    pub commitment_ok: Option<bool>,
+}
+
+impl AuditedGame {
+    pub fn passed(&self) -> bool {
+        self.recorded == self.derived as u8 && self.commitment_ok != Some(false)
+    }
+}
+
+/// Outcome of `audit_config`
+pub struct AuditReport {
+    pub games: Vec<AuditedGame>,
+    /// Settled games per `Outcome`
+    pub counts: [u64; 3],
+    pub chi_square: f64,
+}
+
+impl AuditReport {
+    /// Builds the report of `games`, comparing the derived outcomes with a fair draw
+    pub fn new(games: Vec<AuditedGame>) -> Self {
+        let mut counts = [0u64; 3];
+        for game in &games {
+            counts[game.derived as usize] += 1;
+        }
+
+        // every hand beats two of the others, loses to two and draws against itself
+        let total = games.len() as f64;
+        let decisive = (HANDS - 1) as f64 / 2.0 / HANDS as f64;
+        let expected = [decisive * total, decisive * total, total / HANDS as f64];
+        let chi_square = if games.is_empty() {
+            0.0
+        } else {
+            counts
+                .iter()
+                .zip(expected)
+                .map(|(count, expected)| (*count as f64 - expected).powi(2) / expected)
+                .sum()
+        };
+
+        Self {
+            games,
+            counts,
+            chi_square,
+        }
+    }
+
+    /// Games whose recorded result or commitment doesn't check out
+    pub fn failures(&self) -> impl Iterator<Item = &AuditedGame> {
+        self.games.iter().filter(|game| !game.passed())
+    }
+
+    /// Probability of a chi-square at least this large from fair games, exact for 2 degrees
+    /// of freedom
+    pub fn p_value(&self) -> f64 {
+        (-self.chi_square / 2.0).exp()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none() && self.chi_square <= CHI_SQUARE_CRITICAL
+    }
+}
+
+/// Audits up to `limit` of the most recent transactions of `config`: every game settled in
+/// them is re-derived from the revealed seeds and checked against the recorded result and
+/// the public seed commitment of the game
+pub fn audit_config(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    config: &Pubkey,
+    limit: usize,
+) -> ClientResult<AuditReport> {
+    let mut signatures = Vec::new();
+    let mut before = None;
+    while signatures.len() < limit {
+        let page = rpc.get_signatures_for_address_with_config(
+            config,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                limit: Some(SIGNATURES_PAGE.min(limit - signatures.len())),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(Signature::from_str(&last.signature)?);
+        signatures.extend(
+            page.into_iter()
+                .filter(|status| status.err.is_none())
+                .map(|status| status.signature),
+        );
+    }
+
+    // oldest first, so a game is opened before it is settled
+    let mut commitments = HashMap::new();
+    let mut games = Vec::new();
+    for signature in signatures.iter().rev() {
+        let signature = Signature::from_str(signature)?;
+        audit_transaction(rpc, program_id, signature, &mut commitments, &mut games)?;
+    }
+
+    Ok(AuditReport::new(games))
+}
+
+/// Audits the games settled by the transaction `signature`, looking up the transactions
+/// that opened them for their public seed commitment
+pub fn verify_transaction(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signature: &Signature,
+) -> ClientResult<Vec<AuditedGame>> {
+    let mut settled = Vec::new();
+    audit_transaction(
+        rpc,
+        program_id,
+        *signature,
+        &mut HashMap::new(),
+        &mut settled,
+    )?;
+
+    // the opening transaction is in the history of the game account, before the settlement
+    let mut commitments = HashMap::new();
+    for game in &settled {
+        let history = rpc.get_signatures_for_address_with_config(
+            &game.game,
+            GetConfirmedSignaturesForAddress2Config {
+                before: Some(*signature),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+        for status in history.iter().rev().filter(|status| status.err.is_none()) {
+            let signature = Signature::from_str(&status.signature)?;
+            audit_transaction(
+                rpc,
+                program_id,
+                signature,
+                &mut commitments,
+                &mut Vec::new(),
+            )?;
+        }
+    }
+
+    let mut games = Vec::new();
+    audit_transaction(rpc, program_id, *signature, &mut commitments, &mut games)?;
+    Ok(games)
+}
+
+/// Records the games opened in the transaction `signature` and audits the ones it settled
+fn audit_transaction(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signature: Signature,
+    commitments: &mut HashMap<u64, ([u8; 32], u64)>,
+    games: &mut Vec<AuditedGame>,
+) -> ClientResult<()> {
+    let confirmed = rpc.get_transaction(&signature, UiTransactionEncoding::Base64)?;
+    let transaction = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or("transaction could not be decoded")?;
+    let keys = transaction.message.static_account_keys();
+    let logs = match confirmed.transaction.meta.map(|meta| meta.log_messages) {
+        Some(OptionSerializer::Some(logs)) => logs,
+        _ => return Ok(()),
+    };
+
+    // top-level instructions of the program, by game account: the commitment of a game
+    // opened by the instruction, and the seeds revealed if it settles one
+    let mut opened = HashMap::new();
+    let mut revealed = HashMap::new();
+    for ix in transaction.message.instructions() {
+        if keys.get(ix.program_id_index as usize) != Some(program_id) {
+            continue;
+        }
+        let key = |position: usize| {
+            ix.accounts
+                .get(position)
+                .and_then(|index| keys.get(*index as usize))
+                .copied()
+        };
+        match RoshamboInstruction::unpack(&ix.data) {
+            Ok(RoshamboInstruction::NewGame {
+                public_seed_commitment,
+                seed_slot,
+                ..
+            })
+            | Ok(RoshamboInstruction::NewGameDelegated {
+                public_seed_commitment,
+                seed_slot,
+                ..
+            })
+            | Ok(RoshamboInstruction::NewGameV2 {
+                public_seed_commitment,
+                seed_slot,
+                ..
+            }) => {
+                opened.extend(key(2).map(|game| (game, (public_seed_commitment, seed_slot))));
+            }
+            Ok(RoshamboInstruction::ClaimReward {
+                host_seed,
+                public_seed,
+            }) => {
+                revealed.extend(key(2).map(|game| (game, (host_seed, public_seed))));
+            }
+            Ok(RoshamboInstruction::ClaimAndRebet {
+                host_seed,
+                public_seed,
+                next_public_seed_commitment,
+                next_seed_slot,
+            }) => {
+                revealed.extend(key(2).map(|game| (game, (host_seed, public_seed))));
+                opened.extend(
+                    key(8).map(|game| (game, (next_public_seed_commitment, next_seed_slot))),
+                );
+            }
+            Ok(RoshamboInstruction::ClaimRewards { seeds }) => {
+                for (index, seeds) in seeds.into_iter().enumerate() {
+                    revealed.extend(key(8 + index).map(|game| (game, seeds)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for event in program_events(&logs, program_id) {
+        match event {
+            RoshamboEvent::GameCreated { game, game_id, .. } => {
+                if let Some(commitment) = opened.get(&game) {
+                    commitments.insert(game_id, *commitment);
+                }
+            }
+            RoshamboEvent::GameSettled {
+                game,
+                game_id,
+                result,
+                ..
+            } => {
+                let Some((host_seed, public_seed)) = revealed.get(&game) else {
+                    continue;
+                };
+                games.push(AuditedGame {
+                    signature,
+                    slot: confirmed.slot,
+                    game,
+                    game_id,
+                    recorded: result,
+                    derived: outcome(*host_seed, *public_seed),
+                    commitment_ok: commitments.remove(&game_id).map(|(commitment, seed_slot)| {
+                        public_seed_commitment(&game, *public_seed, seed_slot) == commitment
+                    }),
+                });
+            }
+            RoshamboEvent::GameDeclined { game_id, .. }
+            | RoshamboEvent::GameCancelled { game_id, .. } => {
+                commitments.remove(&game_id);
+            }
+            RoshamboEvent::EmergencyWithdrawScheduled { .. }
+            | RoshamboEvent::ConfigUpdated { .. }
+            | RoshamboEvent::Withdrawn { .. }
+            | RoshamboEvent::CircuitBreakerTripped { .. } => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Decodes the events logged by `program_id` itself, skipping the `Program data:` lines of
+/// the programs it invokes or is invoked by
+fn program_events(logs: &[String], program_id: &Pubkey) -> Vec<RoshamboEvent> {
+    let invoke = format!("Program {} invoke", program_id);
+    let mut stack = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        if line.starts_with("Program ") && line.contains(" invoke [") {
+            stack.push(line.starts_with(&invoke));
+        } else if line.starts_with("Program ")
+            && (line.ends_with(" success") || line.contains(" failed: "))
+        {
+            stack.pop();
+        } else if let Some(data) = line.strip_prefix("Program data: ") {
+            if stack.last() != Some(&true) {
+                continue;
+            }
+            events.extend(
+                data.split(' ')
+                    .filter_map(|part| STANDARD.decode(part).ok())
+                    .filter_map(|bytes| RoshamboEvent::unpack(&bytes)),
+            );
+        }
+    }
+    events
+}
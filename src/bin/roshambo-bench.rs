@@ -0,0 +1,544 @@
+// compute unit benchmark: plays games through solana-program-test and prints the compute units
+// every instruction consumes per scenario, with the change against a previous run
+//
+// usage: roshambo-bench [BASELINE]
+//
+// Scenarios: Initialize, NewGame (first and later game of a player), CommitHostSeed, RevealSeed,
+// ClaimReward (win, lose, draw), DeclineGame, CancelGame, ClaimRewards over a batch of games,
+// and a session: OpenSession, CommitSessionSeed, PlaySession, RevealSessionSeed, CloseSession.
+//
+// The SBF build is measured when SBF_OUT_DIR (or BPF_OUT_DIR) holds roshambo_sol.so from
+// `cargo build-sbf`, the native processor otherwise, which only meters the token CPIs.
+
+use std::{env, error::Error, fs, process::exit};
+
+use roshambo_sol::{
+    instruction,
+    outcome::{
+        host_seed_commitment, outcome, public_seed_commitment, session_seed_commitment, Outcome,
+        HANDS,
+    },
+    pda::{find_house_authority, find_house_vault_address, find_player_stats_address},
+    processor::Processor,
+    state::{Config, Game, Session},
+};
+use solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    sysvar::clock::Clock,
+    transaction::Transaction,
+};
+
+type BenchResult<T> = Result<T, Box<dyn Error>>;
+
+const DECIMALS: u8 = 6;
+const BET_AMOUNT: u64 = 1_000_000;
+const BANKROLL: u64 = 1_000_000_000_000;
+
+/// Games played, one per settlement scenario
+const GAMES: usize = 4;
+
+/// Games settled together by the `ClaimRewards` scenario
+const BATCH_GAMES: usize = 4;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() > 2 {
+        eprintln!("usage: roshambo-bench [BASELINE]");
+        exit(2);
+    }
+    let baseline = match args.get(1).map(fs::read_to_string).transpose() {
+        Ok(baseline) => baseline.map(|table| parse_table(&table)),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit(2);
+        }
+    };
+
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit(2);
+        }
+    };
+    let results = match runtime.block_on(run()) {
+        Ok(results) => results,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit(2);
+        }
+    };
+
+    if env::var("SBF_OUT_DIR").is_err() && env::var("BPF_OUT_DIR").is_err() {
+        eprintln!("note: native processor, set SBF_OUT_DIR to measure the SBF build");
+    }
+    print_table(&results, baseline.as_deref());
+}
+
+/// Accounts of the benchmark, created at genesis
+struct Bench {
+    context: ProgramTestContext,
+    program_id: Pubkey,
+    config: Pubkey,
+    mint: Pubkey,
+    house_authority: Pubkey,
+    house_token_account: Pubkey,
+    player: Keypair,
+    player_token_account: Pubkey,
+    player_stats: Pubkey,
+    results: Vec<(String, u64)>,
+}
+
+async fn run() -> BenchResult<Vec<(String, u64)>> {
+    let program_id = roshambo_sol::id();
+    let mut program_test =
+        ProgramTest::new("roshambo_sol", program_id, processor!(Processor::process));
+    let rent = Rent::default();
+
+    let config = Pubkey::new_unique();
+    program_test.add_account(config, program_account(&rent, Config::LEN, &program_id));
+    // the settlement scenarios, the cancelled game, then the batch
+    let games: Vec<Keypair> = (0..GAMES + 1 + BATCH_GAMES)
+        .map(|_| Keypair::new())
+        .collect();
+    for game in &games {
+        program_test.add_account(
+            game.pubkey(),
+            program_account(&rent, Game::LEN, &program_id),
+        );
+    }
+    let session = Pubkey::new_unique();
+    program_test.add_account(session, program_account(&rent, Session::LEN, &program_id));
+
+    let mint = Pubkey::new_unique();
+    let mint_state = spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply: 2 * BANKROLL,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    program_test.add_account(mint, packed_account(&rent, mint_state, &spl_token::id()));
+
+    // the bankroll is the single vault of the config, funded at genesis
+    let (house_authority, _) = find_house_authority(&program_id);
+    let (house_token_account, _) = find_house_vault_address(&config, 0, &program_id);
+    add_token_account(
+        &mut program_test,
+        &rent,
+        house_token_account,
+        &mint,
+        &house_authority,
+    );
+    let player = Keypair::new();
+    let player_token_account = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        &rent,
+        player_token_account,
+        &mint,
+        &player.pubkey(),
+    );
+    program_test.add_account(
+        player.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+    let (player_stats, _) = find_player_stats_address(&config, &player.pubkey(), &program_id);
+
+    let mut bench = Bench {
+        context: program_test.start_with_context().await,
+        program_id,
+        config,
+        mint,
+        house_authority,
+        house_token_account,
+        player,
+        player_token_account,
+        player_stats,
+        results: Vec::new(),
+    };
+
+    let house = bench.context.payer.pubkey();
+    let initialize = instruction::initialize(
+        &program_id,
+        &house,
+        &config,
+        &mint,
+        BET_AMOUNT,
+        100 * BET_AMOUNT,
+    );
+    bench.execute(Some("Initialize"), initialize, &[]).await?;
+    let init_vault = instruction::init_house_vaults(
+        &program_id,
+        &house,
+        &config,
+        &mint,
+        &[house_token_account],
+        1,
+    );
+    bench.execute(None, init_vault, &[]).await?;
+
+    let scenarios = [
+        ("ClaimReward/win", Some(Outcome::Win)),
+        ("ClaimReward/lose", Some(Outcome::Lose)),
+        ("ClaimReward/draw", Some(Outcome::Draw)),
+        ("DeclineGame", None),
+    ];
+    for (i, (game, (label, result))) in games[..GAMES].iter().zip(scenarios).enumerate() {
+        // the first game of the player creates its stats account
+        let new_game_label = match i {
+            0 => Some("NewGame/first"),
+            1 => Some("NewGame"),
+            _ => None,
+        };
+        let public_seed = i as u64;
+        bench.new_game(new_game_label, game, public_seed).await?;
+        match result {
+            Some(result) => {
+                let host_seed = (0..HANDS)
+                    .find(|host_seed| outcome(*host_seed, public_seed) == result)
+                    .ok_or("no host seed for the outcome")?;
+                let first = i == 0;
+                bench
+                    .reveal(first, &game.pubkey(), host_seed, public_seed)
+                    .await?;
+                bench
+                    .claim(label, &game.pubkey(), host_seed, public_seed)
+                    .await?;
+            }
+            None => bench.decline(label, &game.pubkey()).await?,
+        }
+    }
+
+    let cancelled = &games[GAMES];
+    bench.new_game(None, cancelled, GAMES as u64).await?;
+    bench.cancel("CancelGame", &cancelled.pubkey()).await?;
+
+    let mut batch = Vec::new();
+    for (i, game) in games[GAMES + 1..].iter().enumerate() {
+        let (host_seed, public_seed) = (i as u64, (GAMES + 1 + i) as u64);
+        bench.new_game(None, game, public_seed).await?;
+        bench
+            .reveal(false, &game.pubkey(), host_seed, public_seed)
+            .await?;
+        batch.push((game.pubkey(), host_seed, public_seed));
+    }
+    let label = format!("ClaimRewards/{}", BATCH_GAMES);
+    bench.claim_batch(&label, &batch).await?;
+
+    bench.session(&session).await?;
+
+    Ok(bench.results)
+}
+
+impl Bench {
+    /// Runs `instruction` alone in a transaction paid by the house, recording its compute
+    /// units under `label`
+    async fn execute(
+        &mut self,
+        label: Option<&str>,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> BenchResult<()> {
+        let payer = self.context.payer.insecure_clone();
+        let mut all_signers = vec![&payer];
+        all_signers.extend(signers);
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let processed = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        processed.result?;
+        if let (Some(label), Some(metadata)) = (label, processed.metadata) {
+            self.results
+                .push((label.to_string(), metadata.compute_units_consumed));
+        }
+        Ok(())
+    }
+
+    async fn new_game(
+        &mut self,
+        label: Option<&str>,
+        game: &Keypair,
+        public_seed: u64,
+    ) -> BenchResult<()> {
+        let clock = self.context.banks_client.get_sysvar::<Clock>().await?;
+        let commitment = public_seed_commitment(&game.pubkey(), public_seed, clock.slot);
+        let new_game = instruction::new_game(
+            &self.program_id,
+            &self.player.pubkey(),
+            &self.player_token_account,
+            &game.pubkey(),
+            &self.house_token_account,
+            &self.config,
+            &self.player_stats,
+            BET_AMOUNT,
+            commitment,
+            clock.slot,
+        );
+        let new_game = instruction::with_mint(new_game, &self.mint);
+        let player = self.player.insecure_clone();
+        self.execute(label, new_game, &[&player, game]).await?;
+
+        // settlements land after the slot of the game
+        self.context.warp_to_slot(clock.slot + 2)?;
+        Ok(())
+    }
+
+    /// Commits the host seed and reveals the public seed of the game, recording both for the
+    /// `first` game only
+    async fn reveal(
+        &mut self,
+        first: bool,
+        game: &Pubkey,
+        host_seed: u64,
+        public_seed: u64,
+    ) -> BenchResult<()> {
+        let house = self.context.payer.pubkey();
+        let commit = instruction::commit_host_seed(
+            &self.program_id,
+            &house,
+            game,
+            &self.config,
+            host_seed_commitment(game, host_seed),
+        );
+        self.execute(first.then_some("CommitHostSeed"), commit, &[])
+            .await?;
+
+        let reveal =
+            instruction::reveal_seed(&self.program_id, &self.player.pubkey(), game, public_seed);
+        let player = self.player.insecure_clone();
+        self.execute(first.then_some("RevealSeed"), reveal, &[&player])
+            .await
+    }
+
+    async fn claim(
+        &mut self,
+        label: &str,
+        game: &Pubkey,
+        host_seed: u64,
+        public_seed: u64,
+    ) -> BenchResult<()> {
+        let house = self.context.payer.pubkey();
+        let claim = instruction::claim_reward(
+            &self.program_id,
+            &self.player.pubkey(),
+            &house,
+            game,
+            &self.player_token_account,
+            &self.house_token_account,
+            &self.config,
+            &self.house_authority,
+            host_seed,
+            public_seed,
+        );
+        let claim = instruction::with_player_stats(claim, &self.player_stats);
+        let claim = instruction::with_mint(claim, &self.mint);
+        self.execute(Some(label), claim, &[]).await
+    }
+
+    async fn decline(&mut self, label: &str, game: &Pubkey) -> BenchResult<()> {
+        let decline = instruction::decline_game(
+            &self.program_id,
+            &self.context.payer.pubkey(),
+            game,
+            &self.player.pubkey(),
+            &self.player_token_account,
+            &self.house_token_account,
+            &self.config,
+            &self.house_authority,
+        );
+        let decline = instruction::with_player_stats(decline, &self.player_stats);
+        let decline = instruction::with_mint(decline, &self.mint);
+        self.execute(Some(label), decline, &[]).await
+    }
+
+    async fn cancel(&mut self, label: &str, game: &Pubkey) -> BenchResult<()> {
+        let cancel = instruction::cancel_game(
+            &self.program_id,
+            &self.player.pubkey(),
+            game,
+            &self.player_token_account,
+            &self.house_token_account,
+            &self.config,
+            &self.house_authority,
+        );
+        let cancel = instruction::with_player_stats(cancel, &self.player_stats);
+        let cancel = instruction::with_mint(cancel, &self.mint);
+        let player = self.player.insecure_clone();
+        self.execute(Some(label), cancel, &[&player]).await
+    }
+
+    /// Claims revealed games of the player, given with their host and public seeds, in a
+    /// single `ClaimRewards`
+    async fn claim_batch(&mut self, label: &str, games: &[(Pubkey, u64, u64)]) -> BenchResult<()> {
+        let claim = instruction::claim_rewards(
+            &self.program_id,
+            &self.player.pubkey(),
+            &self.context.payer.pubkey(),
+            &self.player_token_account,
+            &self.house_token_account,
+            &self.config,
+            &self.house_authority,
+            &self.player_stats,
+            games,
+        );
+        let claim = instruction::with_mint(claim, &self.mint);
+        self.execute(Some(label), claim, &[]).await
+    }
+
+    /// Opens a session, plays a single game in it and closes it
+    async fn session(&mut self, session: &Pubkey) -> BenchResult<()> {
+        let house = self.context.payer.pubkey();
+        let player = self.player.insecure_clone();
+        let open = instruction::open_session(
+            &self.program_id,
+            &self.player.pubkey(),
+            &self.player_token_account,
+            session,
+            &self.house_token_account,
+            &self.config,
+            2 * BET_AMOUNT,
+        );
+        let open = instruction::with_mint(open, &self.mint);
+        self.execute(Some("OpenSession"), open, &[&player]).await?;
+
+        let host_seed = 0;
+        let commit = instruction::commit_session_seed(
+            &self.program_id,
+            &house,
+            session,
+            &self.config,
+            session_seed_commitment(session, 0, host_seed),
+        );
+        self.execute(Some("CommitSessionSeed"), commit, &[]).await?;
+        let play = instruction::play_session(
+            &self.program_id,
+            &self.player.pubkey(),
+            session,
+            &self.config,
+            &self.house_token_account,
+            &self.player_stats,
+            BET_AMOUNT,
+            0,
+        );
+        self.execute(Some("PlaySession"), play, &[&player]).await?;
+        let reveal = instruction::reveal_session_seed(
+            &self.program_id,
+            &house,
+            session,
+            &self.config,
+            host_seed,
+        );
+        self.execute(Some("RevealSessionSeed"), reveal, &[]).await?;
+
+        let close = instruction::close_session(
+            &self.program_id,
+            &self.player.pubkey(),
+            session,
+            &self.player_token_account,
+            &self.house_token_account,
+            &self.config,
+            &self.house_authority,
+        );
+        let close = instruction::with_mint(close, &self.mint);
+        self.execute(Some("CloseSession"), close, &[&player]).await
+    }
+}
+
+fn program_account(rent: &Rent, len: usize, program_id: &Pubkey) -> Account {
+    Account {
+        lamports: rent.minimum_balance(len),
+        data: vec![0; len],
+        owner: *program_id,
+        ..Account::default()
+    }
+}
+
+fn add_token_account(
+    program_test: &mut ProgramTest,
+    rent: &Rent,
+    address: Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) {
+    let account = spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount: BANKROLL,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    };
+    program_test.add_account(address, packed_account(rent, account, &spl_token::id()));
+}
+
+fn packed_account<T: Pack>(rent: &Rent, state: T, owner: &Pubkey) -> Account {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).expect("packs into its own length");
+    Account {
+        lamports: rent.minimum_balance(T::LEN),
+        data,
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+/// Reads back the scenarios and compute units of a previous run, skipping the other lines
+/// (headers, event logs printed by the native processor)
+fn parse_table(table: &str) -> Vec<(String, u64)> {
+    table
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let label = columns.next()?;
+            let units = columns.next()?.parse().ok()?;
+            Some((label.to_string(), units))
+        })
+        .collect()
+}
+
+fn print_table(results: &[(String, u64)], baseline: Option<&[(String, u64)]>) {
+    match baseline {
+        Some(_) => println!(
+            "{:<20} {:>8} {:>8} {:>8}",
+            "scenario", "CU", "baseline", "change"
+        ),
+        None => println!("{:<20} {:>8}", "scenario", "CU"),
+    }
+    for (label, units) in results {
+        let Some(baseline) = baseline else {
+            println!("{:<20} {:>8}", label, units);
+            continue;
+        };
+        match baseline.iter().find(|(previous, _)| previous == label) {
+            Some((_, previous)) => println!(
+                "{:<20} {:>8} {:>8} {:>+8}",
+                label,
+                units,
+                previous,
+                *units as i64 - *previous as i64
+            ),
+            None => println!("{:<20} {:>8} {:>8} {:>8}", label, units, "-", "new"),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// reference house service: commit host seeds and settle the games of a config
+//
+// usage: roshambo-host <RPC_URL> <WS_URL> <KEYPAIR> <PROGRAM_ID> <CONFIG> [LOOKUP_TABLE]
+
+use std::{env, process::exit, str::FromStr};
+
+use roshambo_sol::{
+    client::ClientResult,
+    host_service::{HostService, RetryPolicy},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::read_keypair_file,
+};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 6 && args.len() != 7 {
+        eprintln!(
+            "usage: roshambo-host <RPC_URL> <WS_URL> <KEYPAIR> <PROGRAM_ID> <CONFIG> [LOOKUP_TABLE]"
+        );
+        exit(2);
+    }
+
+    let lookup_table = args.get(6).map(String::as_str);
+    if let Err(err) = run(
+        &args[1],
+        &args[2],
+        &args[3],
+        &args[4],
+        &args[5],
+        lookup_table,
+    ) {
+        eprintln!("error: {}", err);
+        exit(2);
+    }
+}
+
+fn run(
+    url: &str,
+    ws_url: &str,
+    keypair: &str,
+    program_id: &str,
+    config: &str,
+    lookup_table: Option<&str>,
+) -> ClientResult<()> {
+    let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+    let house = read_keypair_file(keypair)?;
+    let program_id = Pubkey::from_str(program_id)?;
+    let config_address = Pubkey::from_str(config)?;
+
+    let mut service = HostService::new(
+        rpc,
+        house,
+        program_id,
+        config_address,
+        RetryPolicy::default(),
+    )?;
+    if let Some(lookup_table) = lookup_table {
+        service = service.with_lookup_table(&Pubkey::from_str(lookup_table)?)?;
+    }
+    println!("settling games of {}", config_address);
+    service.run(ws_url)
+}
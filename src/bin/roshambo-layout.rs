@@ -0,0 +1,10 @@
+// account layout export: prints the field names, offsets and types of every account as JSON,
+// for indexers to generate their decoders from
+//
+// usage: roshambo-layout
+
+use roshambo_sol::layout::layouts_json;
+
+fn main() {
+    print!("{}", layouts_json());
+}
@@ -0,0 +1,200 @@
+// reference player client: create a game, wait for it to be settled and verify the result
+//
+// usage: roshambo-play <RPC_URL> <KEYPAIR> <PROGRAM_ID> <CONFIG> <AMOUNT>
+
+use std::{env, process::exit, str::FromStr};
+
+use roshambo_sol::{
+    client::{
+        fetch_state, find_recycled_game, find_settlement, find_token_account, max_bet,
+        verify_settlement, wait_for_close, wait_for_host_commitment, ClientResult,
+    },
+    instruction,
+    outcome::public_seed_commitment,
+    pda::{
+        find_counter_address, find_player_stats_address, find_player_vault_address, player_shard,
+    },
+    state::{Config, Game},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 6 {
+        eprintln!("usage: roshambo-play <RPC_URL> <KEYPAIR> <PROGRAM_ID> <CONFIG> <AMOUNT>");
+        exit(2);
+    }
+
+    match run(&args[1], &args[2], &args[3], &args[4], &args[5]) {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit(2);
+        }
+    }
+}
+
+fn run(
+    url: &str,
+    keypair: &str,
+    program_id: &str,
+    config: &str,
+    amount: &str,
+) -> ClientResult<bool> {
+    let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+    let player = read_keypair_file(keypair)?;
+    let program_id = Pubkey::from_str(program_id)?;
+    let config_address = Pubkey::from_str(config)?;
+    let amount = u64::from_str(amount)?;
+
+    let config = fetch_state::<Config>(&rpc, &config_address)?;
+    let (player_stats, _) =
+        find_player_stats_address(&config_address, &player.pubkey(), &program_id);
+    let player_token_account =
+        find_token_account(&rpc, &player.pubkey(), &config.mint_token_pubkey)?;
+    let (house_token_account, _) = find_player_vault_address(
+        &config_address,
+        config.vault_shards,
+        &player.pubkey(),
+        &program_id,
+    );
+    let max_bet = max_bet(
+        &rpc,
+        &program_id,
+        &config_address,
+        &config,
+        &player.pubkey(),
+        &house_token_account,
+    )?;
+    if amount > max_bet {
+        return Err(format!("the house covers bets of at most {} right now", max_bet).into());
+    }
+
+    // reuse a pooled game account when the config recycles them, otherwise create one
+    let game = Keypair::new();
+    let recycled = if config.game_pool {
+        find_recycled_game(&rpc, &program_id, &config_address)?
+    } else {
+        None
+    };
+    let game_address = recycled.unwrap_or_else(|| game.pubkey());
+
+    // the public seed stays on this machine until it is revealed for the claim
+    let public_seed: u64 = rand::random();
+    let seed_slot = rpc.get_slot()?;
+    let commitment = public_seed_commitment(&game_address, public_seed, seed_slot);
+
+    let mut new_game = instruction::new_game(
+        &program_id,
+        &player.pubkey(),
+        &player_token_account,
+        &game_address,
+        &house_token_account,
+        &config_address,
+        &player_stats,
+        amount,
+        commitment,
+        seed_slot,
+    );
+    if config.counter_shards > 0 {
+        let shard = player_shard(&player.pubkey(), config.counter_shards);
+        let (counter, _) = find_counter_address(&config_address, shard, &program_id);
+        new_game = instruction::with_counter_shard(new_game, &config_address, &counter);
+    }
+    if config.min_stake > 0 {
+        if let COption::Some(stake_mint) = config.stake_mint {
+            let stake_account = find_token_account(&rpc, &player.pubkey(), &stake_mint)?;
+            new_game = instruction::with_stake_account(new_game, &stake_account);
+        }
+    }
+    new_game = instruction::with_mint(new_game, &config.mint_token_pubkey);
+    if config.heartbeat_max_age_slots > 0 {
+        new_game = instruction::with_heartbeat(new_game, &program_id, &config_address);
+    }
+    if config.insurance_bps > 0 {
+        new_game = instruction::with_insurance_fund(new_game, &program_id, &config_address);
+    }
+    if config.exposure_tracking {
+        new_game = instruction::with_exposure(new_game, &program_id, &config_address);
+    }
+    if recycled.is_some() {
+        new_game = instruction::from_game_pool(new_game, &game_address);
+    }
+
+    let mut instructions = Vec::new();
+    let mut signers = vec![&player];
+    if recycled.is_none() {
+        let rent = rpc.get_minimum_balance_for_rent_exemption(Game::LEN)?;
+        instructions.push(system_instruction::create_account(
+            &player.pubkey(),
+            &game.pubkey(),
+            rent,
+            Game::LEN as u64,
+            &program_id,
+        ));
+        signers.push(&game);
+    }
+    instructions.push(new_game);
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&player.pubkey()),
+        &signers,
+        rpc.get_latest_blockhash()?,
+    );
+    let signature = rpc.send_and_confirm_transaction(&transaction)?;
+
+    println!("game:        {}", game_address);
+    println!("created in:  {}", signature);
+    println!("public seed: {}", public_seed);
+    println!("waiting for the house to commit its seed...");
+
+    // the seed is only revealed once the house can no longer pick its own
+    let game_info = wait_for_host_commitment(&rpc, &game_address)?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction::reveal_seed(
+            &program_id,
+            &player.pubkey(),
+            &game_address,
+            public_seed,
+        )],
+        Some(&player.pubkey()),
+        &[&player],
+        rpc.get_latest_blockhash()?,
+    );
+    let signature = rpc.send_and_confirm_transaction(&transaction)?;
+
+    println!("revealed in: {}", signature);
+    println!("waiting for settlement...");
+
+    wait_for_close(&rpc, &game_address)?;
+    let settlement = find_settlement(&rpc, &program_id, &game_address)?;
+    let verdict = verify_settlement(&game_address, &game_info, &settlement);
+
+    println!(
+        "settled in:  {} (slot {})",
+        settlement.signature, settlement.slot
+    );
+    println!("host seed:   {}", settlement.host_seed);
+    println!("outcome:     {:?}", verdict.outcome);
+    println!(
+        "payout:      {} (expected {})",
+        settlement.paid_out, verdict.expected_payout
+    );
+    if settlement.public_seed != public_seed {
+        println!("FAIL: the claim revealed a different public seed");
+        return Ok(false);
+    }
+    println!("{}", if verdict.passed() { "PASS" } else { "FAIL" });
+
+    Ok(verdict.passed())
+}
@@ -0,0 +1,112 @@
+// house edge simulator: plays random games with a payout table and reports the house edge
+// an operator can expect before shipping a config
+//
+// usage: roshambo-sim <PAYOUT_MULTIPLIER_BPS> <FEE_BPS> <GAMES>
+
+use std::{env, process::exit, str::FromStr};
+
+use roshambo_sol::{
+    kelly::kelly_max_bet,
+    outcome::{outcome, win_payout, Outcome, BPS_DENOMINATOR, HANDS},
+};
+
+/// Stake of every simulated game, large enough for the basis point math to stay exact
+const BET_AMOUNT: u64 = 1_000_000_000;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 4 {
+        eprintln!("usage: roshambo-sim <PAYOUT_MULTIPLIER_BPS> <FEE_BPS> <GAMES>");
+        exit(2);
+    }
+
+    let (payout_multiplier_bps, fee_bps, games) = match (
+        u16::from_str(&args[1]),
+        u16::from_str(&args[2]),
+        u64::from_str(&args[3]),
+    ) {
+        (Ok(multiplier), Ok(fee), Ok(games)) if games > 0 => (multiplier, fee, games),
+        _ => {
+            eprintln!("error: invalid arguments");
+            exit(2);
+        }
+    };
+    let payout = match win_payout(BET_AMOUNT, payout_multiplier_bps, fee_bps) {
+        Some(payout) => payout,
+        None => {
+            eprintln!("error: the payout multiplier must be at least 1x");
+            exit(2);
+        }
+    };
+
+    // house profit of each outcome, in stakes
+    let win = -((payout - BET_AMOUNT) as f64 / BET_AMOUNT as f64);
+    let lose = 1.0;
+    let draw = 0.0;
+
+    let mut counts = [0u64; 3];
+    let mut sum = 0.0;
+    let mut sum_squares = 0.0;
+    for _ in 0..games {
+        let result = outcome(rand::random(), rand::random());
+        counts[result as usize] += 1;
+        let profit = match result {
+            Outcome::Win => win,
+            Outcome::Lose => lose,
+            Outcome::Draw => draw,
+        };
+        sum += profit;
+        sum_squares += profit * profit;
+    }
+
+    let mean = sum / games as f64;
+    let variance = sum_squares / games as f64 - mean * mean;
+
+    // every hand beats two of the others, loses to two and draws against itself
+    let decisive = (HANDS - 1) as f64 / 2.0 / HANDS as f64;
+    let expected = decisive * (win + lose);
+    let expected_variance =
+        decisive * (win * win + lose * lose) + draw * draw / HANDS as f64 - expected * expected;
+
+    println!("games:            {}", games);
+    for result in [Outcome::Win, Outcome::Lose, Outcome::Draw] {
+        let count = counts[result as usize];
+        println!(
+            "{:<17} {} ({:.4}%)",
+            format!("{}:", result.as_str()),
+            count,
+            count as f64 * 100.0 / games as f64
+        );
+    }
+    println!(
+        "win payout:       {:.4}x the stake",
+        payout as f64 / BET_AMOUNT as f64
+    );
+    println!(
+        "house edge:       {:.4}% (expected {:.4}%)",
+        mean * 100.0,
+        expected * 100.0
+    );
+    println!(
+        "variance:         {:.6} (expected {:.6}) stakes^2 per game",
+        variance, expected_variance
+    );
+    println!(
+        "std error:        {:.4}% of the stake",
+        (variance / games as f64).sqrt() * 100.0
+    );
+
+    // full Kelly, out of a bankroll of one stake
+    match kelly_max_bet(
+        BET_AMOUNT,
+        payout_multiplier_bps,
+        fee_bps,
+        BPS_DENOMINATOR as u16,
+    ) {
+        u64::MAX => println!("kelly max bet:    unbounded, a win pays no profit"),
+        kelly => println!(
+            "kelly max bet:    {:.4}% of the bankroll",
+            kelly as f64 * 100.0 / BET_AMOUNT as f64
+        ),
+    }
+}
@@ -0,0 +1,62 @@
+// provably-fair verifier: re-derive the result of the games settled by a transaction
+//
+// usage: roshambo-verify <RPC_URL> <PROGRAM_ID> <SIGNATURE>
+
+use std::{env, process::exit, str::FromStr};
+
+use roshambo_sol::{audit::verify_transaction, client::ClientResult};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 4 {
+        eprintln!("usage: roshambo-verify <RPC_URL> <PROGRAM_ID> <SIGNATURE>");
+        exit(2);
+    }
+
+    match run(&args[1], &args[2], &args[3]) {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit(2);
+        }
+    }
+}
+
+fn run(url: &str, program_id: &str, signature: &str) -> ClientResult<bool> {
+    let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+    let program_id = Pubkey::from_str(program_id)?;
+    let signature = Signature::from_str(signature)?;
+
+    let games = verify_transaction(&rpc, &program_id, &signature)?;
+    if games.is_empty() {
+        println!("FAIL: the transaction settled no game");
+        return Ok(false);
+    }
+
+    for game in &games {
+        println!("game:        {} (id {})", game.game, game.game_id);
+        println!("settled in:  {} (slot {})", game.signature, game.slot);
+        println!("outcome:     {:?}", game.derived);
+        println!(
+            "recorded:    {}",
+            if game.recorded == game.derived as u8 {
+                "matches"
+            } else {
+                "differs"
+            }
+        );
+        println!(
+            "commitment:  {}",
+            match game.commitment_ok {
+                Some(true) => "matches",
+                Some(false) => "differs",
+                None => "opening transaction not found",
+            }
+        );
+        println!("{}", if game.passed() { "PASS" } else { "FAIL" });
+    }
+    Ok(games.iter().all(|game| game.passed()))
+}
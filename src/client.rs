@@ -0,0 +1,450 @@
+// off-chain helpers shared by the client binaries, only built with the "client" feature
+
+use std::{error::Error, str::FromStr, thread::sleep, time::Duration};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonce_utils,
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::TokenAccountsFilter,
+};
+use solana_program::{
+    address_lookup_table::{state::AddressLookupTable, AddressLookupTableAccount},
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, Message, VersionedMessage},
+    program_option::COption,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    packet::PACKET_DATA_SIZE,
+    signature::Signature,
+    signers::Signers,
+    transaction::{Transaction, VersionedTransaction},
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiTransactionEncoding, UiTransactionTokenBalance,
+};
+
+use crate::{
+    instruction::RoshamboInstruction,
+    outcome::{host_seed_commitment, outcome, public_seed_commitment, win_payout, Outcome},
+    pda::{find_counter_address, player_shard},
+    state::{max_allowed_bet, Config, Counter, Game},
+};
+
+pub type ClientResult<T> = Result<T, Box<dyn Error>>;
+
+/// How often the helpers below poll the cluster
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A claim transaction found on chain, decoded back into the revealed seeds
+pub struct Settlement {
+    pub signature: Signature,
+    pub slot: u64,
+    pub host_seed: u64,
+    pub public_seed: u64,
+    pub rebet: bool,
+    pub paid_out: u64,
+}
+
+/// Result of re-deriving a settlement locally
+pub struct Verdict {
+    pub outcome: Outcome,
+    pub expected_payout: u64,
+    pub commitment_ok: bool,
+    /// Whether the host seed matches the one the house committed, true for co-signed games
+    pub host_commitment_ok: bool,
+    pub payout_ok: bool,
+}
+
+impl Verdict {
+    pub fn passed(&self) -> bool {
+        self.commitment_ok && self.host_commitment_ok && self.payout_ok
+    }
+}
+
+/// Returns the first token account of `mint` owned by `owner`
+pub fn find_token_account(rpc: &RpcClient, owner: &Pubkey, mint: &Pubkey) -> ClientResult<Pubkey> {
+    let accounts = rpc.get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(*mint))?;
+    let account = accounts
+        .first()
+        .ok_or_else(|| format!("no {} token account owned by {}", mint, owner))?;
+    Ok(Pubkey::from_str(&account.pubkey)?)
+}
+
+/// Fetches and unpacks a program account
+pub fn fetch_state<T: Pack + IsInitialized>(rpc: &RpcClient, address: &Pubkey) -> ClientResult<T> {
+    let account = rpc.get_account(address)?;
+    Ok(T::unpack(&account.data)?)
+}
+
+/// Returns a game account recycled into the pool of `config`, to pass to `NewGame` instead
+/// of creating a new one
+pub fn find_recycled_game(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    config: &Pubkey,
+) -> ClientResult<Option<Pubkey>> {
+    let accounts = rpc.get_program_accounts_with_config(
+        program_id,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(Game::LEN as u64),
+                // `recycled` is the last field of the game
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(Game::LEN - 1, vec![1])),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        },
+    )?;
+
+    for (address, account) in accounts {
+        let game = Game::unpack_unchecked(&account.data)?;
+        if game.recycled && !game.is_initialized() && game.config_pubkey == *config {
+            return Ok(Some(address));
+        }
+    }
+    Ok(None)
+}
+
+/// Sum of the maximum payouts of every open game of a config, counter shards included
+pub fn outstanding_exposure(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    config_address: &Pubkey,
+    config: &Config,
+) -> ClientResult<u64> {
+    let mut exposure = config.outstanding_exposure;
+    for shard in 0..config.counter_shards {
+        let (counter, _) = find_counter_address(config_address, shard, program_id);
+        let counter = fetch_state::<Counter>(rpc, &counter)?;
+        exposure = exposure.saturating_add_signed(counter.outstanding_exposure);
+    }
+    Ok(exposure)
+}
+
+/// Largest stake `player` can wager on `config` right now, the limit `NewGame` enforces (see
+/// `state::max_allowed_bet`). `house_token_account` is the one the player's games go to, the
+/// player's house vault with vault shards.
+pub fn max_bet(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    config_address: &Pubkey,
+    config: &Config,
+    player: &Pubkey,
+    house_token_account: &Pubkey,
+) -> ClientResult<u64> {
+    let mut exposure = config.outstanding_exposure;
+    if config.counter_shards > 0 {
+        let shard = player_shard(player, config.counter_shards);
+        let (counter, _) = find_counter_address(config_address, shard, program_id);
+        let counter = fetch_state::<Counter>(rpc, &counter)?;
+        exposure = exposure.saturating_add_signed(counter.outstanding_exposure);
+    }
+    let house_balance = fetch_state::<spl_token::state::Account>(rpc, house_token_account)?.amount;
+    Ok(max_allowed_bet(config, house_balance, exposure))
+}
+
+/// Blocks until the game at `address` has been settled, i.e. closed, recycled into the
+/// game pool of its config or left open with a deferred payout
+pub fn wait_for_close(rpc: &RpcClient, address: &Pubkey) -> ClientResult<()> {
+    while let Some(account) = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value
+    {
+        let game = Game::unpack_unchecked(&account.data)?;
+        if !game.is_initialized() || !game.status.is_open() {
+            break;
+        }
+        sleep(POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// Blocks until the house committed its seed for `address`, returning the updated game
+pub fn wait_for_host_commitment(rpc: &RpcClient, address: &Pubkey) -> ClientResult<Game> {
+    loop {
+        let game = fetch_state::<Game>(rpc, address)?;
+        if game.host_seed_commitment.is_some() {
+            return Ok(game);
+        }
+        sleep(POLL_INTERVAL);
+    }
+}
+
+/// Finds the transaction that settled `game` and decodes the seeds it revealed
+pub fn find_settlement(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    game: &Pubkey,
+) -> ClientResult<Settlement> {
+    for status in rpc.get_signatures_for_address(game)? {
+        if status.err.is_some() {
+            continue;
+        }
+        let signature = Signature::from_str(&status.signature)?;
+        if let Some(settlement) = decode_settlement(rpc, program_id, game, signature)? {
+            return Ok(settlement);
+        }
+    }
+    Err(format!("no settlement transaction found for game {}", game).into())
+}
+
+/// Decodes a claim of `game` out of the transaction `signature`, if it contains one
+pub fn decode_settlement(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    game: &Pubkey,
+    signature: Signature,
+) -> ClientResult<Option<Settlement>> {
+    let confirmed = rpc.get_transaction(&signature, UiTransactionEncoding::Base64)?;
+    let transaction = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or("transaction could not be decoded")?;
+    let keys = transaction.message.static_account_keys();
+
+    for ix in transaction.message.instructions() {
+        if keys.get(ix.program_id_index as usize) != Some(program_id) {
+            continue;
+        }
+        let account = |position: usize| ix.accounts.get(position).map(|index| *index as usize);
+        if account(2).and_then(|index| keys.get(index)) != Some(game) {
+            continue;
+        }
+
+        let (host_seed, public_seed, rebet) = match RoshamboInstruction::unpack(&ix.data) {
+            Ok(RoshamboInstruction::ClaimReward {
+                host_seed,
+                public_seed,
+            }) => (host_seed, public_seed, false),
+            Ok(RoshamboInstruction::ClaimAndRebet {
+                host_seed,
+                public_seed,
+                ..
+            }) => (host_seed, public_seed, true),
+            _ => continue,
+        };
+
+        let receiver_index = account(3).ok_or("claim is missing the receiver account")?;
+        let (pre, post) = match &confirmed.transaction.meta {
+            Some(meta) => (
+                token_balance(&meta.pre_token_balances, receiver_index),
+                token_balance(&meta.post_token_balances, receiver_index),
+            ),
+            None => (0, 0),
+        };
+
+        return Ok(Some(Settlement {
+            signature,
+            slot: confirmed.slot,
+            host_seed,
+            public_seed,
+            rebet,
+            paid_out: post.saturating_sub(pre),
+        }));
+    }
+
+    Ok(None)
+}
+
+fn token_balance(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+    account_index: usize,
+) -> u64 {
+    match balances {
+        OptionSerializer::Some(balances) => balances
+            .iter()
+            .find(|balance| balance.account_index as usize == account_index)
+            .and_then(|balance| balance.ui_token_amount.amount.parse().ok())
+            .unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Re-derives the result of a settlement from its seeds and checks it against the game
+pub fn verify_settlement(game_address: &Pubkey, game: &Game, settlement: &Settlement) -> Verdict {
+    let result = outcome(settlement.host_seed, settlement.public_seed);
+    let expected_payout = match result {
+        Outcome::Win => {
+            let payout = win_payout(game.bet_amount, game.payout_multiplier_bps, game.fee_bps)
+                .unwrap_or(u64::MAX);
+            if settlement.rebet {
+                payout.saturating_sub(game.bet_amount)
+            } else {
+                payout
+            }
+        }
+        Outcome::Lose => 0,
+        Outcome::Draw => game.bet_amount,
+    };
+
+    Verdict {
+        outcome: result,
+        expected_payout,
+        commitment_ok: public_seed_commitment(game_address, settlement.public_seed, game.seed_slot)
+            == game.public_seed_commitment,
+        host_commitment_ok: match game.host_seed_commitment {
+            COption::Some(commitment) => {
+                host_seed_commitment(game_address, settlement.host_seed) == commitment
+            }
+            COption::None => true,
+        },
+        payout_ok: settlement.paid_out == expected_payout,
+    }
+}
+
+/// A durable nonce account to build transactions against instead of a recent blockhash, so
+/// they can be signed offline and sent any time before the nonce is advanced
+pub struct DurableNonce {
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub blockhash: Hash,
+}
+
+impl DurableNonce {
+    /// Reads the current nonce value of `account`
+    pub fn fetch(rpc: &RpcClient, account: &Pubkey, authority: &Pubkey) -> ClientResult<Self> {
+        let nonce_account =
+            nonce_utils::get_account_with_commitment(rpc, account, rpc.commitment())?;
+        let data = nonce_utils::data_from_account(&nonce_account)?;
+        if data.authority != *authority {
+            return Err(format!("{} is not the authority of nonce {}", authority, account).into());
+        }
+
+        Ok(Self {
+            account: *account,
+            authority: *authority,
+            blockhash: data.blockhash(),
+        })
+    }
+
+    /// Builds an unsigned transaction of `instructions` (e.g. from `instruction::initialize`,
+    /// `new_game` or `claim_reward`), advancing the nonce first. Sign it with
+    /// `Transaction::sign(&signers, self.blockhash)`, the nonce authority included.
+    pub fn transaction(&self, instructions: &[Instruction], payer: &Pubkey) -> Transaction {
+        let mut message = Message::new_with_nonce(
+            instructions.to_vec(),
+            Some(payer),
+            &self.account,
+            &self.authority,
+        );
+        message.recent_blockhash = self.blockhash;
+        Transaction::new_unsigned(message)
+    }
+}
+
+/// Compute units a transaction can request
+pub const MAX_TRANSACTION_COMPUTE_UNITS: u32 = 1_400_000;
+
+/// Compute units budgeted per claim by `ClaimBatcher`, a settlement with every optional CPI
+/// (memo, settlement tree, burn, partner share) enabled stays below it
+pub const CLAIM_COMPUTE_UNITS: u32 = 150_000;
+
+/// Fetches an address lookup table, to compile claim batches against
+pub fn fetch_lookup_table(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> ClientResult<AddressLookupTableAccount> {
+    let account = rpc.get_account(address)?;
+    let table = AddressLookupTable::deserialize(&account.data)?;
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+/// Packs claim instructions (`ClaimReward`, `ClaimAndRebet`, `ClaimDeferred`...) into as few
+/// transactions as fit the packet size and compute limits. Accounts found in the lookup
+/// tables are referenced by index, so a table holding the config, the house token accounts
+/// and the programs fits many more claims in a transaction.
+pub struct ClaimBatcher {
+    payer: Pubkey,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    compute_units_per_claim: u32,
+}
+
+impl ClaimBatcher {
+    pub fn new(payer: &Pubkey) -> Self {
+        Self {
+            payer: *payer,
+            lookup_tables: Vec::new(),
+            compute_units_per_claim: CLAIM_COMPUTE_UNITS,
+        }
+    }
+
+    pub fn with_lookup_tables(mut self, lookup_tables: Vec<AddressLookupTableAccount>) -> Self {
+        self.lookup_tables = lookup_tables;
+        self
+    }
+
+    pub fn with_compute_units_per_claim(mut self, compute_units: u32) -> Self {
+        self.compute_units_per_claim = compute_units;
+        self
+    }
+
+    /// Splits `claims` into batches, in order, each fitting a single transaction
+    pub fn batches(&self, claims: &[Instruction]) -> ClientResult<Vec<Vec<Instruction>>> {
+        let max_claims = (MAX_TRANSACTION_COMPUTE_UNITS / self.compute_units_per_claim) as usize;
+        let mut batches: Vec<Vec<Instruction>> = Vec::new();
+        let mut batch = Vec::new();
+        for claim in claims {
+            batch.push(claim.clone());
+            if batch.len() <= max_claims && self.fits(&batch)? {
+                continue;
+            }
+            let claim = batch.pop().unwrap();
+            if batch.is_empty() {
+                return Err("a single claim doesn't fit in a transaction".into());
+            }
+            batches.push(batch);
+            batch = vec![claim];
+        }
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+        Ok(batches)
+    }
+
+    /// Builds the signed transaction of a batch, with its compute unit limit
+    pub fn transaction<T: Signers + ?Sized>(
+        &self,
+        batch: &[Instruction],
+        signers: &T,
+        blockhash: Hash,
+    ) -> ClientResult<VersionedTransaction> {
+        let message = self.message(batch, blockhash)?;
+        Ok(VersionedTransaction::try_new(message, signers)?)
+    }
+
+    fn message(&self, batch: &[Instruction], blockhash: Hash) -> ClientResult<VersionedMessage> {
+        let compute_units = self
+            .compute_units_per_claim
+            .saturating_mul(batch.len() as u32)
+            .min(MAX_TRANSACTION_COMPUTE_UNITS);
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            compute_units,
+        )];
+        instructions.extend_from_slice(batch);
+        let message =
+            v0::Message::try_compile(&self.payer, &instructions, &self.lookup_tables, blockhash)?;
+        Ok(VersionedMessage::V0(message))
+    }
+
+    /// Whether the transaction of `batch` fits a packet: the signatures (and their short
+    /// vector length) followed by the message
+    fn fits(&self, batch: &[Instruction]) -> ClientResult<bool> {
+        let message = self.message(batch, Hash::default())?;
+        let signatures = message.header().num_required_signatures as usize;
+        Ok(1 + signatures * 64 + message.serialize().len() <= PACKET_DATA_SIZE)
+    }
+}
@@ -0,0 +1,54 @@
+// minimal spl-account-compression interface, used to log settlements in a concurrent merkle tree
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+solana_program::declare_id!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// SPL Noop program, the compression program logs the tree changes through it
+pub mod noop {
+    solana_program::declare_id!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+}
+
+// anchor discriminators, sha256("global:<instruction>")[..8]
+const INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR: [u8; 8] = [191, 11, 119, 7, 180, 107, 220, 110];
+const APPEND_DISCRIMINATOR: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+
+/// Creates an `init_empty_merkle_tree` instruction, `merkle_tree` must already be allocated
+/// and owned by the compression program
+pub fn init_empty_merkle_tree(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Instruction {
+    let mut data = INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&max_depth.to_le_bytes());
+    data.extend_from_slice(&max_buffer_size.to_le_bytes());
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*merkle_tree, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(noop::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Creates an `append` instruction adding `leaf` to `merkle_tree`
+pub fn append(merkle_tree: &Pubkey, authority: &Pubkey, leaf: [u8; 32]) -> Instruction {
+    let mut data = APPEND_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&leaf);
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*merkle_tree, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(noop::id(), false),
+        ],
+        data,
+    }
+}
@@ -21,6 +21,168 @@ pub enum RoshamboError {
     /// Amount Overflow
     #[error("Invalid bet amount")]
     InvalidBetAmount,
+    /// Insufficient Session Balance
+    #[error("Insufficient session balance")]
+    InsufficientSessionBalance,
+    /// Rate Limited
+    #[error("Too many games in the current window")]
+    RateLimited,
+    /// Settlement Too Early
+    #[error("Game can't be settled yet")]
+    SettlementTooEarly,
+    /// Invalid Fee Config
+    #[error("Invalid fee or payout multiplier")]
+    InvalidFeeConfig,
+    /// Invalid Seed Reveal
+    #[error("Public seed doesn't match the game commitment")]
+    InvalidSeedReveal,
+    /// Host Seed Not Committed
+    #[error("The house hasn't committed its seed yet")]
+    HostSeedNotCommitted,
+    /// Host Seed Already Committed
+    #[error("The house already committed its seed")]
+    HostSeedAlreadyCommitted,
+    /// Delegate Not Approved
+    #[error("The house isn't approved as delegate of the token account")]
+    DelegateNotApproved,
+    /// Session Key Expired
+    #[error("Session key expired")]
+    SessionKeyExpired,
+    /// Session Key Wager Exceeded
+    #[error("Wager exceeds the session key limit")]
+    SessionKeyWagerExceeded,
+    /// House Vault Mismatch
+    #[error("House token account isn't the vault shard of the player")]
+    HouseVaultMismatch,
+    /// House Insolvent
+    #[error("House balance doesn't cover the payout of this bet")]
+    HouseInsolvent,
+    /// Emergency Withdraw Not Scheduled
+    #[error("No emergency withdrawal was scheduled")]
+    EmergencyWithdrawNotScheduled,
+    /// Emergency Withdraw Locked
+    #[error("Emergency withdrawal is still timelocked")]
+    EmergencyWithdrawLocked,
+    /// Season Active
+    #[error("The current season hasn't ended")]
+    SeasonActive,
+    /// Season Not Active
+    #[error("No season is running")]
+    SeasonNotActive,
+    /// Stale Seed Slot
+    #[error("The seed slot of the game isn't recent")]
+    StaleSeedSlot,
+    /// Daily Seed Not Committed
+    #[error("The house hasn't committed the daily seed of the game's day")]
+    DailySeedNotCommitted,
+    /// Daily Seed Too Late
+    #[error("A daily seed must be committed before its day starts")]
+    DailySeedTooLate,
+    /// Daily Seed Active
+    #[error("The day of the daily seed isn't over")]
+    DailySeedActive,
+    /// Invalid Daily Seed
+    #[error("Secret doesn't match the daily seed commitment")]
+    InvalidDailySeed,
+    /// Invalid Bet Limits
+    #[error("Bet limits must be non-zero, ordered and within the mint's range")]
+    InvalidBetLimits,
+    /// Token Account Frozen
+    #[error("Token account is frozen")]
+    TokenAccountFrozen,
+    /// No Deferred Payout
+    #[error("The game has no deferred payout")]
+    NoDeferredPayout,
+    /// Foreign Close Authority
+    #[error("Token account can be closed by another account than its owner")]
+    ForeignCloseAuthority,
+    /// Betting Paused
+    #[error("Betting is paused on this config")]
+    BettingPaused,
+    /// Missing Permission
+    #[error("Admin lacks the permission for this instruction")]
+    MissingPermission,
+    /// Rebet Not Allowed
+    #[error("Rebets aren't allowed while results can be disputed")]
+    RebetNotAllowed,
+    /// Dispute Window Closed
+    #[error("The game can't be disputed anymore")]
+    DisputeWindowClosed,
+    /// Already Disputed
+    #[error("The game is already disputed")]
+    AlreadyDisputed,
+    /// Not Disputed
+    #[error("The game isn't disputed")]
+    NotDisputed,
+    /// Payout Held
+    #[error("The payout of the game is held until its release slot or dispute resolution")]
+    PayoutHeld,
+    /// Cancellation Too Late
+    #[error("The game can't be cancelled once the house committed its seed")]
+    CancellationTooLate,
+    /// Insufficient Stake
+    #[error("Player doesn't hold the minimum stake of the config")]
+    InsufficientStake,
+    /// No Cashback
+    #[error("No cashback to claim, or the promotion hasn't ended yet")]
+    NoCashback,
+    /// Quest Not Completed
+    #[error("The quest isn't completed in the current period")]
+    QuestNotCompleted,
+    /// Quest Already Claimed
+    #[error("The quest reward was already claimed in the current period")]
+    QuestAlreadyClaimed,
+    /// Not Batchable
+    #[error("The game can't be settled in a batch, settle it with ClaimReward")]
+    NotBatchable,
+    /// Swap Program Not Allowed
+    #[error("The swap program isn't an allowed swap program whitelisted by the config")]
+    SwapProgramNotAllowed,
+    /// Swap Slippage
+    #[error("The swap spent more than the amount in or returned less than the minimum out")]
+    SwapSlippage,
+    /// Sweep Exceeds Profits
+    #[error("The amount swept exceeds the house profits left to sweep")]
+    SweepExceedsProfits,
+    /// Nothing Vested
+    #[error("Nothing vested since the last release")]
+    NothingVested,
+    /// Installment Not Due
+    #[error("The next installment isn't due yet")]
+    InstallmentNotDue,
+    /// Invalid Game Status
+    #[error("The game can't move to this status")]
+    InvalidGameStatus,
+    /// Config Cooldown
+    #[error("The bet limits changed too recently to open a game")]
+    ConfigCooldown,
+    /// Instruction Expired
+    #[error("The instruction landed after its expiry slot")]
+    InstructionExpired,
+    /// Host Offline
+    #[error("The settlement service of the config missed its heartbeat")]
+    HostOffline,
+    /// Insurance Withdraw Locked
+    #[error("No insurance fund withdrawal was scheduled or it is still timelocked")]
+    InsuranceWithdrawLocked,
+    /// Mint Not Recoverable
+    #[error("Tokens of the config mint are house funds, they can't be recovered")]
+    MintNotRecoverable,
+    /// Settlement Expired
+    #[error("The game is past the settlement age bound, it can only be refunded")]
+    SettlementExpired,
+    /// Recovery Account Not Allowed
+    #[error("Only the associated token account of the house PDA can be recovered")]
+    RecoveryAccountNotAllowed,
+    /// Swap Account Not Allowed
+    #[error("The swap can only touch the escrows among the token accounts of the house")]
+    SwapAccountNotAllowed,
+    /// Self Referral
+    #[error("A player can't be the partner of their own games")]
+    SelfReferral,
+    /// Daily Seed Not Revealed
+    #[error("The daily seed of the game's day must be revealed before the game is settled")]
+    DailySeedNotRevealed,
 }
 
 impl From<RoshamboError> for ProgramError {
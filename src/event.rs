@@ -0,0 +1,279 @@
+// program events, emitted through sol_log_data for indexers and bots
+
+use arrayref::{array_ref, array_refs};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoshamboEvent {
+    /// A pending game was declined by the operator and its stake refunded
+    GameDeclined {
+        game: Pubkey,
+        player: Pubkey,
+        refund: u64,
+        game_id: u64,
+    },
+    /// A game was opened, by `NewGame` or a rebet
+    GameCreated {
+        game: Pubkey,
+        player: Pubkey,
+        game_id: u64,
+        amount: u64,
+    },
+    /// A game was settled with `result` (see `Outcome`), `frontend_id` is the frontend it was
+    /// opened through (0 for none)
+    GameSettled {
+        game: Pubkey,
+        player: Pubkey,
+        game_id: u64,
+        result: u8,
+        frontend_id: u16,
+    },
+    /// The owner of `config` scheduled an emergency withdrawal of the house, possible from
+    /// `unlock_slot` on, players have until then to settle their open games
+    EmergencyWithdrawScheduled { config: Pubkey, unlock_slot: u64 },
+    /// The bet limits of `config` were changed by `UpdateConfig`
+    ConfigUpdated {
+        config: Pubkey,
+        old_min_bet_amount: u64,
+        old_max_bet_amount: u64,
+        min_bet_amount: u64,
+        max_bet_amount: u64,
+    },
+    /// `amount` of the house of `config` was withdrawn to the `destination` token account of
+    /// its owner, bypassing the solvency floor if `emergency`
+    Withdrawn {
+        config: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+        emergency: bool,
+    },
+    /// The circuit breaker of `config` paused it at `slot`, the house having lost
+    /// `window_loss` over the breaker window
+    CircuitBreakerTripped {
+        config: Pubkey,
+        window_loss: u64,
+        slot: u64,
+    },
+    /// A pending game was cancelled by its player and `refund` of its stake returned, the
+    /// house keeping the cancellation `fee`
+    GameCancelled {
+        game: Pubkey,
+        player: Pubkey,
+        refund: u64,
+        fee: u64,
+        game_id: u64,
+    },
+}
+
+impl RoshamboEvent {
+    /// Serializes the event as a tag byte followed by its little-endian fields
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::GameDeclined {
+                game,
+                player,
+                refund,
+                game_id,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(game.as_ref());
+                buf.extend_from_slice(player.as_ref());
+                buf.extend_from_slice(&refund.to_le_bytes());
+                buf.extend_from_slice(&game_id.to_le_bytes());
+            }
+            Self::GameCreated {
+                game,
+                player,
+                game_id,
+                amount,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(game.as_ref());
+                buf.extend_from_slice(player.as_ref());
+                buf.extend_from_slice(&game_id.to_le_bytes());
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::GameSettled {
+                game,
+                player,
+                game_id,
+                result,
+                frontend_id,
+            } => {
+                buf.push(2);
+                buf.extend_from_slice(game.as_ref());
+                buf.extend_from_slice(player.as_ref());
+                buf.extend_from_slice(&game_id.to_le_bytes());
+                buf.push(*result);
+                buf.extend_from_slice(&frontend_id.to_le_bytes());
+            }
+            Self::EmergencyWithdrawScheduled {
+                config,
+                unlock_slot,
+            } => {
+                buf.push(3);
+                buf.extend_from_slice(config.as_ref());
+                buf.extend_from_slice(&unlock_slot.to_le_bytes());
+            }
+            Self::ConfigUpdated {
+                config,
+                old_min_bet_amount,
+                old_max_bet_amount,
+                min_bet_amount,
+                max_bet_amount,
+            } => {
+                buf.push(4);
+                buf.extend_from_slice(config.as_ref());
+                buf.extend_from_slice(&old_min_bet_amount.to_le_bytes());
+                buf.extend_from_slice(&old_max_bet_amount.to_le_bytes());
+                buf.extend_from_slice(&min_bet_amount.to_le_bytes());
+                buf.extend_from_slice(&max_bet_amount.to_le_bytes());
+            }
+            Self::Withdrawn {
+                config,
+                destination,
+                amount,
+                emergency,
+            } => {
+                buf.push(5);
+                buf.extend_from_slice(config.as_ref());
+                buf.extend_from_slice(destination.as_ref());
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(*emergency as u8);
+            }
+            Self::CircuitBreakerTripped {
+                config,
+                window_loss,
+                slot,
+            } => {
+                buf.push(6);
+                buf.extend_from_slice(config.as_ref());
+                buf.extend_from_slice(&window_loss.to_le_bytes());
+                buf.extend_from_slice(&slot.to_le_bytes());
+            }
+            Self::GameCancelled {
+                game,
+                player,
+                refund,
+                fee,
+                game_id,
+            } => {
+                buf.push(7);
+                buf.extend_from_slice(game.as_ref());
+                buf.extend_from_slice(player.as_ref());
+                buf.extend_from_slice(&refund.to_le_bytes());
+                buf.extend_from_slice(&fee.to_le_bytes());
+                buf.extend_from_slice(&game_id.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    /// Decodes an event packed by `pack`, e.g. from a `Program data:` log line
+    pub fn unpack(input: &[u8]) -> Option<Self> {
+        let (&tag, rest) = input.split_first()?;
+        Some(match tag {
+            0 if rest.len() == 80 => {
+                let (game, player, refund, game_id) =
+                    array_refs![array_ref![rest, 0, 80], 32, 32, 8, 8];
+                Self::GameDeclined {
+                    game: Pubkey::new_from_array(*game),
+                    player: Pubkey::new_from_array(*player),
+                    refund: u64::from_le_bytes(*refund),
+                    game_id: u64::from_le_bytes(*game_id),
+                }
+            }
+            1 if rest.len() == 80 => {
+                let (game, player, game_id, amount) =
+                    array_refs![array_ref![rest, 0, 80], 32, 32, 8, 8];
+                Self::GameCreated {
+                    game: Pubkey::new_from_array(*game),
+                    player: Pubkey::new_from_array(*player),
+                    game_id: u64::from_le_bytes(*game_id),
+                    amount: u64::from_le_bytes(*amount),
+                }
+            }
+            2 if rest.len() == 75 => {
+                let (game, player, game_id, result, frontend_id) =
+                    array_refs![array_ref![rest, 0, 75], 32, 32, 8, 1, 2];
+                Self::GameSettled {
+                    game: Pubkey::new_from_array(*game),
+                    player: Pubkey::new_from_array(*player),
+                    game_id: u64::from_le_bytes(*game_id),
+                    result: result[0],
+                    frontend_id: u16::from_le_bytes(*frontend_id),
+                }
+            }
+            // settled before games were attributed to a frontend
+            2 if rest.len() == 73 => {
+                let (game, player, game_id, result) =
+                    array_refs![array_ref![rest, 0, 73], 32, 32, 8, 1];
+                Self::GameSettled {
+                    game: Pubkey::new_from_array(*game),
+                    player: Pubkey::new_from_array(*player),
+                    game_id: u64::from_le_bytes(*game_id),
+                    result: result[0],
+                    frontend_id: 0,
+                }
+            }
+            3 if rest.len() == 40 => {
+                let (config, unlock_slot) = array_refs![array_ref![rest, 0, 40], 32, 8];
+                Self::EmergencyWithdrawScheduled {
+                    config: Pubkey::new_from_array(*config),
+                    unlock_slot: u64::from_le_bytes(*unlock_slot),
+                }
+            }
+            4 if rest.len() == 64 => {
+                let (
+                    config,
+                    old_min_bet_amount,
+                    old_max_bet_amount,
+                    min_bet_amount,
+                    max_bet_amount,
+                ) = array_refs![array_ref![rest, 0, 64], 32, 8, 8, 8, 8];
+                Self::ConfigUpdated {
+                    config: Pubkey::new_from_array(*config),
+                    old_min_bet_amount: u64::from_le_bytes(*old_min_bet_amount),
+                    old_max_bet_amount: u64::from_le_bytes(*old_max_bet_amount),
+                    min_bet_amount: u64::from_le_bytes(*min_bet_amount),
+                    max_bet_amount: u64::from_le_bytes(*max_bet_amount),
+                }
+            }
+            5 if rest.len() == 73 => {
+                let (config, destination, amount, emergency) =
+                    array_refs![array_ref![rest, 0, 73], 32, 32, 8, 1];
+                Self::Withdrawn {
+                    config: Pubkey::new_from_array(*config),
+                    destination: Pubkey::new_from_array(*destination),
+                    amount: u64::from_le_bytes(*amount),
+                    emergency: emergency[0] != 0,
+                }
+            }
+            6 if rest.len() == 48 => {
+                let (config, window_loss, slot) = array_refs![array_ref![rest, 0, 48], 32, 8, 8];
+                Self::CircuitBreakerTripped {
+                    config: Pubkey::new_from_array(*config),
+                    window_loss: u64::from_le_bytes(*window_loss),
+                    slot: u64::from_le_bytes(*slot),
+                }
+            }
+            7 if rest.len() == 88 => {
+                let (game, player, refund, fee, game_id) =
+                    array_refs![array_ref![rest, 0, 88], 32, 32, 8, 8, 8];
+                Self::GameCancelled {
+                    game: Pubkey::new_from_array(*game),
+                    player: Pubkey::new_from_array(*player),
+                    refund: u64::from_le_bytes(*refund),
+                    fee: u64::from_le_bytes(*fee),
+                    game_id: u64::from_le_bytes(*game_id),
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    pub fn emit(&self) {
+        sol_log_data(&[&self.pack()]);
+    }
+}
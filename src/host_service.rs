@@ -0,0 +1,655 @@
+// reference house settlement service, only built with the "host-service" feature
+//
+// The service watches the program's game accounts. For every pending game of its config it
+// commits a host seed, and once the player revealed the public seed it settles the game.
+// A game past the settlement age bound of the config is refunded with `DeclineGame` instead.
+// Settlements held by a dispute window or a large win hold are closed with `ClaimDeferred`
+// once released. While the config requires a heartbeat the service sends one every
+// `HEARTBEAT_INTERVAL`.
+
+use std::{
+    slice,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::RpcFilterType,
+};
+use solana_program::{
+    address_lookup_table::AddressLookupTableAccount, hash::hash, program_option::COption,
+    program_pack::Pack, pubkey::Pubkey,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+use crate::{
+    client::{fetch_lookup_table, fetch_state, find_token_account, ClaimBatcher, ClientResult},
+    instruction,
+    listener::{ProgramEvent, ProgramListener},
+    outcome::{
+        daily_host_seed, daily_seed_commitment, daily_seed_day, host_seed_commitment, outcome,
+        win_payout, Outcome,
+    },
+    pda::{
+        find_admin_address, find_counter_address, find_daily_seed_address,
+        find_hourly_metrics_address, find_house_authority, find_player_stats_address,
+        find_player_vault_address, find_settlement_tree_authority, find_streak_leaderboard_address,
+        player_shard, DAILY_SEED_SEED,
+    },
+    state::{Admin, Config, DailySeed, Game, GameStatus, HourlyMetrics, Partner, ADMIN_CAN_SETTLE},
+};
+
+/// How often pending games are rescanned when no account update comes in
+pub const RESCAN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the heartbeat is sent while the config requires one, about 75 slots: the
+/// heartbeat max age of the config should leave room for a few missed ones
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Exponential backoff applied to every transaction the service sends
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs `f` until it succeeds or `max_attempts` is reached, returning the last error
+    pub fn run<T>(&self, mut f: impl FnMut() -> ClientResult<T>) -> ClientResult<T> {
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match f() {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt >= self.max_attempts => return Err(err),
+                Err(err) => {
+                    eprintln!("attempt {} failed: {}, retrying", attempt, err);
+                    sleep(backoff);
+                    backoff = (backoff * 2).min(self.max_backoff);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// House side of the commit/reveal protocol for a single config
+pub struct HostService {
+    rpc: RpcClient,
+    house: Keypair,
+    program_id: Pubkey,
+    config_address: Pubkey,
+    house_authority: Pubkey,
+    /// Admin PDA of the house, unless it is the config owner
+    admin: Option<Pubkey>,
+    retry: RetryPolicy,
+    /// Address lookup tables the claim batches of `process_pending` are compiled against
+    lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl HostService {
+    pub fn new(
+        rpc: RpcClient,
+        house: Keypair,
+        program_id: Pubkey,
+        config_address: Pubkey,
+        retry: RetryPolicy,
+    ) -> ClientResult<Self> {
+        // the house is the config owner, or an admin of the config allowed to settle
+        let config = fetch_state::<Config>(&rpc, &config_address)?;
+        let admin = if config.owner_pubkey == house.pubkey() {
+            None
+        } else {
+            let (admin, _) = find_admin_address(&config_address, &house.pubkey(), &program_id);
+            match fetch_state::<Admin>(&rpc, &admin) {
+                Ok(state) if state.permissions & ADMIN_CAN_SETTLE != 0 => Some(admin),
+                _ => {
+                    return Err(format!(
+                        "{} is neither the owner nor a settling admin of {}",
+                        house.pubkey(),
+                        config_address
+                    )
+                    .into())
+                }
+            }
+        };
+        let (house_authority, _) = find_house_authority(&program_id);
+
+        Ok(Self {
+            rpc,
+            house,
+            program_id,
+            config_address,
+            house_authority,
+            admin,
+            retry,
+            lookup_tables: Vec::new(),
+        })
+    }
+
+    /// Compiles the claim batches against the address lookup table at `address`
+    pub fn with_lookup_table(mut self, address: &Pubkey) -> ClientResult<Self> {
+        self.lookup_tables
+            .push(fetch_lookup_table(&self.rpc, address)?);
+        Ok(self)
+    }
+
+    /// Host seed of a game, derived from the house's signature over the game address so that it
+    /// is unpredictable to players and survives restarts without any local storage, or from the
+    /// secret of the game's day while the config has `daily_seed` enabled
+    pub fn host_seed(&self, address: &Pubkey, game: &Game, daily_seed: bool) -> u64 {
+        if daily_seed {
+            let secret = self.daily_secret(daily_seed_day(game.created_slot));
+            return daily_host_seed(&secret, game.game_id);
+        }
+        let signature = self.house.sign_message(address.as_ref());
+        let digest = hash(signature.as_ref()).to_bytes();
+        u64::from_le_bytes(digest[..8].try_into().unwrap())
+    }
+
+    /// Secret of a daily seed, derived from the house's signature like the host seeds
+    pub fn daily_secret(&self, day: u64) -> [u8; 32] {
+        let message = [
+            DAILY_SEED_SEED,
+            self.config_address.as_ref(),
+            &day.to_le_bytes(),
+        ]
+        .concat();
+        hash(self.house.sign_message(&message).as_ref()).to_bytes()
+    }
+
+    /// Commits the daily seed of tomorrow and reveals the one of yesterday, while the config
+    /// has `daily_seed` enabled
+    pub fn maintain_daily_seeds(&self) -> ClientResult<()> {
+        let config = fetch_state::<Config>(&self.rpc, &self.config_address)?;
+        if !config.daily_seed {
+            return Ok(());
+        }
+        let today = daily_seed_day(self.rpc.get_slot()?);
+
+        let (tomorrow, _) =
+            find_daily_seed_address(&self.config_address, today + 1, &self.program_id);
+        if self.rpc.get_account_data(&tomorrow).is_err() {
+            let signature = self.send(instruction::commit_daily_seed(
+                &self.program_id,
+                &self.house.pubkey(),
+                &self.config_address,
+                today + 1,
+                daily_seed_commitment(&self.daily_secret(today + 1)),
+            ))?;
+            println!("day {}: committed daily seed in {}", today + 1, signature);
+        }
+
+        let yesterday = today.saturating_sub(1);
+        let (address, _) =
+            find_daily_seed_address(&self.config_address, yesterday, &self.program_id);
+        if let Ok(daily_seed) = fetch_state::<DailySeed>(&self.rpc, &address) {
+            if daily_seed.secret.is_none() {
+                let signature = self.send(instruction::reveal_daily_seed(
+                    &self.program_id,
+                    &self.config_address,
+                    yesterday,
+                    self.daily_secret(yesterday),
+                ))?;
+                println!("day {}: revealed daily seed in {}", yesterday, signature);
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the hourly metrics of the current and next hours ahead of the settlements
+    /// counting in them, while the config has `hourly_metrics` enabled
+    pub fn maintain_hourly_metrics(&self) -> ClientResult<()> {
+        let config = fetch_state::<Config>(&self.rpc, &self.config_address)?;
+        if !config.hourly_metrics {
+            return Ok(());
+        }
+        let hour = HourlyMetrics::hour(self.rpc.get_slot()?);
+        self.create_hourly_metrics(hour)?;
+        self.create_hourly_metrics(hour + 1)
+    }
+
+    /// Creates the hourly metrics of `hour` unless they exist
+    fn create_hourly_metrics(&self, hour: u64) -> ClientResult<()> {
+        let (address, _) =
+            find_hourly_metrics_address(&self.config_address, hour, &self.program_id);
+        if self.rpc.get_account_data(&address).is_ok() {
+            return Ok(());
+        }
+        let signature = self.send(instruction::init_hourly_metrics(
+            &self.program_id,
+            &self.house.pubkey(),
+            &self.config_address,
+            hour,
+        ))?;
+        println!("hour {}: created hourly metrics in {}", hour, signature);
+        Ok(())
+    }
+
+    /// Sends a heartbeat, while the config has a heartbeat max age
+    pub fn maintain_heartbeat(&self) -> ClientResult<()> {
+        let config = fetch_state::<Config>(&self.rpc, &self.config_address)?;
+        if config.heartbeat_max_age_slots == 0 {
+            return Ok(());
+        }
+        self.send(instruction::heartbeat(
+            &self.program_id,
+            &self.house.pubkey(),
+            &self.config_address,
+        ))?;
+        Ok(())
+    }
+
+    /// Settles games forever: catches up on existing games, then follows account updates
+    pub fn run(&self, ws_url: &str) -> ClientResult<()> {
+        let mut listener =
+            ProgramListener::subscribe(ws_url, &self.program_id, self.rpc.commitment())?;
+        self.process_pending()?;
+
+        let mut last_heartbeat: Option<Instant> = None;
+        loop {
+            if last_heartbeat.is_none_or(|sent| sent.elapsed() >= HEARTBEAT_INTERVAL) {
+                if let Err(err) = self.maintain_heartbeat() {
+                    eprintln!("heartbeat: {}", err);
+                }
+                last_heartbeat = Some(Instant::now());
+            }
+            match listener.recv_timeout(RESCAN_INTERVAL)? {
+                Some(ProgramEvent::GameOpened { address, game })
+                | Some(ProgramEvent::GameUpdated { address, game }) => {
+                    self.process_logged(&address, &game)
+                }
+                Some(_) => {}
+                None => self.process_pending()?,
+            }
+        }
+    }
+
+    /// Handles every pending game currently on chain, and the daily seeds. The games ready to
+    /// be settled are claimed in batches, as many per transaction as fit.
+    pub fn process_pending(&self) -> ClientResult<()> {
+        if let Err(err) = self.maintain_daily_seeds() {
+            eprintln!("daily seed: {}", err);
+        }
+        if let Err(err) = self.maintain_hourly_metrics() {
+            eprintln!("hourly metrics: {}", err);
+        }
+
+        let config = fetch_state::<Config>(&self.rpc, &self.config_address)?;
+        let accounts = self
+            .rpc
+            .get_program_accounts_with_config(&self.program_id, self.games_filter())?;
+        let mut claims = Vec::new();
+        for (address, account) in accounts {
+            let Ok(game) = Game::unpack(&account.data) else {
+                continue;
+            };
+            if game.config_pubkey == self.config_address && game.status.is_open() {
+                match self.ready_claim(&address, &game, &config) {
+                    Ok(Some(claim)) => {
+                        claims.push(self.with_admin(claim));
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        eprintln!("{}: {}", address, err);
+                        continue;
+                    }
+                }
+            }
+            self.process_logged(&address, &game);
+        }
+
+        let batcher =
+            ClaimBatcher::new(&self.house.pubkey()).with_lookup_tables(self.lookup_tables.clone());
+        for batch in batcher.batches(&claims)? {
+            let sent = self.retry.run(|| {
+                let transaction = batcher.transaction(
+                    &batch,
+                    &[&self.house],
+                    self.rpc.get_latest_blockhash()?,
+                )?;
+                Ok(self.rpc.send_and_confirm_transaction(&transaction)?)
+            });
+            match sent {
+                Ok(signature) => println!("settled {} games in {}", batch.len(), signature),
+                Err(err) => eprintln!("claim batch of {} games: {}", batch.len(), err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves a game one step forward: commits the host seed, or settles it once revealed
+    pub fn process_game(&self, address: &Pubkey, game: &Game) -> ClientResult<()> {
+        if game.config_pubkey != self.config_address {
+            return Ok(());
+        }
+        if !game.status.is_open() {
+            return self.release_held(address, game);
+        }
+
+        let config = fetch_state::<Config>(&self.rpc, &self.config_address)?;
+        if game.settlement_expired(&config, self.rpc.get_slot()?) {
+            let signature = self.send(self.refund(address, game, &config)?)?;
+            println!("{}: refunded in {}", address, signature);
+            return Ok(());
+        }
+        let host_seed = self.host_seed(address, game, config.daily_seed);
+        match (game.host_seed_commitment, game.revealed_public_seed) {
+            (COption::None, _) => {
+                let signature = self.send(instruction::commit_host_seed(
+                    &self.program_id,
+                    &self.house.pubkey(),
+                    address,
+                    &self.config_address,
+                    host_seed_commitment(address, host_seed),
+                ))?;
+                println!("{}: committed host seed in {}", address, signature);
+            }
+            (COption::Some(_), COption::Some(_)) => {
+                if let Some(claim) = self.ready_claim(address, game, &config)? {
+                    let signature = self.send(claim)?;
+                    println!("{}: settled in {}", address, signature);
+                }
+            }
+            // waiting for the player to reveal
+            (COption::Some(_), COption::None) => {}
+        }
+        Ok(())
+    }
+
+    /// Claim of a game whose public seed was revealed, once it can be settled
+    fn ready_claim(
+        &self,
+        address: &Pubkey,
+        game: &Game,
+        config: &Config,
+    ) -> ClientResult<Option<Instruction>> {
+        let (COption::Some(commitment), COption::Some(public_seed)) =
+            (game.host_seed_commitment, game.revealed_public_seed)
+        else {
+            return Ok(None);
+        };
+        let host_seed = self.host_seed(address, game, config.daily_seed);
+        if commitment != host_seed_commitment(address, host_seed) {
+            return Err("committed host seed was not derived by this house".into());
+        }
+        let settle_slot = game
+            .created_slot
+            .saturating_add(config.min_settle_delay_slots);
+        let slot = self.rpc.get_slot()?;
+        if slot <= settle_slot {
+            // picked up again by the next rescan
+            return Ok(None);
+        }
+        if game.settlement_expired(config, slot) {
+            // refunded by `process_game`
+            return Ok(None);
+        }
+        let daily_seed = config.daily_seed.then(|| {
+            find_daily_seed_address(
+                &self.config_address,
+                daily_seed_day(game.created_slot),
+                &self.program_id,
+            )
+            .0
+        });
+        if let Some(daily_seed) = daily_seed {
+            // settled once the secret of the game's day is revealed, by `maintain_daily_seeds`
+            match fetch_state::<DailySeed>(&self.rpc, &daily_seed) {
+                Ok(daily_seed) if daily_seed.secret.is_some() => {}
+                _ => return Ok(None),
+            }
+        }
+
+        let receiver_token_account = find_token_account(
+            &self.rpc,
+            &game.game_creator_pubkey,
+            &config.mint_token_pubkey,
+        )?;
+        let house_token_account = self.house_token_account(config, &game.game_creator_pubkey);
+        let mut claim = instruction::claim_reward(
+            &self.program_id,
+            &game.game_creator_pubkey,
+            &self.house.pubkey(),
+            address,
+            &receiver_token_account,
+            &house_token_account,
+            &self.config_address,
+            &self.house_authority,
+            host_seed,
+            public_seed,
+        );
+        let (player_stats, _) = find_player_stats_address(
+            &self.config_address,
+            &game.game_creator_pubkey,
+            &self.program_id,
+        );
+        claim = instruction::with_player_stats(claim, &player_stats);
+        if config.settlement_memo {
+            claim = instruction::with_settlement_memo(claim);
+        }
+        if config.streak_leaderboard {
+            let (leaderboard, _) = find_streak_leaderboard_address(
+                &self.config_address,
+                config.season,
+                &self.program_id,
+            );
+            claim = instruction::with_streak_leaderboard(claim, &leaderboard);
+        }
+        if let COption::Some(tree) = config.settlement_tree {
+            let (tree_authority, _) =
+                find_settlement_tree_authority(&self.config_address, &self.program_id);
+            claim = instruction::with_settlement_tree(claim, &tree, &tree_authority);
+        }
+        if let Some(daily_seed) = daily_seed {
+            claim = instruction::with_daily_seed(claim, &daily_seed);
+        }
+        if config.hourly_metrics {
+            // a game from an hour the service missed still needs its metrics to settle
+            let hour = HourlyMetrics::hour(game.created_slot);
+            self.create_hourly_metrics(hour)?;
+            claim = instruction::with_hourly_metrics(
+                claim,
+                &self.program_id,
+                &self.config_address,
+                hour,
+            );
+        }
+        if config.counter_shards > 0 {
+            let shard = player_shard(&game.game_creator_pubkey, config.counter_shards);
+            let (counter, _) = find_counter_address(&self.config_address, shard, &self.program_id);
+            claim = instruction::with_counter_shard(claim, &self.config_address, &counter);
+        }
+        if config.audit_log {
+            claim = instruction::with_audit_log(claim, &self.program_id, &self.config_address);
+        }
+        if config.exposure_tracking {
+            claim = instruction::with_exposure(claim, &self.program_id, &self.config_address);
+        }
+        if let COption::Some(partner) = game.partner {
+            let token_account = fetch_state::<Partner>(&self.rpc, &partner)?.token_account;
+            claim = instruction::with_partner_share(claim, &partner, &token_account);
+        }
+        // a win above the payout cap or the vesting threshold is paid out of a PDA the
+        // house funds
+        let payout = win_payout(game.bet_amount, game.payout_multiplier_bps, game.fee_bps)
+            .filter(|_| outcome(host_seed, public_seed) == Outcome::Win)
+            .unwrap_or_default();
+        let capped = match config.max_payout_per_game {
+            0 => payout,
+            max_payout => {
+                if payout > max_payout {
+                    claim = instruction::with_payable(
+                        claim,
+                        &self.program_id,
+                        &self.config_address,
+                        game.game_id,
+                    );
+                }
+                payout.min(max_payout)
+            }
+        };
+        if config.vesting_threshold > 0 && capped > config.vesting_threshold {
+            claim = instruction::with_vesting(
+                claim,
+                &self.program_id,
+                &self.config_address,
+                game.game_id,
+            );
+        }
+        claim = if config.burn_bps > 0 {
+            instruction::with_burn(claim, &config.mint_token_pubkey)
+        } else {
+            instruction::with_mint(claim, &config.mint_token_pubkey)
+        };
+        Ok(Some(claim))
+    }
+
+    /// Refund of a game too old to be settled
+    fn refund(&self, address: &Pubkey, game: &Game, config: &Config) -> ClientResult<Instruction> {
+        let receiver_token_account = find_token_account(
+            &self.rpc,
+            &game.game_creator_pubkey,
+            &config.mint_token_pubkey,
+        )?;
+        let house_token_account = self.house_token_account(config, &game.game_creator_pubkey);
+        let mut refund = instruction::decline_game(
+            &self.program_id,
+            &self.house.pubkey(),
+            address,
+            &game.game_creator_pubkey,
+            &receiver_token_account,
+            &house_token_account,
+            &self.config_address,
+            &self.house_authority,
+        );
+        let (player_stats, _) = find_player_stats_address(
+            &self.config_address,
+            &game.game_creator_pubkey,
+            &self.program_id,
+        );
+        refund = instruction::with_player_stats(refund, &player_stats);
+        if config.counter_shards > 0 {
+            let shard = player_shard(&game.game_creator_pubkey, config.counter_shards);
+            let (counter, _) = find_counter_address(&self.config_address, shard, &self.program_id);
+            refund = instruction::with_counter_shard(refund, &self.config_address, &counter);
+        }
+        if config.exposure_tracking {
+            refund = instruction::with_exposure(refund, &self.program_id, &self.config_address);
+        }
+        Ok(instruction::with_mint(refund, &config.mint_token_pubkey))
+    }
+
+    /// Closes a settled game whose held payout was released, paying it to the player
+    fn release_held(&self, address: &Pubkey, game: &Game) -> ClientResult<()> {
+        if game.release_slot == 0
+            || game.status == GameStatus::Disputed
+            || self.rpc.get_slot()? < game.release_slot
+        {
+            return Ok(());
+        }
+
+        let config = fetch_state::<Config>(&self.rpc, &self.config_address)?;
+        // a payout above the cap or vesting is released by the player, paying the rent of the
+        // payable or vesting PDA
+        if (config.max_payout_per_game > 0 && game.deferred_payout > config.max_payout_per_game)
+            || (config.vesting_threshold > 0 && game.deferred_payout > config.vesting_threshold)
+        {
+            return Ok(());
+        }
+        let receiver_token_account = find_token_account(
+            &self.rpc,
+            &game.game_creator_pubkey,
+            &config.mint_token_pubkey,
+        )?;
+        let mut claim = instruction::claim_deferred(
+            &self.program_id,
+            &game.game_creator_pubkey,
+            address,
+            &receiver_token_account,
+            &self.house_token_account(&config, &game.game_creator_pubkey),
+            &self.config_address,
+            &self.house_authority,
+        );
+        if config.counter_shards > 0 {
+            let shard = player_shard(&game.game_creator_pubkey, config.counter_shards);
+            let (counter, _) = find_counter_address(&self.config_address, shard, &self.program_id);
+            claim = instruction::with_counter_shard(claim, &self.config_address, &counter);
+        }
+        claim = instruction::with_mint(claim, &config.mint_token_pubkey);
+        let signature = self.send(claim)?;
+        println!("{}: released held payout in {}", address, signature);
+        Ok(())
+    }
+
+    /// House vault paying the games of `player`
+    fn house_token_account(&self, config: &Config, player: &Pubkey) -> Pubkey {
+        find_player_vault_address(
+            &self.config_address,
+            config.vault_shards,
+            player,
+            &self.program_id,
+        )
+        .0
+    }
+
+    fn process_logged(&self, address: &Pubkey, game: &Game) {
+        if let Err(err) = self.process_game(address, game) {
+            eprintln!("{}: {}", address, err);
+        }
+    }
+
+    /// Appends the admin PDA of the house, unless it is the config owner
+    fn with_admin(&self, mut instruction: Instruction) -> Instruction {
+        if let Some(admin) = self.admin {
+            instruction
+                .accounts
+                .push(AccountMeta::new_readonly(admin, false));
+        }
+        instruction
+    }
+
+    fn send(&self, instruction: Instruction) -> ClientResult<Signature> {
+        let instruction = self.with_admin(instruction);
+        self.retry.run(|| {
+            let transaction = Transaction::new_signed_with_payer(
+                slice::from_ref(&instruction),
+                Some(&self.house.pubkey()),
+                &[&self.house],
+                self.rpc.get_latest_blockhash()?,
+            );
+            Ok(self.rpc.send_and_confirm_transaction(&transaction)?)
+        })
+    }
+
+    fn games_filter(&self) -> RpcProgramAccountsConfig {
+        RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::DataSize(Game::LEN as u64)]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.rpc.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        }
+    }
+}
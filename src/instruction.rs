@@ -1,11 +1,65 @@
 // program API, (de)serializing instruction data
 
-use solana_program::program_error::ProgramError;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
 
-use crate::error::RoshamboError::InvalidInstruction;
+use crate::{
+    compression,
+    error::RoshamboError::InvalidInstruction,
+    pda::{
+        find_admin_address, find_associated_token_address, find_audit_log_address,
+        find_counter_address, find_daily_seed_address, find_exposure_address,
+        find_fee_exemption_address, find_heartbeat_address, find_hourly_metrics_address,
+        find_insurance_fund_address, find_partner_address, find_payable_address,
+        find_quest_address, find_recovery_authority_address, find_swap_authority_address,
+        find_vesting_address,
+    },
+    tlv::{write_field, Fields},
+};
+
+/// SPL Memo program, called at settlement when the config enables `settlement_memo`
+pub mod spl_memo {
+    solana_program::declare_id!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+}
+
+/// Jupiter aggregator v6
+pub mod jupiter {
+    solana_program::declare_id!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+}
+
+/// Orca Whirlpools
+pub mod whirlpool {
+    solana_program::declare_id!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+}
+
+/// Swap programs `UpdateSwapProgram` may whitelist
+pub const ALLOWED_SWAP_PROGRAMS: [Pubkey; 2] = [jupiter::ID, whirlpool::ID];
+
+/// First tag of the v2 instructions. The tags below it keep their v1 payloads for existing
+/// clients, an instruction needing a richer payload gets a new tag from here on instead of
+/// changing an existing one (see `RoshamboInstruction::unpack_v2`). The payload of a v2
+/// instruction is a list of `tlv` fields, a new optional field is appended without changing
+/// the encoding of the others.
+pub const V2_TAG_START: u8 = 100;
+
+/// `NewGameV2` field: the stake, u64
+pub const FIELD_AMOUNT: u8 = 1;
+/// `NewGameV2` field: the public seed commitment, 32 bytes
+pub const FIELD_PUBLIC_SEED_COMMITMENT: u8 = 2;
+/// `NewGameV2` field: the slot the commitment is bound to, u64
+pub const FIELD_SEED_SLOT: u8 = 3;
+/// `NewGameV2` field, optional: the last slot the instruction can land in, u64
+pub const FIELD_EXPIRY_SLOT: u8 = 4;
+/// `NewGameV2` field, optional: the frontend the game is attributed to, u16
+pub const FIELD_FRONTEND_ID: u8 = 5;
 
 pub enum RoshamboInstruction {
-    /// Initialize Config - All games using this config will use the Mint Token same as this config
+    /// Initialize Config - All games using this config will use the Mint Token same as this config.
+    /// The mint decimals are stored in the config and bound the bet limits.
     ///
     ///
     /// Accounts expected:
@@ -18,35 +72,56 @@ pub enum RoshamboInstruction {
         max_bet_amount: u64,
     },
 
-    /// Create a new game by deposit amount of $TOKEN (e.g: wrapped SOL)
+    /// Create a new game by deposit amount of $TOKEN (e.g: wrapped SOL), committing to the
+    /// public seed revealed at claim time (see `outcome::public_seed_commitment`). The
+    /// commitment is bound to `seed_slot`, which must be at most `MAX_SEED_SLOT_AGE` slots old.
+    /// The creator token account can't be frozen nor closable by anyone but its owner, which
+    /// can be an SPL token multisig signing through its signers (see `with_multisig_signers`).
     ///
     ///
     /// Accounts expected:
     ///
     /// 0. `[signer]` The account of the person create the game
     /// 1. `[writable]` Creator token account
-    /// 2. `[writable]` The game account, it will hold all necessary info about the game.
-    /// 3. `[writable]` House token account owned by PDA
-    /// 4. `[writable]` Roshambo config
+    /// 2. `[writable, signer]` The game account, it will hold all necessary info about the game.
+    ///    A fresh keypair signs, a game account recycled into the pool doesn't (see
+    ///    `from_game_pool`).
+    /// 3. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 4. `[writable]` Roshambo config (read-only if sharded, see `SetCounterShards`)
     /// 5. `[]` The token program
-    NewGame { amount: u64 },
+    /// 6. `[writable]` Player stats PDA - created on the player's first game
+    /// 7. `[]` The system program
+    /// 8. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    NewGame {
+        amount: u64,
+        public_seed_commitment: [u8; 32],
+        seed_slot: u64,
+    },
 
-    /// End a game - Receive reward amount if this game win (x2) - or nothing if lose
+    /// End a game - Receive reward amount if this game win (x2) - or nothing if lose.
+    /// `public_seed` must match the commitment made in `NewGame`. If the house committed its seed
+    /// with `CommitHostSeed`, `host_seed` must match it and the house can settle alone, otherwise
+    /// the game creator must sign as well.
     ///
     ///
     /// Accounts expected:
     ///
-    /// 0. `[signer]` The account of the person owned the game - game creator
-    /// 1. `[signer]` The account of the house verify the result of this game
+    /// 0. `[writable, signer]` The account of the person owned the game - game creator (signer only needed without host commitment)
+    /// 1. `[signer]` The account of the house (config owner) verify the result of this game
     /// 2. `[writable]` The game account, it will hold all necessary info about the game (close after this and refund rent fee back to caller)
     /// 3. `[writable]` Temporary token account owned by PDA that the game creator bet before (close if lose - double if win)
-    /// 4. `[writable]` House token account owned by PDA (change based on game result)
+    /// 4. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
     /// 5. `[writable]` Roshambo config
     /// 6. `[]` The token program
     /// 7. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 8. `[writable]` Player stats PDA of the game creator, anywhere after the session key
+    ///    accounts (see `with_player_stats`), updated with the result and achievements
+    /// 9. `[]` The mint of the config, anywhere in the accounts (see `with_mint`), writable
+    ///    while the config burns (see `with_burn`)
     ClaimReward { host_seed: u64, public_seed: u64 },
 
-    /// Update min - max bet amount for specific config
+    /// Update min - max bet amount for specific config, within `MAX_BET_WHOLE_TOKENS` whole
+    /// tokens of the mint
     ///
     ///
     /// Accounts expected:
@@ -65,87 +140,4404 @@ pub enum RoshamboInstruction {
     ///
     /// 0. `[signer]` The account of the person who create the config
     /// 1. `[]` Initialized Config account
-    /// 2. `[writable]` House token account owned by PDA
+    /// 2. `[writable]` House vault of the config to withdraw from (see `InitHouseVaults`)
     /// 3. `[]` The token program
     /// 4. `[]` The PDA account - get by PublicKey.findProgramAddress
-    Withdraw { amount: u64 },
-}
+    /// 5. `[writable]` Token account of the config owner receiving the withdrawal
+    /// 6. `[]` Counter PDA of every shard, on a sharded config (see `with_counters`)
+    /// 7. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    ///
+    /// The house vault can't go below the outstanding exposure of its open games (see
+    /// `InitHouseVaults`) plus the config's withdraw bond (its share of it with house vaults).
+    /// An `emergency` withdrawal skips that floor once scheduled with
+    /// `ScheduleEmergencyWithdraw` and its timelock passed, it takes the config writable and
+    /// consumes the schedule.
+    Withdraw { amount: u64, emergency: bool },
 
-impl RoshamboInstruction {
-    /// Unpacks a byte buffer into a [RoshamboInstruction](enum.RoshamboInstruction.html).
-    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
-        let (tag, rest) = input.split_first().ok_or(InvalidInstruction)?;
+    /// Open a session by locking a deposit of $TOKEN in the house account, games played
+    /// in the session move the session balance instead of transferring tokens
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the player opening the session
+    /// 1. `[writable]` Player token account
+    /// 2. `[writable]` The session account, it will hold the session balance
+    /// 3. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 4. `[]` Roshambo config
+    /// 5. `[]` The token program
+    /// 6. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    OpenSession { deposit: u64 },
 
-        Ok(match tag {
-            0 => {
-                let (min_bet_amount, max_bet_amount) = Self::unpack_config(rest)?;
-                Self::Initialize {
-                    min_bet_amount,
-                    max_bet_amount,
-                }
-            }
-            1 => Self::NewGame {
-                amount: Self::unpack_amount(rest)?,
-            },
-            2 => {
-                let (host_seed, public_seed) = Self::unpack_claim_reward(rest)?;
-                Self::ClaimReward {
-                    host_seed,
-                    public_seed,
-                }
-            }
-            3 => {
-                let (min_bet_amount, max_bet_amount) = Self::unpack_config(rest)?;
-                Self::UpdateConfig {
-                    min_bet_amount,
-                    max_bet_amount,
-                }
-            }
-            4 => Self::Withdraw {
-                amount: Self::unpack_amount(rest)?,
-            },
-            _ => return Err(InvalidInstruction.into()),
-        })
-    }
+    /// Play a game against the session balance with `public_seed`, once the house committed
+    /// the host seed of the game with `CommitSessionSeed`. The game is settled when the house
+    /// reveals its seed with `RevealSessionSeed` - win adds the amount, lose removes it.
+    /// Replaces tag 6, which took both seeds in plain text. Like `NewGame`, the amount must
+    /// fit the bet limit of the player's house vault, less the session balance, and its open
+    /// games (with the counter shard, see `with_counter_shard`), the game counts against the
+    /// player's rate limit and the host must be alive (see `with_heartbeat`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the player owned the session
+    /// 1. `[writable]` The session account
+    /// 2. `[]` Roshambo config
+    /// 3. `[]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 4. `[writable]` Player stats PDA - created on the player's first game
+    /// 5. `[]` The system program
+    PlaySession { amount: u64, public_seed: u64 },
 
-    fn unpack_config(input: &[u8]) -> Result<(u64, u64), ProgramError> {
-        let min_bet_amount = input
-            .get(..8)
-            .and_then(|slice| slice.try_into().ok())
-            .map(u64::from_le_bytes)
-            .ok_or(InvalidInstruction)?;
+    /// Close a session - pay out the remaining session balance in a single transfer and book
+    /// the session games, with the house's net result, in the config counters
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the player owned the session
+    /// 1. `[writable]` The session account (close after this and refund rent fee back to player)
+    /// 2. `[writable]` Player token account receive the session balance
+    /// 3. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 4. `[writable]` Roshambo config (read-only if sharded, see `with_counter_shard`)
+    /// 5. `[]` The token program
+    /// 6. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 7. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    CloseSession,
 
-        let max_bet_amount = input
-            .get(9..16)
-            .and_then(|slice| slice.try_into().ok())
-            .map(u64::from_le_bytes)
-            .ok_or(InvalidInstruction)?;
+    /// End a game like `ClaimReward`, but on a win only the profit is paid out and the original
+    /// stake is immediately wagered again in a new game committed to `next_public_seed_commitment`
+    /// (bound to `next_seed_slot`). The new game goes through the checks of `NewGame`: the
+    /// house must cover it once the win is paid, the heartbeat and stake accounts are expected
+    /// like there, and the insurance share of the stake moves to the insurance fund.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0-7. Same as `ClaimReward`
+    /// 8. `[writable, signer]` The new game account, only used on a win, signing like in `NewGame`
+    /// 9. `[writable]` Player stats PDA
+    /// 10. `[]` The mint of the config, anywhere in the accounts (see `with_mint`), writable
+    ///    while the config burns (see `with_burn`)
+    ClaimAndRebet {
+        host_seed: u64,
+        public_seed: u64,
+        next_public_seed_commitment: [u8; 32],
+        next_seed_slot: u64,
+    },
 
-        Ok((min_bet_amount, max_bet_amount))
-    }
+    /// Decline a pending game - refund the stake to the game creator and close the game. The
+    /// insurance cut of the stake (see `UpdateInsuranceFund`) isn't refunded.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` The game account (close after this and refund rent fee back to creator)
+    /// 2. `[writable]` The account of the person owned the game - game creator
+    /// 3. `[writable]` Creator token account receive the refund
+    /// 4. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 5. `[writable]` Roshambo config (read-only if sharded, see `SetCounterShards`)
+    /// 6. `[]` The token program
+    /// 7. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 8. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    /// 9. `[writable]` The creator's player stats PDA, anywhere in the accounts (see
+    ///    `with_player_stats`), counting the refund
+    DeclineGame,
 
-    fn unpack_amount(input: &[u8]) -> Result<u64, ProgramError> {
-        let bet_amount = input
-            .get(..8)
-            .and_then(|slice| slice.try_into().ok())
-            .map(u64::from_le_bytes)
-            .ok_or(InvalidInstruction)?;
-        Ok(bet_amount)
-    }
+    /// Update the per-player rate limit - at most `max_games_per_window` new games per player
+    /// every `window_slots` slots, zero disables the limit
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateRateLimit {
+        max_games_per_window: u64,
+        window_slots: u64,
+    },
 
-    fn unpack_claim_reward(input: &[u8]) -> Result<(u64, u64), ProgramError> {
-        let host_seed = input
-            .get(..8)
-            .and_then(|slice| slice.try_into().ok())
-            .map(u64::from_le_bytes)
-            .ok_or(InvalidInstruction)?;
+    /// Update how many slots must pass after a game is created before it can be claimed,
+    /// a game can never be claimed in the slot it was created in
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateSettleDelay { min_settle_delay_slots: u64 },
 
-        let public_seed = input
-            .get(9..16)
-            .and_then(|slice| slice.try_into().ok())
-            .map(u64::from_le_bytes)
-            .ok_or(InvalidInstruction)?;
+    /// Update the fee taken from win profits and the win payout multiplier, both in basis
+    /// points. Games already created keep the values they were created with.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateFees {
+        fee_bps: u16,
+        payout_multiplier_bps: u16,
+    },
 
-        Ok((host_seed, public_seed))
+    /// Commit the house's seed for a pending game (see `outcome::host_seed_commitment`), so the
+    /// player can reveal its public seed and the house can settle the game on its own
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the house (config owner)
+    /// 1. `[writable]` The game account
+    /// 2. `[]` Roshambo config of the game
+    CommitHostSeed { host_seed_commitment: [u8; 32] },
+
+    /// Reveal the public seed of a game on chain once the house committed its seed
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person owned the game - game creator
+    /// 1. `[writable]` The game account
+    RevealSeed { public_seed: u64 },
+
+    /// Create a new game like `NewGame`, but the stake is pulled by the house PDA, approved
+    /// beforehand as SPL token delegate of the creator token account (one approval, many bets)
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0-7. Same as `NewGame`
+    /// 8. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 9. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    NewGameDelegated {
+        amount: u64,
+        public_seed_commitment: [u8; 32],
+        seed_slot: u64,
+    },
+
+    /// Register (or update) a short-lived session key allowed to act for the player until
+    /// `expiry_slot`, wagering at most `max_wager` per game. A session key signs in place of the
+    /// player by appending `[signer]` session key and `[]` session authority PDA to the accounts
+    /// of `NewGameDelegated`, `RevealSeed` and `ClaimReward` (see `with_session_key`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the player, paying for the session authority
+    /// 1. `[]` The session key
+    /// 2. `[writable]` Session authority PDA
+    /// 3. `[]` The system program
+    RegisterSessionKey { expiry_slot: u64, max_wager: u64 },
+
+    /// Revoke a session key
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the player
+    /// 1. `[writable]` Session authority PDA (close after this and refund rent fee back to player)
+    RevokeSessionKey,
+
+    /// Enable or disable the SPL Memo written at settlement ("roshambo game <game> <result>").
+    /// While enabled, `ClaimReward` and `ClaimAndRebet` also expect the SPL Memo program among
+    /// their accounts (see `with_settlement_memo`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateSettlementMemo { enabled: bool },
+
+    /// Copy the config counters (games, volume, house PnL) into the stats snapshot of the
+    /// current epoch, anyone can call it. Taking it again within the epoch refreshes it.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account paying for the snapshot
+    /// 1. `[]` Roshambo config
+    /// 2. `[writable]` Stats snapshot PDA of the current epoch
+    /// 3. `[]` The system program
+    /// 4. `[]` Counter PDA of every shard, on a sharded config (see `with_counters`)
+    SnapshotStats,
+
+    /// Initialize a concurrent merkle tree (spl-account-compression) logging every settlement
+    /// of the config from now on, see `outcome::settlement_leaf`. The tree account must be
+    /// allocated beforehand and owned by the compression program. While a tree is set,
+    /// `ClaimReward` and `ClaimAndRebet` also expect the accounts of `with_settlement_tree`.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[writable]` The merkle tree account
+    /// 3. `[]` Settlement tree authority PDA of the config
+    /// 4. `[]` The spl-account-compression program
+    /// 5. `[]` The spl-noop program
+    InitSettlementTree {
+        max_depth: u32,
+        max_buffer_size: u32,
+    },
+
+    /// Raise the number of counter shards of the config, creating the new `Counter` PDAs.
+    /// Once sharded, new games and settlements add to the counter shard of the player (see
+    /// `pda::player_shard`) and the config is no longer written, so `NewGame`, `ClaimReward`
+    /// and `ClaimAndRebet` take it read-only plus the counter shard (see `with_counter_shard`).
+    /// Config totals are the config counters plus the sum of all shards. There is one shard per
+    /// house vault (see `InitHouseVaults`), counting the games of the vault of the same index,
+    /// so `shards` must be the number of vaults. Not available while the config has a circuit
+    /// breaker or tracks its exposure (see `UpdateExposureTracking`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for the shards
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[]` The system program
+    /// 3. `[writable]` Counter PDA of every new shard
+    SetCounterShards { shards: u8 },
+
+    /// Create the house vaults of the config up to `shards`, token accounts at PDAs of the
+    /// config owned by the house PDA holding its bankroll. Games are only played and funds
+    /// only withdrawn through them, so a config needs at least one: a token account of the
+    /// house PDA outside them can't be told apart from the bankroll of another config with the
+    /// same mint. With several shards each game uses the vault of its player (see
+    /// `pda::find_player_vault_address`) instead of a single one every game write-locks. The
+    /// owner funds and rebalances the vaults with plain transfers and `Withdraw`. Every vault
+    /// backs the whole outstanding exposure of the config until the counters are sharded along
+    /// with them (see `SetCounterShards`), which then tracks it per vault. Vaults can't be
+    /// added once the counters are sharded.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for the vaults
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[]` The config token mint
+    /// 3. `[]` The token program
+    /// 4. `[]` The system program
+    /// 5. `[writable]` Vault PDA of every new shard
+    InitHouseVaults { shards: u8 },
+
+    /// Enable or disable the game pool. While enabled, settled and declined games aren't
+    /// closed but zeroed and marked `recycled` for the config, keeping their rent instead of
+    /// refunding it to the creator, and `NewGame` accepts a recycled game of the config in
+    /// place of a freshly created account (see `client::find_recycled_game`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateGamePool { enabled: bool },
+
+    /// Set the amount `Withdraw` keeps in the house on top of the outstanding exposure
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateWithdrawBond { bond: u64 },
+
+    /// Schedule an emergency withdrawal, possible `EMERGENCY_WITHDRAW_DELAY_SLOTS` from now,
+    /// or cancel the scheduled one. Scheduling emits `EmergencyWithdrawScheduled` so players
+    /// can settle their open games before the house is emptied.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    ScheduleEmergencyWithdraw { enabled: bool },
+
+    /// Create the streak leaderboard of the config's current season. From then on
+    /// `ClaimReward` and `ClaimAndRebet` rank the creator's win streak on it and expect it
+    /// among their accounts (see `with_streak_leaderboard`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for the leaderboard
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[writable]` Streak leaderboard PDA of the current season
+    /// 3. `[]` The system program
+    InitStreakLeaderboard,
+
+    /// End the current season: its streak leaderboard is frozen and copied into the season's
+    /// `SeasonResult` PDA, and settlements stop ranking streaks until `StartSeason`
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for the result
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[]` Streak leaderboard PDA of the current season
+    /// 3. `[writable]` Season result PDA of the current season
+    /// 4. `[]` The system program
+    EndSeason,
+
+    /// Start the next season with an empty streak leaderboard, once the current one ended.
+    /// Player stats restart their season counters and win streak the next time they play.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for the leaderboard
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[writable]` Streak leaderboard PDA of the next season
+    /// 3. `[]` The system program
+    StartSeason,
+
+    /// Pay `amounts` from the treasury of the config (see `UpdateTreasury`) straight to
+    /// recipient token accounts of its settlement mint, e.g. for seasonal rewards. The owner
+    /// signs for the treasury, which must be its own or delegated to it, the house vaults
+    /// are never touched.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config, owner or delegate of
+    ///    the treasury
+    /// 1. `[]` Initialized Config account
+    /// 2. `[writable]` The treasury token account of the config
+    /// 3. `[]` The token program
+    /// 4. `[writable]` Token account of each recipient, in the order of `amounts`
+    /// 5. `[]` The settlement mint, anywhere in the accounts (see `with_mint`)
+    Airdrop { amounts: Vec<u64> },
+
+    /// Commit the house secret of `day` (see `outcome::daily_seed_day`) before it starts. While
+    /// `daily_seed` is enabled, the host seed of every game is `outcome::daily_host_seed` of
+    /// the secret of the game's day and its id, so a whole day can be verified at once when
+    /// the secret is revealed.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for the daily seed
+    /// 1. `[]` Initialized Config account
+    /// 2. `[writable]` Daily seed PDA of `day`
+    /// 3. `[]` The system program
+    CommitDailySeed { day: u64, commitment: [u8; 32] },
+
+    /// Publish the secret of a daily seed once its day is over
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[]` Initialized Config account
+    /// 1. `[writable]` Daily seed PDA
+    RevealDailySeed { day: u64, secret: [u8; 32] },
+
+    /// Enable or disable daily seeds. While enabled, `ClaimReward` and `ClaimAndRebet` expect
+    /// the daily seed of the game's day among their accounts (see `with_daily_seed`), revealed
+    /// and deriving the host seed of the game, so games are settled the day after theirs.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateDailySeed { enabled: bool },
+
+    /// Pay the payout of a game settled while the creator's token account was frozen, once
+    /// it is thawed, or held (see `UpdateDisputeWindow`) once released, and close the game.
+    /// Anyone can send it, the payout only goes to the game creator. A payout above the
+    /// payout cap pays the cap, the rest is owed in installments (see `with_payable`), and
+    /// one above the vesting threshold vests (see `with_vesting`): the creator then signs,
+    /// paying the rent of the payable or vesting PDA.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable]` The account of the person who created the game (refunded the game rent),
+    ///    signer with a payable or a vesting (see `with_creator_signature`)
+    /// 1. `[writable]` The settled game account
+    /// 2. `[writable]` Creator's token account receiving the payout
+    /// 3. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 4. `[writable]` Roshambo config (read-only if sharded, see `with_counter_shard`)
+    /// 5. `[]` The token program
+    /// 6. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 7. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    ClaimDeferred,
+
+    /// Halt or resume betting in the mint of a config. While paused, `NewGame`,
+    /// `NewGameDelegated`, the rebet of `ClaimAndRebet`, `OpenSession` and `PlaySession` fail,
+    /// open games and sessions can still be settled and closed. Only the owner can resume a
+    /// config paused by its circuit breaker (see `UpdateCircuitBreaker`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdatePause { paused: bool },
+
+    /// Grant an admin key the `ADMIN_CAN_*` bits of `permissions` over a config, or update
+    /// them (0 revokes every permission). An admin signs the instructions it is allowed to in
+    /// place of the owner, with its admin PDA among the accounts (see `with_admin`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for
+    ///    the admin PDA
+    /// 1. `[]` Initialized Config account
+    /// 2. `[]` The admin key
+    /// 3. `[writable]` Admin PDA
+    /// 4. `[]` The system program
+    SetAdmin { permissions: u8 },
+
+    /// Create the audit log of the config. From then on every seed reveal of `ClaimReward` and
+    /// `ClaimAndRebet` is chained into it (see `outcome::audit_entry`), and they expect the
+    /// audit log among their accounts (see `with_audit_log`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for the log
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[writable]` Audit log PDA
+    /// 3. `[]` The system program
+    InitAuditLog,
+
+    /// Set the dispute window of the config, 0 disables disputes. While set, every settlement
+    /// holds its payout (a loss included) for `window_slots`, during which the player can
+    /// `Dispute` it, and `ClaimAndRebet` isn't allowed. `ClaimDeferred` pays held payouts once
+    /// released.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[]` The arbiter resolving disputes - optional, required unless one is set already
+    UpdateDisputeWindow { window_slots: u64 },
+
+    /// Dispute the held result of a game within the dispute window of its config, holding the
+    /// payout until the arbiter resolves it
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who created the game
+    /// 1. `[writable]` The settled game account
+    /// 2. `[]` Roshambo config
+    Dispute,
+
+    /// Resolve a disputed game: `uphold` keeps its result, otherwise it is voided and the
+    /// stake refunded in place of its payout. Either way `ClaimDeferred` can then close it.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The arbiter of the config
+    /// 1. `[writable]` The disputed game account
+    /// 2. `[writable]` Roshambo config (read-only if sharded, see `with_counter_shard`)
+    ResolveDispute { uphold: bool },
+
+    /// Hold wins paying more than `threshold` for `delay_slots` after their settlement, giving
+    /// the operator a window to catch exploits. Held wins are paid by `ClaimDeferred` once
+    /// released, a `threshold` of 0 pays every win instantly.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateLargeWinHold { threshold: u64, delay_slots: u64 },
+
+    /// Cancel a pending game before the house committed its seed (and within the game's day
+    /// with daily seeds), refunding the stake minus its insurance cut and the config's
+    /// cancellation fee. The fee stays in the house vault, or goes to the treasury owner's
+    /// associated token account of the config mint when the config has a treasury (see
+    /// `with_treasury_fee_account`). A game past the settlement age bound (see
+    /// `UpdateMaxSettleAge`) can be cancelled at any point, without fee. Emits `GameCancelled`
+    /// with the refund and fee.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who created the game (refunded the game rent)
+    /// 1. `[writable]` The game account
+    /// 2. `[writable]` Creator token account receiving the refund
+    /// 3. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 4. `[writable]` Roshambo config (read-only if sharded, see `with_counter_shard`)
+    /// 5. `[]` The token program
+    /// 6. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 7. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    /// 8. `[writable]` The creator's player stats PDA, anywhere in the accounts (see
+    ///    `with_player_stats`), counting the refund
+    /// 9. `[]` The treasury of the config, anywhere in the accounts, if it has one
+    /// 10. `[writable]` The associated token account of the treasury owner for the config mint,
+    ///     anywhere in the accounts, if the config has a treasury
+    CancelGame,
+
+    /// Update the share of the stake kept by the house on `CancelGame`, at most
+    /// `BPS_DENOMINATOR`
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateCancelFee { fee_bps: u16 },
+
+    /// Require players to hold at least `min_stake` of a stake token to create games and
+    /// open sessions, 0 disables the requirement. `NewGame`, `NewGameDelegated` and
+    /// `OpenSession` then expect a token account of the stake mint owned by the player among
+    /// their accounts (see `with_stake_account`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[]` The stake mint - optional, required unless one is set already
+    UpdateMinStake { min_stake: u64 },
+
+    /// Burn `burn_bps` of the house's take of every settlement (the stake of a loss, the fee of
+    /// a win) instead of keeping it in the bankroll. While set, `ClaimReward` and
+    /// `ClaimAndRebet` take the mint writable (see `with_burn`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateBurn { burn_bps: u16 },
+
+    /// Run a cashback promotion - games settled from `start_slot` until `end_slot` count toward
+    /// each player's net loss, `cashback_bps` of which they can claim with `ClaimCashback` once
+    /// the promotion ended. A new `start_slot` starts a new promotion.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateCashback {
+        cashback_bps: u16,
+        start_slot: u64,
+        end_slot: u64,
+    },
+
+    /// Pay the player the cashback of their net loss over the last promotion, from the house
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The player
+    /// 1. `[writable]` Player stats PDA of the player
+    /// 2. `[writable]` Player token account receive the cashback
+    /// 3. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 4. `[writable]` Roshambo config
+    /// 5. `[]` The token program
+    /// 6. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 7. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    ClaimCashback,
+
+    /// Create or update quest `quest_id` (below `MAX_QUESTS`) - reach `target` wins or wagered
+    /// tokens (`QUEST_KIND_*`) within a day or a week (`QUEST_PERIOD_*`) to claim `reward`
+    /// with `ClaimQuestReward`, once per period. A zero reward disables the quest.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, pays the rent
+    /// 1. `[]` Initialized Config account
+    /// 2. `[writable]` Quest PDA - get by `pda::find_quest_address`
+    /// 3. `[]` The system program
+    SetQuest {
+        quest_id: u8,
+        kind: u8,
+        period: u8,
+        target: u64,
+        reward: u64,
+    },
+
+    /// Pay the player the reward of a quest completed in its current period, from the house
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The player
+    /// 1. `[writable]` Player stats PDA of the player
+    /// 2. `[]` Quest PDA
+    /// 3. `[writable]` Player token account receive the reward
+    /// 4. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 5. `[writable]` Roshambo config
+    /// 6. `[]` The token program
+    /// 7. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 8. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    ClaimQuestReward,
+
+    /// Register a frontend as a partner of the config - games created with its partner PDA
+    /// among the `NewGame` accounts (see `with_partner`) pay it `partner_share_bps` of the
+    /// house's take at settlement. Registering again updates the token account. A game can't
+    /// be credited to a partner registered by its own creator.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The partner authority, pays the rent
+    /// 1. `[]` Initialized Config account
+    /// 2. `[writable]` Partner PDA - get by `pda::find_partner_address`
+    /// 3. `[]` Token account of the config mint receiving the partner's share
+    /// 4. `[]` The system program
+    RegisterPartner,
+
+    /// Update the share of the house's take of a settlement paid to the partner of the game.
+    /// Together with `burn_bps` it can't exceed `BPS_DENOMINATOR`. While set, claims of
+    /// partner games take the partner PDA and token account (see `with_partner_share`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdatePartnerShare { share_bps: u16 },
+
+    /// Settle up to `MAX_CLAIM_BATCH` games of one player like `ClaimReward`, paying them out
+    /// in a single transfer. `seeds` holds the host and public seed of every game, in the
+    /// order of the game accounts. Every game needs a committed host seed and no partner.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable]` The account of the person owned the games - game creator
+    /// 1. `[signer]` The account of the house (config owner) verify the results
+    /// 2. `[writable]` Creator's token account receiving the payout
+    /// 3. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 4. `[writable]` Roshambo config (read-only if sharded, see `with_counter_shard`)
+    /// 5. `[]` The token program
+    /// 6. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 7. `[writable]` Player stats PDA of the game creator
+    /// 8. `[writable]` Game account of every seed pair
+    /// 9. `[]` The mint of the config, anywhere after the games (see `with_mint`)
+    ClaimRewards { seeds: Vec<(u64, u64)> },
+
+    /// Whitelist the swap program `Rebalance` may route the house funds of the config
+    /// through, one of `ALLOWED_SWAP_PROGRAMS`, leaving it out clears the whitelist.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[]` The swap program - optional
+    UpdateSwapProgram,
+
+    /// Move house funds from the house vault of a config to the one of another config
+    /// (another mint) through the swap program whitelisted by the source config. The swap
+    /// runs between two escrow token accounts of the swap authority of the source config (see
+    /// `pda::find_swap_authority_address`), which signs it instead of the house PDA, and
+    /// may touch no other token account of the house. At most `amount_in` may leave the
+    /// source vault, which has to stay above its withdraw floor, and at least
+    /// `min_amount_out` has to reach the destination. `swap_data` is the instruction data of
+    /// the swap.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create both configs
+    /// 1. `[]` Source Config account
+    /// 2. `[]` Destination Config account
+    /// 3. `[writable]` House vault of the source config, owned by PDA
+    /// 4. `[writable]` House vault of the destination config, owned by PDA
+    /// 5. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 6. `[]` Counter shard PDA of every shard of a sharded source config, in order
+    /// 7. `[]` Swap authority PDA of the source config
+    /// 8. `[writable]` Escrow token account of the source mint, owned by the swap authority
+    /// 9. `[writable]` Escrow token account of the destination mint, owned by the swap
+    ///    authority
+    /// 10. `[]` The mint of the source config
+    /// 11. `[]` The mint of the destination config
+    /// 12. `[]` The token program
+    /// 13. `[]` The swap program
+    /// 14. `[]` Accounts of the swap instruction, in its order
+    Rebalance {
+        amount_in: u64,
+        min_amount_out: u64,
+        swap_data: Vec<u8>,
+    },
+
+    /// Set the token account receiving the profits swept with `SweepProfits` and its mint,
+    /// the settlement mint of the config, leaving them out clears both. The settlement mint
+    /// can't be the config mint and the treasury can't be owned by the house PDA or the swap
+    /// authority of the config. Cancellation fees go to the associated token account of the
+    /// treasury owner for the config mint, which must exist while fees are charged.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[]` The treasury token account - optional
+    /// 3. `[]` The settlement mint, the mint of the treasury - with the treasury
+    UpdateTreasury,
+
+    /// Swap house profits not swept yet (`house_pnl` minus `swept_profits`) into the
+    /// settlement mint of the config through the swap program it whitelisted, by the escrows
+    /// of its swap authority like `Rebalance`. At most `amount_in` may leave the house vault,
+    /// which has to stay above its withdraw floor, and at least `min_amount_out` has to reach
+    /// the treasury. `swap_data` is the instruction data of the swap.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[writable]` House vault of the config, owned by PDA (see `InitHouseVaults`)
+    /// 3. `[writable]` The treasury token account of the config
+    /// 4. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 5. `[]` Counter shard PDA of every shard of a sharded config, in order
+    /// 6. `[]` Swap authority PDA of the config
+    /// 7. `[writable]` Escrow token account of the config mint, owned by the swap authority
+    /// 8. `[writable]` Escrow token account of the settlement mint, owned by the swap
+    ///    authority
+    /// 9. `[]` The mint of the config
+    /// 10. `[]` The settlement mint
+    /// 11. `[]` The token program
+    /// 12. `[]` The swap program
+    /// 13. `[]` Accounts of the swap instruction, in its order
+    SweepProfits {
+        amount_in: u64,
+        min_amount_out: u64,
+        swap_data: Vec<u8>,
+    },
+
+    /// Stream wins paying more than `threshold` at settlement to the player over
+    /// `vesting_slots` rather than at once, protecting the liquidity of the bankroll. Claims
+    /// of such wins create a vesting PDA, the house signer paying its rent (see
+    /// `with_vesting`), a `threshold` of 0 pays every win at once.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateVesting { threshold: u64, vesting_slots: u64 },
+
+    /// Pay out the part of a vested win released since the last call, anyone can crank it.
+    /// The vesting account is closed once everything is released.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable]` Vesting PDA - get by `pda::find_vesting_address`
+    /// 1. `[writable]` Player's token account receiving the releases
+    /// 2. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 3. `[writable]` Roshambo config (read-only if sharded, see `with_counter_shard`)
+    /// 4. `[]` The token program
+    /// 5. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 6. `[writable]` The account that paid the vesting rent, refunded on close
+    /// 7. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    ReleaseVested,
+
+    /// Cap what a single game pays at settlement to `max_payout`. The rest of a larger win is
+    /// owed to the player through a payable PDA, the house signer paying its rent (see
+    /// `with_payable`), and paid by `PayInstallment` in installments of at most `max_payout`
+    /// every `installment_slots`. Held payouts are paid in full by `ClaimDeferred`. A
+    /// `max_payout` of 0 disables the cap.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdatePayoutCap {
+        max_payout: u64,
+        installment_slots: u64,
+    },
+
+    /// Pay the next installment owed by a payable once due, anyone can crank it. The payable
+    /// account is closed once everything is paid.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable]` Payable PDA - get by `pda::find_payable_address`
+    /// 1. `[writable]` Player's token account receiving the installments
+    /// 2. `[writable]` House vault of the player, owned by PDA (see `InitHouseVaults`)
+    /// 3. `[writable]` Roshambo config (read-only if sharded, see `with_counter_shard`)
+    /// 4. `[]` The token program
+    /// 5. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 6. `[writable]` The account that paid the payable rent, refunded on close
+    /// 7. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    PayInstallment,
+
+    /// Set the slots after an `UpdateConfig` during which no game can be opened, so bets
+    /// signed against the old limits are rejected instead of landing under the new ones. A
+    /// `cooldown_slots` of 0 disables the cooldown.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateConfigCooldown { cooldown_slots: u64 },
+
+    /// Exempt a player of the config from the fee, or revoke the exemption. Games the player
+    /// opens while exempt are settled without the fee (`Game::fee_bps` is 0) as long as the
+    /// fee exemption PDA is among the accounts opening them (see `with_fee_exemption`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for
+    ///    the fee exemption PDA
+    /// 1. `[]` Initialized Config account
+    /// 2. `[]` The player
+    /// 3. `[writable]` Fee exemption PDA
+    /// 4. `[]` The system program
+    SetFeeExempt { exempt: bool },
+
+    /// Set the slots the heartbeat of the settlement service can be behind before the config
+    /// refuses new games, so no stake is deposited while no one settles. A `max_age_slots` of
+    /// 0 doesn't require a heartbeat. While set, game opening instructions expect the
+    /// heartbeat PDA among their accounts (see `with_heartbeat`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateHeartbeatMaxAge { max_age_slots: u64 },
+
+    /// Record that the settlement service of the config is alive at the current slot
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The house, the config owner or an admin allowed to settle,
+    ///    paying for the heartbeat PDA
+    /// 1. `[]` Initialized Config account
+    /// 2. `[writable]` Heartbeat PDA
+    /// 3. `[]` The system program
+    Heartbeat,
+
+    /// Create the insurance fund of the config if needed, and move `insurance_bps` of every
+    /// stake into it. The fund is a player protection reserve kept apart from the bankroll:
+    /// it only pays out through `PayInsuranceClaim` or, timelocked, `WithdrawInsurance`. While
+    /// `insurance_bps` is set, game opening instructions expect the fund among their accounts
+    /// (see `with_insurance_fund`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for
+    ///    the fund
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[]` The mint of the config
+    /// 3. `[writable]` Insurance fund PDA
+    /// 4. `[]` The token program
+    /// 5. `[]` The system program
+    UpdateInsuranceFund { insurance_bps: u16 },
+
+    /// Schedule a withdrawal from the insurance fund, possible
+    /// `INSURANCE_WITHDRAW_DELAY_SLOTS` from now, or cancel the scheduled one
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    ScheduleInsuranceWithdraw { enabled: bool },
+
+    /// Withdraw from the insurance fund once the scheduled withdrawal is unlocked, consuming
+    /// the schedule
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[writable]` Insurance fund PDA
+    /// 3. `[writable]` The token account receiving the withdrawal
+    /// 4. `[]` The token program
+    /// 5. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 6. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    WithdrawInsurance { amount: u64 },
+
+    /// Compensate the creator of a disputed game with up to its stake from the insurance
+    /// fund, resolving the dispute with its result upheld. `ClaimDeferred` then closes it.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The arbiter of the config
+    /// 1. `[writable]` The disputed game account
+    /// 2. `[]` Roshambo config
+    /// 3. `[writable]` Insurance fund PDA
+    /// 4. `[writable]` Token account of the game creator
+    /// 5. `[]` The token program
+    /// 6. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 7. `[]` The mint of the config, anywhere in the accounts (see `with_mint`)
+    PayInsuranceClaim { amount: u64 },
+
+    /// Set the cashback tiers of the config, `(min_volume, cashback_bps)` by increasing
+    /// `min_volume`, at most `MAX_CASHBACK_TIERS` (none restores the flat rate of
+    /// `UpdateCashback`). A game settled during the promotion accrues cashback at the rate of
+    /// the highest tier its player's volume over the promotion reached.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateCashbackTiers { tiers: Vec<(u64, u16)> },
+
+    /// Create the hourly metrics PDA of `hour` (see `state::HourlyMetrics::hour`), anyone can
+    /// pay for it ahead of the games it counts
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The payer of the hourly metrics
+    /// 1. `[]` Initialized Config account
+    /// 2. `[writable]` Hourly metrics PDA of `hour`
+    /// 3. `[]` The system program
+    InitHourlyMetrics { hour: u64 },
+
+    /// Enable or disable hourly metrics. While enabled, `ClaimReward`, `ClaimAndRebet` and
+    /// `ClaimRewards` count every game they settle in the hourly metrics of the hour it was
+    /// created in, expected among their accounts (see `with_hourly_metrics`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateHourlyMetrics { enabled: bool },
+
+    /// Cap every stake at `fraction_bps` of the Kelly criterion over the house balance left
+    /// after the open games (see `kelly::kelly_max_bet`), at most `BPS_DENOMINATOR` for full
+    /// Kelly. A `fraction_bps` of 0 removes the cap.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateKellyFraction { fraction_bps: u16 },
+
+    /// Move the whole balance of the associated token account of the house PDA, holding tokens
+    /// sent to it by mistake, then close the account. The house PDA is shared by every config
+    /// of the program and no config keeps funds in that account (see
+    /// `pda::find_house_token_address`): only the program-level recovery authority can
+    /// recover them (see `SetRecoveryAuthority`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The recovery authority, receiving the rent of the closed account
+    /// 1. `[writable]` The associated token account of the house PDA holding the tokens
+    /// 2. `[writable]` The token account receiving them, of the same mint
+    /// 3. `[]` The mint of the tokens
+    /// 4. `[]` The token program
+    /// 5. `[]` The PDA account - get by PublicKey.findProgramAddress
+    /// 6. `[]` The recovery authority PDA (see `pda::find_recovery_authority_address`)
+    RecoverTokens,
+
+    /// Move the lamports sent to the house PDA by mistake, which never holds any itself. Only
+    /// the program-level recovery authority can recover them (see `SetRecoveryAuthority`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The recovery authority
+    /// 1. `[writable]` The PDA account - get by PublicKey.findProgramAddress
+    /// 2. `[writable]` The account receiving the lamports
+    /// 3. `[]` The system program
+    /// 4. `[]` The recovery authority PDA (see `pda::find_recovery_authority_address`)
+    RecoverLamports,
+
+    /// Reclaim the rent of program owned accounts funded but never initialized, left behind by
+    /// failed client flows, into the treasury of a config. Anyone can call it. Accounts don't
+    /// record when they were created: the first call stamps the slot in the last 8 bytes of
+    /// an account with no state written, a later one closes it once past the settlement age of
+    /// the config (`max_settle_age_slots`, `state::DEFAULT_SETTLE_AGE_SLOTS` without a bound).
+    /// Initializing the account meanwhile overwrites the stamp, it can't be closed anymore.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[]` Initialized Config account, with a treasury
+    /// 1. `[writable]` The treasury of the config, receiving the rent
+    /// 2. `[writable]` The abandoned accounts, any number of them
+    ReclaimRent,
+
+    /// Bound the age of the games that can be settled: `max_age_slots` after its creation a
+    /// game can't be claimed anymore, so no result is computed from seeds that sat revealed
+    /// for that long. The house refunds it with `DeclineGame`, or the player with `CancelGame`,
+    /// without cancellation fee. A `max_age_slots` of 0 only bounds the games whose host seed is fixed, by
+    /// `state::DEFAULT_SETTLE_AGE_SLOTS`, any other must exceed the settlement delay.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateMaxSettleAge { max_age_slots: u64 },
+
+    /// Start or stop following the live exposure of the house in the `Exposure` PDA of the
+    /// config. Starting creates the PDA if needed and sets it to the outstanding exposure of
+    /// the config, its count of open games starts at 0. While tracked, every instruction
+    /// opening or settling a game, or changing what the house owes, expects the PDA anywhere
+    /// in its accounts (see `with_exposure`). Not available on sharded configs, whose games
+    /// would all write-lock the PDA.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The account of the person who create the config, paying for
+    ///    the exposure PDA
+    /// 1. `[writable]` Initialized Config account
+    /// 2. `[writable]` Exposure PDA, when starting
+    /// 3. `[]` The system program, when starting
+    UpdateExposureTracking { enabled: bool },
+
+    /// Pause the config as soon as the house loses more than `max_loss` net within a window
+    /// of `window_slots`, restarting once that long, emitting `CircuitBreakerTripped`. Claims,
+    /// closed sessions and overturned disputes all count towards the loss. A
+    /// config paused by the breaker can only be unpaused by its owner. A `max_loss` of 0
+    /// removes the breaker. Not available on sharded configs, whose settlements don't write
+    /// the config.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the person who create the config
+    /// 1. `[writable]` Initialized Config account
+    UpdateCircuitBreaker { max_loss: u64, window_slots: u64 },
+
+    /// Commit the house's seed for the next game of a session (see
+    /// `outcome::session_seed_commitment`), before the player picks its public seed with
+    /// `PlaySession`. The previous session game must have been revealed.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the house (config owner)
+    /// 1. `[writable]` The session account
+    /// 2. `[]` Roshambo config of the session
+    CommitSessionSeed { host_seed_commitment: [u8; 32] },
+
+    /// Reveal the host seed committed for the pending session game, settling it against the
+    /// session balance
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the house (config owner)
+    /// 1. `[writable]` The session account
+    /// 2. `[]` Roshambo config of the session
+    RevealSessionSeed { host_seed: u64 },
+
+    /// Hand the recovery instructions over to a new program-level authority, kept in the
+    /// recovery authority PDA from then on. Signed by the current authority, the
+    /// `recovery_authority` the program is built with until the first rotation, and by the
+    /// new one so a mistyped key can't lock recoveries out.
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[writable, signer]` The current recovery authority, paying for the PDA on the first
+    ///    rotation
+    /// 1. `[writable]` The recovery authority PDA (see `pda::find_recovery_authority_address`)
+    /// 2. `[signer]` The new recovery authority
+    /// 3. `[]` The system program
+    SetRecoveryAuthority,
+
+    /// Create a new game like `NewGame`, rejected once `expiry_slot` has passed if set so a
+    /// transaction landing late can't open a game its player gave up on. First v2 instruction
+    /// (tag `V2_TAG_START`), its payload holds the `FIELD_*` fields. `frontend_id` attributes
+    /// the game to the frontend that opened it, it is stored in the game and logged with its
+    /// settlement (0 for none, see `with_frontend_id`).
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0-8. Same as `NewGame`
+    NewGameV2 {
+        amount: u64,
+        public_seed_commitment: [u8; 32],
+        seed_slot: u64,
+        expiry_slot: Option<u64>,
+        frontend_id: u16,
+    },
+}
+
+impl RoshamboInstruction {
+    /// Unpacks a byte buffer into a [RoshamboInstruction](enum.RoshamboInstruction.html).
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (tag, rest) = input.split_first().ok_or(InvalidInstruction)?;
+        if *tag >= V2_TAG_START {
+            return Self::unpack_v2(*tag, rest);
+        }
+
+        Ok(match tag {
+            0 => {
+                let (min_bet_amount, max_bet_amount) = Self::unpack_config(rest)?;
+                Self::Initialize {
+                    min_bet_amount,
+                    max_bet_amount,
+                }
+            }
+            1 => {
+                let (amount, public_seed_commitment, seed_slot) = Self::unpack_new_game(rest)?;
+                Self::NewGame {
+                    amount,
+                    public_seed_commitment,
+                    seed_slot,
+                }
+            }
+            2 => {
+                let (host_seed, public_seed) = Self::unpack_claim_reward(rest)?;
+                Self::ClaimReward {
+                    host_seed,
+                    public_seed,
+                }
+            }
+            3 => {
+                let (min_bet_amount, max_bet_amount) = Self::unpack_config(rest)?;
+                Self::UpdateConfig {
+                    min_bet_amount,
+                    max_bet_amount,
+                }
+            }
+            4 => Self::Withdraw {
+                amount: Self::unpack_amount(rest)?,
+                emergency: match rest.get(8) {
+                    None | Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            5 => Self::OpenSession {
+                deposit: Self::unpack_amount(rest)?,
+            },
+            // 6 was the plain text `PlaySession`, retired for the committed flow below
+            7 => Self::CloseSession,
+            8 => {
+                let (host_seed, public_seed) = Self::unpack_claim_reward(rest)?;
+                let next_public_seed_commitment = Self::unpack_commitment(rest.get(16..))?;
+                Self::ClaimAndRebet {
+                    host_seed,
+                    public_seed,
+                    next_public_seed_commitment,
+                    next_seed_slot: Self::unpack_amount(rest.get(48..).unwrap_or_default())?,
+                }
+            }
+            9 => Self::DeclineGame,
+            10 => {
+                let (max_games_per_window, window_slots) = Self::unpack_config(rest)?;
+                Self::UpdateRateLimit {
+                    max_games_per_window,
+                    window_slots,
+                }
+            }
+            11 => Self::UpdateSettleDelay {
+                min_settle_delay_slots: Self::unpack_amount(rest)?,
+            },
+            12 => {
+                let (fee_bps, payout_multiplier_bps) = Self::unpack_fees(rest)?;
+                Self::UpdateFees {
+                    fee_bps,
+                    payout_multiplier_bps,
+                }
+            }
+            13 => Self::CommitHostSeed {
+                host_seed_commitment: Self::unpack_commitment(Some(rest))?,
+            },
+            14 => Self::RevealSeed {
+                public_seed: Self::unpack_amount(rest)?,
+            },
+            15 => {
+                let (amount, public_seed_commitment, seed_slot) = Self::unpack_new_game(rest)?;
+                Self::NewGameDelegated {
+                    amount,
+                    public_seed_commitment,
+                    seed_slot,
+                }
+            }
+            16 => {
+                let (expiry_slot, max_wager) = Self::unpack_config(rest)?;
+                Self::RegisterSessionKey {
+                    expiry_slot,
+                    max_wager,
+                }
+            }
+            17 => Self::RevokeSessionKey,
+            18 => Self::UpdateSettlementMemo {
+                enabled: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            19 => Self::SnapshotStats,
+            20 => {
+                let (max_depth, max_buffer_size) = Self::unpack_tree_config(rest)?;
+                Self::InitSettlementTree {
+                    max_depth,
+                    max_buffer_size,
+                }
+            }
+            21 => Self::SetCounterShards {
+                shards: *rest.first().ok_or(InvalidInstruction)?,
+            },
+            22 => Self::InitHouseVaults {
+                shards: *rest.first().ok_or(InvalidInstruction)?,
+            },
+            23 => Self::UpdateGamePool {
+                enabled: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            24 => Self::UpdateWithdrawBond {
+                bond: Self::unpack_amount(rest)?,
+            },
+            25 => Self::ScheduleEmergencyWithdraw {
+                enabled: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            26 => Self::InitStreakLeaderboard,
+            27 => Self::EndSeason,
+            28 => Self::StartSeason,
+            29 => Self::Airdrop {
+                amounts: Self::unpack_amounts(rest)?,
+            },
+            30 => Self::CommitDailySeed {
+                day: Self::unpack_amount(rest)?,
+                commitment: Self::unpack_commitment(rest.get(8..))?,
+            },
+            31 => Self::RevealDailySeed {
+                day: Self::unpack_amount(rest)?,
+                secret: Self::unpack_commitment(rest.get(8..))?,
+            },
+            32 => Self::UpdateDailySeed {
+                enabled: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            33 => Self::ClaimDeferred,
+            34 => Self::UpdatePause {
+                paused: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            35 => Self::SetAdmin {
+                permissions: *rest.first().ok_or(InvalidInstruction)?,
+            },
+            36 => Self::InitAuditLog,
+            37 => Self::UpdateDisputeWindow {
+                window_slots: Self::unpack_amount(rest)?,
+            },
+            38 => Self::Dispute,
+            39 => Self::ResolveDispute {
+                uphold: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            40 => {
+                let (threshold, delay_slots) = Self::unpack_config(rest)?;
+                Self::UpdateLargeWinHold {
+                    threshold,
+                    delay_slots,
+                }
+            }
+            41 => Self::CancelGame,
+            42 => Self::UpdateCancelFee {
+                fee_bps: rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?,
+            },
+            43 => Self::UpdateMinStake {
+                min_stake: Self::unpack_amount(rest)?,
+            },
+            44 => Self::UpdateBurn {
+                burn_bps: rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?,
+            },
+            45 => {
+                let (start_slot, end_slot) =
+                    Self::unpack_config(rest.get(2..).unwrap_or_default())?;
+                Self::UpdateCashback {
+                    cashback_bps: rest
+                        .get(..2)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(u16::from_le_bytes)
+                        .ok_or(InvalidInstruction)?,
+                    start_slot,
+                    end_slot,
+                }
+            }
+            46 => Self::ClaimCashback,
+            47 => {
+                let (target, reward) = Self::unpack_config(rest.get(3..).unwrap_or_default())?;
+                Self::SetQuest {
+                    quest_id: *rest.first().ok_or(InvalidInstruction)?,
+                    kind: *rest.get(1).ok_or(InvalidInstruction)?,
+                    period: *rest.get(2).ok_or(InvalidInstruction)?,
+                    target,
+                    reward,
+                }
+            }
+            48 => Self::ClaimQuestReward,
+            49 => Self::RegisterPartner,
+            50 => Self::UpdatePartnerShare {
+                share_bps: rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?,
+            },
+            51 => {
+                let (count, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                let seeds = (0..*count as usize)
+                    .map(|index| {
+                        Self::unpack_claim_reward(rest.get(index * 16..).unwrap_or_default())
+                    })
+                    .collect::<Result<_, _>>()?;
+                Self::ClaimRewards { seeds }
+            }
+            52 => Self::UpdateSwapProgram,
+            53 => {
+                let (amount_in, min_amount_out) = Self::unpack_config(rest)?;
+                Self::Rebalance {
+                    amount_in,
+                    min_amount_out,
+                    swap_data: rest[16..].to_vec(),
+                }
+            }
+            54 => Self::UpdateTreasury,
+            55 => {
+                let (amount_in, min_amount_out) = Self::unpack_config(rest)?;
+                Self::SweepProfits {
+                    amount_in,
+                    min_amount_out,
+                    swap_data: rest[16..].to_vec(),
+                }
+            }
+            56 => {
+                let (threshold, vesting_slots) = Self::unpack_config(rest)?;
+                Self::UpdateVesting {
+                    threshold,
+                    vesting_slots,
+                }
+            }
+            57 => Self::ReleaseVested,
+            58 => {
+                let (max_payout, installment_slots) = Self::unpack_config(rest)?;
+                Self::UpdatePayoutCap {
+                    max_payout,
+                    installment_slots,
+                }
+            }
+            59 => Self::PayInstallment,
+            60 => Self::UpdateConfigCooldown {
+                cooldown_slots: Self::unpack_amount(rest)?,
+            },
+            61 => Self::SetFeeExempt {
+                exempt: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            62 => Self::UpdateHeartbeatMaxAge {
+                max_age_slots: Self::unpack_amount(rest)?,
+            },
+            63 => Self::Heartbeat,
+            64 => Self::UpdateInsuranceFund {
+                insurance_bps: rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?,
+            },
+            65 => Self::ScheduleInsuranceWithdraw {
+                enabled: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            66 => Self::WithdrawInsurance {
+                amount: Self::unpack_amount(rest)?,
+            },
+            67 => Self::PayInsuranceClaim {
+                amount: Self::unpack_amount(rest)?,
+            },
+            68 => {
+                let (count, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                let tiers = (0..*count as usize)
+                    .map(|index| {
+                        Self::unpack_cashback_tier(rest.get(index * 10..).unwrap_or_default())
+                    })
+                    .collect::<Result<_, _>>()?;
+                Self::UpdateCashbackTiers { tiers }
+            }
+            69 => Self::InitHourlyMetrics {
+                hour: Self::unpack_amount(rest)?,
+            },
+            70 => Self::UpdateHourlyMetrics {
+                enabled: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            71 => Self::UpdateKellyFraction {
+                fraction_bps: rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?,
+            },
+            72 => Self::RecoverTokens,
+            73 => Self::RecoverLamports,
+            74 => Self::ReclaimRent,
+            75 => Self::UpdateMaxSettleAge {
+                max_age_slots: Self::unpack_amount(rest)?,
+            },
+            76 => Self::UpdateExposureTracking {
+                enabled: match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                },
+            },
+            77 => {
+                let (max_loss, window_slots) = Self::unpack_config(rest)?;
+                Self::UpdateCircuitBreaker {
+                    max_loss,
+                    window_slots,
+                }
+            }
+            78 => Self::CommitSessionSeed {
+                host_seed_commitment: Self::unpack_commitment(Some(rest))?,
+            },
+            79 => {
+                let (amount, public_seed) = Self::unpack_config(rest)?;
+                Self::PlaySession {
+                    amount,
+                    public_seed,
+                }
+            }
+            80 => Self::RevealSessionSeed {
+                host_seed: Self::unpack_amount(rest)?,
+            },
+            81 => Self::SetRecoveryAuthority,
+            _ => return Err(InvalidInstruction.into()),
+        })
+    }
+
+    /// Unpacks the `tlv` fields of the v2 instruction `tag`
+    fn unpack_v2(tag: u8, rest: &[u8]) -> Result<Self, ProgramError> {
+        let fields = Fields::read(rest)?;
+        Ok(match tag {
+            V2_TAG_START => {
+                let fields = fields.only(&[
+                    FIELD_AMOUNT,
+                    FIELD_PUBLIC_SEED_COMMITMENT,
+                    FIELD_SEED_SLOT,
+                    FIELD_EXPIRY_SLOT,
+                    FIELD_FRONTEND_ID,
+                ])?;
+                Self::NewGameV2 {
+                    amount: u64::from_le_bytes(fields.required(FIELD_AMOUNT)?),
+                    public_seed_commitment: fields.required(FIELD_PUBLIC_SEED_COMMITMENT)?,
+                    seed_slot: u64::from_le_bytes(fields.required(FIELD_SEED_SLOT)?),
+                    expiry_slot: fields.optional(FIELD_EXPIRY_SLOT)?.map(u64::from_le_bytes),
+                    frontend_id: fields
+                        .optional(FIELD_FRONTEND_ID)?
+                        .map_or(0, u16::from_le_bytes),
+                }
+            }
+            _ => return Err(InvalidInstruction.into()),
+        })
+    }
+
+    /// Packs a [RoshamboInstruction](enum.RoshamboInstruction.html) into a byte buffer.
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::Initialize {
+                min_bet_amount,
+                max_bet_amount,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&min_bet_amount.to_le_bytes());
+                buf.extend_from_slice(&max_bet_amount.to_le_bytes());
+            }
+            Self::NewGame {
+                amount,
+                public_seed_commitment,
+                seed_slot,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(public_seed_commitment);
+                buf.extend_from_slice(&seed_slot.to_le_bytes());
+            }
+            Self::ClaimReward {
+                host_seed,
+                public_seed,
+            } => {
+                buf.push(2);
+                buf.extend_from_slice(&host_seed.to_le_bytes());
+                buf.extend_from_slice(&public_seed.to_le_bytes());
+            }
+            Self::UpdateConfig {
+                min_bet_amount,
+                max_bet_amount,
+            } => {
+                buf.push(3);
+                buf.extend_from_slice(&min_bet_amount.to_le_bytes());
+                buf.extend_from_slice(&max_bet_amount.to_le_bytes());
+            }
+            Self::Withdraw { amount, emergency } => {
+                buf.push(4);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                if *emergency {
+                    buf.push(1);
+                }
+            }
+            Self::OpenSession { deposit } => {
+                buf.push(5);
+                buf.extend_from_slice(&deposit.to_le_bytes());
+            }
+            Self::PlaySession {
+                amount,
+                public_seed,
+            } => {
+                buf.push(79);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&public_seed.to_le_bytes());
+            }
+            Self::CloseSession => buf.push(7),
+            Self::ClaimAndRebet {
+                host_seed,
+                public_seed,
+                next_public_seed_commitment,
+                next_seed_slot,
+            } => {
+                buf.push(8);
+                buf.extend_from_slice(&host_seed.to_le_bytes());
+                buf.extend_from_slice(&public_seed.to_le_bytes());
+                buf.extend_from_slice(next_public_seed_commitment);
+                buf.extend_from_slice(&next_seed_slot.to_le_bytes());
+            }
+            Self::DeclineGame => buf.push(9),
+            Self::UpdateRateLimit {
+                max_games_per_window,
+                window_slots,
+            } => {
+                buf.push(10);
+                buf.extend_from_slice(&max_games_per_window.to_le_bytes());
+                buf.extend_from_slice(&window_slots.to_le_bytes());
+            }
+            Self::UpdateSettleDelay {
+                min_settle_delay_slots,
+            } => {
+                buf.push(11);
+                buf.extend_from_slice(&min_settle_delay_slots.to_le_bytes());
+            }
+            Self::UpdateFees {
+                fee_bps,
+                payout_multiplier_bps,
+            } => {
+                buf.push(12);
+                buf.extend_from_slice(&fee_bps.to_le_bytes());
+                buf.extend_from_slice(&payout_multiplier_bps.to_le_bytes());
+            }
+            Self::CommitHostSeed {
+                host_seed_commitment,
+            } => {
+                buf.push(13);
+                buf.extend_from_slice(host_seed_commitment);
+            }
+            Self::RevealSeed { public_seed } => {
+                buf.push(14);
+                buf.extend_from_slice(&public_seed.to_le_bytes());
+            }
+            Self::NewGameDelegated {
+                amount,
+                public_seed_commitment,
+                seed_slot,
+            } => {
+                buf.push(15);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(public_seed_commitment);
+                buf.extend_from_slice(&seed_slot.to_le_bytes());
+            }
+            Self::RegisterSessionKey {
+                expiry_slot,
+                max_wager,
+            } => {
+                buf.push(16);
+                buf.extend_from_slice(&expiry_slot.to_le_bytes());
+                buf.extend_from_slice(&max_wager.to_le_bytes());
+            }
+            Self::RevokeSessionKey => buf.push(17),
+            Self::UpdateSettlementMemo { enabled } => {
+                buf.push(18);
+                buf.push(*enabled as u8);
+            }
+            Self::SnapshotStats => buf.push(19),
+            Self::InitSettlementTree {
+                max_depth,
+                max_buffer_size,
+            } => {
+                buf.push(20);
+                buf.extend_from_slice(&max_depth.to_le_bytes());
+                buf.extend_from_slice(&max_buffer_size.to_le_bytes());
+            }
+            Self::SetCounterShards { shards } => {
+                buf.push(21);
+                buf.push(*shards);
+            }
+            Self::InitHouseVaults { shards } => {
+                buf.push(22);
+                buf.push(*shards);
+            }
+            Self::UpdateGamePool { enabled } => {
+                buf.push(23);
+                buf.push(*enabled as u8);
+            }
+            Self::UpdateWithdrawBond { bond } => {
+                buf.push(24);
+                buf.extend_from_slice(&bond.to_le_bytes());
+            }
+            Self::ScheduleEmergencyWithdraw { enabled } => {
+                buf.push(25);
+                buf.push(*enabled as u8);
+            }
+            Self::InitStreakLeaderboard => buf.push(26),
+            Self::EndSeason => buf.push(27),
+            Self::StartSeason => buf.push(28),
+            Self::Airdrop { amounts } => {
+                buf.push(29);
+                buf.push(amounts.len() as u8);
+                for amount in amounts {
+                    buf.extend_from_slice(&amount.to_le_bytes());
+                }
+            }
+            Self::CommitDailySeed { day, commitment } => {
+                buf.push(30);
+                buf.extend_from_slice(&day.to_le_bytes());
+                buf.extend_from_slice(commitment);
+            }
+            Self::RevealDailySeed { day, secret } => {
+                buf.push(31);
+                buf.extend_from_slice(&day.to_le_bytes());
+                buf.extend_from_slice(secret);
+            }
+            Self::UpdateDailySeed { enabled } => {
+                buf.push(32);
+                buf.push(*enabled as u8);
+            }
+            Self::ClaimDeferred => buf.push(33),
+            Self::UpdatePause { paused } => {
+                buf.push(34);
+                buf.push(*paused as u8);
+            }
+            Self::SetAdmin { permissions } => {
+                buf.push(35);
+                buf.push(*permissions);
+            }
+            Self::InitAuditLog => buf.push(36),
+            Self::UpdateDisputeWindow { window_slots } => {
+                buf.push(37);
+                buf.extend_from_slice(&window_slots.to_le_bytes());
+            }
+            Self::Dispute => buf.push(38),
+            Self::ResolveDispute { uphold } => {
+                buf.push(39);
+                buf.push(*uphold as u8);
+            }
+            Self::UpdateLargeWinHold {
+                threshold,
+                delay_slots,
+            } => {
+                buf.push(40);
+                buf.extend_from_slice(&threshold.to_le_bytes());
+                buf.extend_from_slice(&delay_slots.to_le_bytes());
+            }
+            Self::CancelGame => buf.push(41),
+            Self::UpdateCancelFee { fee_bps } => {
+                buf.push(42);
+                buf.extend_from_slice(&fee_bps.to_le_bytes());
+            }
+            Self::UpdateMinStake { min_stake } => {
+                buf.push(43);
+                buf.extend_from_slice(&min_stake.to_le_bytes());
+            }
+            Self::UpdateBurn { burn_bps } => {
+                buf.push(44);
+                buf.extend_from_slice(&burn_bps.to_le_bytes());
+            }
+            Self::UpdateCashback {
+                cashback_bps,
+                start_slot,
+                end_slot,
+            } => {
+                buf.push(45);
+                buf.extend_from_slice(&cashback_bps.to_le_bytes());
+                buf.extend_from_slice(&start_slot.to_le_bytes());
+                buf.extend_from_slice(&end_slot.to_le_bytes());
+            }
+            Self::ClaimCashback => buf.push(46),
+            Self::SetQuest {
+                quest_id,
+                kind,
+                period,
+                target,
+                reward,
+            } => {
+                buf.push(47);
+                buf.push(*quest_id);
+                buf.push(*kind);
+                buf.push(*period);
+                buf.extend_from_slice(&target.to_le_bytes());
+                buf.extend_from_slice(&reward.to_le_bytes());
+            }
+            Self::ClaimQuestReward => buf.push(48),
+            Self::RegisterPartner => buf.push(49),
+            Self::UpdatePartnerShare { share_bps } => {
+                buf.push(50);
+                buf.extend_from_slice(&share_bps.to_le_bytes());
+            }
+            Self::ClaimRewards { seeds } => {
+                buf.push(51);
+                buf.push(seeds.len() as u8);
+                for (host_seed, public_seed) in seeds {
+                    buf.extend_from_slice(&host_seed.to_le_bytes());
+                    buf.extend_from_slice(&public_seed.to_le_bytes());
+                }
+            }
+            Self::UpdateSwapProgram => buf.push(52),
+            Self::Rebalance {
+                amount_in,
+                min_amount_out,
+                swap_data,
+            } => {
+                buf.push(53);
+                buf.extend_from_slice(&amount_in.to_le_bytes());
+                buf.extend_from_slice(&min_amount_out.to_le_bytes());
+                buf.extend_from_slice(swap_data);
+            }
+            Self::UpdateTreasury => buf.push(54),
+            Self::SweepProfits {
+                amount_in,
+                min_amount_out,
+                swap_data,
+            } => {
+                buf.push(55);
+                buf.extend_from_slice(&amount_in.to_le_bytes());
+                buf.extend_from_slice(&min_amount_out.to_le_bytes());
+                buf.extend_from_slice(swap_data);
+            }
+            Self::UpdateVesting {
+                threshold,
+                vesting_slots,
+            } => {
+                buf.push(56);
+                buf.extend_from_slice(&threshold.to_le_bytes());
+                buf.extend_from_slice(&vesting_slots.to_le_bytes());
+            }
+            Self::ReleaseVested => buf.push(57),
+            Self::UpdatePayoutCap {
+                max_payout,
+                installment_slots,
+            } => {
+                buf.push(58);
+                buf.extend_from_slice(&max_payout.to_le_bytes());
+                buf.extend_from_slice(&installment_slots.to_le_bytes());
+            }
+            Self::PayInstallment => buf.push(59),
+            Self::UpdateConfigCooldown { cooldown_slots } => {
+                buf.push(60);
+                buf.extend_from_slice(&cooldown_slots.to_le_bytes());
+            }
+            Self::SetFeeExempt { exempt } => {
+                buf.push(61);
+                buf.push(*exempt as u8);
+            }
+            Self::UpdateHeartbeatMaxAge { max_age_slots } => {
+                buf.push(62);
+                buf.extend_from_slice(&max_age_slots.to_le_bytes());
+            }
+            Self::Heartbeat => buf.push(63),
+            Self::UpdateInsuranceFund { insurance_bps } => {
+                buf.push(64);
+                buf.extend_from_slice(&insurance_bps.to_le_bytes());
+            }
+            Self::ScheduleInsuranceWithdraw { enabled } => {
+                buf.push(65);
+                buf.push(*enabled as u8);
+            }
+            Self::WithdrawInsurance { amount } => {
+                buf.push(66);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::PayInsuranceClaim { amount } => {
+                buf.push(67);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::UpdateCashbackTiers { tiers } => {
+                buf.push(68);
+                buf.push(tiers.len() as u8);
+                for (min_volume, cashback_bps) in tiers {
+                    buf.extend_from_slice(&min_volume.to_le_bytes());
+                    buf.extend_from_slice(&cashback_bps.to_le_bytes());
+                }
+            }
+            Self::InitHourlyMetrics { hour } => {
+                buf.push(69);
+                buf.extend_from_slice(&hour.to_le_bytes());
+            }
+            Self::UpdateHourlyMetrics { enabled } => {
+                buf.push(70);
+                buf.push(*enabled as u8);
+            }
+            Self::UpdateKellyFraction { fraction_bps } => {
+                buf.push(71);
+                buf.extend_from_slice(&fraction_bps.to_le_bytes());
+            }
+            Self::RecoverTokens => buf.push(72),
+            Self::RecoverLamports => buf.push(73),
+            Self::ReclaimRent => buf.push(74),
+            Self::UpdateMaxSettleAge { max_age_slots } => {
+                buf.push(75);
+                buf.extend_from_slice(&max_age_slots.to_le_bytes());
+            }
+            Self::UpdateExposureTracking { enabled } => {
+                buf.push(76);
+                buf.push(*enabled as u8);
+            }
+            Self::UpdateCircuitBreaker {
+                max_loss,
+                window_slots,
+            } => {
+                buf.push(77);
+                buf.extend_from_slice(&max_loss.to_le_bytes());
+                buf.extend_from_slice(&window_slots.to_le_bytes());
+            }
+            Self::CommitSessionSeed {
+                host_seed_commitment,
+            } => {
+                buf.push(78);
+                buf.extend_from_slice(host_seed_commitment);
+            }
+            Self::RevealSessionSeed { host_seed } => {
+                buf.push(80);
+                buf.extend_from_slice(&host_seed.to_le_bytes());
+            }
+            Self::SetRecoveryAuthority => buf.push(81),
+            Self::NewGameV2 {
+                amount,
+                public_seed_commitment,
+                seed_slot,
+                expiry_slot,
+                frontend_id,
+            } => {
+                buf.push(V2_TAG_START);
+                write_field(&mut buf, FIELD_AMOUNT, &amount.to_le_bytes());
+                write_field(
+                    &mut buf,
+                    FIELD_PUBLIC_SEED_COMMITMENT,
+                    public_seed_commitment,
+                );
+                write_field(&mut buf, FIELD_SEED_SLOT, &seed_slot.to_le_bytes());
+                if let Some(expiry_slot) = expiry_slot {
+                    write_field(&mut buf, FIELD_EXPIRY_SLOT, &expiry_slot.to_le_bytes());
+                }
+                if *frontend_id != 0 {
+                    write_field(&mut buf, FIELD_FRONTEND_ID, &frontend_id.to_le_bytes());
+                }
+            }
+        }
+        buf
+    }
+
+    fn unpack_config(input: &[u8]) -> Result<(u64, u64), ProgramError> {
+        let min_bet_amount = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        let max_bet_amount = input
+            .get(8..16)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        Ok((min_bet_amount, max_bet_amount))
+    }
+
+    fn unpack_amount(input: &[u8]) -> Result<u64, ProgramError> {
+        let bet_amount = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(bet_amount)
+    }
+
+    /// A count byte followed by that many amounts
+    fn unpack_amounts(input: &[u8]) -> Result<Vec<u64>, ProgramError> {
+        let (count, rest) = input.split_first().ok_or(InvalidInstruction)?;
+        let amounts = rest
+            .chunks_exact(8)
+            .take(*count as usize)
+            .map(Self::unpack_amount)
+            .collect::<Result<Vec<_>, _>>()?;
+        if amounts.len() != *count as usize {
+            return Err(InvalidInstruction.into());
+        }
+        Ok(amounts)
+    }
+
+    fn unpack_new_game(input: &[u8]) -> Result<(u64, [u8; 32], u64), ProgramError> {
+        let amount = Self::unpack_amount(input)?;
+        let public_seed_commitment = Self::unpack_commitment(input.get(8..))?;
+        let seed_slot = Self::unpack_amount(input.get(40..).unwrap_or_default())?;
+        Ok((amount, public_seed_commitment, seed_slot))
+    }
+
+    fn unpack_commitment(input: Option<&[u8]>) -> Result<[u8; 32], ProgramError> {
+        let commitment = input
+            .and_then(|input| input.get(..32))
+            .and_then(|slice| slice.try_into().ok())
+            .ok_or(InvalidInstruction)?;
+        Ok(commitment)
+    }
+
+    fn unpack_claim_reward(input: &[u8]) -> Result<(u64, u64), ProgramError> {
+        let host_seed = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        let public_seed = input
+            .get(8..16)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        Ok((host_seed, public_seed))
+    }
+
+    fn unpack_cashback_tier(input: &[u8]) -> Result<(u64, u16), ProgramError> {
+        let min_volume = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        let cashback_bps = input
+            .get(8..10)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        Ok((min_volume, cashback_bps))
+    }
+
+    fn unpack_tree_config(input: &[u8]) -> Result<(u32, u32), ProgramError> {
+        let max_depth = input
+            .get(..4)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        let max_buffer_size = input
+            .get(4..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        Ok((max_depth, max_buffer_size))
+    }
+
+    fn unpack_fees(input: &[u8]) -> Result<(u16, u16), ProgramError> {
+        let fee_bps = input
+            .get(..2)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        let payout_multiplier_bps = input
+            .get(2..4)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+
+        Ok((fee_bps, payout_multiplier_bps))
+    }
+}
+
+/// Creates an `Initialize` instruction
+pub fn initialize(
+    program_id: &Pubkey,
+    config_creator: &Pubkey,
+    config_account: &Pubkey,
+    mint: &Pubkey,
+    min_bet_amount: u64,
+    max_bet_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_creator, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(*mint, false),
+        ],
+        data: RoshamboInstruction::Initialize {
+            min_bet_amount,
+            max_bet_amount,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `NewGame` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn new_game(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    creator_token_account: &Pubkey,
+    game_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    player_stats_account: &Pubkey,
+    amount: u64,
+    public_seed_commitment: [u8; 32],
+    seed_slot: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*game_creator, true),
+            AccountMeta::new(*creator_token_account, false),
+            AccountMeta::new(*game_account, true),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(*player_stats_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::NewGame {
+            amount,
+            public_seed_commitment,
+            seed_slot,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `ClaimReward` instruction settled by the house alone, for a game without a host
+/// seed commitment the game creator (`accounts[0]`) must be marked as a signer too
+#[allow(clippy::too_many_arguments)]
+pub fn claim_reward(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    house: &Pubkey,
+    game_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+    host_seed: u64,
+    public_seed: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*game_creator, false),
+            AccountMeta::new_readonly(*house, true),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new(*receiver_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::ClaimReward {
+            host_seed,
+            public_seed,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CommitHostSeed` instruction
+pub fn commit_host_seed(
+    program_id: &Pubkey,
+    house: &Pubkey,
+    game_account: &Pubkey,
+    config_account: &Pubkey,
+    host_seed_commitment: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*house, true),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+        ],
+        data: RoshamboInstruction::CommitHostSeed {
+            host_seed_commitment,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `RevealSeed` instruction
+pub fn reveal_seed(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    game_account: &Pubkey,
+    public_seed: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*game_creator, true),
+            AccountMeta::new(*game_account, false),
+        ],
+        data: RoshamboInstruction::RevealSeed { public_seed }.pack(),
+    }
+}
+
+/// Creates an `UpdateConfig` instruction
+pub fn update_config(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    min_bet_amount: u64,
+    max_bet_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateConfig {
+            min_bet_amount,
+            max_bet_amount,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `Withdraw` instruction
+pub fn withdraw(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    house_token_account: &Pubkey,
+    house_authority: &Pubkey,
+    destination_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+            AccountMeta::new(*destination_token_account, false),
+        ],
+        data: RoshamboInstruction::Withdraw {
+            amount,
+            emergency: false,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an emergency `Withdraw` instruction, see `ScheduleEmergencyWithdraw`
+pub fn emergency_withdraw(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    house_token_account: &Pubkey,
+    house_authority: &Pubkey,
+    destination_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+            AccountMeta::new(*destination_token_account, false),
+        ],
+        data: RoshamboInstruction::Withdraw {
+            amount,
+            emergency: true,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `OpenSession` instruction
+pub fn open_session(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    player_token_account: &Pubkey,
+    session_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    deposit: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*player, true),
+            AccountMeta::new(*player_token_account, false),
+            AccountMeta::new(*session_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: RoshamboInstruction::OpenSession { deposit }.pack(),
+    }
+}
+
+/// Creates a `PlaySession` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn play_session(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    session_account: &Pubkey,
+    config_account: &Pubkey,
+    house_token_account: &Pubkey,
+    player_stats_account: &Pubkey,
+    amount: u64,
+    public_seed: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(*session_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new_readonly(*house_token_account, false),
+            AccountMeta::new(*player_stats_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::PlaySession {
+            amount,
+            public_seed,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CommitSessionSeed` instruction
+pub fn commit_session_seed(
+    program_id: &Pubkey,
+    house: &Pubkey,
+    session_account: &Pubkey,
+    config_account: &Pubkey,
+    host_seed_commitment: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*house, true),
+            AccountMeta::new(*session_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+        ],
+        data: RoshamboInstruction::CommitSessionSeed {
+            host_seed_commitment,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `RevealSessionSeed` instruction
+pub fn reveal_session_seed(
+    program_id: &Pubkey,
+    house: &Pubkey,
+    session_account: &Pubkey,
+    config_account: &Pubkey,
+    host_seed: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*house, true),
+            AccountMeta::new(*session_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+        ],
+        data: RoshamboInstruction::RevealSessionSeed { host_seed }.pack(),
+    }
+}
+
+/// Creates a `CloseSession` instruction
+pub fn close_session(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    session_account: &Pubkey,
+    player_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(*session_account, false),
+            AccountMeta::new(*player_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::CloseSession.pack(),
+    }
+}
+
+/// Creates a `ClaimAndRebet` instruction, signed by both the game creator and the house
+#[allow(clippy::too_many_arguments)]
+pub fn claim_and_rebet(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    house: &Pubkey,
+    game_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+    next_game_account: &Pubkey,
+    player_stats_account: &Pubkey,
+    host_seed: u64,
+    public_seed: u64,
+    next_public_seed_commitment: [u8; 32],
+    next_seed_slot: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*game_creator, true),
+            AccountMeta::new_readonly(*house, true),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new(*receiver_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+            AccountMeta::new(*next_game_account, true),
+            AccountMeta::new(*player_stats_account, false),
+        ],
+        data: RoshamboInstruction::ClaimAndRebet {
+            host_seed,
+            public_seed,
+            next_public_seed_commitment,
+            next_seed_slot,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `DeclineGame` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn decline_game(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    game_account: &Pubkey,
+    game_creator: &Pubkey,
+    creator_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new(*game_creator, false),
+            AccountMeta::new(*creator_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::DeclineGame.pack(),
+    }
+}
+
+/// Creates an `UpdateRateLimit` instruction
+pub fn update_rate_limit(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    max_games_per_window: u64,
+    window_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateRateLimit {
+            max_games_per_window,
+            window_slots,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `UpdateSettleDelay` instruction
+pub fn update_settle_delay(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    min_settle_delay_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateSettleDelay {
+            min_settle_delay_slots,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `UpdateFees` instruction
+pub fn update_fees(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    fee_bps: u16,
+    payout_multiplier_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateFees {
+            fee_bps,
+            payout_multiplier_bps,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `NewGameDelegated` instruction, the house authority must have been approved as
+/// delegate of `creator_token_account` (see `spl_token::instruction::approve`)
+#[allow(clippy::too_many_arguments)]
+pub fn new_game_delegated(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    creator_token_account: &Pubkey,
+    game_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    player_stats_account: &Pubkey,
+    house_authority: &Pubkey,
+    amount: u64,
+    public_seed_commitment: [u8; 32],
+    seed_slot: u64,
+) -> Instruction {
+    let mut instruction = new_game(
+        program_id,
+        game_creator,
+        creator_token_account,
+        game_account,
+        house_token_account,
+        config_account,
+        player_stats_account,
+        amount,
+        public_seed_commitment,
+        seed_slot,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*house_authority, false));
+    instruction.data = RoshamboInstruction::NewGameDelegated {
+        amount,
+        public_seed_commitment,
+        seed_slot,
+    }
+    .pack();
+    instruction
+}
+
+/// Creates a `RegisterSessionKey` instruction
+pub fn register_session_key(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    session_key: &Pubkey,
+    session_authority: &Pubkey,
+    expiry_slot: u64,
+    max_wager: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new_readonly(*session_key, false),
+            AccountMeta::new(*session_authority, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::RegisterSessionKey {
+            expiry_slot,
+            max_wager,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `RevokeSessionKey` instruction
+pub fn revoke_session_key(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    session_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(*session_authority, false),
+        ],
+        data: RoshamboInstruction::RevokeSessionKey.pack(),
+    }
+}
+
+/// Turns a `NewGameDelegated`, `RevealSeed` or `ClaimReward` instruction into one signed by a
+/// session key of the player instead of the player itself. The session key pays for the
+/// player stats account if it doesn't exist yet.
+pub fn with_session_key(
+    mut instruction: Instruction,
+    session_key: &Pubkey,
+    session_authority: &Pubkey,
+) -> Instruction {
+    instruction.accounts[0].is_signer = false;
+    instruction
+        .accounts
+        .push(AccountMeta::new(*session_key, true));
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*session_authority, false));
+    instruction
+}
+
+/// Turns a `NewGame`, `NewGameDelegated`, `RevealSeed` or `ClaimReward` instruction of a
+/// game creator that is an SPL token multisig into one signed by `signers`, at least the
+/// multisig threshold of them. The first signer pays for the player stats account if it
+/// doesn't exist yet.
+pub fn with_multisig_signers(mut instruction: Instruction, signers: &[Pubkey]) -> Instruction {
+    instruction.accounts[0].is_signer = false;
+    instruction
+        .accounts
+        .extend(signers.iter().map(|signer| AccountMeta::new(*signer, true)));
+    instruction
+}
+
+/// Appends the creator's player stats PDA to a `ClaimReward` instruction, after
+/// `with_session_key` if the claim is signed by a session key, or to a `DeclineGame` or
+/// `CancelGame` instruction
+pub fn with_player_stats(mut instruction: Instruction, player_stats: &Pubkey) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new(*player_stats, false));
+    instruction
+}
+
+/// Creates an `UpdateSettlementMemo` instruction
+pub fn update_settlement_memo(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    enabled: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateSettlementMemo { enabled }.pack(),
+    }
+}
+
+/// Appends the SPL Memo program to a `ClaimReward` or `ClaimAndRebet` instruction, required
+/// while the config has `settlement_memo` enabled
+pub fn with_settlement_memo(mut instruction: Instruction) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(spl_memo::id(), false));
+    instruction
+}
+
+/// Creates a `SnapshotStats` instruction
+pub fn snapshot_stats(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    config_account: &Pubkey,
+    snapshot_account: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(*snapshot_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::SnapshotStats.pack(),
+    }
+}
+
+/// Creates an `InitSettlementTree` instruction
+pub fn init_settlement_tree(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    merkle_tree: &Pubkey,
+    tree_authority: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new(*merkle_tree, false),
+            AccountMeta::new_readonly(*tree_authority, false),
+            AccountMeta::new_readonly(compression::id(), false),
+            AccountMeta::new_readonly(compression::noop::id(), false),
+        ],
+        data: RoshamboInstruction::InitSettlementTree {
+            max_depth,
+            max_buffer_size,
+        }
+        .pack(),
+    }
+}
+
+/// Appends the settlement tree accounts to a `ClaimReward` or `ClaimAndRebet` instruction,
+/// required while the config has a settlement tree
+pub fn with_settlement_tree(
+    mut instruction: Instruction,
+    merkle_tree: &Pubkey,
+    tree_authority: &Pubkey,
+) -> Instruction {
+    instruction.accounts.extend([
+        AccountMeta::new(*merkle_tree, false),
+        AccountMeta::new_readonly(*tree_authority, false),
+        AccountMeta::new_readonly(compression::id(), false),
+        AccountMeta::new_readonly(compression::noop::id(), false),
+    ]);
+    instruction
+}
+
+/// Creates a `SetCounterShards` instruction
+pub fn set_counter_shards(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    new_counters: &[Pubkey],
+    shards: u8,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*config_owner, true),
+        AccountMeta::new(*config_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(
+        new_counters
+            .iter()
+            .map(|counter| AccountMeta::new(*counter, false)),
+    );
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::SetCounterShards { shards }.pack(),
+    }
+}
+
+/// Makes the config read-only in a `NewGame`, `NewGameDelegated`, `ClaimReward`, `ClaimAndRebet`,
+/// `DeclineGame`, `PlaySession` or `CloseSession` instruction and appends the player's counter
+/// shard, for sharded configs
+pub fn with_counter_shard(
+    mut instruction: Instruction,
+    config_account: &Pubkey,
+    counter: &Pubkey,
+) -> Instruction {
+    for account in instruction.accounts.iter_mut() {
+        if account.pubkey == *config_account {
+            account.is_writable = false;
+        }
+    }
+    instruction.accounts.push(AccountMeta::new(*counter, false));
+    instruction
+}
+
+/// Creates an `InitHouseVaults` instruction
+pub fn init_house_vaults(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    mint: &Pubkey,
+    new_vaults: &[Pubkey],
+    shards: u8,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*config_owner, true),
+        AccountMeta::new(*config_account, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(
+        new_vaults
+            .iter()
+            .map(|vault| AccountMeta::new(*vault, false)),
+    );
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::InitHouseVaults { shards }.pack(),
+    }
+}
+
+/// Creates an `UpdateGamePool` instruction
+pub fn update_game_pool(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    enabled: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateGamePool { enabled }.pack(),
+    }
+}
+
+/// Appends the counter PDA of every shard of a sharded config to a `SnapshotStats` or
+/// `Withdraw` instruction, read-only
+pub fn with_counters(
+    mut instruction: Instruction,
+    config_account: &Pubkey,
+    counter_shards: u8,
+) -> Instruction {
+    for shard in 0..counter_shards {
+        let (counter, _) = find_counter_address(config_account, shard, &instruction.program_id);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(counter, false));
+    }
+    instruction
+}
+
+/// Creates an `UpdateWithdrawBond` instruction
+pub fn update_withdraw_bond(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    bond: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateWithdrawBond { bond }.pack(),
+    }
+}
+
+/// Creates a `ScheduleEmergencyWithdraw` instruction
+pub fn schedule_emergency_withdraw(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    enabled: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::ScheduleEmergencyWithdraw { enabled }.pack(),
+    }
+}
+
+/// Creates an `InitStreakLeaderboard` instruction
+pub fn init_streak_leaderboard(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    leaderboard: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new(*leaderboard, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::InitStreakLeaderboard.pack(),
+    }
+}
+
+/// Appends the current season's streak leaderboard to a `ClaimReward` or `ClaimAndRebet`
+/// instruction, required while the config has `streak_leaderboard` enabled
+pub fn with_streak_leaderboard(mut instruction: Instruction, leaderboard: &Pubkey) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new(*leaderboard, false));
+    instruction
+}
+
+/// Creates an `EndSeason` instruction
+pub fn end_season(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    leaderboard: &Pubkey,
+    season_result: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(*leaderboard, false),
+            AccountMeta::new(*season_result, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::EndSeason.pack(),
+    }
+}
+
+/// Creates a `StartSeason` instruction
+pub fn start_season(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    next_leaderboard: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new(*next_leaderboard, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::StartSeason.pack(),
+    }
+}
+
+/// Creates an `Airdrop` instruction paying each `(recipient token account, amount)`, up to
+/// 255 recipients (fewer fit in a transaction)
+pub fn airdrop(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    treasury: &Pubkey,
+    recipients: &[(Pubkey, u64)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*config_owner, true),
+        AccountMeta::new_readonly(*config_account, false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(
+        recipients
+            .iter()
+            .map(|(recipient, _)| AccountMeta::new(*recipient, false)),
+    );
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::Airdrop {
+            amounts: recipients.iter().map(|(_, amount)| *amount).collect(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CommitDailySeed` instruction
+pub fn commit_daily_seed(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    day: u64,
+    commitment: [u8; 32],
+) -> Instruction {
+    let (daily_seed, _) = find_daily_seed_address(config_account, day, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(daily_seed, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::CommitDailySeed { day, commitment }.pack(),
+    }
+}
+
+/// Creates a `RevealDailySeed` instruction
+pub fn reveal_daily_seed(
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+    day: u64,
+    secret: [u8; 32],
+) -> Instruction {
+    let (daily_seed, _) = find_daily_seed_address(config_account, day, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(daily_seed, false),
+        ],
+        data: RoshamboInstruction::RevealDailySeed { day, secret }.pack(),
+    }
+}
+
+/// Creates an `UpdateDailySeed` instruction
+pub fn update_daily_seed(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    enabled: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateDailySeed { enabled }.pack(),
+    }
+}
+
+/// Appends the daily seed of the game's day to a `ClaimReward` or `ClaimAndRebet`
+/// instruction, required while the config has `daily_seed` enabled
+pub fn with_daily_seed(mut instruction: Instruction, daily_seed: &Pubkey) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*daily_seed, false));
+    instruction
+}
+
+/// Appends the mint of the config, read by every instruction moving tokens for
+/// `transfer_checked`. Append it after the session key and other positional accounts.
+pub fn with_mint(mut instruction: Instruction, mint: &Pubkey) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*mint, false));
+    instruction
+}
+
+/// Marks `game_account` of a `NewGame`, `NewGameDelegated` or `ClaimAndRebet` instruction as
+/// a game account recycled into the pool of the config (see `client::find_recycled_game`),
+/// which can't sign unlike a fresh keypair
+pub fn from_game_pool(mut instruction: Instruction, game_account: &Pubkey) -> Instruction {
+    for account in &mut instruction.accounts {
+        if account.pubkey == *game_account {
+            account.is_signer = false;
+        }
+    }
+    instruction
+}
+
+/// Creates a `ClaimDeferred` instruction
+pub fn claim_deferred(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    game_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*game_creator, false),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new(*receiver_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::ClaimDeferred.pack(),
+    }
+}
+
+/// Makes the game creator sign a `ClaimDeferred` instruction, paying the rent of the PDA
+/// opened for its released payout
+pub fn with_creator_signature(mut instruction: Instruction) -> Instruction {
+    instruction.accounts[0].is_signer = true;
+    instruction
+}
+
+/// Creates an `UpdatePause` instruction
+pub fn update_pause(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    paused: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdatePause { paused }.pack(),
+    }
+}
+
+/// Creates a `SetAdmin` instruction
+pub fn set_admin(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    admin: &Pubkey,
+    permissions: u8,
+) -> Instruction {
+    let (admin_account, _) = find_admin_address(config_account, admin, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new_readonly(*admin, false),
+            AccountMeta::new(admin_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::SetAdmin { permissions }.pack(),
+    }
+}
+
+/// Appends the admin PDA of `admin` to an instruction it signs in place of the config
+/// owner, see `SetAdmin`
+pub fn with_admin(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+    admin: &Pubkey,
+) -> Instruction {
+    let (admin_account, _) = find_admin_address(config_account, admin, program_id);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(admin_account, false));
+    instruction
+}
+
+/// Creates an `InitAuditLog` instruction
+pub fn init_audit_log(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    let (audit_log, _) = find_audit_log_address(config_account, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new(audit_log, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::InitAuditLog.pack(),
+    }
+}
+
+/// Appends the audit log of the config to a `ClaimReward` or `ClaimAndRebet` instruction,
+/// required once the config has one
+pub fn with_audit_log(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    let (audit_log, _) = find_audit_log_address(config_account, program_id);
+    instruction
+        .accounts
+        .push(AccountMeta::new(audit_log, false));
+    instruction
+}
+
+/// Creates an `UpdateDisputeWindow` instruction, `arbiter` replacing the config's arbiter
+pub fn update_dispute_window(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    arbiter: Option<&Pubkey>,
+    window_slots: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*config_owner, true),
+        AccountMeta::new(*config_account, false),
+    ];
+    accounts.extend(arbiter.map(|arbiter| AccountMeta::new_readonly(*arbiter, false)));
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::UpdateDisputeWindow { window_slots }.pack(),
+    }
+}
+
+/// Creates a `Dispute` instruction
+pub fn dispute(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    game_account: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*game_creator, true),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+        ],
+        data: RoshamboInstruction::Dispute.pack(),
+    }
+}
+
+/// Creates a `ResolveDispute` instruction
+pub fn resolve_dispute(
+    program_id: &Pubkey,
+    arbiter: &Pubkey,
+    game_account: &Pubkey,
+    config_account: &Pubkey,
+    uphold: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*arbiter, true),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::ResolveDispute { uphold }.pack(),
+    }
+}
+
+/// Creates an `UpdateLargeWinHold` instruction
+pub fn update_large_win_hold(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    threshold: u64,
+    delay_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateLargeWinHold {
+            threshold,
+            delay_slots,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CancelGame` instruction
+pub fn cancel_game(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    game_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*game_creator, true),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new(*receiver_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::CancelGame.pack(),
+    }
+}
+
+/// Appends the treasury of a config and the associated token account of its owner for the
+/// config `mint` to a `CancelGame` instruction, receiving the cancellation fee
+pub fn with_treasury_fee_account(
+    mut instruction: Instruction,
+    treasury: &Pubkey,
+    treasury_owner: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*treasury, false));
+    instruction.accounts.push(AccountMeta::new(
+        find_associated_token_address(treasury_owner, mint),
+        false,
+    ));
+    instruction
+}
+
+/// Creates an `UpdateCancelFee` instruction
+pub fn update_cancel_fee(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    fee_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateCancelFee { fee_bps }.pack(),
+    }
+}
+
+/// Creates an `UpdateMinStake` instruction, `stake_mint` replacing the config's stake mint
+pub fn update_min_stake(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    stake_mint: Option<&Pubkey>,
+    min_stake: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*config_owner, true),
+        AccountMeta::new(*config_account, false),
+    ];
+    accounts.extend(stake_mint.map(|mint| AccountMeta::new_readonly(*mint, false)));
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::UpdateMinStake { min_stake }.pack(),
+    }
+}
+
+/// Appends the player's token account of the stake mint to a `NewGame`, `NewGameDelegated`
+/// or `OpenSession` instruction, required while the config has a minimum stake
+pub fn with_stake_account(mut instruction: Instruction, stake_account: &Pubkey) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*stake_account, false));
+    instruction
+}
+
+/// Creates an `UpdateBurn` instruction
+pub fn update_burn(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    burn_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateBurn { burn_bps }.pack(),
+    }
+}
+
+/// Appends the mint writable to a `ClaimReward` or `ClaimAndRebet` instruction, in place of
+/// `with_mint`, while the config burns a share of the house's take
+pub fn with_burn(mut instruction: Instruction, mint: &Pubkey) -> Instruction {
+    instruction.accounts.push(AccountMeta::new(*mint, false));
+    instruction
+}
+
+/// Creates an `UpdateCashback` instruction
+pub fn update_cashback(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    cashback_bps: u16,
+    start_slot: u64,
+    end_slot: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateCashback {
+            cashback_bps,
+            start_slot,
+            end_slot,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `ClaimCashback` instruction
+pub fn claim_cashback(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    player_stats_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*player, true),
+            AccountMeta::new(*player_stats_account, false),
+            AccountMeta::new(*receiver_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::ClaimCashback.pack(),
+    }
+}
+
+/// Creates a `SetQuest` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn set_quest(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    quest_id: u8,
+    kind: u8,
+    period: u8,
+    target: u64,
+    reward: u64,
+) -> Instruction {
+    let (quest, _) = find_quest_address(config_account, quest_id, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(quest, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::SetQuest {
+            quest_id,
+            kind,
+            period,
+            target,
+            reward,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `ClaimQuestReward` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn claim_quest_reward(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    player_stats_account: &Pubkey,
+    quest_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*player, true),
+            AccountMeta::new(*player_stats_account, false),
+            AccountMeta::new_readonly(*quest_account, false),
+            AccountMeta::new(*receiver_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::ClaimQuestReward.pack(),
+    }
+}
+
+/// Creates a `RegisterPartner` instruction
+pub fn register_partner(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config_account: &Pubkey,
+    partner_token_account: &Pubkey,
+) -> Instruction {
+    let (partner, _) = find_partner_address(config_account, authority, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(partner, false),
+            AccountMeta::new_readonly(*partner_token_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::RegisterPartner.pack(),
+    }
+}
+
+/// Creates an `UpdatePartnerShare` instruction
+pub fn update_partner_share(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    share_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdatePartnerShare { share_bps }.pack(),
+    }
+}
+
+/// Appends the partner PDA of the frontend to a `NewGame` or `NewGameDelegated` instruction,
+/// crediting it with the game. The game creator can't be the partner authority.
+pub fn with_partner(mut instruction: Instruction, partner: &Pubkey) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*partner, false));
+    instruction
+}
+
+/// Appends the partner PDA and its token account to a `ClaimReward` or `ClaimAndRebet`
+/// instruction of a game created through a partner
+pub fn with_partner_share(
+    mut instruction: Instruction,
+    partner: &Pubkey,
+    partner_token_account: &Pubkey,
+) -> Instruction {
+    instruction.accounts.push(AccountMeta::new(*partner, false));
+    instruction
+        .accounts
+        .push(AccountMeta::new(*partner_token_account, false));
+    instruction
+}
+
+/// Creates a `ClaimRewards` instruction settling `games`, each with its host and public seed,
+/// signed by the house
+#[allow(clippy::too_many_arguments)]
+pub fn claim_rewards(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    house: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+    player_stats_account: &Pubkey,
+    games: &[(Pubkey, u64, u64)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*game_creator, false),
+        AccountMeta::new_readonly(*house, true),
+        AccountMeta::new(*receiver_token_account, false),
+        AccountMeta::new(*house_token_account, false),
+        AccountMeta::new(*config_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*house_authority, false),
+        AccountMeta::new(*player_stats_account, false),
+    ];
+    accounts.extend(
+        games
+            .iter()
+            .map(|(game, _, _)| AccountMeta::new(*game, false)),
+    );
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::ClaimRewards {
+            seeds: games
+                .iter()
+                .map(|(_, host_seed, public_seed)| (*host_seed, *public_seed))
+                .collect(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `UpdateSwapProgram` instruction, `None` clears the whitelist
+pub fn update_swap_program(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    swap_program: Option<&Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*config_owner, true),
+        AccountMeta::new(*config_account, false),
+    ];
+    accounts.extend(swap_program.map(|program| AccountMeta::new_readonly(*program, false)));
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::UpdateSwapProgram.pack(),
+    }
+}
+
+/// Creates a `Rebalance` instruction running `swap`, an instruction of the swap program
+/// built with the swap authority of the source config as the owner of the tokens swapped,
+/// from `escrows` (source then destination mint). `counter_shards` is the number of
+/// counter shards of the source config, `mints` the mints of the source and destination
+/// configs.
+#[allow(clippy::too_many_arguments)]
+pub fn rebalance(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    source_config: &Pubkey,
+    destination_config: &Pubkey,
+    source_house_token: &Pubkey,
+    destination_house_token: &Pubkey,
+    house_authority: &Pubkey,
+    counter_shards: u8,
+    escrows: [&Pubkey; 2],
+    mints: [&Pubkey; 2],
+    swap: &Instruction,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*config_owner, true),
+        AccountMeta::new_readonly(*source_config, false),
+        AccountMeta::new_readonly(*destination_config, false),
+        AccountMeta::new(*source_house_token, false),
+        AccountMeta::new(*destination_house_token, false),
+        AccountMeta::new_readonly(*house_authority, false),
+    ];
+    accounts.extend((0..counter_shards).map(|shard| {
+        let (counter, _) = find_counter_address(source_config, shard, program_id);
+        AccountMeta::new_readonly(counter, false)
+    }));
+    accounts.extend(swap_accounts(
+        program_id,
+        source_config,
+        escrows,
+        mints,
+        swap,
+    ));
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::Rebalance {
+            amount_in,
+            min_amount_out,
+            swap_data: swap.data.clone(),
+        }
+        .pack(),
+    }
+}
+
+/// Accounts of a swap through the escrows of the swap authority of `config`, from the swap
+/// authority on
+fn swap_accounts(
+    program_id: &Pubkey,
+    config: &Pubkey,
+    escrows: [&Pubkey; 2],
+    mints: [&Pubkey; 2],
+    swap: &Instruction,
+) -> Vec<AccountMeta> {
+    let (swap_authority, _) = find_swap_authority_address(config, program_id);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(swap_authority, false),
+        AccountMeta::new(*escrows[0], false),
+        AccountMeta::new(*escrows[1], false),
+        AccountMeta::new_readonly(*mints[0], false),
+        AccountMeta::new_readonly(*mints[1], false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(swap.program_id, false),
+    ];
+    // the program signs for the swap authority
+    accounts.extend(swap.accounts.iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != swap_authority,
+        ..meta.clone()
+    }));
+    accounts
+}
+
+/// Creates an `UpdateTreasury` instruction for a treasury and its settlement mint, `None`
+/// clears them
+pub fn update_treasury(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    treasury: Option<(&Pubkey, &Pubkey)>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*config_owner, true),
+        AccountMeta::new(*config_account, false),
+    ];
+    if let Some((treasury, settlement_mint)) = treasury {
+        accounts.push(AccountMeta::new_readonly(*treasury, false));
+        accounts.push(AccountMeta::new_readonly(*settlement_mint, false));
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::UpdateTreasury.pack(),
+    }
+}
+
+/// Creates a `SweepProfits` instruction running `swap`, an instruction of the swap program
+/// built with the swap authority of the config as the owner of the tokens swapped, from
+/// `escrows` (config then settlement mint). `counter_shards` is the number of counter shards
+/// of the config, `mints` the config and settlement mints.
+#[allow(clippy::too_many_arguments)]
+pub fn sweep_profits(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    house_token: &Pubkey,
+    treasury: &Pubkey,
+    house_authority: &Pubkey,
+    counter_shards: u8,
+    escrows: [&Pubkey; 2],
+    mints: [&Pubkey; 2],
+    swap: &Instruction,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*config_owner, true),
+        AccountMeta::new(*config_account, false),
+        AccountMeta::new(*house_token, false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new_readonly(*house_authority, false),
+    ];
+    accounts.extend((0..counter_shards).map(|shard| {
+        let (counter, _) = find_counter_address(config_account, shard, program_id);
+        AccountMeta::new_readonly(counter, false)
+    }));
+    accounts.extend(swap_accounts(
+        program_id,
+        config_account,
+        escrows,
+        mints,
+        swap,
+    ));
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::SweepProfits {
+            amount_in,
+            min_amount_out,
+            swap_data: swap.data.clone(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `UpdateVesting` instruction
+pub fn update_vesting(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    threshold: u64,
+    vesting_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateVesting {
+            threshold,
+            vesting_slots,
+        }
+        .pack(),
+    }
+}
+
+/// Appends the vesting PDA of game `game_id` and the system program to a `ClaimReward`,
+/// `ClaimAndRebet` or `ClaimDeferred` instruction of a win above the config's vesting
+/// threshold, the house signer pays the rent of the vesting account (the game creator on
+/// `ClaimDeferred`)
+pub fn with_vesting(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+    game_id: u64,
+) -> Instruction {
+    let (vesting, _) = find_vesting_address(config_account, game_id, program_id);
+    instruction.accounts[1].is_writable = true;
+    instruction.accounts.push(AccountMeta::new(vesting, false));
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(system_program::id(), false));
+    instruction
+}
+
+/// Creates a `ReleaseVested` instruction
+pub fn release_vested(
+    program_id: &Pubkey,
+    vesting_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*vesting_account, false),
+            AccountMeta::new(*receiver_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+            AccountMeta::new(*payer, false),
+        ],
+        data: RoshamboInstruction::ReleaseVested.pack(),
+    }
+}
+
+/// Creates an `UpdatePayoutCap` instruction
+pub fn update_payout_cap(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    max_payout: u64,
+    installment_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdatePayoutCap {
+            max_payout,
+            installment_slots,
+        }
+        .pack(),
+    }
+}
+
+/// Appends the payable PDA of game `game_id` and the system program to a `ClaimReward`,
+/// `ClaimAndRebet` or `ClaimDeferred` instruction of a win above the config's payout cap, the
+/// house signer pays the rent of the payable account (the game creator on `ClaimDeferred`)
+pub fn with_payable(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+    game_id: u64,
+) -> Instruction {
+    let (payable, _) = find_payable_address(config_account, game_id, program_id);
+    instruction.accounts[1].is_writable = true;
+    instruction.accounts.push(AccountMeta::new(payable, false));
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(system_program::id(), false));
+    instruction
+}
+
+/// Creates a `PayInstallment` instruction
+pub fn pay_installment(
+    program_id: &Pubkey,
+    payable_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payable_account, false),
+            AccountMeta::new(*receiver_token_account, false),
+            AccountMeta::new(*house_token_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+            AccountMeta::new(*payer, false),
+        ],
+        data: RoshamboInstruction::PayInstallment.pack(),
+    }
+}
+
+/// Creates an `UpdateConfigCooldown` instruction
+pub fn update_config_cooldown(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    cooldown_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateConfigCooldown { cooldown_slots }.pack(),
+    }
+}
+
+/// Creates a `NewGameV2` instruction, the game can't be opened after `expiry_slot` if set
+#[allow(clippy::too_many_arguments)]
+pub fn new_game_v2(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    creator_token_account: &Pubkey,
+    game_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    player_stats_account: &Pubkey,
+    amount: u64,
+    public_seed_commitment: [u8; 32],
+    seed_slot: u64,
+    expiry_slot: Option<u64>,
+) -> Instruction {
+    let mut instruction = new_game(
+        program_id,
+        game_creator,
+        creator_token_account,
+        game_account,
+        house_token_account,
+        config_account,
+        player_stats_account,
+        amount,
+        public_seed_commitment,
+        seed_slot,
+    );
+    instruction.data = RoshamboInstruction::NewGameV2 {
+        amount,
+        public_seed_commitment,
+        seed_slot,
+        expiry_slot,
+        frontend_id: 0,
+    }
+    .pack();
+    instruction
+}
+
+/// Creates a `SetFeeExempt` instruction
+pub fn set_fee_exempt(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    player: &Pubkey,
+    exempt: bool,
+) -> Instruction {
+    let (fee_exemption, _) = find_fee_exemption_address(config_account, player, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new_readonly(*player, false),
+            AccountMeta::new(fee_exemption, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::SetFeeExempt { exempt }.pack(),
+    }
+}
+
+/// Appends the fee exemption PDA of `player` to an instruction opening games for them, see
+/// `SetFeeExempt`
+pub fn with_fee_exemption(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+    player: &Pubkey,
+) -> Instruction {
+    let (fee_exemption, _) = find_fee_exemption_address(config_account, player, program_id);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(fee_exemption, false));
+    instruction
+}
+
+/// Creates an `UpdateHeartbeatMaxAge` instruction
+pub fn update_heartbeat_max_age(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    max_age_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateHeartbeatMaxAge { max_age_slots }.pack(),
+    }
+}
+
+/// Creates a `Heartbeat` instruction
+pub fn heartbeat(program_id: &Pubkey, house: &Pubkey, config_account: &Pubkey) -> Instruction {
+    let (heartbeat, _) = find_heartbeat_address(config_account, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*house, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(heartbeat, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::Heartbeat.pack(),
+    }
+}
+
+/// Appends the heartbeat PDA of the config to an instruction opening a game, required while
+/// the config has a heartbeat max age (see `UpdateHeartbeatMaxAge`)
+pub fn with_heartbeat(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    let (heartbeat, _) = find_heartbeat_address(config_account, program_id);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(heartbeat, false));
+    instruction
+}
+
+/// Creates an `UpdateInsuranceFund` instruction
+pub fn update_insurance_fund(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    mint: &Pubkey,
+    insurance_bps: u16,
+) -> Instruction {
+    let (insurance_fund, _) = find_insurance_fund_address(config_account, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(insurance_fund, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::UpdateInsuranceFund { insurance_bps }.pack(),
+    }
+}
+
+/// Creates a `ScheduleInsuranceWithdraw` instruction
+pub fn schedule_insurance_withdraw(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    enabled: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::ScheduleInsuranceWithdraw { enabled }.pack(),
+    }
+}
+
+/// Creates a `WithdrawInsurance` instruction
+pub fn withdraw_insurance(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    destination: &Pubkey,
+    house_authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (insurance_fund, _) = find_insurance_fund_address(config_account, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new(insurance_fund, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::WithdrawInsurance { amount }.pack(),
+    }
+}
+
+/// Creates a `PayInsuranceClaim` instruction
+pub fn pay_insurance_claim(
+    program_id: &Pubkey,
+    arbiter: &Pubkey,
+    game_account: &Pubkey,
+    config_account: &Pubkey,
+    creator_token_account: &Pubkey,
+    house_authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (insurance_fund, _) = find_insurance_fund_address(config_account, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*arbiter, true),
+            AccountMeta::new(*game_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(insurance_fund, false),
+            AccountMeta::new(*creator_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+        ],
+        data: RoshamboInstruction::PayInsuranceClaim { amount }.pack(),
+    }
+}
+
+/// Appends the insurance fund of the config to an instruction opening a game, required while
+/// the config moves a share of every stake into it (see `UpdateInsuranceFund`)
+pub fn with_insurance_fund(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    let (insurance_fund, _) = find_insurance_fund_address(config_account, program_id);
+    instruction
+        .accounts
+        .push(AccountMeta::new(insurance_fund, false));
+    instruction
+}
+
+/// Creates an `UpdateCashbackTiers` instruction
+pub fn update_cashback_tiers(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    tiers: Vec<(u64, u16)>,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateCashbackTiers { tiers }.pack(),
+    }
+}
+
+/// Attributes the game opened by a `NewGame` or `NewGameV2` instruction to `frontend_id`,
+/// logged with its settlement. The frontend is only carried by `NewGameV2`, a `NewGame` is
+/// turned into one with the same accounts. Other instructions are returned unchanged.
+pub fn with_frontend_id(mut instruction: Instruction, frontend_id: u16) -> Instruction {
+    instruction.data = match RoshamboInstruction::unpack(&instruction.data) {
+        Ok(RoshamboInstruction::NewGame {
+            amount,
+            public_seed_commitment,
+            seed_slot,
+        }) => RoshamboInstruction::NewGameV2 {
+            amount,
+            public_seed_commitment,
+            seed_slot,
+            expiry_slot: None,
+            frontend_id,
+        },
+        Ok(RoshamboInstruction::NewGameV2 {
+            amount,
+            public_seed_commitment,
+            seed_slot,
+            expiry_slot,
+            ..
+        }) => RoshamboInstruction::NewGameV2 {
+            amount,
+            public_seed_commitment,
+            seed_slot,
+            expiry_slot,
+            frontend_id,
+        },
+        _ => return instruction,
+    }
+    .pack();
+    instruction
+}
+
+/// Creates an `InitHourlyMetrics` instruction
+pub fn init_hourly_metrics(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    config_account: &Pubkey,
+    hour: u64,
+) -> Instruction {
+    let (metrics, _) = find_hourly_metrics_address(config_account, hour, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(metrics, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::InitHourlyMetrics { hour }.pack(),
+    }
+}
+
+/// Creates an `UpdateHourlyMetrics` instruction
+pub fn update_hourly_metrics(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    enabled: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateHourlyMetrics { enabled }.pack(),
+    }
+}
+
+/// Appends the hourly metrics of `hour`, the hour of the settled game, to a `ClaimReward`,
+/// `ClaimAndRebet` or `ClaimRewards` instruction, required while the config has
+/// `hourly_metrics` enabled
+pub fn with_hourly_metrics(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+    hour: u64,
+) -> Instruction {
+    let (metrics, _) = find_hourly_metrics_address(config_account, hour, program_id);
+    instruction.accounts.push(AccountMeta::new(metrics, false));
+    instruction
+}
+
+/// Creates an `UpdateKellyFraction` instruction
+pub fn update_kelly_fraction(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    fraction_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateKellyFraction { fraction_bps }.pack(),
+    }
+}
+
+/// Creates a `RecoverTokens` instruction
+pub fn recover_tokens(
+    program_id: &Pubkey,
+    recovery_authority: &Pubkey,
+    source_token_account: &Pubkey,
+    destination_token_account: &Pubkey,
+    mint: &Pubkey,
+    house_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*recovery_authority, true),
+            AccountMeta::new(*source_token_account, false),
+            AccountMeta::new(*destination_token_account, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*house_authority, false),
+            AccountMeta::new_readonly(find_recovery_authority_address(program_id).0, false),
+        ],
+        data: RoshamboInstruction::RecoverTokens.pack(),
+    }
+}
+
+/// Creates a `RecoverLamports` instruction
+pub fn recover_lamports(
+    program_id: &Pubkey,
+    recovery_authority: &Pubkey,
+    house_authority: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*recovery_authority, true),
+            AccountMeta::new(*house_authority, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(find_recovery_authority_address(program_id).0, false),
+        ],
+        data: RoshamboInstruction::RecoverLamports.pack(),
+    }
+}
+
+/// Creates a `SetRecoveryAuthority` instruction
+pub fn set_recovery_authority(
+    program_id: &Pubkey,
+    recovery_authority: &Pubkey,
+    new_recovery_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*recovery_authority, true),
+            AccountMeta::new(find_recovery_authority_address(program_id).0, false),
+            AccountMeta::new_readonly(*new_recovery_authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RoshamboInstruction::SetRecoveryAuthority.pack(),
+    }
+}
+
+/// Creates a `ReclaimRent` instruction
+pub fn reclaim_rent(
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+    treasury: &Pubkey,
+    abandoned_accounts: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*config_account, false),
+        AccountMeta::new(*treasury, false),
+    ];
+    accounts.extend(
+        abandoned_accounts
+            .iter()
+            .map(|account| AccountMeta::new(*account, false)),
+    );
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::ReclaimRent.pack(),
+    }
+}
+
+/// Creates an `UpdateMaxSettleAge` instruction
+pub fn update_max_settle_age(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    max_age_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateMaxSettleAge { max_age_slots }.pack(),
+    }
+}
+
+/// Creates an `UpdateExposureTracking` instruction
+pub fn update_exposure_tracking(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    enabled: bool,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*config_owner, true),
+        AccountMeta::new(*config_account, false),
+    ];
+    if enabled {
+        let (exposure, _) = find_exposure_address(config_account, program_id);
+        accounts.push(AccountMeta::new(exposure, false));
+        accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: RoshamboInstruction::UpdateExposureTracking { enabled }.pack(),
+    }
+}
+
+/// Appends the exposure PDA of the config to an instruction opening or settling a game,
+/// required while the config tracks its exposure (see `UpdateExposureTracking`)
+pub fn with_exposure(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    let (exposure, _) = find_exposure_address(config_account, program_id);
+    instruction.accounts.push(AccountMeta::new(exposure, false));
+    instruction
+}
+
+/// Creates an `UpdateCircuitBreaker` instruction
+pub fn update_circuit_breaker(
+    program_id: &Pubkey,
+    config_owner: &Pubkey,
+    config_account: &Pubkey,
+    max_loss: u64,
+    window_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*config_owner, true),
+            AccountMeta::new(*config_account, false),
+        ],
+        data: RoshamboInstruction::UpdateCircuitBreaker {
+            max_loss,
+            window_slots,
+        }
+        .pack(),
     }
 }
@@ -0,0 +1,44 @@
+// Kelly criterion sizing of the stakes the house takes, recommended off-chain and enforced by
+// configs with a Kelly fraction (see `state::max_allowed_bet`)
+//
+// A win and a loss are equally likely, a draw refunds the stake. On a stake `s` the house
+// wins `s` on a loss and pays the profit `e * s` of a win, odds of `b = 1 / e`. The Kelly
+// fraction of the bankroll put at risk, `(p * b - q) / (b * (p + q))` with `p == q`, is
+// `(b - 1) / (2 * b)`, which is a stake of `(1 - e) / (2 * e)` of the bankroll.
+
+use crate::outcome::{BPS_DENOMINATOR, HANDS};
+
+/// Profit of a win per unit of stake after the fee, in basis points: what the house risks
+/// for every unit it wins on a loss
+pub fn win_profit_bps(payout_multiplier_bps: u16, fee_bps: u16) -> u64 {
+    let profit = (payout_multiplier_bps as u64).saturating_sub(BPS_DENOMINATOR);
+    profit * BPS_DENOMINATOR.saturating_sub(fee_bps as u64) / BPS_DENOMINATOR
+}
+
+/// Expected house gain per unit of stake in basis points, negative if the players have the
+/// edge
+pub fn house_edge_bps(payout_multiplier_bps: u16, fee_bps: u16) -> i64 {
+    let edge = BPS_DENOMINATOR as i64 - win_profit_bps(payout_multiplier_bps, fee_bps) as i64;
+    // the probability of a loss, and of a win
+    edge * (HANDS as i64 - 1) / (2 * HANDS as i64)
+}
+
+/// Largest stake the house should take out of `bankroll` at `fraction_bps` of the Kelly
+/// criterion (`BPS_DENOMINATOR` for full Kelly). 0 without house edge, unbounded if a win
+/// pays no profit.
+pub fn kelly_max_bet(
+    bankroll: u64,
+    payout_multiplier_bps: u16,
+    fee_bps: u16,
+    fraction_bps: u16,
+) -> u64 {
+    let profit = win_profit_bps(payout_multiplier_bps, fee_bps) as u128;
+    if profit == 0 {
+        return u64::MAX;
+    }
+    let Some(edge) = (BPS_DENOMINATOR as u128).checked_sub(profit) else {
+        return 0;
+    };
+    let full = bankroll as u128 * edge / (2 * profit);
+    u64::try_from(full * fraction_bps as u128 / BPS_DENOMINATOR as u128).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,607 @@
+// machine-readable description of the account layouts, for indexers to generate their decoders
+// from (see the `roshambo-layout` binary)
+//
+// Accounts carry no discriminator, every account type has a distinct size so the type is
+// detected from the data length (see `state::dump_account`). Integers are little-endian, a
+// `COption` is a 4-byte little-endian tag (1 if set) followed by the value, zeroed if unset.
+
+use std::fmt::Write;
+
+use crate::state::{
+    GameStatus, CASHBACK_TIER_LENGTH, MAX_CASHBACK_TIERS, STREAK_ENTRY_LENGTH,
+    STREAK_LEADERBOARD_SIZE,
+};
+
+/// Type of a field of an account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I64,
+    Pubkey,
+    /// 32 raw bytes, a hash or a seed
+    Hash,
+    OptionU8,
+    OptionU64,
+    OptionPubkey,
+    OptionHash,
+    /// `GameStatus` as a `u8`, see `GAME_STATUSES`
+    GameStatus,
+    /// `len` items laid out as the `ITEM_LAYOUTS` entry named `item`
+    Array {
+        item: &'static str,
+        item_size: usize,
+        len: usize,
+    },
+}
+
+impl FieldType {
+    /// Size of the field in bytes
+    pub fn size(self) -> usize {
+        match self {
+            FieldType::Bool | FieldType::U8 | FieldType::GameStatus => 1,
+            FieldType::U16 => 2,
+            FieldType::U32 => 4,
+            FieldType::U64 | FieldType::I64 => 8,
+            FieldType::Pubkey | FieldType::Hash => 32,
+            FieldType::OptionU8 => 5,
+            FieldType::OptionU64 => 12,
+            FieldType::OptionPubkey | FieldType::OptionHash => 36,
+            FieldType::Array { item_size, len, .. } => item_size * len,
+        }
+    }
+
+    /// Name of the type in the JSON description
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldType::Bool => "bool",
+            FieldType::U8 => "u8",
+            FieldType::U16 => "u16",
+            FieldType::U32 => "u32",
+            FieldType::U64 => "u64",
+            FieldType::I64 => "i64",
+            FieldType::Pubkey => "pubkey",
+            FieldType::Hash => "bytes32",
+            FieldType::OptionU8 => "coption<u8>",
+            FieldType::OptionU64 => "coption<u64>",
+            FieldType::OptionPubkey => "coption<pubkey>",
+            FieldType::OptionHash => "coption<bytes32>",
+            FieldType::GameStatus => "GameStatus",
+            FieldType::Array { .. } => "array",
+        }
+    }
+}
+
+/// Fields of an account in the order they are packed, without padding
+pub struct AccountLayout {
+    pub name: &'static str,
+    pub fields: &'static [(&'static str, FieldType)],
+}
+
+impl AccountLayout {
+    /// Size of the packed account, its `Pack::LEN`
+    pub fn size(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|(_, field_type)| field_type.size())
+            .sum()
+    }
+
+    /// Fields with their offset in the account data
+    pub fn offsets(&self) -> impl Iterator<Item = (usize, &'static str, FieldType)> + '_ {
+        self.fields.iter().scan(0, |offset, &(name, field_type)| {
+            let start = *offset;
+            *offset += field_type.size();
+            Some((start, name, field_type))
+        })
+    }
+}
+
+/// Every `GameStatus`, stored as its discriminant
+pub const GAME_STATUSES: [GameStatus; 7] = [
+    GameStatus::Created,
+    GameStatus::Committed,
+    GameStatus::ResultPosted,
+    GameStatus::Settled,
+    GameStatus::Cancelled,
+    GameStatus::Disputed,
+    GameStatus::Expired,
+];
+
+const CASHBACK_TIERS: FieldType = FieldType::Array {
+    item: "CashbackTier",
+    item_size: CASHBACK_TIER_LENGTH,
+    len: MAX_CASHBACK_TIERS,
+};
+
+const STREAK_ENTRIES: FieldType = FieldType::Array {
+    item: "StreakEntry",
+    item_size: STREAK_ENTRY_LENGTH,
+    len: STREAK_LEADERBOARD_SIZE,
+};
+
+/// Every program account, in the order of `state`
+pub const ACCOUNT_LAYOUTS: &[AccountLayout] = &[
+    AccountLayout {
+        name: "Game",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("bet_amount", FieldType::U64),
+            ("game_creator_pubkey", FieldType::Pubkey),
+            ("result", FieldType::OptionU8),
+            ("created_slot", FieldType::U64),
+            ("min_bet_amount", FieldType::U64),
+            ("max_bet_amount", FieldType::U64),
+            ("fee_bps", FieldType::U16),
+            ("payout_multiplier_bps", FieldType::U16),
+            ("public_seed_commitment", FieldType::Hash),
+            ("config_pubkey", FieldType::Pubkey),
+            ("host_seed_commitment", FieldType::OptionHash),
+            ("revealed_public_seed", FieldType::OptionU64),
+            ("game_id", FieldType::U64),
+            ("seed_slot", FieldType::U64),
+            ("deferred_payout", FieldType::U64),
+            ("release_slot", FieldType::U64),
+            ("status", FieldType::GameStatus),
+            ("partner", FieldType::OptionPubkey),
+            ("frontend_id", FieldType::U16),
+            ("insurance_bps", FieldType::U16),
+            ("recycled", FieldType::Bool),
+        ],
+    },
+    AccountLayout {
+        name: "Config",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("total_games", FieldType::U64),
+            ("min_bet_amount", FieldType::U64),
+            ("max_bet_amount", FieldType::U64),
+            ("owner_pubkey", FieldType::Pubkey),
+            ("mint_token_pubkey", FieldType::Pubkey),
+            ("max_games_per_window", FieldType::U64),
+            ("rate_limit_window_slots", FieldType::U64),
+            ("min_settle_delay_slots", FieldType::U64),
+            ("fee_bps", FieldType::U16),
+            ("payout_multiplier_bps", FieldType::U16),
+            ("settlement_memo", FieldType::Bool),
+            ("total_volume", FieldType::U64),
+            ("house_pnl", FieldType::I64),
+            ("settlement_tree", FieldType::OptionPubkey),
+            ("counter_shards", FieldType::U8),
+            ("vault_shards", FieldType::U8),
+            ("game_pool", FieldType::Bool),
+            ("outstanding_exposure", FieldType::U64),
+            ("withdraw_bond", FieldType::U64),
+            ("emergency_unlock_slot", FieldType::OptionU64),
+            ("season", FieldType::U32),
+            ("streak_leaderboard", FieldType::Bool),
+            ("daily_seed", FieldType::Bool),
+            ("decimals", FieldType::U8),
+            ("paused", FieldType::Bool),
+            ("audit_log", FieldType::Bool),
+            ("arbiter", FieldType::OptionPubkey),
+            ("dispute_window_slots", FieldType::U64),
+            ("large_win_threshold", FieldType::U64),
+            ("large_win_delay_slots", FieldType::U64),
+            ("cancel_fee_bps", FieldType::U16),
+            ("stake_mint", FieldType::OptionPubkey),
+            ("min_stake", FieldType::U64),
+            ("burn_bps", FieldType::U16),
+            ("cashback_bps", FieldType::U16),
+            ("cashback_start_slot", FieldType::U64),
+            ("cashback_end_slot", FieldType::U64),
+            ("cashback_tiers", CASHBACK_TIERS),
+            ("partner_share_bps", FieldType::U16),
+            ("swap_program", FieldType::OptionPubkey),
+            ("treasury", FieldType::OptionPubkey),
+            ("swept_profits", FieldType::U64),
+            ("vesting_threshold", FieldType::U64),
+            ("vesting_slots", FieldType::U64),
+            ("max_payout_per_game", FieldType::U64),
+            ("installment_slots", FieldType::U64),
+            ("settled_volume", FieldType::U64),
+            ("total_paid_out", FieldType::U64),
+            ("config_cooldown_slots", FieldType::U64),
+            ("limits_updated_slot", FieldType::U64),
+            ("heartbeat_max_age_slots", FieldType::U64),
+            ("insurance_bps", FieldType::U16),
+            ("insurance_unlock_slot", FieldType::OptionU64),
+            ("hourly_metrics", FieldType::Bool),
+            ("kelly_fraction_bps", FieldType::U16),
+            ("games_cancelled", FieldType::U64),
+            ("refunds_total", FieldType::U64),
+            ("max_settle_age_slots", FieldType::U64),
+            ("exposure_tracking", FieldType::Bool),
+            ("breaker_max_loss", FieldType::U64),
+            ("breaker_window_slots", FieldType::U64),
+            ("breaker_window_start", FieldType::U64),
+            ("breaker_window_loss", FieldType::I64),
+            ("breaker_tripped", FieldType::Bool),
+            ("settlement_mint", FieldType::OptionPubkey),
+        ],
+    },
+    AccountLayout {
+        name: "Session",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("player_pubkey", FieldType::Pubkey),
+            ("config_pubkey", FieldType::Pubkey),
+            ("balance", FieldType::U64),
+            ("games_played", FieldType::U64),
+            ("deposit", FieldType::U64),
+            ("wagered", FieldType::U64),
+            ("host_seed_commitment", FieldType::OptionHash),
+            ("pending_amount", FieldType::U64),
+            ("pending_public_seed", FieldType::U64),
+        ],
+    },
+    AccountLayout {
+        name: "PlayerStats",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("player_pubkey", FieldType::Pubkey),
+            ("config_pubkey", FieldType::Pubkey),
+            ("games_played", FieldType::U64),
+            ("window_start_slot", FieldType::U64),
+            ("games_in_window", FieldType::U64),
+            ("wins", FieldType::U64),
+            ("win_streak", FieldType::U64),
+            ("achievements", FieldType::U64),
+            ("season", FieldType::U32),
+            ("season_games", FieldType::U64),
+            ("season_wins", FieldType::U64),
+            ("cashback_promo", FieldType::U64),
+            ("cashback_net_loss", FieldType::I64),
+            ("cashback_volume", FieldType::U64),
+            ("cashback_accrued", FieldType::I64),
+            ("quest_day", FieldType::U64),
+            ("day_wins", FieldType::U64),
+            ("day_wagered", FieldType::U64),
+            ("day_quests_claimed", FieldType::U64),
+            ("quest_week", FieldType::U64),
+            ("week_wins", FieldType::U64),
+            ("week_wagered", FieldType::U64),
+            ("week_quests_claimed", FieldType::U64),
+            ("referrer", FieldType::OptionPubkey),
+            ("games_cancelled", FieldType::U64),
+            ("refunds_total", FieldType::U64),
+        ],
+    },
+    AccountLayout {
+        name: "SessionAuthority",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("player_pubkey", FieldType::Pubkey),
+            ("session_key_pubkey", FieldType::Pubkey),
+            ("expiry_slot", FieldType::U64),
+            ("max_wager", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "StatsSnapshot",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("epoch", FieldType::U64),
+            ("slot", FieldType::U64),
+            ("total_games", FieldType::U64),
+            ("total_volume", FieldType::U64),
+            ("house_pnl", FieldType::I64),
+            ("settled_volume", FieldType::U64),
+            ("total_paid_out", FieldType::U64),
+        ],
+    },
+    AccountLayout {
+        name: "Counter",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("shard", FieldType::U8),
+            ("total_games", FieldType::U64),
+            ("total_volume", FieldType::U64),
+            ("house_pnl", FieldType::I64),
+            ("outstanding_exposure", FieldType::I64),
+            ("settled_volume", FieldType::U64),
+            ("total_paid_out", FieldType::U64),
+            ("games_cancelled", FieldType::U64),
+            ("refunds_total", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "StreakLeaderboard",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("season", FieldType::U32),
+            ("entries", STREAK_ENTRIES),
+        ],
+    },
+    AccountLayout {
+        name: "SeasonResult",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("season", FieldType::U32),
+            ("end_slot", FieldType::U64),
+            ("entries", STREAK_ENTRIES),
+        ],
+    },
+    AccountLayout {
+        name: "DailySeed",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("day", FieldType::U64),
+            ("commitment", FieldType::Hash),
+            ("secret", FieldType::OptionHash),
+        ],
+    },
+    AccountLayout {
+        name: "AuditLog",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("entries", FieldType::U64),
+            ("head", FieldType::Hash),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "Admin",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("admin_pubkey", FieldType::Pubkey),
+            ("permissions", FieldType::U8),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "Quest",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("quest_id", FieldType::U8),
+            ("kind", FieldType::U8),
+            ("period", FieldType::U8),
+            ("target", FieldType::U64),
+            ("reward", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "Partner",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("authority_pubkey", FieldType::Pubkey),
+            ("token_account", FieldType::Pubkey),
+            ("referred_players", FieldType::U64),
+            ("games", FieldType::U64),
+            ("volume", FieldType::U64),
+            ("fees_earned", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "Vesting",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("game_id", FieldType::U64),
+            ("player_pubkey", FieldType::Pubkey),
+            ("receiver", FieldType::Pubkey),
+            ("payer", FieldType::Pubkey),
+            ("total", FieldType::U64),
+            ("released", FieldType::U64),
+            ("start_slot", FieldType::U64),
+            ("end_slot", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "Payable",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("game_id", FieldType::U64),
+            ("player_pubkey", FieldType::Pubkey),
+            ("receiver", FieldType::Pubkey),
+            ("payer", FieldType::Pubkey),
+            ("owed", FieldType::U64),
+            ("paid", FieldType::U64),
+            ("installment", FieldType::U64),
+            ("installment_slots", FieldType::U64),
+            ("next_slot", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "FeeExemption",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("player_pubkey", FieldType::Pubkey),
+            ("exempt", FieldType::Bool),
+            ("updated_slot", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "Heartbeat",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("host_pubkey", FieldType::Pubkey),
+            ("first_slot", FieldType::U64),
+            ("last_slot", FieldType::U64),
+            ("beats", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "HourlyMetrics",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("hour", FieldType::U64),
+            ("games", FieldType::U64),
+            ("volume", FieldType::U64),
+            ("payouts", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "Exposure",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("config_pubkey", FieldType::Pubkey),
+            ("open_games", FieldType::U64),
+            ("potential_payout", FieldType::U64),
+            ("slot", FieldType::U64),
+            ("bump", FieldType::U8),
+        ],
+    },
+    AccountLayout {
+        name: "RecoveryAuthority",
+        fields: &[
+            ("is_initialized", FieldType::Bool),
+            ("authority", FieldType::Pubkey),
+            ("bump", FieldType::U8),
+        ],
+    },
+];
+
+/// Items of the array fields
+pub const ITEM_LAYOUTS: &[AccountLayout] = &[
+    AccountLayout {
+        name: "CashbackTier",
+        fields: &[
+            ("min_volume", FieldType::U64),
+            ("cashback_bps", FieldType::U16),
+        ],
+    },
+    AccountLayout {
+        name: "StreakEntry",
+        fields: &[
+            ("player_pubkey", FieldType::Pubkey),
+            ("streak", FieldType::U64),
+        ],
+    },
+];
+
+/// JSON description of every account layout: `accounts` and the array `items`, each with its
+/// `size` and `fields` (`name`, `offset`, `size`, `type`, plus `item` and `len` for arrays),
+/// and the `enums` stored in them
+pub fn layouts_json() -> String {
+    let mut json = String::from("{\n  \"accounts\": [");
+    write_layouts(&mut json, ACCOUNT_LAYOUTS);
+    json.push_str("],\n  \"items\": [");
+    write_layouts(&mut json, ITEM_LAYOUTS);
+    json.push_str(
+        "],\n  \"enums\": [\n    {\"name\": \"GameStatus\", \"size\": 1, \"variants\": [",
+    );
+    for (i, status) in GAME_STATUSES.iter().enumerate() {
+        let separator = if i == 0 { "" } else { ", " };
+        let _ = write!(
+            json,
+            "{separator}{{\"name\": \"{status:?}\", \"value\": {}}}",
+            *status as u8
+        );
+    }
+    json.push_str("]}\n  ]\n}\n");
+    json
+}
+
+fn write_layouts(json: &mut String, layouts: &[AccountLayout]) {
+    for (i, layout) in layouts.iter().enumerate() {
+        let separator = if i == 0 { "" } else { "," };
+        let _ = write!(
+            json,
+            "{separator}\n    {{\"name\": \"{}\", \"size\": {}, \"fields\": [",
+            layout.name,
+            layout.size()
+        );
+        for (j, (offset, name, field_type)) in layout.offsets().enumerate() {
+            let separator = if j == 0 { "" } else { "," };
+            let _ = write!(
+                json,
+                "{separator}\n      {{\"name\": \"{name}\", \"offset\": {offset}, \"size\": {}, \"type\": \"{}\"",
+                field_type.size(),
+                field_type.name()
+            );
+            if let FieldType::Array { item, len, .. } = field_type {
+                let _ = write!(json, ", \"item\": \"{item}\", \"len\": {len}");
+            }
+            json.push('}');
+        }
+        json.push_str("\n    ]}");
+    }
+    json.push_str("\n  ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::*;
+    use solana_program::program_pack::Pack;
+
+    #[test]
+    fn test_layouts_match_pack_sizes() {
+        let lens = [
+            Game::LEN,
+            Config::LEN,
+            Session::LEN,
+            PlayerStats::LEN,
+            SessionAuthority::LEN,
+            StatsSnapshot::LEN,
+            Counter::LEN,
+            StreakLeaderboard::LEN,
+            SeasonResult::LEN,
+            DailySeed::LEN,
+            AuditLog::LEN,
+            Admin::LEN,
+            Quest::LEN,
+            Partner::LEN,
+            Vesting::LEN,
+            Payable::LEN,
+            FeeExemption::LEN,
+            Heartbeat::LEN,
+            HourlyMetrics::LEN,
+            Exposure::LEN,
+            RecoveryAuthority::LEN,
+        ];
+        assert_eq!(ACCOUNT_LAYOUTS.len(), lens.len());
+        for (layout, len) in ACCOUNT_LAYOUTS.iter().zip(lens) {
+            assert_eq!(layout.size(), len, "{}", layout.name);
+        }
+        assert_eq!(ITEM_LAYOUTS[0].size(), CASHBACK_TIER_LENGTH);
+        assert_eq!(ITEM_LAYOUTS[1].size(), STREAK_ENTRY_LENGTH);
+        assert!(GameStatus::try_from(GAME_STATUSES.len() as u8).is_err());
+    }
+
+    #[test]
+    fn test_layout_offsets() {
+        let game = &ACCOUNT_LAYOUTS[0];
+        let (offset, _, _) = game
+            .offsets()
+            .find(|(_, name, _)| *name == "result")
+            .unwrap();
+        assert_eq!(offset, INITIALIZED_BYTES + U64_LENGTH + PUBKEY_BYTES);
+
+        let mut data = vec![0; Game::LEN];
+        let mut packed = Game::unpack_unchecked(&data).unwrap();
+        packed.bet_amount = 1_000;
+        Game::pack(packed, &mut data).unwrap();
+        let (offset, _, _) = game
+            .offsets()
+            .find(|(_, name, _)| *name == "bet_amount")
+            .unwrap();
+        assert_eq!(data[offset..offset + U64_LENGTH], 1_000u64.to_le_bytes());
+    }
+}
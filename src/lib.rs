@@ -1,7 +1,36 @@
+#[cfg(feature = "audit")]
+pub mod audit;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod compression;
 pub mod error;
+pub mod event;
+#[cfg(feature = "host-service")]
+pub mod host_service;
 pub mod instruction;
+pub mod kelly;
+pub mod layout;
+#[cfg(feature = "client")]
+pub mod listener;
+pub mod log;
+pub mod outcome;
+pub mod pda;
 pub mod processor;
 pub mod state;
+pub mod tlv;
+pub mod validation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
+
+// canonical program address, exported as `ID`, `id()` and `check_id()`
+solana_program::declare_id!("9kKdaxYNTKDx1YqAe22ubfRLDWMfTp1jqT8NsQ44nfUz");
+
+/// Program-level authority of the recovery instructions, moving what was sent by mistake to
+/// the house PDA shared by every config. It only holds until the first `SetRecoveryAuthority`
+/// moves them to a key kept in the recovery authority PDA, which rotates from then on.
+pub mod recovery_authority {
+    solana_program::declare_id!("877tEuoZeQHWvsV2pqq7DSZb9NLGSNtNew24qQdkBa3V");
+}
@@ -0,0 +1,149 @@
+// typed program account subscription, only built with the "client" feature
+
+use std::{collections::HashSet, str::FromStr, time::Duration};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    pubsub_client::{PubsubClient, PubsubProgramClientSubscription},
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_response::{Response, RpcKeyedAccount},
+};
+use solana_program::{
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig};
+
+use crate::{
+    client::ClientResult,
+    state::{Config, Game},
+};
+
+/// A program account update, decoded
+pub enum ProgramEvent {
+    /// A game seen for the first time by this listener
+    GameOpened { address: Pubkey, game: Game },
+    /// A known game changed, e.g. the house committed its seed or the player revealed
+    GameUpdated { address: Pubkey, game: Game },
+    /// A known game was settled (or declined) and its account closed
+    GameSettled { address: Pubkey },
+    /// A config was initialized or updated
+    ConfigUpdated {
+        address: Pubkey,
+        config: Box<Config>,
+    },
+}
+
+/// Program-subscribe stream decoding `Game` and `Config` updates into `ProgramEvent`s
+pub struct ProgramListener {
+    subscription: PubsubProgramClientSubscription,
+    receiver: crossbeam_channel::Receiver<Response<RpcKeyedAccount>>,
+    games: HashSet<Pubkey>,
+}
+
+impl ProgramListener {
+    pub fn subscribe(
+        ws_url: &str,
+        program_id: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<Self> {
+        let config = RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(commitment),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let (subscription, receiver) =
+            PubsubClient::program_subscribe(ws_url, program_id, Some(config))?;
+
+        Ok(Self {
+            subscription,
+            receiver,
+            games: HashSet::new(),
+        })
+    }
+
+    /// Marks `address` as a known game, e.g. one found with `getProgramAccounts` before
+    /// subscribing, so that its settlement is reported
+    pub fn track_game(&mut self, address: Pubkey) {
+        self.games.insert(address);
+    }
+
+    /// Blocks until the next event
+    pub fn recv(&mut self) -> ClientResult<ProgramEvent> {
+        loop {
+            let response = self.receiver.recv()?;
+            if let Some(event) = self.decode(response)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Waits up to `timeout` for the next event
+    pub fn recv_timeout(&mut self, timeout: Duration) -> ClientResult<Option<ProgramEvent>> {
+        let response = match self.receiver.recv_timeout(timeout) {
+            Ok(response) => response,
+            Err(err) if err.is_timeout() => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        self.decode(response)
+    }
+
+    /// Unsubscribes and closes the socket
+    pub fn shutdown(mut self) -> ClientResult<()> {
+        self.subscription.send_unsubscribe()?;
+        self.subscription
+            .shutdown()
+            .map_err(|_| "subscription thread panicked")?;
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        response: Response<RpcKeyedAccount>,
+    ) -> ClientResult<Option<ProgramEvent>> {
+        let address = Pubkey::from_str(&response.value.pubkey)?;
+        let account = response
+            .value
+            .account
+            .decode::<Account>()
+            .ok_or("account update could not be decoded")?;
+
+        // a closed account comes through with no lamports
+        if account.lamports == 0 {
+            return Ok(self
+                .games
+                .remove(&address)
+                .then_some(ProgramEvent::GameSettled { address }));
+        }
+
+        if account.data.len() == Game::LEN {
+            // a game recycled into the pool of its config is settled as well
+            let game = Game::unpack_unchecked(&account.data)?;
+            if !game.is_initialized() {
+                return Ok(self
+                    .games
+                    .remove(&address)
+                    .then_some(ProgramEvent::GameSettled { address }));
+            }
+            if !game.status.is_open() {
+                self.games.remove(&address);
+                return Ok(Some(ProgramEvent::GameSettled { address }));
+            }
+            return Ok(Some(if self.games.insert(address) {
+                ProgramEvent::GameOpened { address, game }
+            } else {
+                ProgramEvent::GameUpdated { address, game }
+            }));
+        }
+
+        if account.data.len() == Config::LEN {
+            let config = Box::new(Config::unpack(&account.data)?);
+            return Ok(Some(ProgramEvent::ConfigUpdated { address, config }));
+        }
+
+        Ok(None)
+    }
+}
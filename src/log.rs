@@ -0,0 +1,63 @@
+// program logs, messages with the "verbose-logs" feature and numeric codes otherwise
+
+use std::fmt::{self, Write};
+
+use solana_program::log::{sol_log, sol_log_64};
+
+/// Prefix and version of the structured log lines, see `structured_log`
+pub const STRUCTURED_LOG_PREFIX: &str = "RSB1";
+
+/// First value of a `sol_log_64` line: the instruction tag
+pub const LOG_INSTRUCTION: u64 = 1;
+/// First value of a `sol_log_64` line: the result of a session game
+pub const LOG_SESSION_RESULT: u64 = 2;
+/// First value of a `sol_log_64` line: the unlock slot of an emergency withdrawal
+pub const LOG_EMERGENCY_UNLOCK: u64 = 3;
+/// First value of a `sol_log_64` line: the total airdropped and the number of recipients
+pub const LOG_AIRDROP: u64 = 4;
+/// First value of a `sol_log_64` line: the amounts swapped out and in by `Rebalance`
+pub const LOG_REBALANCE: u64 = 5;
+/// First value of a `sol_log_64` line: the profits swept and the amount the treasury received
+pub const LOG_SWEEP: u64 = 6;
+
+/// Logs `code` and up to four values on a single `sol_log_64` line, the values left out are 0
+pub fn log_values(code: u64, values: &[u64]) {
+    let value = |index: usize| values.get(index).copied().unwrap_or_default();
+    sol_log_64(code, value(0), value(1), value(2), value(3));
+}
+
+/// Logs `RSB1:<kind>:<field>:...` on a single line with the "structured-logs" feature, next to
+/// the event of the same step for log-based pipelines that can't decode `sol_log_data`
+pub fn structured_log(kind: &str, fields: &[&dyn fmt::Display]) {
+    if !cfg!(feature = "structured-logs") {
+        return;
+    }
+    let mut line = format!("{}:{}", STRUCTURED_LOG_PREFIX, kind);
+    for field in fields {
+        let _ = write!(line, ":{}", field);
+    }
+    sol_log(&line);
+}
+
+/// `msg!` with the "verbose-logs" feature, nothing otherwise: the steps it reports show up as
+/// the CPIs the runtime logs anyway
+macro_rules! verbose_msg {
+    ($($arg:tt)+) => {
+        if cfg!(feature = "verbose-logs") {
+            solana_program::msg!($($arg)+);
+        }
+    };
+}
+pub(crate) use verbose_msg;
+
+/// `msg!` with the "verbose-logs" feature, otherwise `code` and its values through `log_values`
+macro_rules! log_code {
+    ($code:expr, [$($value:expr),*], $($arg:tt)+) => {
+        if cfg!(feature = "verbose-logs") {
+            solana_program::msg!($($arg)+);
+        } else {
+            $crate::log::log_values($code, &[$($value as u64),*]);
+        }
+    };
+}
+pub(crate) use log_code;
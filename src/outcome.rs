@@ -0,0 +1,146 @@
+// game outcome, shared by every settlement path and by off-chain verifiers
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+/// Number of hands in play (rock, paper, scissors, lizard, spock)
+pub const HANDS: u64 = 5;
+
+/// Denominator of every basis point value (fees, payout multipliers)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Payout multiplier of a win before any config update - 2x the stake
+pub const DEFAULT_PAYOUT_MULTIPLIER_BPS: u16 = 20_000;
+
+/// Result of a game from the player's point of view, stored in `Game.result`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win = 0,
+    Lose = 1,
+    Draw = 2,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Win => "win",
+            Outcome::Lose => "lose",
+            Outcome::Draw => "draw",
+        }
+    }
+}
+
+/// Resolves a game from the host and player seeds
+pub fn outcome(host_seed: u64, public_seed: u64) -> Outcome {
+    let selection = public_seed % HANDS;
+    let host_result = host_seed % HANDS;
+
+    if selection == host_result {
+        return Outcome::Draw;
+    }
+
+    let tmp_calc = selection + HANDS - host_result;
+    if tmp_calc == 1 || tmp_calc == 3 || tmp_calc == 6 || tmp_calc == 8 {
+        Outcome::Lose
+    } else {
+        Outcome::Win
+    }
+}
+
+/// Amount paid to a winning player, stake included, after the fee taken from the profit
+pub fn win_payout(bet_amount: u64, payout_multiplier_bps: u16, fee_bps: u16) -> Option<u64> {
+    let gross = (bet_amount as u128) * (payout_multiplier_bps as u128) / (BPS_DENOMINATOR as u128);
+    let profit = gross.checked_sub(bet_amount as u128)?;
+    let fee = profit * (fee_bps as u128) / (BPS_DENOMINATOR as u128);
+    u64::try_from(gross - fee).ok()
+}
+
+/// Fee taken from the profit of a win, kept by the house
+pub fn win_fee(bet_amount: u64, payout_multiplier_bps: u16, fee_bps: u16) -> Option<u64> {
+    let gross = (bet_amount as u128) * (payout_multiplier_bps as u128) / (BPS_DENOMINATOR as u128);
+    let profit = gross.checked_sub(bet_amount as u128)?;
+    u64::try_from(profit * (fee_bps as u128) / (BPS_DENOMINATOR as u128)).ok()
+}
+
+/// Commitment to the player's public seed, bound to the game account so it can't be reused
+/// and to a recent slot (`Game.seed_slot`) so it can't be made long before the game
+pub fn public_seed_commitment(game: &Pubkey, public_seed: u64, seed_slot: u64) -> [u8; 32] {
+    hashv(&[
+        game.as_ref(),
+        &public_seed.to_le_bytes(),
+        &seed_slot.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Commitment to the house's host seed for a game, posted with `CommitHostSeed`
+pub fn host_seed_commitment(game: &Pubkey, host_seed: u64) -> [u8; 32] {
+    hashv(&[b"host", game.as_ref(), &host_seed.to_le_bytes()]).to_bytes()
+}
+
+/// Commitment to the house's host seed for game `game_index` of a session (its
+/// `games_played` when committed), posted with `CommitSessionSeed`
+pub fn session_seed_commitment(session: &Pubkey, game_index: u64, host_seed: u64) -> [u8; 32] {
+    hashv(&[
+        b"session",
+        session.as_ref(),
+        &game_index.to_le_bytes(),
+        &host_seed.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Slots of a daily seed day (~1 day)
+pub const DAILY_SEED_SLOTS: u64 = 216_000;
+
+/// Day of `slot`, the day of a game is the one it was created in
+pub fn daily_seed_day(slot: u64) -> u64 {
+    slot / DAILY_SEED_SLOTS
+}
+
+/// Commitment to a daily secret, posted with `CommitDailySeed`
+pub fn daily_seed_commitment(secret: &[u8; 32]) -> [u8; 32] {
+    hashv(&[b"daily", secret]).to_bytes()
+}
+
+/// Host seed of game `game_id` under the secret of its day, anyone can check it once the
+/// secret is revealed
+pub fn daily_host_seed(secret: &[u8; 32], game_id: u64) -> u64 {
+    let digest = hashv(&[secret, &game_id.to_le_bytes()]).to_bytes();
+    u64::from_le_bytes(digest[..8].try_into().unwrap())
+}
+
+/// Entry of the audit log for a seed reveal
+pub fn audit_entry(game: &Pubkey, host_seed: u64, public_seed: u64, result: Outcome) -> [u8; 32] {
+    hashv(&[
+        game.as_ref(),
+        &host_seed.to_le_bytes(),
+        &public_seed.to_le_bytes(),
+        &[result as u8],
+    ])
+    .to_bytes()
+}
+
+/// Next head of an audit log after chaining `entry`, replaying the entries of every
+/// settlement from a zero head gives back `AuditLog.head`
+pub fn audit_log_head(head: &[u8; 32], entry: &[u8; 32]) -> [u8; 32] {
+    hashv(&[head, entry]).to_bytes()
+}
+
+/// Leaf appended to the config's settlement tree for every settled game
+pub fn settlement_leaf(
+    game: &Pubkey,
+    game_creator: &Pubkey,
+    bet_amount: u64,
+    result: Outcome,
+    slot: u64,
+) -> [u8; 32] {
+    hashv(&[
+        game.as_ref(),
+        game_creator.as_ref(),
+        &bet_amount.to_le_bytes(),
+        &[result as u8],
+        &slot.to_le_bytes(),
+    ])
+    .to_bytes()
+}
@@ -0,0 +1,311 @@
+// program derived addresses, shared by the processor and by clients
+//
+// Games, configs and sessions are plain keypair accounts, only the accounts below are PDAs.
+
+use solana_program::pubkey::Pubkey;
+
+/// Seed of the house authority, the PDA owning the house and bet token accounts
+pub const HOUSE_AUTHORITY_SEED: &[u8] = b"roshambo";
+
+/// Seed prefix of a player's stats account, followed by the config and player addresses
+pub const PLAYER_STATS_SEED: &[u8] = b"player";
+
+/// Returns the house authority PDA and its bump
+pub fn find_house_authority(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HOUSE_AUTHORITY_SEED], program_id)
+}
+
+/// SPL Associated Token Account program, wallets send tokens to the house PDA through it
+pub mod associated_token {
+    solana_program::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
+/// Returns the associated token account of `wallet` for `mint`
+pub fn find_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), spl_token::id().as_ref(), mint.as_ref()],
+        &associated_token::id(),
+    )
+    .0
+}
+
+/// Returns the associated token account of the house PDA for `mint`. No config keeps funds
+/// there (bankrolls live in the vaults), it only collects tokens sent by mistake.
+pub fn find_house_token_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    let (house_authority, _) = find_house_authority(program_id);
+    find_associated_token_address(&house_authority, mint)
+}
+
+/// Returns the stats PDA of `player` under `config` and its bump
+pub fn find_player_stats_address(
+    config: &Pubkey,
+    player: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PLAYER_STATS_SEED, config.as_ref(), player.as_ref()],
+        program_id,
+    )
+}
+
+/// Seed prefix of a session key authority, followed by the player and session key addresses
+pub const SESSION_AUTHORITY_SEED: &[u8] = b"session-authority";
+
+/// Returns the authority PDA of `session_key` acting for `player` and its bump
+pub fn find_session_authority_address(
+    player: &Pubkey,
+    session_key: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SESSION_AUTHORITY_SEED,
+            player.as_ref(),
+            session_key.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Seed prefix of a stats snapshot, followed by the config address and the epoch (LE)
+pub const STATS_SNAPSHOT_SEED: &[u8] = b"stats";
+
+/// Returns the stats snapshot PDA of `config` for `epoch` and its bump
+pub fn find_stats_snapshot_address(
+    config: &Pubkey,
+    epoch: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[STATS_SNAPSHOT_SEED, config.as_ref(), &epoch.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Seed prefix of the authority of a config's settlement tree, followed by the config address
+pub const SETTLEMENT_TREE_AUTHORITY_SEED: &[u8] = b"tree";
+
+/// Returns the authority PDA of the settlement tree of `config` and its bump
+pub fn find_settlement_tree_authority(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SETTLEMENT_TREE_AUTHORITY_SEED, config.as_ref()],
+        program_id,
+    )
+}
+
+/// Seed prefix of a config counter shard, followed by the config address and the shard index
+pub const COUNTER_SEED: &[u8] = b"counter";
+
+/// Returns the counter PDA of `shard` for `config` and its bump
+pub fn find_counter_address(config: &Pubkey, shard: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COUNTER_SEED, config.as_ref(), &[shard]], program_id)
+}
+
+/// Shard used by `player` on a config with `shards` counter or vault shards
+pub fn player_shard(player: &Pubkey, shards: u8) -> u8 {
+    player.as_ref()[0] % shards.max(1)
+}
+
+/// Seed prefix of a house vault shard, followed by the config address and the shard index
+pub const HOUSE_VAULT_SEED: &[u8] = b"vault";
+
+/// Returns the house vault token account PDA of `shard` for `config` and its bump
+pub fn find_house_vault_address(config: &Pubkey, shard: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HOUSE_VAULT_SEED, config.as_ref(), &[shard]], program_id)
+}
+
+/// Returns the house vault the games of `player` go through on a config with `vault_shards`
+/// vaults and its bump
+pub fn find_player_vault_address(
+    config: &Pubkey,
+    vault_shards: u8,
+    player: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    find_house_vault_address(config, player_shard(player, vault_shards), program_id)
+}
+
+/// Seed prefix of the swap authority of a config, followed by the config address. It owns the
+/// escrow token accounts the house funds of the config are swapped through, the house PDA
+/// never signs a swap.
+pub const SWAP_AUTHORITY_SEED: &[u8] = b"swap";
+
+/// Returns the swap authority PDA of `config` and its bump
+pub fn find_swap_authority_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SWAP_AUTHORITY_SEED, config.as_ref()], program_id)
+}
+
+/// Seed prefix of a season's streak leaderboard, followed by the config address and the
+/// season as little-endian bytes
+pub const STREAK_LEADERBOARD_SEED: &[u8] = b"streaks";
+
+/// Returns the streak leaderboard PDA of `season` for `config` and its bump
+pub fn find_streak_leaderboard_address(
+    config: &Pubkey,
+    season: u32,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            STREAK_LEADERBOARD_SEED,
+            config.as_ref(),
+            &season.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Seed prefix of a season's result, followed by the config address and the season as
+/// little-endian bytes
+pub const SEASON_RESULT_SEED: &[u8] = b"season";
+
+/// Returns the season result PDA of `season` for `config` and its bump
+pub fn find_season_result_address(
+    config: &Pubkey,
+    season: u32,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SEASON_RESULT_SEED, config.as_ref(), &season.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Seed prefix of a daily seed, followed by the config address and the day as little-endian
+/// bytes
+pub const DAILY_SEED_SEED: &[u8] = b"daily";
+
+/// Returns the daily seed PDA of `day` for `config` and its bump
+pub fn find_daily_seed_address(config: &Pubkey, day: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DAILY_SEED_SEED, config.as_ref(), &day.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Seed prefix of an admin, followed by the config address and the admin key
+pub const ADMIN_SEED: &[u8] = b"admin";
+
+/// Returns the admin PDA of `admin` for `config` and its bump
+pub fn find_admin_address(config: &Pubkey, admin: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ADMIN_SEED, config.as_ref(), admin.as_ref()], program_id)
+}
+
+/// Seed prefix of the audit log of a config, followed by the config address
+pub const AUDIT_LOG_SEED: &[u8] = b"audit";
+
+/// Returns the audit log PDA of `config` and its bump
+pub fn find_audit_log_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUDIT_LOG_SEED, config.as_ref()], program_id)
+}
+
+/// Seed prefix of a quest, followed by the config address and the quest id
+pub const QUEST_SEED: &[u8] = b"quest";
+
+/// Returns the PDA of quest `quest_id` of `config` and its bump
+pub fn find_quest_address(config: &Pubkey, quest_id: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[QUEST_SEED, config.as_ref(), &[quest_id]], program_id)
+}
+
+/// Seed prefix of a partner, followed by the config address and the partner authority
+pub const PARTNER_SEED: &[u8] = b"partner";
+
+/// Returns the partner PDA of `authority` for `config` and its bump
+pub fn find_partner_address(
+    config: &Pubkey,
+    authority: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PARTNER_SEED, config.as_ref(), authority.as_ref()],
+        program_id,
+    )
+}
+
+/// Seed prefix of the vesting of a win, followed by the config address and the game id
+pub const VESTING_SEED: &[u8] = b"vesting";
+
+/// Returns the vesting PDA of the payout of game `game_id` of `config` and its bump. Game
+/// ids are never reused, unlike the accounts of a game pool.
+pub fn find_vesting_address(config: &Pubkey, game_id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VESTING_SEED, config.as_ref(), &game_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Seed prefix of the installments owed for a win, followed by the config address and the
+/// game id
+pub const PAYABLE_SEED: &[u8] = b"payable";
+
+/// Returns the payable PDA of game `game_id` of `config` and its bump
+pub fn find_payable_address(config: &Pubkey, game_id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYABLE_SEED, config.as_ref(), &game_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Seed prefix of the fee exemption of a player, followed by the config address and the player
+pub const FEE_EXEMPTION_SEED: &[u8] = b"fee-exempt";
+
+/// Returns the fee exemption PDA of `player` for `config` and its bump
+pub fn find_fee_exemption_address(
+    config: &Pubkey,
+    player: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FEE_EXEMPTION_SEED, config.as_ref(), player.as_ref()],
+        program_id,
+    )
+}
+
+/// Seed prefix of the heartbeat of the settlement service, followed by the config address
+pub const HEARTBEAT_SEED: &[u8] = b"heartbeat";
+
+/// Returns the heartbeat PDA of `config` and its bump
+pub fn find_heartbeat_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HEARTBEAT_SEED, config.as_ref()], program_id)
+}
+
+/// Seed of the insurance fund token account of a config, followed by the config address
+pub const INSURANCE_FUND_SEED: &[u8] = b"insurance";
+
+/// Returns the insurance fund token account PDA of `config` and its bump
+pub fn find_insurance_fund_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INSURANCE_FUND_SEED, config.as_ref()], program_id)
+}
+
+/// Seed prefix of the hourly metrics of a config, followed by the config address and the hour
+/// bucket (LE)
+pub const HOURLY_METRICS_SEED: &[u8] = b"metrics";
+
+/// Returns the hourly metrics PDA of `config` for `hour` (see `HourlyMetrics::hour`) and its
+/// bump
+pub fn find_hourly_metrics_address(
+    config: &Pubkey,
+    hour: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[HOURLY_METRICS_SEED, config.as_ref(), &hour.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Seed of the program-level account holding the rotated recovery authority
+pub const RECOVERY_AUTHORITY_SEED: &[u8] = b"recovery";
+
+/// Returns the recovery authority PDA and its bump
+pub fn find_recovery_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECOVERY_AUTHORITY_SEED], program_id)
+}
+
+/// Seed prefix of the exposure account of a config, followed by the config address
+pub const EXPOSURE_SEED: &[u8] = b"exposure";
+
+/// Returns the exposure PDA of `config` and its bump
+pub fn find_exposure_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EXPOSURE_SEED, config.as_ref()], program_id)
+}
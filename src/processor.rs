@@ -1,23 +1,75 @@
 // program logic
 
+use std::{cmp::Reverse, slice};
+
 use crate::{
+    compression,
     error::RoshamboError,
-    instruction::RoshamboInstruction,
-    state::{Config, Game},
+    event::RoshamboEvent,
+    instruction::{spl_memo, RoshamboInstruction, ALLOWED_SWAP_PROGRAMS},
+    log::{
+        log_code, log_values, structured_log, verbose_msg, LOG_AIRDROP, LOG_EMERGENCY_UNLOCK,
+        LOG_INSTRUCTION, LOG_REBALANCE, LOG_SESSION_RESULT, LOG_SWEEP,
+    },
+    outcome::{
+        audit_entry, audit_log_head, daily_host_seed, daily_seed_commitment, daily_seed_day,
+        host_seed_commitment, outcome, public_seed_commitment, session_seed_commitment,
+        settlement_leaf, win_fee, win_payout, Outcome, BPS_DENOMINATOR,
+        DEFAULT_PAYOUT_MULTIPLIER_BPS,
+    },
+    pda::{
+        find_admin_address, find_associated_token_address, find_audit_log_address,
+        find_counter_address, find_daily_seed_address, find_exposure_address,
+        find_fee_exemption_address, find_heartbeat_address, find_hourly_metrics_address,
+        find_house_authority, find_house_token_address, find_house_vault_address,
+        find_insurance_fund_address, find_partner_address, find_payable_address,
+        find_player_stats_address, find_player_vault_address, find_quest_address,
+        find_recovery_authority_address, find_season_result_address,
+        find_session_authority_address, find_settlement_tree_authority,
+        find_stats_snapshot_address, find_streak_leaderboard_address, find_swap_authority_address,
+        find_vesting_address, player_shard, ADMIN_SEED, AUDIT_LOG_SEED, COUNTER_SEED,
+        DAILY_SEED_SEED, EXPOSURE_SEED, FEE_EXEMPTION_SEED, HEARTBEAT_SEED, HOURLY_METRICS_SEED,
+        HOUSE_AUTHORITY_SEED, HOUSE_VAULT_SEED, INSURANCE_FUND_SEED, PARTNER_SEED, PAYABLE_SEED,
+        PLAYER_STATS_SEED, QUEST_SEED, RECOVERY_AUTHORITY_SEED, SEASON_RESULT_SEED,
+        SESSION_AUTHORITY_SEED, SETTLEMENT_TREE_AUTHORITY_SEED, STATS_SNAPSHOT_SEED,
+        STREAK_LEADERBOARD_SEED, SWAP_AUTHORITY_SEED, VESTING_SEED,
+    },
+    state::{
+        max_allowed_bet, Admin, AuditLog, CashbackTier, Config, Counter, DailySeed, Exposure,
+        FeeExemption, Game, GameStatus, Heartbeat, HourlyMetrics, Partner, Payable, PlayerStats,
+        Quest, RecoveryAuthority, SeasonResult, Session, SessionAuthority, StatsSnapshot,
+        StreakEntry, StreakLeaderboard, Vesting, ACHIEVEMENT_1000_GAMES, ACHIEVEMENT_100_GAMES,
+        ACHIEVEMENT_FIRST_DRAW, ACHIEVEMENT_FIRST_WIN, ACHIEVEMENT_MAX_BET_WIN,
+        ACHIEVEMENT_WIN_STREAK_5, ADMIN_CAN_PAUSE, ADMIN_CAN_SETTLE, ADMIN_CAN_UPDATE_LIMITS,
+        ADMIN_CAN_WITHDRAW, EMERGENCY_WITHDRAW_DELAY_SLOTS, INSURANCE_WITHDRAW_DELAY_SLOTS,
+        MAX_BET_WHOLE_TOKENS, MAX_CASHBACK_TIERS, MAX_CLAIM_BATCH, MAX_QUESTS, MAX_SEED_SLOT_AGE,
+        QUEST_KIND_WAGER, QUEST_KIND_WINS, QUEST_PERIOD_DAY, QUEST_PERIOD_WEEK, QUEST_WEEK_SLOTS,
+        STREAK_LEADERBOARD_SIZE, U64_LENGTH,
+    },
+    validation::{
+        assert_not_frozen, assert_owned_by, assert_owner_closes, assert_pda, assert_rent_exempt,
+        assert_signer, assert_token_account,
+    },
 };
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
-    msg,
+    instruction::{AccountMeta, Instruction},
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_option::COption,
     program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
     rent::Rent,
+    system_instruction, system_program,
     sysvar::Sysvar,
 };
-use spl_token::state::Account as TokenAccount;
+use spl_token::state::{Account, Mint, Multisig};
+
+/// Games, volume, house PnL, settled volume, paid out amount, cancelled games and refunded
+/// amount of a config, see `Processor::config_counters`
+type ConfigCounters = (u64, u64, i64, u64, u64, u64, u64);
 
 pub struct Processor;
 impl Processor {
@@ -27,36 +79,470 @@ impl Processor {
         instruction_data: &[u8],
     ) -> ProgramResult {
         let instruction = RoshamboInstruction::unpack(instruction_data)?;
+        if !cfg!(feature = "verbose-logs") {
+            log_values(LOG_INSTRUCTION, &[instruction_data[0] as u64]);
+        }
 
         match instruction {
             RoshamboInstruction::Initialize {
                 min_bet_amount,
                 max_bet_amount,
             } => {
-                msg!("Instruction: Initialize");
-                Self::process_initialize(accounts, min_bet_amount, max_bet_amount)
+                verbose_msg!("Instruction: Initialize");
+                Self::process_initialize(accounts, min_bet_amount, max_bet_amount, program_id)
+            }
+            RoshamboInstruction::NewGame {
+                amount,
+                public_seed_commitment,
+                seed_slot,
+            } => {
+                verbose_msg!("Instruction: NewGame");
+                Self::process_new_game(
+                    accounts,
+                    amount,
+                    public_seed_commitment,
+                    seed_slot,
+                    0,
+                    false,
+                    program_id,
+                )
+            }
+            RoshamboInstruction::RegisterSessionKey {
+                expiry_slot,
+                max_wager,
+            } => {
+                verbose_msg!("Instruction: Register Session Key");
+                Self::process_register_session_key(accounts, expiry_slot, max_wager, program_id)
+            }
+            RoshamboInstruction::RevokeSessionKey => {
+                verbose_msg!("Instruction: Revoke Session Key");
+                Self::process_revoke_session_key(accounts, program_id)
+            }
+            RoshamboInstruction::UpdateSettlementMemo { enabled } => {
+                verbose_msg!("Instruction: Update Settlement Memo");
+                Self::process_update_settlement_memo(accounts, enabled, program_id)
+            }
+            RoshamboInstruction::SnapshotStats => {
+                verbose_msg!("Instruction: Snapshot Stats");
+                Self::process_snapshot_stats(accounts, program_id)
+            }
+            RoshamboInstruction::InitSettlementTree {
+                max_depth,
+                max_buffer_size,
+            } => {
+                verbose_msg!("Instruction: Init Settlement Tree");
+                Self::process_init_settlement_tree(accounts, max_depth, max_buffer_size, program_id)
+            }
+            RoshamboInstruction::SetCounterShards { shards } => {
+                verbose_msg!("Instruction: Set Counter Shards");
+                Self::process_set_counter_shards(accounts, shards, program_id)
+            }
+            RoshamboInstruction::InitHouseVaults { shards } => {
+                verbose_msg!("Instruction: Init House Vaults");
+                Self::process_init_house_vaults(accounts, shards, program_id)
+            }
+            RoshamboInstruction::UpdateGamePool { enabled } => {
+                verbose_msg!("Instruction: Update Game Pool");
+                Self::process_update_game_pool(accounts, enabled, program_id)
+            }
+            RoshamboInstruction::UpdateWithdrawBond { bond } => {
+                verbose_msg!("Instruction: Update Withdraw Bond");
+                Self::process_update_withdraw_bond(accounts, bond, program_id)
+            }
+            RoshamboInstruction::ScheduleEmergencyWithdraw { enabled } => {
+                verbose_msg!("Instruction: Schedule Emergency Withdraw");
+                Self::process_schedule_emergency_withdraw(accounts, enabled, program_id)
+            }
+            RoshamboInstruction::InitStreakLeaderboard => {
+                verbose_msg!("Instruction: Init Streak Leaderboard");
+                Self::process_init_streak_leaderboard(accounts, program_id)
+            }
+            RoshamboInstruction::EndSeason => {
+                verbose_msg!("Instruction: End Season");
+                Self::process_end_season(accounts, program_id)
+            }
+            RoshamboInstruction::StartSeason => {
+                verbose_msg!("Instruction: Start Season");
+                Self::process_start_season(accounts, program_id)
+            }
+            RoshamboInstruction::Airdrop { amounts } => {
+                verbose_msg!("Instruction: Airdrop");
+                Self::process_airdrop(accounts, &amounts, program_id)
+            }
+            RoshamboInstruction::CommitDailySeed { day, commitment } => {
+                verbose_msg!("Instruction: Commit Daily Seed");
+                Self::process_commit_daily_seed(accounts, day, commitment, program_id)
+            }
+            RoshamboInstruction::RevealDailySeed { day, secret } => {
+                verbose_msg!("Instruction: Reveal Daily Seed");
+                Self::process_reveal_daily_seed(accounts, day, secret, program_id)
+            }
+            RoshamboInstruction::UpdateDailySeed { enabled } => {
+                verbose_msg!("Instruction: Update Daily Seed");
+                Self::process_update_daily_seed(accounts, enabled, program_id)
+            }
+            RoshamboInstruction::ClaimDeferred => {
+                verbose_msg!("Instruction: Claim Deferred");
+                Self::process_claim_deferred(accounts, program_id)
+            }
+            RoshamboInstruction::UpdatePause { paused } => {
+                verbose_msg!("Instruction: Update Pause");
+                Self::process_update_pause(accounts, paused, program_id)
+            }
+            RoshamboInstruction::SetAdmin { permissions } => {
+                verbose_msg!("Instruction: Set Admin");
+                Self::process_set_admin(accounts, permissions, program_id)
+            }
+            RoshamboInstruction::InitAuditLog => {
+                verbose_msg!("Instruction: Init Audit Log");
+                Self::process_init_audit_log(accounts, program_id)
+            }
+            RoshamboInstruction::UpdateDisputeWindow { window_slots } => {
+                verbose_msg!("Instruction: Update Dispute Window");
+                Self::process_update_dispute_window(accounts, window_slots, program_id)
+            }
+            RoshamboInstruction::Dispute => {
+                verbose_msg!("Instruction: Dispute");
+                Self::process_dispute(accounts, program_id)
+            }
+            RoshamboInstruction::ResolveDispute { uphold } => {
+                verbose_msg!("Instruction: Resolve Dispute");
+                Self::process_resolve_dispute(accounts, uphold, program_id)
+            }
+            RoshamboInstruction::UpdateLargeWinHold {
+                threshold,
+                delay_slots,
+            } => {
+                verbose_msg!("Instruction: Update Large Win Hold");
+                Self::process_update_large_win_hold(accounts, threshold, delay_slots, program_id)
+            }
+            RoshamboInstruction::CancelGame => {
+                verbose_msg!("Instruction: Cancel Game");
+                Self::process_cancel_game(accounts, program_id)
+            }
+            RoshamboInstruction::UpdateCancelFee { fee_bps } => {
+                verbose_msg!("Instruction: Update Cancel Fee");
+                Self::process_update_cancel_fee(accounts, fee_bps, program_id)
+            }
+            RoshamboInstruction::UpdateMinStake { min_stake } => {
+                verbose_msg!("Instruction: Update Min Stake");
+                Self::process_update_min_stake(accounts, min_stake, program_id)
+            }
+            RoshamboInstruction::UpdateBurn { burn_bps } => {
+                verbose_msg!("Instruction: Update Burn");
+                Self::process_update_burn(accounts, burn_bps, program_id)
+            }
+            RoshamboInstruction::UpdateCashback {
+                cashback_bps,
+                start_slot,
+                end_slot,
+            } => {
+                verbose_msg!("Instruction: Update Cashback");
+                Self::process_update_cashback(
+                    accounts,
+                    cashback_bps,
+                    start_slot,
+                    end_slot,
+                    program_id,
+                )
+            }
+            RoshamboInstruction::ClaimCashback => {
+                verbose_msg!("Instruction: Claim Cashback");
+                Self::process_claim_cashback(accounts, program_id)
+            }
+            RoshamboInstruction::SetQuest {
+                quest_id,
+                kind,
+                period,
+                target,
+                reward,
+            } => {
+                verbose_msg!("Instruction: Set Quest");
+                Self::process_set_quest(
+                    accounts, quest_id, kind, period, target, reward, program_id,
+                )
+            }
+            RoshamboInstruction::ClaimQuestReward => {
+                verbose_msg!("Instruction: Claim Quest Reward");
+                Self::process_claim_quest_reward(accounts, program_id)
+            }
+            RoshamboInstruction::RegisterPartner => {
+                verbose_msg!("Instruction: Register Partner");
+                Self::process_register_partner(accounts, program_id)
+            }
+            RoshamboInstruction::UpdatePartnerShare { share_bps } => {
+                verbose_msg!("Instruction: Update Partner Share");
+                Self::process_update_partner_share(accounts, share_bps, program_id)
+            }
+            RoshamboInstruction::ClaimRewards { seeds } => {
+                verbose_msg!("Instruction: Claim Rewards");
+                Self::process_claim_rewards(accounts, &seeds, program_id)
+            }
+            RoshamboInstruction::UpdateSwapProgram => {
+                verbose_msg!("Instruction: Update Swap Program");
+                Self::process_update_swap_program(accounts, program_id)
+            }
+            RoshamboInstruction::Rebalance {
+                amount_in,
+                min_amount_out,
+                swap_data,
+            } => {
+                verbose_msg!("Instruction: Rebalance");
+                Self::process_rebalance(accounts, amount_in, min_amount_out, &swap_data, program_id)
+            }
+            RoshamboInstruction::UpdateTreasury => {
+                verbose_msg!("Instruction: Update Treasury");
+                Self::process_update_treasury(accounts, program_id)
+            }
+            RoshamboInstruction::SweepProfits {
+                amount_in,
+                min_amount_out,
+                swap_data,
+            } => {
+                verbose_msg!("Instruction: Sweep Profits");
+                Self::process_sweep_profits(
+                    accounts,
+                    amount_in,
+                    min_amount_out,
+                    &swap_data,
+                    program_id,
+                )
+            }
+            RoshamboInstruction::UpdateVesting {
+                threshold,
+                vesting_slots,
+            } => {
+                verbose_msg!("Instruction: Update Vesting");
+                Self::process_update_vesting(accounts, threshold, vesting_slots, program_id)
+            }
+            RoshamboInstruction::ReleaseVested => {
+                verbose_msg!("Instruction: Release Vested");
+                Self::process_release_vested(accounts, program_id)
+            }
+            RoshamboInstruction::UpdatePayoutCap {
+                max_payout,
+                installment_slots,
+            } => {
+                verbose_msg!("Instruction: Update Payout Cap");
+                Self::process_update_payout_cap(accounts, max_payout, installment_slots, program_id)
+            }
+            RoshamboInstruction::PayInstallment => {
+                verbose_msg!("Instruction: Pay Installment");
+                Self::process_pay_installment(accounts, program_id)
+            }
+            RoshamboInstruction::UpdateConfigCooldown { cooldown_slots } => {
+                verbose_msg!("Instruction: Update Config Cooldown");
+                Self::process_update_config_cooldown(accounts, cooldown_slots, program_id)
+            }
+            RoshamboInstruction::SetFeeExempt { exempt } => {
+                verbose_msg!("Instruction: Set Fee Exempt");
+                Self::process_set_fee_exempt(accounts, exempt, program_id)
+            }
+            RoshamboInstruction::UpdateHeartbeatMaxAge { max_age_slots } => {
+                verbose_msg!("Instruction: Update Heartbeat Max Age");
+                Self::process_update_heartbeat_max_age(accounts, max_age_slots, program_id)
+            }
+            RoshamboInstruction::Heartbeat => {
+                verbose_msg!("Instruction: Heartbeat");
+                Self::process_heartbeat(accounts, program_id)
+            }
+            RoshamboInstruction::UpdateInsuranceFund { insurance_bps } => {
+                verbose_msg!("Instruction: Update Insurance Fund");
+                Self::process_update_insurance_fund(accounts, insurance_bps, program_id)
+            }
+            RoshamboInstruction::ScheduleInsuranceWithdraw { enabled } => {
+                verbose_msg!("Instruction: Schedule Insurance Withdraw");
+                Self::process_schedule_insurance_withdraw(accounts, enabled, program_id)
+            }
+            RoshamboInstruction::WithdrawInsurance { amount } => {
+                verbose_msg!("Instruction: Withdraw Insurance");
+                Self::process_withdraw_insurance(accounts, amount, program_id)
+            }
+            RoshamboInstruction::PayInsuranceClaim { amount } => {
+                verbose_msg!("Instruction: Pay Insurance Claim");
+                Self::process_pay_insurance_claim(accounts, amount, program_id)
+            }
+            RoshamboInstruction::UpdateCashbackTiers { tiers } => {
+                verbose_msg!("Instruction: Update Cashback Tiers");
+                Self::process_update_cashback_tiers(accounts, &tiers, program_id)
+            }
+            RoshamboInstruction::InitHourlyMetrics { hour } => {
+                verbose_msg!("Instruction: Init Hourly Metrics");
+                Self::process_init_hourly_metrics(accounts, hour, program_id)
+            }
+            RoshamboInstruction::UpdateHourlyMetrics { enabled } => {
+                verbose_msg!("Instruction: Update Hourly Metrics");
+                Self::process_update_hourly_metrics(accounts, enabled, program_id)
+            }
+            RoshamboInstruction::UpdateKellyFraction { fraction_bps } => {
+                verbose_msg!("Instruction: Update Kelly Fraction");
+                Self::process_update_kelly_fraction(accounts, fraction_bps, program_id)
+            }
+            RoshamboInstruction::RecoverTokens => {
+                verbose_msg!("Instruction: Recover Tokens");
+                Self::process_recover_tokens(accounts, program_id)
+            }
+            RoshamboInstruction::RecoverLamports => {
+                verbose_msg!("Instruction: Recover Lamports");
+                Self::process_recover_lamports(accounts, program_id)
+            }
+            RoshamboInstruction::ReclaimRent => {
+                verbose_msg!("Instruction: Reclaim Rent");
+                Self::process_reclaim_rent(accounts, program_id)
+            }
+            RoshamboInstruction::UpdateMaxSettleAge { max_age_slots } => {
+                verbose_msg!("Instruction: Update Max Settle Age");
+                Self::process_update_max_settle_age(accounts, max_age_slots, program_id)
+            }
+            RoshamboInstruction::UpdateExposureTracking { enabled } => {
+                verbose_msg!("Instruction: Update Exposure Tracking");
+                Self::process_update_exposure_tracking(accounts, enabled, program_id)
+            }
+            RoshamboInstruction::UpdateCircuitBreaker {
+                max_loss,
+                window_slots,
+            } => {
+                verbose_msg!("Instruction: Update Circuit Breaker");
+                Self::process_update_circuit_breaker(accounts, max_loss, window_slots, program_id)
+            }
+            RoshamboInstruction::CommitSessionSeed {
+                host_seed_commitment,
+            } => {
+                verbose_msg!("Instruction: Commit Session Seed");
+                Self::process_commit_session_seed(accounts, host_seed_commitment, program_id)
+            }
+            RoshamboInstruction::RevealSessionSeed { host_seed } => {
+                verbose_msg!("Instruction: Reveal Session Seed");
+                Self::process_reveal_session_seed(accounts, host_seed, program_id)
+            }
+            RoshamboInstruction::SetRecoveryAuthority => {
+                verbose_msg!("Instruction: Set Recovery Authority");
+                Self::process_set_recovery_authority(accounts, program_id)
+            }
+            RoshamboInstruction::NewGameV2 {
+                amount,
+                public_seed_commitment,
+                seed_slot,
+                expiry_slot,
+                frontend_id,
+            } => {
+                verbose_msg!("Instruction: NewGame V2");
+                let slot = Clock::get()?.slot;
+                if expiry_slot.is_some_and(|expiry_slot| slot > expiry_slot) {
+                    return Err(RoshamboError::InstructionExpired.into());
+                }
+                Self::process_new_game(
+                    accounts,
+                    amount,
+                    public_seed_commitment,
+                    seed_slot,
+                    frontend_id,
+                    false,
+                    program_id,
+                )
             }
-            RoshamboInstruction::NewGame { amount } => {
-                msg!("Instruction: NewGame");
-                Self::process_new_game(accounts, amount)
+            RoshamboInstruction::NewGameDelegated {
+                amount,
+                public_seed_commitment,
+                seed_slot,
+            } => {
+                verbose_msg!("Instruction: NewGame Delegated");
+                Self::process_new_game(
+                    accounts,
+                    amount,
+                    public_seed_commitment,
+                    seed_slot,
+                    0,
+                    true,
+                    program_id,
+                )
             }
             RoshamboInstruction::ClaimReward {
                 host_seed,
                 public_seed,
             } => {
-                msg!("Instruction: Claim");
-                Self::process_claim(accounts, host_seed, public_seed, program_id)
+                verbose_msg!("Instruction: Claim");
+                Self::process_claim(accounts, host_seed, public_seed, None, program_id)
+            }
+            RoshamboInstruction::ClaimAndRebet {
+                host_seed,
+                public_seed,
+                next_public_seed_commitment,
+                next_seed_slot,
+            } => {
+                verbose_msg!("Instruction: Claim And Rebet");
+                Self::process_claim(
+                    accounts,
+                    host_seed,
+                    public_seed,
+                    Some((next_public_seed_commitment, next_seed_slot)),
+                    program_id,
+                )
+            }
+            RoshamboInstruction::DeclineGame => {
+                verbose_msg!("Instruction: Decline Game");
+                Self::process_decline_game(accounts, program_id)
+            }
+            RoshamboInstruction::UpdateRateLimit {
+                max_games_per_window,
+                window_slots,
+            } => {
+                verbose_msg!("Instruction: Update Rate Limit");
+                Self::process_update_rate_limit(
+                    accounts,
+                    max_games_per_window,
+                    window_slots,
+                    program_id,
+                )
+            }
+            RoshamboInstruction::CommitHostSeed {
+                host_seed_commitment,
+            } => {
+                verbose_msg!("Instruction: Commit Host Seed");
+                Self::process_commit_host_seed(accounts, host_seed_commitment, program_id)
+            }
+            RoshamboInstruction::RevealSeed { public_seed } => {
+                verbose_msg!("Instruction: Reveal Seed");
+                Self::process_reveal_seed(accounts, public_seed, program_id)
+            }
+            RoshamboInstruction::UpdateFees {
+                fee_bps,
+                payout_multiplier_bps,
+            } => {
+                verbose_msg!("Instruction: Update Fees");
+                Self::process_update_fees(accounts, fee_bps, payout_multiplier_bps, program_id)
+            }
+            RoshamboInstruction::UpdateSettleDelay {
+                min_settle_delay_slots,
+            } => {
+                verbose_msg!("Instruction: Update Settle Delay");
+                Self::process_update_settle_delay(accounts, min_settle_delay_slots, program_id)
             }
             RoshamboInstruction::UpdateConfig {
                 min_bet_amount,
                 max_bet_amount,
             } => {
-                msg!("Instruction: Update Config");
-                Self::process_update_config(accounts, min_bet_amount, max_bet_amount)
+                verbose_msg!("Instruction: Update Config");
+                Self::process_update_config(accounts, min_bet_amount, max_bet_amount, program_id)
+            }
+            RoshamboInstruction::Withdraw { amount, emergency } => {
+                verbose_msg!("Instruction: Withdraw");
+                Self::process_withdraw(accounts, amount, emergency, program_id)
+            }
+            RoshamboInstruction::OpenSession { deposit } => {
+                verbose_msg!("Instruction: Open Session");
+                Self::process_open_session(accounts, deposit, program_id)
+            }
+            RoshamboInstruction::PlaySession {
+                amount,
+                public_seed,
+            } => {
+                verbose_msg!("Instruction: Play Session");
+                Self::process_play_session(accounts, amount, public_seed, program_id)
             }
-            RoshamboInstruction::Withdraw { amount } => {
-                msg!("Instruction: Withdraw");
-                Self::process_withdraw(accounts, amount, program_id)
+            RoshamboInstruction::CloseSession => {
+                verbose_msg!("Instruction: Close Session");
+                Self::process_close_session(accounts, program_id)
             }
         }
     }
@@ -65,21 +551,17 @@ impl Processor {
         accounts: &[AccountInfo],
         min_bet_amount: u64,
         max_bet_amount: u64,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
         let config_creator = next_account_info(account_info_iter)?;
-        if !config_creator.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
+        assert_signer(config_creator)?;
 
         // Config Account (store config info data) -> Make sure fee exempt
         let config_account = next_account_info(account_info_iter)?;
-
-        let rent = Rent::get()?;
-        if !rent.is_exempt(config_account.lamports(), config_account.data_len()) {
-            return Err(RoshamboError::NotRentExempt.into());
-        }
+        assert_owned_by(config_account, program_id)?;
+        assert_rent_exempt(&Rent::get()?, config_account)?;
 
         // Check if this config account is already initialize
         let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
@@ -88,6 +570,9 @@ impl Processor {
         }
 
         let mint_token_account = next_account_info(account_info_iter)?;
+        assert_owned_by(mint_token_account, &spl_token::id())?;
+        let mint = Mint::unpack(&mint_token_account.try_borrow_data()?)?;
+        Self::assert_bet_limits(min_bet_amount, max_bet_amount, mint.decimals)?;
 
         // Update game account with new game data
         config_info.is_initialized = true;
@@ -96,93 +581,590 @@ impl Processor {
         config_info.max_bet_amount = max_bet_amount;
         config_info.owner_pubkey = *config_creator.key;
         config_info.mint_token_pubkey = *mint_token_account.key;
+        config_info.max_games_per_window = 0;
+        config_info.rate_limit_window_slots = 0;
+        config_info.min_settle_delay_slots = 0;
+        config_info.fee_bps = 0;
+        config_info.payout_multiplier_bps = DEFAULT_PAYOUT_MULTIPLIER_BPS;
+        config_info.settlement_memo = false;
+        config_info.total_volume = 0;
+        config_info.house_pnl = 0;
+        config_info.settlement_tree = COption::None;
+        config_info.counter_shards = 0;
+        config_info.vault_shards = 0;
+        config_info.game_pool = false;
+        config_info.outstanding_exposure = 0;
+        config_info.withdraw_bond = 0;
+        config_info.emergency_unlock_slot = COption::None;
+        config_info.season = 0;
+        config_info.streak_leaderboard = false;
+        config_info.daily_seed = false;
+        config_info.decimals = mint.decimals;
+        config_info.paused = false;
+        config_info.audit_log = false;
+        config_info.arbiter = COption::None;
+        config_info.dispute_window_slots = 0;
+        config_info.large_win_threshold = 0;
+        config_info.large_win_delay_slots = 0;
+        config_info.cancel_fee_bps = 0;
+        config_info.stake_mint = COption::None;
+        config_info.min_stake = 0;
+        config_info.burn_bps = 0;
+        config_info.cashback_bps = 0;
+        config_info.cashback_start_slot = 0;
+        config_info.cashback_end_slot = 0;
+        config_info.cashback_tiers = [CashbackTier::default(); MAX_CASHBACK_TIERS];
+        config_info.partner_share_bps = 0;
+        config_info.swap_program = COption::None;
+        config_info.treasury = COption::None;
+        config_info.swept_profits = 0;
+        config_info.vesting_threshold = 0;
+        config_info.vesting_slots = 0;
+        config_info.max_payout_per_game = 0;
+        config_info.installment_slots = 0;
+        config_info.settled_volume = 0;
+        config_info.total_paid_out = 0;
+        config_info.config_cooldown_slots = 0;
+        config_info.limits_updated_slot = 0;
+        config_info.heartbeat_max_age_slots = 0;
+        config_info.insurance_bps = 0;
+        config_info.hourly_metrics = false;
+        config_info.kelly_fraction_bps = 0;
+        config_info.games_cancelled = 0;
+        config_info.refunds_total = 0;
+        config_info.max_settle_age_slots = 0;
+        config_info.exposure_tracking = false;
+        config_info.breaker_max_loss = 0;
+        config_info.breaker_window_slots = 0;
+        config_info.breaker_window_start = 0;
+        config_info.breaker_window_loss = 0;
+        config_info.breaker_tripped = false;
+        config_info.settlement_mint = COption::None;
+        config_info.insurance_unlock_slot = COption::None;
         Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
 
         Ok(())
     }
 
-    fn process_new_game(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    fn process_new_game(
+        accounts: &[AccountInfo],
+        amount: u64,
+        public_seed_commitment: [u8; 32],
+        seed_slot: u64,
+        frontend_id: u16,
+        delegated: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
         let game_creator = next_account_info(account_info_iter)?;
-        if !game_creator.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
+        // a delegated stake can also be wagered by a session key of the creator, checked below,
+        // a creator that is an SPL token multisig signs through its signers
+        let multisig_signers = if delegated || game_creator.is_signer {
+            Vec::new()
+        } else {
+            Self::multisig_signers(game_creator, accounts.get(8..).unwrap_or_default())?
+        };
 
         let creator_token_account = next_account_info(account_info_iter)?;
         let game_account = next_account_info(account_info_iter)?;
         let house_token_account = next_account_info(account_info_iter)?;
         let config_account = next_account_info(account_info_iter)?;
 
-        // Validate if this token account match with config account
-        // No need to check house_token_account because creator_token_account will transfer to house_token_account later on
-        let creator_token_account_info =
-            TokenAccount::unpack(&creator_token_account.try_borrow_data()?)?;
+        assert_owned_by(config_account, program_id)?;
         let mut config_account_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_account_info)?;
+        let counters = Self::config_counters(&config_account_info);
+
+        // Validate if both token accounts match with config account
+        let (pda, nonce) = find_house_authority(program_id);
+        let creator_token = assert_token_account(
+            creator_token_account,
+            &config_account_info.mint_token_pubkey,
+            Some(game_creator.key),
+        )?;
+        assert_not_frozen(&creator_token)?;
+        assert_owner_closes(&creator_token)?;
+        // the stake is pulled by the house PDA, approved beforehand as delegate of the creator
+        // for at least the stake, any other delegate is left alone as the creator signs
+        if delegated
+            && (creator_token.delegate != COption::Some(pda)
+                || creator_token.delegated_amount < amount)
+        {
+            return Err(RoshamboError::DelegateNotApproved.into());
+        }
+        let house_token = assert_token_account(
+            house_token_account,
+            &config_account_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_account_info,
+            game_creator.key,
+            program_id,
+        )?;
+        Self::assert_min_stake(accounts, game_creator.key, &config_account_info)?;
+        Self::assert_host_alive(
+            accounts,
+            config_account.key,
+            &config_account_info,
+            program_id,
+        )?;
+        let fee_bps = Self::player_fee_bps(
+            accounts,
+            config_account.key,
+            &config_account_info,
+            game_creator.key,
+            program_id,
+        )?;
+
+        // the house must be able to pay this bet on top of every open game
+        let outstanding_exposure = Self::outstanding_exposure(
+            accounts,
+            config_account.key,
+            &config_account_info,
+            game_creator.key,
+            program_id,
+        )?;
+        if amount
+            > max_allowed_bet(
+                &config_account_info,
+                house_token.amount,
+                outstanding_exposure,
+            )
+        {
+            return Err(RoshamboError::HouseInsolvent.into());
+        }
+        let exposure = win_payout(amount, config_account_info.payout_multiplier_bps, fee_bps)
+            .ok_or(RoshamboError::AmountOverflow)?;
 
-        if creator_token_account_info.mint != config_account_info.mint_token_pubkey {
+        let game_id = Self::next_game_id(
+            accounts,
+            config_account.key,
+            &config_account_info,
+            game_creator.key,
+            program_id,
+        )?;
+        Self::init_game(
+            game_account,
+            game_creator.key,
+            amount,
+            public_seed_commitment,
+            seed_slot,
+            config_account.key,
+            &mut config_account_info,
+            fee_bps,
+            game_id,
+            Self::find_partner(accounts, config_account.key, game_creator.key, program_id)?,
+            frontend_id,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let player_stats_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        let signer = if delegated {
+            Self::authorize_player(
+                game_creator,
+                accounts.get(9..).unwrap_or_default(),
+                amount,
+                program_id,
+            )?
+        } else {
+            multisig_signers.first().copied().unwrap_or(game_creator)
+        };
+
+        // count the game against the player's rate limit
+        let mut player_stats = Self::load_or_create_player_stats(
+            signer,
+            game_creator,
+            player_stats_account,
+            system_program_account,
+            config_account.key,
+            program_id,
+        )?;
+        Self::record_new_game(&mut player_stats, &config_account_info)?;
+        PlayerStats::pack(
+            player_stats,
+            &mut player_stats_account.try_borrow_mut_data()?,
+        )?;
+
+        // the insurance share of the stake never reaches the bankroll
+        let mut deposits = vec![(house_token_account, amount)];
+        if let Some((insurance_fund, cut)) = Self::insurance_cut(
+            accounts,
+            config_account.key,
+            &config_account_info,
+            amount,
+            program_id,
+        )? {
+            deposits = vec![(house_token_account, amount - cut), (insurance_fund, cut)];
+            config_account_info.house_pnl = config_account_info
+                .house_pnl
+                .checked_sub(Self::signed_amount(cut)?)
+                .ok_or(RoshamboError::AmountOverflow)?;
+        }
+
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_account_info,
+            counters,
+            Self::signed_amount(exposure)?,
+            1,
+            game_creator.key,
+            program_id,
+        )?;
+
+        verbose_msg!("Calling the token program to transfer token to house token account...");
+        if delegated {
+            let pda_program = next_account_info(account_info_iter)?;
+            assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+            // CPI call token program transfer bet amount to house PDA, signed by the PDA as delegate
+            for (destination, amount) in deposits {
+                let deposit_bet_ix = spl_token::instruction::transfer_checked(
+                    token_program.key,
+                    creator_token_account.key,
+                    mint_account.key,
+                    destination.key,
+                    &pda,
+                    &[&pda],
+                    amount,
+                    decimals,
+                )?;
+                invoke_signed(
+                    &deposit_bet_ix,
+                    &[
+                        creator_token_account.clone(),
+                        destination.clone(),
+                        pda_program.clone(),
+                        mint_account.clone(),
+                        token_program.clone(),
+                    ],
+                    &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+                )?;
+            }
+        } else {
+            // CPI call token program transfer bet amount to house PDA
+            let signer_keys = if multisig_signers.is_empty() {
+                vec![game_creator.key]
+            } else {
+                multisig_signers.iter().map(|signer| signer.key).collect()
+            };
+            for (destination, amount) in deposits {
+                let deposit_bet_ix = spl_token::instruction::transfer_checked(
+                    token_program.key,
+                    creator_token_account.key,
+                    mint_account.key,
+                    destination.key,
+                    game_creator.key,
+                    &signer_keys,
+                    amount,
+                    decimals,
+                )?;
+                let mut account_infos = vec![
+                    creator_token_account.clone(),
+                    destination.clone(),
+                    game_creator.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ];
+                account_infos.extend(multisig_signers.iter().map(|signer| (*signer).clone()));
+                invoke(&deposit_bet_ix, &account_infos)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insurance fund of the config and its share of a stake of `amount`, `None` unless the
+    /// config has an insurance cut. The fund can be passed anywhere in the accounts (see
+    /// `instruction::with_insurance_fund`).
+    fn insurance_cut<'a, 'b>(
+        accounts: &'b [AccountInfo<'a>],
+        config_key: &Pubkey,
+        config_info: &Config,
+        amount: u64,
+        program_id: &Pubkey,
+    ) -> Result<Option<(&'b AccountInfo<'a>, u64)>, ProgramError> {
+        let cut =
+            (amount as u128 * config_info.insurance_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        if cut == 0 {
+            return Ok(None);
+        }
+
+        let (insurance_fund_key, _) = find_insurance_fund_address(config_key, program_id);
+        let insurance_fund = Self::find_account(accounts, &insurance_fund_key)?;
+        let (pda, _nonce) = find_house_authority(program_id);
+        assert_token_account(insurance_fund, &config_info.mint_token_pubkey, Some(&pda))?;
+        Ok(Some((insurance_fund, cut)))
+    }
+
+    /// Loads the player's stats PDA for this config, creating it on the player's first game
+    fn load_or_create_player_stats<'a>(
+        payer: &AccountInfo<'a>,
+        player: &AccountInfo<'a>,
+        player_stats_account: &AccountInfo<'a>,
+        system_program_account: &AccountInfo<'a>,
+        config_key: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<PlayerStats, ProgramError> {
+        let (stats_pda, bump) = find_player_stats_address(config_key, player.key, program_id);
+        if *player_stats_account.key != stats_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if player_stats_account.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                payer,
+                player_stats_account,
+                system_program_account,
+                PlayerStats::LEN,
+                &[
+                    PLAYER_STATS_SEED,
+                    config_key.as_ref(),
+                    player.key.as_ref(),
+                    &[bump],
+                ],
+                program_id,
+            )?;
+
+            return Ok(PlayerStats {
+                is_initialized: true,
+                player_pubkey: *player.key,
+                config_pubkey: *config_key,
+                games_played: 0,
+                window_start_slot: 0,
+                games_in_window: 0,
+                wins: 0,
+                win_streak: 0,
+                achievements: 0,
+                season: 0,
+                season_games: 0,
+                season_wins: 0,
+                cashback_promo: 0,
+                cashback_net_loss: 0,
+                cashback_volume: 0,
+                cashback_accrued: 0,
+                quest_day: 0,
+                day_wins: 0,
+                day_wagered: 0,
+                day_quests_claimed: 0,
+                quest_week: 0,
+                week_wins: 0,
+                week_wagered: 0,
+                week_quests_claimed: 0,
+                referrer: COption::None,
+                games_cancelled: 0,
+                refunds_total: 0,
+            });
+        }
+
+        Self::load_player_stats(player_stats_account, player.key, config_key, program_id)
+    }
+
+    /// Loads an existing player stats account and checks it belongs to `player` on this config
+    fn load_player_stats(
+        player_stats_account: &AccountInfo,
+        player: &Pubkey,
+        config_key: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<PlayerStats, ProgramError> {
+        assert_owned_by(player_stats_account, program_id)?;
+        let player_stats = PlayerStats::unpack(&player_stats_account.try_borrow_data()?)?;
+        if player_stats.player_pubkey != *player || player_stats.config_pubkey != *config_key {
             return Err(ProgramError::InvalidAccountData);
         }
+        Ok(player_stats)
+    }
 
-        // Game Account (store game info data) -> Make sure fee exempt
-        let rent = Rent::get()?;
-        if !rent.is_exempt(game_account.lamports(), game_account.data_len()) {
-            return Err(RoshamboError::NotRentExempt.into());
+    /// Creates a rent exempt, program owned account at a PDA, funded by `payer`.
+    /// Works even if someone already sent lamports to the address.
+    fn create_pda_account<'a>(
+        payer: &AccountInfo<'a>,
+        new_account: &AccountInfo<'a>,
+        system_program_account: &AccountInfo<'a>,
+        space: usize,
+        signer_seeds: &[&[u8]],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let required_lamports = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(new_account.lamports());
+
+        if new_account.lamports() == 0 {
+            return invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    new_account.key,
+                    required_lamports,
+                    space as u64,
+                    program_id,
+                ),
+                &[
+                    payer.clone(),
+                    new_account.clone(),
+                    system_program_account.clone(),
+                ],
+                &[signer_seeds],
+            );
+        }
+
+        if required_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, new_account.key, required_lamports),
+                &[
+                    payer.clone(),
+                    new_account.clone(),
+                    system_program_account.clone(),
+                ],
+            )?;
+        }
+        invoke_signed(
+            &system_instruction::allocate(new_account.key, space as u64),
+            &[new_account.clone(), system_program_account.clone()],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(new_account.key, program_id),
+            &[new_account.clone(), system_program_account.clone()],
+            &[signer_seeds],
+        )
+    }
+
+    /// Counts a new game against the player's rate limit window, if the config enables one
+    fn record_new_game(player_stats: &mut PlayerStats, config_info: &Config) -> ProgramResult {
+        if config_info.max_games_per_window > 0 {
+            let slot = Clock::get()?.slot;
+            if slot
+                >= player_stats
+                    .window_start_slot
+                    .saturating_add(config_info.rate_limit_window_slots)
+            {
+                player_stats.window_start_slot = slot;
+                player_stats.games_in_window = 0;
+            }
+            if player_stats.games_in_window >= config_info.max_games_per_window {
+                return Err(RoshamboError::RateLimited.into());
+            }
+        }
+
+        Self::roll_player_season(player_stats, config_info.season);
+        player_stats.games_in_window += 1;
+        player_stats.games_played += 1;
+        player_stats.season_games += 1;
+        Ok(())
+    }
+
+    /// Checks the bet limits of the config didn't change within its cooldown, a bet signed
+    /// before the change could otherwise land under the new limits
+    fn assert_limits_settled(config_info: &Config, slot: u64) -> ProgramResult {
+        let cooldown_end = config_info
+            .limits_updated_slot
+            .saturating_add(config_info.config_cooldown_slots);
+        if slot < cooldown_end {
+            return Err(RoshamboError::ConfigCooldown.into());
         }
+        Ok(())
+    }
+
+    /// Records a new game for `game_creator` in a fresh game account and counts it in the config
+    #[allow(clippy::too_many_arguments)]
+    fn init_game(
+        game_account: &AccountInfo,
+        game_creator: &Pubkey,
+        amount: u64,
+        public_seed_commitment: [u8; 32],
+        seed_slot: u64,
+        config_key: &Pubkey,
+        config_info: &mut Config,
+        fee_bps: u16,
+        game_id: u64,
+        partner: COption<Pubkey>,
+        frontend_id: u16,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        // Game Account (store game info data) -> Make sure fee exempt
+        assert_owned_by(game_account, program_id)?;
+        assert_rent_exempt(&Rent::get()?, game_account)?;
 
         // Check if this game account is already initialize
         let mut game_info = Game::unpack_unchecked(&game_account.try_borrow_data()?)?;
         if game_info.is_initialized() {
             return Err(ProgramError::AccountAlreadyInitialized);
         }
-
-        // verify house token account
-        if *house_token_account.key != config_account_info.owner_pubkey {
+        // pooled game accounts are only reused by the config they were recycled into
+        if game_info.recycled && game_info.config_pubkey != *config_key {
             return Err(ProgramError::InvalidAccountData);
         }
+        // any other game account is a fresh keypair, signing so no one else's account is taken
+        if !game_info.recycled && !game_account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if config_info.paused {
+            return Err(RoshamboError::BettingPaused.into());
+        }
 
         // validate bet amount in range of max - min config
-        if amount < config_account_info.min_bet_amount
-            || amount > config_account_info.max_bet_amount
-        {
+        let slot = Clock::get()?.slot;
+        Self::assert_limits_settled(config_info, slot)?;
+        if amount < config_info.min_bet_amount || amount > config_info.max_bet_amount {
             return Err(RoshamboError::InvalidBetAmount.into());
         }
 
+        // the commitment must have been made against a recent slot, not prepared long before
+        if seed_slot > slot || slot - seed_slot > MAX_SEED_SLOT_AGE {
+            return Err(RoshamboError::StaleSeedSlot.into());
+        }
+
         // increase total games by one
-        config_account_info.total_games += 1;
-        Config::pack(
-            config_account_info,
-            &mut config_account.try_borrow_mut_data()?,
-        )?;
+        config_info.total_games += 1;
+        config_info.total_volume = config_info
+            .total_volume
+            .checked_add(amount)
+            .ok_or(RoshamboError::AmountOverflow)?;
 
         // Update game account with new game data
         game_info.is_initialized = true;
         game_info.bet_amount = amount;
-        game_info.game_creator_pubkey = *game_creator.key;
+        game_info.game_creator_pubkey = *game_creator;
         game_info.result = COption::None;
+        game_info.created_slot = slot;
+        // later config updates must not change the economics of this game
+        game_info.min_bet_amount = config_info.min_bet_amount;
+        game_info.max_bet_amount = config_info.max_bet_amount;
+        game_info.fee_bps = fee_bps;
+        game_info.payout_multiplier_bps = config_info.payout_multiplier_bps;
+        game_info.insurance_bps = config_info.insurance_bps;
+        game_info.public_seed_commitment = public_seed_commitment;
+        game_info.config_pubkey = *config_key;
+        game_info.host_seed_commitment = COption::None;
+        game_info.revealed_public_seed = COption::None;
+        game_info.recycled = false;
+        game_info.game_id = game_id;
+        game_info.seed_slot = seed_slot;
+        game_info.deferred_payout = 0;
+        game_info.release_slot = 0;
+        game_info.status = GameStatus::Created;
+        game_info.partner = partner;
+        game_info.frontend_id = frontend_id;
         Game::pack(game_info, &mut game_account.try_borrow_mut_data()?)?;
 
-        // CPI call token program transfer bet amount to house PDA
-        let token_program = next_account_info(account_info_iter)?;
-        let deposit_bet_ix = spl_token::instruction::transfer(
-            token_program.key,
-            creator_token_account.key,
-            house_token_account.key,
-            &game_creator.key,
-            &[&game_creator.key],
+        RoshamboEvent::GameCreated {
+            game: *game_account.key,
+            player: *game_creator,
+            game_id,
             amount,
-        )?;
-
-        msg!("Calling the token program to transfer token to house token account...");
-        invoke(
-            &deposit_bet_ix,
-            &[
-                creator_token_account.clone(),
-                house_token_account.clone(),
-                game_creator.clone(),
-                token_program.clone(),
-            ],
-        )?;
+        }
+        .emit();
+        structured_log("create", &[&game_id, game_creator, &amount]);
 
         Ok(())
     }
@@ -191,6 +1173,7 @@ impl Processor {
         accounts: &[AccountInfo],
         host_seed: u64,
         public_seed: u64,
+        rebet_commitment: Option<([u8; 32], u64)>,
         program_id: &Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
@@ -201,121 +1184,542 @@ impl Processor {
         // / 5. `[writable]` House token account owned by PDA (change based on game result)
         let game_creator = next_account_info(account_info_iter)?;
         let house_account = next_account_info(account_info_iter)?;
-
-        if !game_creator.is_signer || !house_account.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
+        assert_signer(house_account)?;
 
         let game_account = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
         // Check if this game account is already initialize and not ended
         let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
         if game_info.game_creator_pubkey != *game_creator.key {
             return Err(ProgramError::InvalidAccountData);
         }
-        if game_info.result.is_some() {
+        if !game_info.status.is_open() {
             return Err(RoshamboError::GameEnded.into());
         }
 
+        // the revealed public seed must be the one committed when the game was created
+        if public_seed_commitment(game_account.key, public_seed, game_info.seed_slot)
+            != game_info.public_seed_commitment
+        {
+            return Err(RoshamboError::InvalidSeedReveal.into());
+        }
+
+        // with a committed host seed the house settles alone, otherwise both sides sign the seeds
+        match game_info.host_seed_commitment {
+            COption::Some(commitment) => {
+                if host_seed_commitment(game_account.key, host_seed) != commitment {
+                    return Err(RoshamboError::InvalidSeedReveal.into());
+                }
+            }
+            COption::None => {
+                Self::authorize_player(
+                    game_creator,
+                    accounts.get(8..).unwrap_or_default(),
+                    0,
+                    program_id,
+                )?;
+            }
+        }
+        // a rebet wagers the player's stake again, only the player can ask for it
+        if rebet_commitment.is_some() {
+            assert_signer(game_creator)?;
+        }
+
+        // the settled game no longer backs a payout
+        let mut exposure_delta = -Self::signed_amount(
+            win_payout(
+                game_info.bet_amount,
+                game_info.payout_multiplier_bps,
+                game_info.fee_bps,
+            )
+            .ok_or(RoshamboError::AmountOverflow)?,
+        )?;
+
         // Check the result based on host_seed and public_seed
-        let selection = public_seed % 5;
-        let host_result = host_seed % 5;
+        let result = outcome(host_seed, public_seed);
+        game_info.result = COption::Some(result as u8);
 
         // just need 1 PDA that can own N temporary token accounts
-        let (pda, nonce) = Pubkey::find_program_address(&[b"roshambo"], program_id);
+        let (pda, nonce) = find_house_authority(program_id);
 
         let receiver_account = next_account_info(account_info_iter)?;
         let house_token_account = next_account_info(account_info_iter)?;
         let config_account = next_account_info(account_info_iter)?;
 
-        // validate if house token account match config
-        let config_account_info = Config::unpack(&config_account.try_borrow_data()?)?;
-        if *house_token_account.key != config_account_info.owner_pubkey {
+        // validate if both token accounts match config, the one the game was created under
+        assert_owned_by(config_account, program_id)?;
+        if game_info.config_pubkey != *config_account.key {
             return Err(ProgramError::InvalidAccountData);
         }
+        let mut config_account_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_account_info)?;
+        Self::assert_admin(
+            accounts,
+            house_account.key,
+            config_account.key,
+            &config_account_info,
+            ADMIN_CAN_SETTLE,
+            program_id,
+        )?;
+        let counters = Self::config_counters(&config_account_info);
+        let settlement_memo = config_account_info.settlement_memo;
+        let settlement_tree = config_account_info.settlement_tree;
+        let audit_log = config_account_info.audit_log;
+        let dispute_window_slots = config_account_info.dispute_window_slots;
+        // a rebet would wager a stake whose result can still be overturned
+        if rebet_commitment.is_some() && dispute_window_slots > 0 {
+            return Err(RoshamboError::RebetNotAllowed.into());
+        }
+        let game_pool = config_account_info.game_pool;
+        let season = config_account_info.season;
+        let streak_season = config_account_info
+            .streak_leaderboard
+            .then_some(config_account_info.season);
+        // a frozen receiver can't be paid now, the game is settled with its payout deferred
+        let receiver_frozen = assert_token_account(
+            receiver_account,
+            &config_account_info.mint_token_pubkey,
+            Some(game_creator.key),
+        )?
+        .is_frozen();
+        let house_balance = assert_token_account(
+            house_token_account,
+            &config_account_info.mint_token_pubkey,
+            Some(&pda),
+        )?
+        .amount;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_account_info,
+            game_creator.key,
+            program_id,
+        )?;
+
+        // the game can't be settled in the slot it was created in, nor within the configured delay
+        let settle_slot = game_info
+            .created_slot
+            .saturating_add(config_account_info.min_settle_delay_slots);
+        let slot = Clock::get()?.slot;
+        if slot <= settle_slot {
+            return Err(RoshamboError::SettlementTooEarly.into());
+        }
+        if game_info.settlement_expired(&config_account_info, slot) {
+            return Err(RoshamboError::SettlementExpired.into());
+        }
+
+        // the host seed must come from a secret committed before the game's day started
+        if config_account_info.daily_seed {
+            Self::assert_daily_seed(
+                accounts,
+                config_account.key,
+                daily_seed_day(game_info.created_slot),
+                game_info.game_id,
+                host_seed,
+                program_id,
+            )?;
+        }
 
         let token_program = next_account_info(account_info_iter)?;
         let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        let rebet = rebet_commitment.filter(|_| result == Outcome::Win);
+        let payout = Self::settle_payout(
+            &mut game_info,
+            result,
+            &config_account_info,
+            slot,
+            receiver_frozen,
+            rebet.is_some(),
+        )?;
+        // a win above the payout cap pays the cap, the rest is owed in installments
+        let owed = Self::payout_over_cap(payout, &config_account_info);
+        if owed > 0 {
+            Self::open_payable(
+                accounts,
+                house_account,
+                &game_info,
+                receiver_account.key,
+                config_account.key,
+                &config_account_info,
+                owed,
+                program_id,
+            )?;
+            exposure_delta += Self::signed_amount(owed)?;
+        }
+        let payout = payout - owed;
+        // a win above the vesting threshold is streamed out by `ReleaseVested` instead
+        let payout = if Self::vests(payout, &config_account_info) {
+            Self::start_vesting(
+                accounts,
+                house_account,
+                &game_info,
+                receiver_account.key,
+                config_account.key,
+                &config_account_info,
+                payout,
+                program_id,
+            )?;
+            exposure_delta += Self::signed_amount(payout)?;
+            0
+        } else {
+            payout
+        };
+
+        // the game is marked settled before any transfer, so the `GameEnded` check above
+        // rejects every other settlement of it, even from within this transaction. It stays
+        // open, settled, until a held payout is claimed.
+        let held = game_info.deferred_payout > 0 || game_info.release_slot > 0;
+        game_info.transition(if held {
+            GameStatus::ResultPosted
+        } else {
+            GameStatus::Settled
+        })?;
+        game_info.pack_into_slice(&mut game_account.try_borrow_mut_data()?);
+
+        // on rebet the original stake stays in the house account for the next game
+        if let Some((next_public_seed_commitment, next_seed_slot)) = rebet {
+            let next_game_account = next_account_info(account_info_iter)?;
+            let player_stats_account = next_account_info(account_info_iter)?;
+
+            // the rebet opens a game behind the gates of `NewGame`, as if the whole win was
+            // paid out and the stake deposited again
+            Self::assert_min_stake(accounts, game_creator.key, &config_account_info)?;
+            Self::assert_host_alive(
+                accounts,
+                config_account.key,
+                &config_account_info,
+                program_id,
+            )?;
+            let outstanding_exposure = Self::outstanding_exposure(
+                accounts,
+                config_account.key,
+                &config_account_info,
+                game_creator.key,
+                program_id,
+            )?
+            .saturating_add_signed(
+                exposure_delta + Self::signed_amount(game_info.deferred_payout)?,
+            );
+            let house_balance = house_balance
+                .saturating_sub(payout)
+                .saturating_sub(game_info.bet_amount);
+            if game_info.bet_amount
+                > max_allowed_bet(&config_account_info, house_balance, outstanding_exposure)
+            {
+                return Err(RoshamboError::HouseInsolvent.into());
+            }
+
+            let game_id = Self::next_game_id(
+                accounts,
+                config_account.key,
+                &config_account_info,
+                game_creator.key,
+                program_id,
+            )?;
+            let fee_bps = Self::player_fee_bps(
+                accounts,
+                config_account.key,
+                &config_account_info,
+                game_creator.key,
+                program_id,
+            )?;
+            Self::init_game(
+                next_game_account,
+                game_creator.key,
+                game_info.bet_amount,
+                next_public_seed_commitment,
+                next_seed_slot,
+                config_account.key,
+                &mut config_account_info,
+                fee_bps,
+                game_id,
+                game_info.partner,
+                game_info.frontend_id,
+                program_id,
+            )?;
+
+            let mut player_stats = Self::load_player_stats(
+                player_stats_account,
+                game_creator.key,
+                config_account.key,
+                program_id,
+            )?;
+            Self::record_new_game(&mut player_stats, &config_account_info)?;
+            PlayerStats::pack(
+                player_stats,
+                &mut player_stats_account.try_borrow_mut_data()?,
+            )?;
+            // the insurance share of the stake leaves the bankroll, as it would on `NewGame`
+            if let Some((insurance_fund, cut)) = Self::insurance_cut(
+                accounts,
+                config_account.key,
+                &config_account_info,
+                game_info.bet_amount,
+                program_id,
+            )? {
+                Self::transfer_signed(
+                    house_token_account,
+                    insurance_fund,
+                    mint_account,
+                    decimals,
+                    pda_program,
+                    token_program,
+                    cut,
+                    &[HOUSE_AUTHORITY_SEED, &[nonce]],
+                )?;
+                config_account_info.house_pnl = config_account_info
+                    .house_pnl
+                    .checked_sub(Self::signed_amount(cut)?)
+                    .ok_or(RoshamboError::AmountOverflow)?;
+            }
+            // the rebet game backs a payout of its own, at the current config economics
+            exposure_delta += Self::signed_amount(
+                win_payout(
+                    game_info.bet_amount,
+                    config_account_info.payout_multiplier_bps,
+                    fee_bps,
+                )
+                .ok_or(RoshamboError::AmountOverflow)?,
+            )?;
+            verbose_msg!("Rebet original stake into a new game...");
+        }
 
-        // Draw
-        if selection == host_result {
-            game_info.result = COption::Some(2);
-            // refund bet amount
-            let refund_ix = spl_token::instruction::transfer(
+        if payout > 0 {
+            let payout_ix = spl_token::instruction::transfer_checked(
                 token_program.key,
                 house_token_account.key,
+                mint_account.key,
                 receiver_account.key,
                 &pda,
                 &[&pda],
-                game_info.bet_amount,
+                payout,
+                decimals,
             )?;
 
-            msg!("Refund bet amount when draw...");
+            verbose_msg!("Pay out the game...");
             invoke_signed(
-                &refund_ix,
+                &payout_ix,
                 &[
                     house_token_account.clone(),
                     receiver_account.clone(),
                     pda_program.clone(),
+                    mint_account.clone(),
                     token_program.clone(),
                 ],
-                &[&[&b"roshambo"[..], &[nonce]]],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
             )?;
-        } else {
-            let tmp_calc = selection + 5 - host_result;
-            if tmp_calc == 1 || tmp_calc == 3 || tmp_calc == 6 || tmp_calc == 8 {
-                // Lose
-                game_info.result = COption::Some(1);
-            } else {
-                // Win
-                game_info.result = COption::Some(0);
+        }
+
+        // a deferred payout stays owed by the house until `ClaimDeferred`
+        exposure_delta += Self::signed_amount(game_info.deferred_payout)?;
+
+        Self::record_settlement(
+            config_account.key,
+            &mut config_account_info,
+            result,
+            &game_info,
+        )?;
+        if config_account_info.hourly_metrics {
+            Self::record_hourly_metrics(
+                accounts,
+                config_account.key,
+                result,
+                &game_info,
+                program_id,
+            )?;
+        }
+
+        // a share of the house's take is burned rather than kept in the bankroll
+        let house_take = Self::house_take(result, &game_info)?;
+        let burned = (house_take as u128 * config_account_info.burn_bps as u128
+            / BPS_DENOMINATOR as u128) as u64;
+        if burned > 0 {
+            let burn_ix = spl_token::instruction::burn_checked(
+                token_program.key,
+                house_token_account.key,
+                mint_account.key,
+                &pda,
+                &[&pda],
+                burned,
+                decimals,
+            )?;
+
+            verbose_msg!("Burn the house share...");
+            invoke_signed(
+                &burn_ix,
+                &[
+                    house_token_account.clone(),
+                    mint_account.clone(),
+                    pda_program.clone(),
+                    token_program.clone(),
+                ],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+            )?;
+            config_account_info.house_pnl = config_account_info
+                .house_pnl
+                .checked_sub(Self::signed_amount(burned)?)
+                .ok_or(RoshamboError::AmountOverflow)?;
+        }
+
+        // the creator's stats can be passed anywhere in the accounts
+        let (player_stats_key, _) =
+            find_player_stats_address(config_account.key, game_creator.key, program_id);
+        let player_stats_account = Self::find_account(accounts, &player_stats_key)?;
+        let mut player_stats = Self::load_player_stats(
+            player_stats_account,
+            game_creator.key,
+            config_account.key,
+            program_id,
+        )?;
+        Self::roll_player_season(&mut player_stats, season);
+        Self::record_player_result(&mut player_stats, result, &game_info);
+        Self::record_cashback(&mut player_stats, &config_account_info, result, &game_info)?;
+        Self::record_quest_progress(&mut player_stats, result, &game_info)?;
+        if let Some(season) = streak_season {
+            Self::record_streak(
+                accounts,
+                config_account.key,
+                season,
+                &player_stats,
+                program_id,
+            )?;
+        }
+        // the partner of the player's first partner game is their referrer for good
+        let referred = game_info.partner.is_some() && player_stats.referrer.is_none();
+        if referred {
+            player_stats.referrer = game_info.partner;
+        }
+        PlayerStats::pack(
+            player_stats,
+            &mut player_stats_account.try_borrow_mut_data()?,
+        )?;
+
+        // the frontend the game was created through earns a share of the house's take
+        if let COption::Some(partner_key) = game_info.partner {
+            let partner_account = Self::find_account(accounts, &partner_key)?;
+            assert_owned_by(partner_account, program_id)?;
+            let mut partner = Partner::unpack(&partner_account.try_borrow_data()?)?;
+            let partner_token_account = Self::find_account(accounts, &partner.token_account)?;
 
-                let claim_reward_ix = spl_token::instruction::transfer(
+            // a closed partner token account forfeits the share instead of blocking settlement
+            let share = (house_take as u128 * config_account_info.partner_share_bps as u128
+                / BPS_DENOMINATOR as u128) as u64;
+            let payable = assert_token_account(
+                partner_token_account,
+                &config_account_info.mint_token_pubkey,
+                None,
+            )
+            .is_ok();
+            let share = if payable { share } else { 0 };
+            if share > 0 {
+                let share_ix = spl_token::instruction::transfer_checked(
                     token_program.key,
                     house_token_account.key,
-                    receiver_account.key,
+                    mint_account.key,
+                    partner_token_account.key,
                     &pda,
                     &[&pda],
-                    game_info.bet_amount * 2,
+                    share,
+                    decimals,
                 )?;
 
-                msg!("Claim win reward...");
+                verbose_msg!("Pay the partner share...");
                 invoke_signed(
-                    &claim_reward_ix,
+                    &share_ix,
                     &[
                         house_token_account.clone(),
-                        receiver_account.clone(),
+                        partner_token_account.clone(),
                         pda_program.clone(),
+                        mint_account.clone(),
                         token_program.clone(),
                     ],
-                    &[&[&b"roshambo"[..], &[nonce]]],
+                    &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
                 )?;
+                config_account_info.house_pnl = config_account_info
+                    .house_pnl
+                    .checked_sub(Self::signed_amount(share)?)
+                    .ok_or(RoshamboError::AmountOverflow)?;
+            }
+
+            if referred {
+                partner.referred_players = partner.referred_players.saturating_add(1);
             }
+            partner.games = partner.games.saturating_add(1);
+            partner.volume = partner.volume.saturating_add(game_info.bet_amount);
+            partner.fees_earned = partner.fees_earned.saturating_add(share);
+            Partner::pack(partner, &mut partner_account.try_borrow_mut_data()?)?;
         }
 
-        msg!("Closing the game account and refund fee back to creator...");
-        **game_creator.try_borrow_mut_lamports()? = game_creator
-            .lamports()
-            .checked_add(game_account.lamports())
-            .ok_or(RoshamboError::AmountOverflow)?;
-        **game_account.try_borrow_mut_lamports()? = 0;
-        *game_account.try_borrow_mut_data()? = &mut [];
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_account_info,
+            counters,
+            exposure_delta,
+            rebet.map_or(-1, |_| 0),
+            game_creator.key,
+            program_id,
+        )?;
 
-        Ok(())
+        RoshamboEvent::GameSettled {
+            game: *game_account.key,
+            player: *game_creator.key,
+            game_id: game_info.game_id,
+            result: result as u8,
+            frontend_id: game_info.frontend_id,
+        }
+        .emit();
+        structured_log(
+            "settle",
+            &[
+                &game_info.game_id,
+                &(result as u8),
+                &Self::result_payout(result, &game_info)?,
+                &game_info.frontend_id,
+            ],
+        );
+
+        if settlement_memo {
+            Self::emit_settlement_memo(accounts, game_account.key, result)?;
+        }
+        if let COption::Some(tree) = settlement_tree {
+            let leaf = settlement_leaf(
+                game_account.key,
+                game_creator.key,
+                game_info.bet_amount,
+                result,
+                Clock::get()?.slot,
+            );
+            Self::append_settlement_leaf(accounts, &tree, config_account.key, leaf, program_id)?;
+        }
+        if audit_log {
+            let entry = audit_entry(game_account.key, host_seed, public_seed, result);
+            Self::append_audit_entry(accounts, config_account.key, &entry, program_id)?;
+        }
+
+        if held {
+            verbose_msg!("Deferring the payout...");
+            return Game::pack(game_info, &mut game_account.try_borrow_mut_data()?);
+        }
+
+        Self::close_game(game_account, game_creator, config_account.key, game_pool)
     }
 
     fn process_update_config(
         accounts: &[AccountInfo],
         min_bet_amount: u64,
         max_bet_amount: u64,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
         let config_creator = next_account_info(account_info_iter)?;
-        if !config_creator.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
+        assert_signer(config_creator)?;
 
         let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
 
         // Check if this config account is already initialize
         let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
@@ -324,13 +1728,34 @@ impl Processor {
         }
 
         // Check if the signer has authority to update the config
-        if config_info.owner_pubkey != *config_creator.key {
-            return Err(ProgramError::InvalidAccountOwner);
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        Self::assert_bet_limits(min_bet_amount, max_bet_amount, config_info.decimals)?;
+
+        RoshamboEvent::ConfigUpdated {
+            config: *config_account.key,
+            old_min_bet_amount: config_info.min_bet_amount,
+            old_max_bet_amount: config_info.max_bet_amount,
+            min_bet_amount,
+            max_bet_amount,
         }
+        .emit();
+        structured_log(
+            "config",
+            &[config_account.key, &min_bet_amount, &max_bet_amount],
+        );
 
         // Update game account with new game data
         config_info.min_bet_amount = min_bet_amount;
         config_info.max_bet_amount = max_bet_amount;
+        config_info.limits_updated_slot = Clock::get()?.slot;
         Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
 
         Ok(())
@@ -339,55 +1764,6495 @@ impl Processor {
     fn process_withdraw(
         accounts: &[AccountInfo],
         amount: u64,
+        emergency: bool,
         program_id: &Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
         let config_creator = next_account_info(account_info_iter)?;
-        if !config_creator.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
+        assert_signer(config_creator)?;
 
         let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
 
         // Check if this config account is already initialize
-        let config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
         if !config_info.is_initialized() {
             return Err(ProgramError::UninitializedAccount);
         }
 
         // Check if the signer has authority to update the config
-        if config_info.owner_pubkey != *config_creator.key {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_WITHDRAW,
+            program_id,
+        )?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
 
         // Withdraw
         let house_token_account = next_account_info(account_info_iter)?;
         let token_program = next_account_info(account_info_iter)?;
         let pda_program = next_account_info(account_info_iter)?;
-        let (pda, nonce) = Pubkey::find_program_address(&[b"roshambo"], program_id);
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+        let house_token = assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        let vault_shard = Self::assert_config_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            program_id,
+        )?;
+        // withdrawals only ever reach the owner, admins included
+        let destination_account = next_account_info(account_info_iter)?;
+        assert_token_account(
+            destination_account,
+            &config_info.mint_token_pubkey,
+            Some(&config_info.owner_pubkey),
+        )?;
+
+        if emergency {
+            // the timelock gives players time to settle or leave before the floor is lifted
+            match config_info.emergency_unlock_slot {
+                COption::Some(slot) if Clock::get()?.slot >= slot => {}
+                COption::Some(_) => return Err(RoshamboError::EmergencyWithdrawLocked.into()),
+                COption::None => {
+                    return Err(RoshamboError::EmergencyWithdrawNotScheduled.into());
+                }
+            }
+            config_info.emergency_unlock_slot = COption::None;
+            Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+        } else {
+            let floor = Self::withdraw_floor(
+                accounts,
+                config_account.key,
+                &config_info,
+                vault_shard,
+                program_id,
+            )?;
+            if house_token.amount.saturating_sub(amount) < floor {
+                return Err(RoshamboError::HouseInsolvent.into());
+            }
+        }
 
-        let withdraw_ix = spl_token::instruction::transfer(
+        let withdraw_ix = spl_token::instruction::transfer_checked(
             token_program.key,
             house_token_account.key,
-            config_creator.key,
+            mint_account.key,
+            destination_account.key,
             &pda,
             &[&pda],
             amount,
+            decimals,
         )?;
 
-        msg!("Refund bet amount when draw...");
+        verbose_msg!("Withdrawing from the house vault...");
         invoke_signed(
             &withdraw_ix,
             &[
                 house_token_account.clone(),
-                config_creator.clone(),
+                destination_account.clone(),
                 pda_program.clone(),
+                mint_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )?;
+
+        RoshamboEvent::Withdrawn {
+            config: *config_account.key,
+            destination: *destination_account.key,
+            amount,
+            emergency,
+        }
+        .emit();
+        structured_log(
+            "withdraw",
+            &[
+                config_account.key,
+                destination_account.key,
+                &amount,
+                &emergency,
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn process_open_session(
+        accounts: &[AccountInfo],
+        deposit: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+        assert_signer(player)?;
+
+        let player_token_account = next_account_info(account_info_iter)?;
+        let session_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        assert_owned_by(config_account, program_id)?;
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+
+        let (pda, _nonce) = find_house_authority(program_id);
+        assert_token_account(
+            player_token_account,
+            &config_info.mint_token_pubkey,
+            Some(player.key),
+        )?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            player.key,
+            program_id,
+        )?;
+
+        Self::assert_min_stake(accounts, player.key, &config_info)?;
+
+        // Session Account (store session balance) -> Make sure fee exempt
+        assert_owned_by(session_account, program_id)?;
+        assert_rent_exempt(&Rent::get()?, session_account)?;
+
+        let mut session_info = Session::unpack_unchecked(&session_account.try_borrow_data()?)?;
+        if session_info.is_initialized() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        if config_info.paused {
+            return Err(RoshamboError::BettingPaused.into());
+        }
+
+        // the deposit must cover at least one minimum bet
+        if deposit < config_info.min_bet_amount {
+            return Err(RoshamboError::InvalidBetAmount.into());
+        }
+
+        session_info.is_initialized = true;
+        session_info.player_pubkey = *player.key;
+        session_info.config_pubkey = *config_account.key;
+        session_info.balance = deposit;
+        session_info.games_played = 0;
+        session_info.deposit = deposit;
+        session_info.wagered = 0;
+        session_info.host_seed_commitment = COption::None;
+        session_info.pending_amount = 0;
+        session_info.pending_public_seed = 0;
+        Session::pack(session_info, &mut session_account.try_borrow_mut_data()?)?;
+
+        // CPI call token program transfer the deposit to house PDA
+        let token_program = next_account_info(account_info_iter)?;
+        let deposit_ix = spl_token::instruction::transfer_checked(
+            token_program.key,
+            player_token_account.key,
+            mint_account.key,
+            house_token_account.key,
+            player.key,
+            &[player.key],
+            deposit,
+            decimals,
+        )?;
+
+        verbose_msg!(
+            "Calling the token program to transfer session deposit to house token account..."
+        );
+        invoke(
+            &deposit_ix,
+            &[
+                player_token_account.clone(),
+                house_token_account.clone(),
+                player.clone(),
+                mint_account.clone(),
                 token_program.clone(),
             ],
-            &[&[&b"roshambo"[..], &[nonce]]],
         )?;
 
         Ok(())
     }
+
+    fn process_play_session(
+        accounts: &[AccountInfo],
+        amount: u64,
+        public_seed: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+        assert_signer(player)?;
+
+        let session_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(session_account, program_id)?;
+        assert_owned_by(config_account, program_id)?;
+
+        let mut session_info = Session::unpack(&session_account.try_borrow_data()?)?;
+        if session_info.player_pubkey != *player.key
+            || session_info.config_pubkey != *config_account.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        if config_info.paused {
+            return Err(RoshamboError::BettingPaused.into());
+        }
+
+        let house_token_account = next_account_info(account_info_iter)?;
+        let player_stats_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        let (pda, _nonce) = find_house_authority(program_id);
+        let house_token = assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            player.key,
+            program_id,
+        )?;
+        Self::assert_host_alive(accounts, config_account.key, &config_info, program_id)?;
+
+        // every hand goes through the bet limit of `NewGame`, the session balance already in
+        // the vault doesn't back it
+        let outstanding_exposure = Self::outstanding_exposure(
+            accounts,
+            config_account.key,
+            &config_info,
+            player.key,
+            program_id,
+        )?;
+        let house_balance = house_token.amount.saturating_sub(session_info.balance);
+        if amount > max_allowed_bet(&config_info, house_balance, outstanding_exposure) {
+            return Err(RoshamboError::HouseInsolvent.into());
+        }
+
+        // and counts against the player's rate limit as a game
+        let mut player_stats = Self::load_or_create_player_stats(
+            player,
+            player,
+            player_stats_account,
+            system_program_account,
+            config_account.key,
+            program_id,
+        )?;
+        Self::record_new_game(&mut player_stats, &config_info)?;
+        PlayerStats::pack(
+            player_stats,
+            &mut player_stats_account.try_borrow_mut_data()?,
+        )?;
+
+        // validate bet amount in range of max - min config and covered by the session
+        Self::assert_limits_settled(&config_info, Clock::get()?.slot)?;
+        if amount == 0 || amount < config_info.min_bet_amount || amount > config_info.max_bet_amount
+        {
+            return Err(RoshamboError::InvalidBetAmount.into());
+        }
+        if amount > session_info.balance {
+            return Err(RoshamboError::InsufficientSessionBalance.into());
+        }
+
+        // the public seed is picked once the host seed of the game is committed, like a
+        // game's is revealed after `CommitHostSeed`
+        if session_info.host_seed_commitment.is_none() {
+            return Err(RoshamboError::HostSeedNotCommitted.into());
+        }
+        if session_info.pending_amount > 0 {
+            return Err(RoshamboError::InvalidGameStatus.into());
+        }
+        session_info.pending_amount = amount;
+        session_info.pending_public_seed = public_seed;
+        Session::pack(session_info, &mut session_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Loads a session of `config_account` for its house, checking `house_account` can settle
+    fn load_house_session(
+        accounts: &[AccountInfo],
+        house_account: &AccountInfo,
+        session_account: &AccountInfo,
+        config_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<(Session, Config), ProgramError> {
+        assert_signer(house_account)?;
+        assert_owned_by(session_account, program_id)?;
+        assert_owned_by(config_account, program_id)?;
+
+        let session_info = Session::unpack(&session_account.try_borrow_data()?)?;
+        if session_info.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // the house verifying session games is the config owner
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        Self::assert_admin(
+            accounts,
+            house_account.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_SETTLE,
+            program_id,
+        )?;
+        Ok((session_info, config_info))
+    }
+
+    fn process_commit_session_seed(
+        accounts: &[AccountInfo],
+        commitment: [u8; 32],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let house_account = next_account_info(account_info_iter)?;
+        let session_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let (mut session_info, _) = Self::load_house_session(
+            accounts,
+            house_account,
+            session_account,
+            config_account,
+            program_id,
+        )?;
+
+        if session_info.host_seed_commitment.is_some() {
+            return Err(RoshamboError::HostSeedAlreadyCommitted.into());
+        }
+        session_info.host_seed_commitment = COption::Some(commitment);
+        Session::pack(session_info, &mut session_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_reveal_session_seed(
+        accounts: &[AccountInfo],
+        host_seed: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let house_account = next_account_info(account_info_iter)?;
+        let session_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let (mut session_info, config_info) = Self::load_house_session(
+            accounts,
+            house_account,
+            session_account,
+            config_account,
+            program_id,
+        )?;
+
+        let COption::Some(commitment) = session_info.host_seed_commitment else {
+            return Err(RoshamboError::HostSeedNotCommitted.into());
+        };
+        if session_info.pending_amount == 0 {
+            return Err(RoshamboError::InvalidGameStatus.into());
+        }
+        if session_seed_commitment(session_account.key, session_info.games_played, host_seed)
+            != commitment
+        {
+            return Err(RoshamboError::InvalidSeedReveal.into());
+        }
+
+        let amount = session_info.pending_amount;
+        let result = outcome(host_seed, session_info.pending_public_seed);
+        let fee_bps = Self::player_fee_bps(
+            accounts,
+            config_account.key,
+            &config_info,
+            &session_info.player_pubkey,
+            program_id,
+        )?;
+        let payout = win_payout(amount, config_info.payout_multiplier_bps, fee_bps)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        session_info.balance = match result {
+            Outcome::Win => session_info.balance.checked_add(payout - amount),
+            Outcome::Lose => session_info.balance.checked_sub(amount),
+            Outcome::Draw => Some(session_info.balance),
+        }
+        .ok_or(RoshamboError::AmountOverflow)?;
+        session_info.games_played = session_info
+            .games_played
+            .checked_add(1)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        session_info.wagered = session_info
+            .wagered
+            .checked_add(amount)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        session_info.host_seed_commitment = COption::None;
+        session_info.pending_amount = 0;
+        session_info.pending_public_seed = 0;
+
+        log_code!(
+            LOG_SESSION_RESULT,
+            [result],
+            "Session game result: {}",
+            result as u8
+        );
+        Session::pack(session_info, &mut session_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_close_session(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+        assert_signer(player)?;
+
+        let session_account = next_account_info(account_info_iter)?;
+        assert_owned_by(session_account, program_id)?;
+        let session_info = Session::unpack(&session_account.try_borrow_data()?)?;
+        if session_info.player_pubkey != *player.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        if session_info.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // validate if both token accounts match config
+        assert_owned_by(config_account, program_id)?;
+        let mut config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_token_account(
+            receiver_account,
+            &config_info.mint_token_pubkey,
+            Some(player.key),
+        )?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            player.key,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        // settle the net result of every session game in one transfer, a game still waiting
+        // for its host seed is refunded as its stake never left the balance
+        if session_info.balance > 0 {
+            let settle_ix = spl_token::instruction::transfer_checked(
+                token_program.key,
+                house_token_account.key,
+                mint_account.key,
+                receiver_account.key,
+                &pda,
+                &[&pda],
+                session_info.balance,
+                decimals,
+            )?;
+
+            verbose_msg!("Paying out session balance...");
+            invoke_signed(
+                &settle_ix,
+                &[
+                    house_token_account.clone(),
+                    receiver_account.clone(),
+                    pda_program.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+            )?;
+        }
+
+        verbose_msg!("Closing the session account and refund fee back to player...");
+        **player.try_borrow_mut_lamports()? = player
+            .lamports()
+            .checked_add(session_account.lamports())
+            .ok_or(RoshamboError::AmountOverflow)?;
+        **session_account.try_borrow_mut_lamports()? = 0;
+        *session_account.try_borrow_mut_data()? = &mut [];
+
+        // the session games are booked in the config counters at once, with their net result
+        let counters = Self::config_counters(&config_info);
+        config_info.total_games = config_info
+            .total_games
+            .checked_add(session_info.games_played)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        config_info.total_volume = config_info
+            .total_volume
+            .checked_add(session_info.wagered)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        let house_result =
+            Self::signed_amount(session_info.deposit)? - Self::signed_amount(session_info.balance)?;
+        config_info.house_pnl = config_info
+            .house_pnl
+            .checked_add(house_result)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Self::record_circuit_breaker(config_account.key, &mut config_info, house_result)?;
+        let wagered = Self::signed_amount(session_info.wagered)?;
+        Self::record_return_to_player(&mut config_info, wagered, wagered - house_result)?;
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_info,
+            counters,
+            0,
+            0,
+            player.key,
+            program_id,
+        )
+    }
+
+    fn process_decline_game(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let game_account = next_account_info(account_info_iter)?;
+        let game_creator = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
+
+        // Check if this game account is already initialize and not ended
+        let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+        if game_info.game_creator_pubkey != *game_creator.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        // Check if the signer has authority over the config of the game
+        assert_owned_by(config_account, program_id)?;
+        if game_info.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+        // a game the house let expire is refunded as expired
+        game_info.transition(
+            if game_info.settlement_expired(&config_info, Clock::get()?.slot) {
+                GameStatus::Expired
+            } else {
+                GameStatus::Cancelled
+            },
+        )?;
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_SETTLE,
+            program_id,
+        )?;
+
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_token_account(
+            receiver_account,
+            &config_info.mint_token_pubkey,
+            Some(game_creator.key),
+        )?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            game_creator.key,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        // the insurance cut of the stake never reached the house
+        let refund = game_info.house_stake();
+        let refund_ix = spl_token::instruction::transfer_checked(
+            token_program.key,
+            house_token_account.key,
+            mint_account.key,
+            receiver_account.key,
+            &pda,
+            &[&pda],
+            refund,
+            decimals,
+        )?;
+
+        verbose_msg!("Refund bet amount of the declined game...");
+        invoke_signed(
+            &refund_ix,
+            &[
+                house_token_account.clone(),
+                receiver_account.clone(),
+                pda_program.clone(),
+                mint_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )?;
+
+        let game_pool = config_info.game_pool;
+
+        // the declined game no longer backs a payout
+        let exposure = win_payout(
+            game_info.bet_amount,
+            game_info.payout_multiplier_bps,
+            game_info.fee_bps,
+        )
+        .ok_or(RoshamboError::AmountOverflow)?;
+        let counters = Self::config_counters(&config_info);
+        config_info.house_pnl = config_info
+            .house_pnl
+            .checked_add(Self::signed_amount(game_info.bet_amount - refund)?)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Self::record_refund(
+            accounts,
+            config_account.key,
+            &mut config_info,
+            game_creator.key,
+            refund,
+            program_id,
+        )?;
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_info,
+            counters,
+            -Self::signed_amount(exposure)?,
+            -1,
+            game_creator.key,
+            program_id,
+        )?;
+
+        RoshamboEvent::GameDeclined {
+            game: *game_account.key,
+            player: *game_creator.key,
+            refund,
+            game_id: game_info.game_id,
+        }
+        .emit();
+        structured_log("decline", &[&game_info.game_id, &refund]);
+
+        Self::close_game(game_account, game_creator, config_account.key, game_pool)
+    }
+
+    fn process_cancel_game(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let game_creator = next_account_info(account_info_iter)?;
+        assert_signer(game_creator)?;
+
+        let game_account = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
+
+        // Check if this game account is already initialize and not ended
+        let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+        if game_info.game_creator_pubkey != *game_creator.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if game_info.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+
+        // once the host seed is fixed the player could learn the result before cancelling:
+        // through the committed seed, or the daily secret revealed after the game's day. A
+        // game too old to be settled has no result left to learn.
+        let slot = Clock::get()?.slot;
+        let expired = game_info.settlement_expired(&config_info, slot);
+        game_info.transition(if expired {
+            GameStatus::Expired
+        } else {
+            GameStatus::Cancelled
+        })?;
+        if !expired
+            && (game_info.host_seed_commitment.is_some()
+                || (config_info.daily_seed
+                    && daily_seed_day(slot) != daily_seed_day(game_info.created_slot)))
+        {
+            return Err(RoshamboError::CancellationTooLate.into());
+        }
+
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_token_account(
+            receiver_account,
+            &config_info.mint_token_pubkey,
+            Some(game_creator.key),
+        )?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            game_creator.key,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        // no cancellation fee once the house let the game expire, and the insurance cut of the
+        // stake never reached the house account
+        let fee = if expired {
+            0
+        } else {
+            (game_info.bet_amount as u128 * config_info.cancel_fee_bps as u128
+                / BPS_DENOMINATOR as u128) as u64
+        };
+        let refund = game_info.house_stake().saturating_sub(fee);
+        if refund > 0 {
+            let refund_ix = spl_token::instruction::transfer_checked(
+                token_program.key,
+                house_token_account.key,
+                mint_account.key,
+                receiver_account.key,
+                &pda,
+                &[&pda],
+                refund,
+                decimals,
+            )?;
+
+            verbose_msg!("Refund bet amount of the cancelled game...");
+            invoke_signed(
+                &refund_ix,
+                &[
+                    house_token_account.clone(),
+                    receiver_account.clone(),
+                    pda_program.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+            )?;
+        }
+
+        // with a treasury, the fee goes to the token account of the treasury owner in the
+        // config mint (the treasury itself holds the settlement mint), out of the bankroll
+        let mut treasury_fee = 0;
+        let retained = game_info.house_stake() - refund;
+        if let (COption::Some(treasury), COption::Some(settlement_mint), true) = (
+            config_info.treasury,
+            config_info.settlement_mint,
+            retained > 0,
+        ) {
+            let treasury_account = Self::find_account(accounts, &treasury)?;
+            let treasury_owner =
+                assert_token_account(treasury_account, &settlement_mint, None)?.owner;
+            let fee_account = Self::find_account(
+                accounts,
+                &find_associated_token_address(&treasury_owner, &config_info.mint_token_pubkey),
+            )?;
+            assert_token_account(
+                fee_account,
+                &config_info.mint_token_pubkey,
+                Some(&treasury_owner),
+            )?;
+
+            let fee_ix = spl_token::instruction::transfer_checked(
+                token_program.key,
+                house_token_account.key,
+                mint_account.key,
+                fee_account.key,
+                &pda,
+                &[&pda],
+                retained,
+                decimals,
+            )?;
+            invoke_signed(
+                &fee_ix,
+                &[
+                    house_token_account.clone(),
+                    fee_account.clone(),
+                    pda_program.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+            )?;
+            treasury_fee = retained;
+        }
+
+        let game_pool = config_info.game_pool;
+
+        // the cancelled game no longer backs a payout
+        let exposure = win_payout(
+            game_info.bet_amount,
+            game_info.payout_multiplier_bps,
+            game_info.fee_bps,
+        )
+        .ok_or(RoshamboError::AmountOverflow)?;
+        let counters = Self::config_counters(&config_info);
+        config_info.house_pnl = config_info
+            .house_pnl
+            .checked_add(Self::signed_amount(
+                game_info.bet_amount - refund - treasury_fee,
+            )?)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Self::record_refund(
+            accounts,
+            config_account.key,
+            &mut config_info,
+            game_creator.key,
+            refund,
+            program_id,
+        )?;
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_info,
+            counters,
+            -Self::signed_amount(exposure)?,
+            -1,
+            game_creator.key,
+            program_id,
+        )?;
+
+        RoshamboEvent::GameCancelled {
+            game: *game_account.key,
+            player: *game_creator.key,
+            refund,
+            fee,
+            game_id: game_info.game_id,
+        }
+        .emit();
+        structured_log("cancel", &[&game_info.game_id, &refund, &fee]);
+
+        Self::close_game(game_account, game_creator, config_account.key, game_pool)
+    }
+
+    fn process_update_rate_limit(
+        accounts: &[AccountInfo],
+        max_games_per_window: u64,
+        window_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        config_info.max_games_per_window = max_games_per_window;
+        config_info.rate_limit_window_slots = window_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_settle_delay(
+        accounts: &[AccountInfo],
+        min_settle_delay_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        config_info.min_settle_delay_slots = min_settle_delay_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_fees(
+        accounts: &[AccountInfo],
+        fee_bps: u16,
+        payout_multiplier_bps: u16,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        // a win must at least return the stake, and the fee can't exceed the profit
+        if fee_bps as u64 > BPS_DENOMINATOR || (payout_multiplier_bps as u64) < BPS_DENOMINATOR {
+            return Err(RoshamboError::InvalidFeeConfig.into());
+        }
+
+        config_info.fee_bps = fee_bps;
+        config_info.payout_multiplier_bps = payout_multiplier_bps;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_commit_host_seed(
+        accounts: &[AccountInfo],
+        commitment: [u8; 32],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let house_account = next_account_info(account_info_iter)?;
+        assert_signer(house_account)?;
+
+        let game_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
+        assert_owned_by(config_account, program_id)?;
+
+        let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+        if !game_info.status.is_open() {
+            return Err(RoshamboError::GameEnded.into());
+        }
+        if game_info.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // only the house of the game's config can commit its seed
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        Self::assert_admin(
+            accounts,
+            house_account.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_SETTLE,
+            program_id,
+        )?;
+
+        if game_info.status == GameStatus::Committed {
+            return Err(RoshamboError::HostSeedAlreadyCommitted.into());
+        }
+        game_info.transition(GameStatus::Committed)?;
+        game_info.host_seed_commitment = COption::Some(commitment);
+        Game::pack(game_info, &mut game_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_reveal_seed(
+        accounts: &[AccountInfo],
+        public_seed: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let game_creator = next_account_info(account_info_iter)?;
+        Self::authorize_player(
+            game_creator,
+            accounts.get(2..).unwrap_or_default(),
+            0,
+            program_id,
+        )?;
+
+        let game_account = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
+
+        let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+        if game_info.game_creator_pubkey != *game_creator.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if !game_info.status.is_open() {
+            return Err(RoshamboError::GameEnded.into());
+        }
+
+        // revealing before the house committed would let it pick its seed with the player's in hand
+        if game_info.status != GameStatus::Committed {
+            return Err(RoshamboError::HostSeedNotCommitted.into());
+        }
+        if public_seed_commitment(game_account.key, public_seed, game_info.seed_slot)
+            != game_info.public_seed_commitment
+        {
+            return Err(RoshamboError::InvalidSeedReveal.into());
+        }
+
+        game_info.revealed_public_seed = COption::Some(public_seed);
+        Game::pack(game_info, &mut game_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Checks `player` signed, that enough signers of the SPL token multisig it is signed, or
+    /// that a live session key registered by the player signed for it. `session_accounts` are
+    /// the optional trailing accounts of the instruction: the multisig signers, or the session
+    /// key and its authority PDA. Returns the account that signed (first signer of a multisig).
+    fn authorize_player<'a, 'b>(
+        player: &'b AccountInfo<'a>,
+        session_accounts: &'b [AccountInfo<'a>],
+        wager: u64,
+        program_id: &Pubkey,
+    ) -> Result<&'b AccountInfo<'a>, ProgramError> {
+        if player.is_signer {
+            return Ok(player);
+        }
+        if *player.owner == spl_token::id() {
+            let signers = Self::multisig_signers(player, session_accounts)?;
+            return signers
+                .first()
+                .copied()
+                .ok_or(ProgramError::MissingRequiredSignature);
+        }
+
+        let session_accounts = &mut session_accounts.iter();
+        let session_key = next_account_info(session_accounts)
+            .map_err(|_| ProgramError::MissingRequiredSignature)?;
+        let session_authority_account = next_account_info(session_accounts)?;
+        assert_signer(session_key)?;
+        assert_owned_by(session_authority_account, program_id)?;
+
+        let session_authority =
+            SessionAuthority::unpack(&session_authority_account.try_borrow_data()?)?;
+        if session_authority.player_pubkey != *player.key
+            || session_authority.session_key_pubkey != *session_key.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        assert_pda(
+            session_authority_account,
+            &[
+                SESSION_AUTHORITY_SEED,
+                player.key.as_ref(),
+                session_key.key.as_ref(),
+            ],
+            session_authority.bump,
+            program_id,
+        )?;
+
+        if Clock::get()?.slot > session_authority.expiry_slot {
+            return Err(RoshamboError::SessionKeyExpired.into());
+        }
+        if wager > session_authority.max_wager {
+            return Err(RoshamboError::SessionKeyWagerExceeded.into());
+        }
+
+        Ok(session_key)
+    }
+
+    /// Signers of the SPL token multisig `owner` found among `accounts`, failing unless at
+    /// least the multisig threshold of them signed
+    fn multisig_signers<'a, 'b>(
+        owner: &'b AccountInfo<'a>,
+        accounts: &'b [AccountInfo<'a>],
+    ) -> Result<Vec<&'b AccountInfo<'a>>, ProgramError> {
+        if *owner.owner != spl_token::id() || owner.data_len() != Multisig::LEN {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let multisig = Multisig::unpack(&owner.try_borrow_data()?)?;
+        let listed = &multisig.signers[..multisig.n as usize];
+
+        let mut signers: Vec<&AccountInfo> = Vec::new();
+        for account in accounts {
+            if account.is_signer
+                && listed.contains(account.key)
+                && !signers.iter().any(|signer| signer.key == account.key)
+            {
+                signers.push(account);
+            }
+        }
+        if signers.len() < multisig.m as usize {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(signers)
+    }
+
+    fn process_register_session_key(
+        accounts: &[AccountInfo],
+        expiry_slot: u64,
+        max_wager: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+        assert_signer(player)?;
+
+        let session_key = next_account_info(account_info_iter)?;
+        let session_authority_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        if expiry_slot <= Clock::get()?.slot {
+            return Err(RoshamboError::SessionKeyExpired.into());
+        }
+
+        let (authority_pda, bump) =
+            find_session_authority_address(player.key, session_key.key, program_id);
+        if *session_authority_account.key != authority_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // registering an existing key again only updates its expiry and limit
+        if session_authority_account.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                player,
+                session_authority_account,
+                system_program_account,
+                SessionAuthority::LEN,
+                &[
+                    SESSION_AUTHORITY_SEED,
+                    player.key.as_ref(),
+                    session_key.key.as_ref(),
+                    &[bump],
+                ],
+                program_id,
+            )?;
+        } else {
+            assert_owned_by(session_authority_account, program_id)?;
+        }
+
+        SessionAuthority::pack(
+            SessionAuthority {
+                is_initialized: true,
+                player_pubkey: *player.key,
+                session_key_pubkey: *session_key.key,
+                expiry_slot,
+                max_wager,
+                bump,
+            },
+            &mut session_authority_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    fn process_revoke_session_key(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+        assert_signer(player)?;
+
+        let session_authority_account = next_account_info(account_info_iter)?;
+        assert_owned_by(session_authority_account, program_id)?;
+
+        let session_authority =
+            SessionAuthority::unpack(&session_authority_account.try_borrow_data()?)?;
+        if session_authority.player_pubkey != *player.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // close the authority account and refund rent fee back to the player
+        session_authority_account.try_borrow_mut_data()?.fill(0);
+        **player.try_borrow_mut_lamports()? = player
+            .lamports()
+            .checked_add(session_authority_account.lamports())
+            .ok_or(RoshamboError::AmountOverflow)?;
+        **session_authority_account.try_borrow_mut_lamports()? = 0;
+
+        Ok(())
+    }
+
+    fn process_update_settlement_memo(
+        accounts: &[AccountInfo],
+        enabled: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        config_info.settlement_memo = enabled;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Writes the settled game and its result as an SPL Memo, the memo program can be passed
+    /// anywhere in the claim accounts
+    fn emit_settlement_memo(
+        accounts: &[AccountInfo],
+        game: &Pubkey,
+        result: Outcome,
+    ) -> ProgramResult {
+        let memo_program = Self::find_account(accounts, &spl_memo::id())?;
+
+        let memo = format!("roshambo game {} {}", game, result.as_str());
+        invoke(
+            &Instruction {
+                program_id: spl_memo::id(),
+                accounts: vec![],
+                data: memo.into_bytes(),
+            },
+            slice::from_ref(memo_program),
+        )
+    }
+
+    /// Books the house result of a settled game: the stake on a loss, minus the profit paid
+    /// out on a win
+    fn record_settlement(
+        config_key: &Pubkey,
+        config_info: &mut Config,
+        result: Outcome,
+        game_info: &Game,
+    ) -> ProgramResult {
+        let bet_amount =
+            i64::try_from(game_info.bet_amount).map_err(|_| RoshamboError::AmountOverflow)?;
+        let house_result = match result {
+            Outcome::Draw => 0,
+            Outcome::Lose => bet_amount,
+            Outcome::Win => {
+                let payout = win_payout(
+                    game_info.bet_amount,
+                    game_info.payout_multiplier_bps,
+                    game_info.fee_bps,
+                )
+                .and_then(|payout| i64::try_from(payout).ok())
+                .ok_or(RoshamboError::AmountOverflow)?;
+                bet_amount - payout
+            }
+        };
+        config_info.house_pnl = config_info
+            .house_pnl
+            .checked_add(house_result)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Self::record_circuit_breaker(config_key, config_info, house_result)?;
+        Self::record_return_to_player(config_info, bet_amount, bet_amount - house_result)
+    }
+
+    /// Books settled stakes of `wagered` paying back `paid_out` in the RTP counters
+    fn record_return_to_player(
+        config_info: &mut Config,
+        wagered: i64,
+        paid_out: i64,
+    ) -> ProgramResult {
+        config_info.settled_volume = config_info
+            .settled_volume
+            .checked_add_signed(wagered)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        config_info.total_paid_out = config_info
+            .total_paid_out
+            .checked_add_signed(paid_out)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Ok(())
+    }
+
+    fn process_snapshot_stats(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let payer = next_account_info(account_info_iter)?;
+        assert_signer(payer)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let snapshot_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+
+        let clock = Clock::get()?;
+        let (snapshot_pda, bump) =
+            find_stats_snapshot_address(config_account.key, clock.epoch, program_id);
+        if *snapshot_account.key != snapshot_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // one snapshot per epoch, taking it again within the epoch refreshes it
+        if snapshot_account.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                payer,
+                snapshot_account,
+                system_program_account,
+                StatsSnapshot::LEN,
+                &[
+                    STATS_SNAPSHOT_SEED,
+                    config_account.key.as_ref(),
+                    &clock.epoch.to_le_bytes(),
+                    &[bump],
+                ],
+                program_id,
+            )?;
+        } else {
+            assert_owned_by(snapshot_account, program_id)?;
+        }
+
+        let (total_games, total_volume, house_pnl, settled_volume, total_paid_out, ..) =
+            Self::total_config_counters(accounts, config_account.key, &config_info, program_id)?;
+
+        StatsSnapshot::pack(
+            StatsSnapshot {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                epoch: clock.epoch,
+                slot: clock.slot,
+                total_games,
+                total_volume,
+                house_pnl,
+                settled_volume,
+                total_paid_out,
+            },
+            &mut snapshot_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Mint of the config and its decimals, for `transfer_checked`. The mint account can be
+    /// passed anywhere in the accounts (see `instruction::with_mint`).
+    fn config_mint<'a, 'b>(
+        accounts: &'b [AccountInfo<'a>],
+        config_info: &Config,
+    ) -> Result<(&'b AccountInfo<'a>, u8), ProgramError> {
+        let mint_account = Self::find_account(accounts, &config_info.mint_token_pubkey)?;
+        Ok((mint_account, config_info.decimals))
+    }
+
+    /// Finds an account by address anywhere in `accounts`, for optional programs and accounts
+    /// appended after the fixed ones
+    fn find_account<'a, 'b>(
+        accounts: &'b [AccountInfo<'a>],
+        key: &Pubkey,
+    ) -> Result<&'b AccountInfo<'a>, ProgramError> {
+        accounts
+            .iter()
+            .find(|account| account.key == key)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    }
+
+    fn process_init_settlement_tree(
+        accounts: &[AccountInfo],
+        max_depth: u32,
+        max_buffer_size: u32,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let merkle_tree = next_account_info(account_info_iter)?;
+        let tree_authority = next_account_info(account_info_iter)?;
+        let compression_program = next_account_info(account_info_iter)?;
+        let noop_program = next_account_info(account_info_iter)?;
+        assert_owned_by(merkle_tree, &compression::id())?;
+        if !compression::check_id(compression_program.key) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let (authority, bump) = find_settlement_tree_authority(config_account.key, program_id);
+        assert_pda(
+            tree_authority,
+            &[SETTLEMENT_TREE_AUTHORITY_SEED, config_account.key.as_ref()],
+            bump,
+            program_id,
+        )?;
+        invoke_signed(
+            &compression::init_empty_merkle_tree(
+                merkle_tree.key,
+                &authority,
+                max_depth,
+                max_buffer_size,
+            ),
+            &[
+                merkle_tree.clone(),
+                tree_authority.clone(),
+                noop_program.clone(),
+                compression_program.clone(),
+            ],
+            &[&[
+                SETTLEMENT_TREE_AUTHORITY_SEED,
+                config_account.key.as_ref(),
+                &[bump],
+            ]],
+        )?;
+
+        // settlements are logged to the new tree from now on
+        config_info.settlement_tree = COption::Some(*merkle_tree.key);
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Appends a settlement to the config's settlement tree, the tree, its authority and the
+    /// compression and noop programs can be passed anywhere in the claim accounts
+    fn append_settlement_leaf(
+        accounts: &[AccountInfo],
+        tree: &Pubkey,
+        config_key: &Pubkey,
+        leaf: [u8; 32],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let merkle_tree = Self::find_account(accounts, tree)?;
+        let compression_program = Self::find_account(accounts, &compression::id())?;
+        let noop_program = Self::find_account(accounts, &compression::noop::id())?;
+        let (authority, bump) = find_settlement_tree_authority(config_key, program_id);
+        let tree_authority = Self::find_account(accounts, &authority)?;
+
+        invoke_signed(
+            &compression::append(merkle_tree.key, &authority, leaf),
+            &[
+                merkle_tree.clone(),
+                tree_authority.clone(),
+                noop_program.clone(),
+                compression_program.clone(),
+            ],
+            &[&[SETTLEMENT_TREE_AUTHORITY_SEED, config_key.as_ref(), &[bump]]],
+        )
+    }
+
+    fn process_init_audit_log(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let audit_log_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let (audit_log_key, bump) = find_audit_log_address(config_account.key, program_id);
+        if *audit_log_account.key != audit_log_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !audit_log_account.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        Self::create_pda_account(
+            config_creator,
+            audit_log_account,
+            system_program_account,
+            AuditLog::LEN,
+            &[AUDIT_LOG_SEED, config_account.key.as_ref(), &[bump]],
+            program_id,
+        )?;
+        AuditLog::pack(
+            AuditLog {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                entries: 0,
+                head: [0; 32],
+                bump,
+            },
+            &mut audit_log_account.try_borrow_mut_data()?,
+        )?;
+
+        // reveals are chained into the new log from now on
+        config_info.audit_log = true;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Chains a seed reveal into the config's audit log, passed anywhere in the claim accounts
+    fn append_audit_entry(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        entry: &[u8; 32],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (audit_log_key, _) = find_audit_log_address(config_key, program_id);
+        let audit_log_account = Self::find_account(accounts, &audit_log_key)?;
+        assert_owned_by(audit_log_account, program_id)?;
+
+        let mut audit_log = AuditLog::unpack(&audit_log_account.try_borrow_data()?)?;
+        audit_log.head = audit_log_head(&audit_log.head, entry);
+        audit_log.entries = audit_log
+            .entries
+            .checked_add(1)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        AuditLog::pack(audit_log, &mut audit_log_account.try_borrow_mut_data()?)
+    }
+
+    /// Counters of the config counted on the config account itself
+    fn config_counters(config_info: &Config) -> ConfigCounters {
+        (
+            config_info.total_games,
+            config_info.total_volume,
+            config_info.house_pnl,
+            config_info.settled_volume,
+            config_info.total_paid_out,
+            config_info.games_cancelled,
+            config_info.refunds_total,
+        )
+    }
+
+    /// Counters of the config (see `config_counters`), counter shards included. A sharded
+    /// config keeps part of its counters in the counter shards, passed anywhere in `accounts`.
+    fn total_config_counters(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &Config,
+        program_id: &Pubkey,
+    ) -> Result<ConfigCounters, ProgramError> {
+        let (
+            mut total_games,
+            mut total_volume,
+            mut house_pnl,
+            mut settled_volume,
+            mut total_paid_out,
+            mut games_cancelled,
+            mut refunds_total,
+        ) = Self::config_counters(config_info);
+        for shard in 0..config_info.counter_shards {
+            let (counter_key, _) = find_counter_address(config_key, shard, program_id);
+            let counter_account = Self::find_account(accounts, &counter_key)?;
+            assert_owned_by(counter_account, program_id)?;
+            let counter = Counter::unpack(&counter_account.try_borrow_data()?)?;
+            total_games = total_games.saturating_add(counter.total_games);
+            total_volume = total_volume.saturating_add(counter.total_volume);
+            house_pnl = house_pnl.saturating_add(counter.house_pnl);
+            settled_volume = settled_volume.saturating_add(counter.settled_volume);
+            total_paid_out = total_paid_out.saturating_add(counter.total_paid_out);
+            games_cancelled = games_cancelled.saturating_add(counter.games_cancelled);
+            refunds_total = refunds_total.saturating_add(counter.refunds_total);
+        }
+        Ok((
+            total_games,
+            total_volume,
+            house_pnl,
+            settled_volume,
+            total_paid_out,
+            games_cancelled,
+            refunds_total,
+        ))
+    }
+
+    /// Persists a config whose counters changed since `baseline` and whose outstanding
+    /// exposure changes by `exposure_delta`, as `open_games_delta` games were opened or
+    /// settled. A sharded config isn't written so it is never write-locked by games: the
+    /// changes go to the player's counter shard instead, passed anywhere in `accounts`. The
+    /// exposure account, passed anywhere in `accounts` too, follows both while tracked.
+    #[allow(clippy::too_many_arguments)]
+    fn commit_config_counters(
+        accounts: &[AccountInfo],
+        config_account: &AccountInfo,
+        mut config_info: Config,
+        baseline: ConfigCounters,
+        exposure_delta: i64,
+        open_games_delta: i64,
+        player: &Pubkey,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        if config_info.exposure_tracking && (exposure_delta != 0 || open_games_delta != 0) {
+            Self::record_exposure(
+                accounts,
+                config_account.key,
+                exposure_delta,
+                open_games_delta,
+                program_id,
+            )?;
+        }
+
+        if config_info.counter_shards == 0 {
+            config_info.outstanding_exposure = config_info
+                .outstanding_exposure
+                .checked_add_signed(exposure_delta)
+                .ok_or(RoshamboError::AmountOverflow)?;
+            return Config::pack(config_info, &mut config_account.try_borrow_mut_data()?);
+        }
+
+        let shard = player_shard(player, config_info.counter_shards);
+        let (counter_key, _) = find_counter_address(config_account.key, shard, program_id);
+        let counter_account = Self::find_account(accounts, &counter_key)?;
+        assert_owned_by(counter_account, program_id)?;
+
+        let (
+            total_games,
+            total_volume,
+            house_pnl,
+            settled_volume,
+            total_paid_out,
+            games_cancelled,
+            refunds_total,
+        ) = Self::config_counters(&config_info);
+        let mut counter = Counter::unpack(&counter_account.try_borrow_data()?)?;
+        counter.total_games = counter
+            .total_games
+            .checked_add(total_games - baseline.0)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        counter.total_volume = counter
+            .total_volume
+            .checked_add(total_volume - baseline.1)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        counter.house_pnl = counter
+            .house_pnl
+            .checked_add(house_pnl - baseline.2)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        counter.settled_volume = counter
+            .settled_volume
+            .checked_add(settled_volume - baseline.3)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        counter.total_paid_out = counter
+            .total_paid_out
+            .checked_add(total_paid_out - baseline.4)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        counter.games_cancelled = counter
+            .games_cancelled
+            .checked_add(games_cancelled - baseline.5)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        counter.refunds_total = counter
+            .refunds_total
+            .checked_add(refunds_total - baseline.6)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        counter.outstanding_exposure = counter
+            .outstanding_exposure
+            .checked_add(exposure_delta)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Counter::pack(counter, &mut counter_account.try_borrow_mut_data()?)
+    }
+
+    fn process_set_counter_shards(
+        accounts: &[AccountInfo],
+        shards: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // shards hold part of the totals, they can be added but never removed. Settlements
+        // through them don't write the config, which a circuit breaker needs, and would all
+        // write the one exposure account. Each one counts the games of the house vault of the
+        // same index, so the exposure of a vault is known from its shard alone.
+        if shards <= config_info.counter_shards
+            || shards != config_info.vault_shards
+            || config_info.breaker_max_loss > 0
+            || config_info.exposure_tracking
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        for shard in config_info.counter_shards..shards {
+            let (counter_key, bump) = find_counter_address(config_account.key, shard, program_id);
+            let counter_account = Self::find_account(accounts, &counter_key)?;
+            if counter_account.data_is_empty() {
+                Self::create_pda_account(
+                    config_creator,
+                    counter_account,
+                    system_program_account,
+                    Counter::LEN,
+                    &[COUNTER_SEED, config_account.key.as_ref(), &[shard], &[bump]],
+                    program_id,
+                )?;
+            }
+            Counter::pack(
+                Counter {
+                    is_initialized: true,
+                    config_pubkey: *config_account.key,
+                    shard,
+                    total_games: 0,
+                    total_volume: 0,
+                    house_pnl: 0,
+                    outstanding_exposure: 0,
+                    settled_volume: 0,
+                    total_paid_out: 0,
+                    games_cancelled: 0,
+                    refunds_total: 0,
+                    bump,
+                },
+                &mut counter_account.try_borrow_mut_data()?,
+            )?;
+        }
+
+        config_info.counter_shards = shards;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Fails unless a token account of the config's stake mint owned by `player` and holding
+    /// at least the minimum stake is passed anywhere in the accounts, while one is required
+    fn assert_min_stake(
+        accounts: &[AccountInfo],
+        player: &Pubkey,
+        config_info: &Config,
+    ) -> ProgramResult {
+        let COption::Some(stake_mint) = config_info.stake_mint else {
+            return Ok(());
+        };
+        if config_info.min_stake == 0 {
+            return Ok(());
+        }
+        let staked = accounts
+            .iter()
+            .filter_map(|account| assert_token_account(account, &stake_mint, Some(player)).ok())
+            .any(|token_account| token_account.amount >= config_info.min_stake);
+        if !staked {
+            return Err(RoshamboError::InsufficientStake.into());
+        }
+        Ok(())
+    }
+
+    /// Fails unless the game of `player` is played through its vault of the config, so a
+    /// game's stake and payout always move through the same house token account and never
+    /// through the bankroll of another config with the same mint
+    fn assert_house_vault(
+        house_token_account: &AccountInfo,
+        config_key: &Pubkey,
+        config_info: &Config,
+        player: &Pubkey,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (vault, _) =
+            find_player_vault_address(config_key, config_info.vault_shards, player, program_id);
+        if *house_token_account.key != vault {
+            return Err(RoshamboError::HouseVaultMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Shard of `house_token_account` among the vaults of the config, failing unless it is
+    /// one of them: the only house token accounts its owner and admins move funds out of
+    fn assert_config_vault(
+        house_token_account: &AccountInfo,
+        config_key: &Pubkey,
+        config_info: &Config,
+        program_id: &Pubkey,
+    ) -> Result<u8, ProgramError> {
+        (0..config_info.vault_shards)
+            .find(|shard| {
+                find_house_vault_address(config_key, *shard, program_id).0
+                    == *house_token_account.key
+            })
+            .ok_or_else(|| RoshamboError::HouseVaultMismatch.into())
+    }
+
+    fn process_init_house_vaults(
+        accounts: &[AccountInfo],
+        shards: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if *token_program.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if config_info.mint_token_pubkey != *mint_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // players are bound to a vault, vaults can be added but never removed, nor once the
+        // counter shards follow them
+        if shards <= config_info.vault_shards || config_info.counter_shards > 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let (pda, _nonce) = find_house_authority(program_id);
+        for shard in config_info.vault_shards..shards {
+            let (vault_key, bump) = find_house_vault_address(config_account.key, shard, program_id);
+            let vault_account = Self::find_account(accounts, &vault_key)?;
+            if !vault_account.data_is_empty() {
+                // already created, e.g. by an earlier attempt
+                assert_token_account(vault_account, mint_account.key, Some(&pda))?;
+                continue;
+            }
+
+            Self::create_pda_account(
+                config_creator,
+                vault_account,
+                system_program_account,
+                spl_token::state::Account::LEN,
+                &[
+                    HOUSE_VAULT_SEED,
+                    config_account.key.as_ref(),
+                    &[shard],
+                    &[bump],
+                ],
+                &spl_token::id(),
+            )?;
+            invoke(
+                &spl_token::instruction::initialize_account3(
+                    token_program.key,
+                    vault_account.key,
+                    mint_account.key,
+                    &pda,
+                )?,
+                &[
+                    vault_account.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        config_info.vault_shards = shards;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Closes a finished game and refunds its rent to the creator, or with a game pool
+    /// returns it zeroed to the config's pool so a later game reuses the account and its rent
+    fn close_game(
+        game_account: &AccountInfo,
+        game_creator: &AccountInfo,
+        config_key: &Pubkey,
+        game_pool: bool,
+    ) -> ProgramResult {
+        if game_pool {
+            verbose_msg!("Recycling the game account into the game pool...");
+            let mut data = game_account.try_borrow_mut_data()?;
+            data.fill(0);
+            let mut game_info = Game::unpack_unchecked(&data)?;
+            game_info.config_pubkey = *config_key;
+            game_info.recycled = true;
+            return Game::pack(game_info, &mut data);
+        }
+
+        verbose_msg!("Closing the game account and refund fee back to creator...");
+        **game_creator.try_borrow_mut_lamports()? = game_creator
+            .lamports()
+            .checked_add(game_account.lamports())
+            .ok_or(RoshamboError::AmountOverflow)?;
+        **game_account.try_borrow_mut_lamports()? = 0;
+        *game_account.try_borrow_mut_data()? = &mut [];
+
+        Ok(())
+    }
+
+    fn process_update_game_pool(
+        accounts: &[AccountInfo],
+        enabled: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        config_info.game_pool = enabled;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Id of the next game of the config: its game counter plus one, or on a sharded config
+    /// the shard index (plus one) in the top byte and the shard's game counter plus one below,
+    /// so ids stay unique without writing the config and whatever the number of shards
+    fn next_game_id(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &Config,
+        player: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<u64, ProgramError> {
+        if config_info.counter_shards == 0 {
+            return config_info
+                .total_games
+                .checked_add(1)
+                .ok_or_else(|| RoshamboError::AmountOverflow.into());
+        }
+
+        let shard = player_shard(player, config_info.counter_shards);
+        let (counter_key, _) = find_counter_address(config_key, shard, program_id);
+        let counter_account = Self::find_account(accounts, &counter_key)?;
+        assert_owned_by(counter_account, program_id)?;
+        let counter = Counter::unpack(&counter_account.try_borrow_data()?)?;
+        Ok(((shard as u64 + 1) << 56) | (counter.total_games + 1))
+    }
+
+    /// Outstanding exposure backed by the house vault of `player` (see `vault_exposure`)
+    fn outstanding_exposure(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &Config,
+        player: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<u64, ProgramError> {
+        let vault_shard = player_shard(player, config_info.vault_shards);
+        Self::vault_exposure(accounts, config_key, config_info, vault_shard, program_id)
+    }
+
+    /// Outstanding exposure backed by the house vault `vault_shard`: the one counted on the
+    /// config, plus on a sharded config the counter shard of the same index, counter shards
+    /// following the vault shards. What the config counts isn't split by vault, every vault
+    /// backs all of it.
+    fn vault_exposure(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &Config,
+        vault_shard: u8,
+        program_id: &Pubkey,
+    ) -> Result<u64, ProgramError> {
+        let mut exposure = config_info.outstanding_exposure;
+        if config_info.counter_shards > 0 {
+            let (counter_key, _) = find_counter_address(config_key, vault_shard, program_id);
+            let counter_account = Self::find_account(accounts, &counter_key)?;
+            assert_owned_by(counter_account, program_id)?;
+            let counter = Counter::unpack(&counter_account.try_borrow_data()?)?;
+            exposure = exposure.saturating_add_signed(counter.outstanding_exposure);
+        }
+        Ok(exposure)
+    }
+
+    fn signed_amount(amount: u64) -> Result<i64, ProgramError> {
+        i64::try_from(amount).map_err(|_| RoshamboError::AmountOverflow.into())
+    }
+
+    /// Outstanding exposure of every open game of the config, sharded or not
+    fn total_outstanding_exposure(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &Config,
+        program_id: &Pubkey,
+    ) -> Result<u64, ProgramError> {
+        let mut exposure = config_info.outstanding_exposure;
+        for shard in 0..config_info.counter_shards {
+            let (counter_key, _) = find_counter_address(config_key, shard, program_id);
+            let counter_account = Self::find_account(accounts, &counter_key)?;
+            assert_owned_by(counter_account, program_id)?;
+            let counter = Counter::unpack(&counter_account.try_borrow_data()?)?;
+            exposure = exposure.saturating_add_signed(counter.outstanding_exposure);
+        }
+        Ok(exposure)
+    }
+
+    fn process_update_withdraw_bond(
+        accounts: &[AccountInfo],
+        bond: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        config_info.withdraw_bond = bond;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_schedule_emergency_withdraw(
+        accounts: &[AccountInfo],
+        enabled: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        config_info.emergency_unlock_slot = if enabled {
+            let unlock_slot = Clock::get()?
+                .slot
+                .checked_add(EMERGENCY_WITHDRAW_DELAY_SLOTS)
+                .ok_or(RoshamboError::AmountOverflow)?;
+            log_code!(
+                LOG_EMERGENCY_UNLOCK,
+                [unlock_slot],
+                "Emergency withdrawal unlocks at slot {}",
+                unlock_slot
+            );
+            RoshamboEvent::EmergencyWithdrawScheduled {
+                config: *config_account.key,
+                unlock_slot,
+            }
+            .emit();
+            structured_log("emergency", &[config_account.key, &unlock_slot]);
+            COption::Some(unlock_slot)
+        } else {
+            COption::None
+        };
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Counts a game refunded instead of settled in the config counters and in the player's
+    /// stats PDA, passed anywhere in `accounts`
+    fn record_refund(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &mut Config,
+        player: &Pubkey,
+        refund: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        config_info.games_cancelled = config_info
+            .games_cancelled
+            .checked_add(1)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        config_info.refunds_total = config_info
+            .refunds_total
+            .checked_add(refund)
+            .ok_or(RoshamboError::AmountOverflow)?;
+
+        let (player_stats_key, _) = find_player_stats_address(config_key, player, program_id);
+        let player_stats_account = Self::find_account(accounts, &player_stats_key)?;
+        let mut player_stats =
+            Self::load_player_stats(player_stats_account, player, config_key, program_id)?;
+        player_stats.games_cancelled = player_stats.games_cancelled.saturating_add(1);
+        player_stats.refunds_total = player_stats.refunds_total.saturating_add(refund);
+        PlayerStats::pack(
+            player_stats,
+            &mut player_stats_account.try_borrow_mut_data()?,
+        )
+    }
+
+    /// Counts a settled game in the player's wins and streak and awards its achievements
+    fn record_player_result(player_stats: &mut PlayerStats, result: Outcome, game_info: &Game) {
+        match result {
+            Outcome::Win => {
+                player_stats.wins = player_stats.wins.saturating_add(1);
+                player_stats.season_wins = player_stats.season_wins.saturating_add(1);
+                player_stats.win_streak = player_stats.win_streak.saturating_add(1);
+                player_stats.achievements |= ACHIEVEMENT_FIRST_WIN;
+                if player_stats.win_streak >= 5 {
+                    player_stats.achievements |= ACHIEVEMENT_WIN_STREAK_5;
+                }
+                if game_info.bet_amount == game_info.max_bet_amount {
+                    player_stats.achievements |= ACHIEVEMENT_MAX_BET_WIN;
+                }
+            }
+            Outcome::Lose => player_stats.win_streak = 0,
+            Outcome::Draw => player_stats.achievements |= ACHIEVEMENT_FIRST_DRAW,
+        }
+        if player_stats.games_played >= 100 {
+            player_stats.achievements |= ACHIEVEMENT_100_GAMES;
+        }
+        if player_stats.games_played >= 1000 {
+            player_stats.achievements |= ACHIEVEMENT_1000_GAMES;
+        }
+    }
+
+    /// Ranks the player's current win streak on the season's streak leaderboard, passed
+    /// anywhere in `accounts`, if it beats their entry or the shortest ranked streak
+    fn record_streak(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        season: u32,
+        player_stats: &PlayerStats,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (board_key, _) = find_streak_leaderboard_address(config_key, season, program_id);
+        let board_account = Self::find_account(accounts, &board_key)?;
+        assert_owned_by(board_account, program_id)?;
+        let mut board = StreakLeaderboard::unpack(&board_account.try_borrow_data()?)?;
+
+        let streak = player_stats.win_streak;
+        let position = board
+            .entries
+            .iter()
+            .position(|entry| entry.player_pubkey == player_stats.player_pubkey)
+            // entries are sorted, the last one holds the shortest streak
+            .unwrap_or(STREAK_LEADERBOARD_SIZE - 1);
+        if board.entries[position].streak >= streak {
+            return Ok(());
+        }
+
+        board.entries[position] = StreakEntry {
+            player_pubkey: player_stats.player_pubkey,
+            streak,
+        };
+        board.entries.sort_by_key(|entry| Reverse(entry.streak));
+        StreakLeaderboard::pack(board, &mut board_account.try_borrow_mut_data()?)
+    }
+
+    fn process_init_streak_leaderboard(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let board_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let season = config_info.season;
+        Self::create_streak_leaderboard(
+            config_creator,
+            config_account.key,
+            board_account,
+            system_program_account,
+            season,
+            program_id,
+        )?;
+
+        config_info.streak_leaderboard = true;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Creates the empty streak leaderboard PDA of `season`
+    fn create_streak_leaderboard<'a>(
+        payer: &AccountInfo<'a>,
+        config_key: &Pubkey,
+        board_account: &AccountInfo<'a>,
+        system_program_account: &AccountInfo<'a>,
+        season: u32,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (board_key, bump) = find_streak_leaderboard_address(config_key, season, program_id);
+        if *board_account.key != board_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !board_account.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        Self::create_pda_account(
+            payer,
+            board_account,
+            system_program_account,
+            StreakLeaderboard::LEN,
+            &[
+                STREAK_LEADERBOARD_SEED,
+                config_key.as_ref(),
+                &season.to_le_bytes(),
+                &[bump],
+            ],
+            program_id,
+        )?;
+        StreakLeaderboard::pack(
+            StreakLeaderboard {
+                is_initialized: true,
+                config_pubkey: *config_key,
+                season,
+                entries: [StreakEntry::default(); STREAK_LEADERBOARD_SIZE],
+            },
+            &mut board_account.try_borrow_mut_data()?,
+        )
+    }
+
+    /// Restarts the season counters (and the win streak ranked on the season leaderboard)
+    /// of a player last seen in an earlier season
+    fn roll_player_season(player_stats: &mut PlayerStats, season: u32) {
+        if player_stats.season != season {
+            player_stats.season = season;
+            player_stats.season_games = 0;
+            player_stats.season_wins = 0;
+            player_stats.win_streak = 0;
+        }
+    }
+
+    fn process_end_season(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let board_account = next_account_info(account_info_iter)?;
+        let result_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if !config_info.streak_leaderboard {
+            return Err(RoshamboError::SeasonNotActive.into());
+        }
+
+        let season = config_info.season;
+        let (board_key, _) =
+            find_streak_leaderboard_address(config_account.key, season, program_id);
+        if *board_account.key != board_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(board_account, program_id)?;
+        let board = StreakLeaderboard::unpack(&board_account.try_borrow_data()?)?;
+
+        let (result_key, bump) = find_season_result_address(config_account.key, season, program_id);
+        if *result_account.key != result_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !result_account.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        Self::create_pda_account(
+            config_creator,
+            result_account,
+            system_program_account,
+            SeasonResult::LEN,
+            &[
+                SEASON_RESULT_SEED,
+                config_account.key.as_ref(),
+                &season.to_le_bytes(),
+                &[bump],
+            ],
+            program_id,
+        )?;
+        SeasonResult::pack(
+            SeasonResult {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                season,
+                end_slot: Clock::get()?.slot,
+                entries: board.entries,
+            },
+            &mut result_account.try_borrow_mut_data()?,
+        )?;
+
+        // settlements stop updating the leaderboard, which stays as it ended
+        config_info.streak_leaderboard = false;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_start_season(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let board_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if config_info.streak_leaderboard {
+            return Err(RoshamboError::SeasonActive.into());
+        }
+
+        // player season counters restart lazily, the next time each player is seen
+        let season = config_info
+            .season
+            .checked_add(1)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Self::create_streak_leaderboard(
+            config_creator,
+            config_account.key,
+            board_account,
+            system_program_account,
+            season,
+            program_id,
+        )?;
+
+        config_info.season = season;
+        config_info.streak_leaderboard = true;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Balance the house vault `vault_shard` keeps when the owner takes funds out of it: the
+    /// funds backing the payouts of its open games (see `vault_exposure`) and its share of the
+    /// bond, split over the house vaults
+    fn withdraw_floor(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &Config,
+        vault_shard: u8,
+        program_id: &Pubkey,
+    ) -> Result<u64, ProgramError> {
+        let exposure =
+            Self::vault_exposure(accounts, config_key, config_info, vault_shard, program_id)?;
+        Ok(exposure
+            .saturating_add(config_info.withdraw_bond / config_info.vault_shards.max(1) as u64))
+    }
+
+    fn process_airdrop(
+        accounts: &[AccountInfo],
+        amounts: &[u64],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // the rewards come out of the treasury, the owner signing for it, never the bankroll
+        let treasury_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let (COption::Some(treasury), COption::Some(settlement_mint)) =
+            (config_info.treasury, config_info.settlement_mint)
+        else {
+            return Err(ProgramError::InvalidArgument);
+        };
+        if treasury != *treasury_account.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        assert_token_account(treasury_account, &settlement_mint, None)?;
+        let mint_account = Self::find_account(accounts, &settlement_mint)?;
+        assert_owned_by(mint_account, &spl_token::id())?;
+        let decimals = Mint::unpack(&mint_account.try_borrow_data()?)?.decimals;
+
+        let total = amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(RoshamboError::AmountOverflow)?;
+
+        for amount in amounts {
+            let recipient_account = next_account_info(account_info_iter)?;
+            assert_token_account(recipient_account, &settlement_mint, None)?;
+
+            let airdrop_ix = spl_token::instruction::transfer_checked(
+                token_program.key,
+                treasury_account.key,
+                mint_account.key,
+                recipient_account.key,
+                config_creator.key,
+                &[config_creator.key],
+                *amount,
+                decimals,
+            )?;
+            invoke(
+                &airdrop_ix,
+                &[
+                    treasury_account.clone(),
+                    recipient_account.clone(),
+                    config_creator.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+        log_code!(
+            LOG_AIRDROP,
+            [total, amounts.len()],
+            "Airdropped {} to {} recipients",
+            total,
+            amounts.len()
+        );
+
+        Ok(())
+    }
+
+    /// Checks that `host_seed` is the one of game `game_id` under the daily seed of `day`,
+    /// committed before the day and revealed since. The account can be passed anywhere in the
+    /// accounts.
+    fn assert_daily_seed(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        day: u64,
+        game_id: u64,
+        host_seed: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (daily_seed_key, _) = find_daily_seed_address(config_key, day, program_id);
+        let daily_seed_account = Self::find_account(accounts, &daily_seed_key)?;
+        if daily_seed_account.data_is_empty() {
+            return Err(RoshamboError::DailySeedNotCommitted.into());
+        }
+        assert_owned_by(daily_seed_account, program_id)?;
+        let daily_seed = DailySeed::unpack(&daily_seed_account.try_borrow_data()?)?;
+        let COption::Some(secret) = daily_seed.secret else {
+            return Err(RoshamboError::DailySeedNotRevealed.into());
+        };
+        if daily_host_seed(&secret, game_id) != host_seed {
+            return Err(RoshamboError::InvalidSeedReveal.into());
+        }
+
+        Ok(())
+    }
+
+    fn process_commit_daily_seed(
+        accounts: &[AccountInfo],
+        day: u64,
+        commitment: [u8; 32],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let daily_seed_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Check if this config account is already initialize
+        let config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_SETTLE,
+            program_id,
+        )?;
+
+        // a secret picked during its day could be chosen against the games already created
+        if day <= daily_seed_day(Clock::get()?.slot) {
+            return Err(RoshamboError::DailySeedTooLate.into());
+        }
+
+        let (daily_seed_key, bump) = find_daily_seed_address(config_account.key, day, program_id);
+        if *daily_seed_account.key != daily_seed_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !daily_seed_account.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        Self::create_pda_account(
+            config_creator,
+            daily_seed_account,
+            system_program_account,
+            DailySeed::LEN,
+            &[
+                DAILY_SEED_SEED,
+                config_account.key.as_ref(),
+                &day.to_le_bytes(),
+                &[bump],
+            ],
+            program_id,
+        )?;
+        DailySeed::pack(
+            DailySeed {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                day,
+                commitment,
+                secret: COption::None,
+            },
+            &mut daily_seed_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    fn process_reveal_daily_seed(
+        accounts: &[AccountInfo],
+        day: u64,
+        secret: [u8; 32],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_account = next_account_info(account_info_iter)?;
+        let daily_seed_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        assert_owned_by(daily_seed_account, program_id)?;
+
+        let (daily_seed_key, _) = find_daily_seed_address(config_account.key, day, program_id);
+        if *daily_seed_account.key != daily_seed_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let mut daily_seed = DailySeed::unpack(&daily_seed_account.try_borrow_data()?)?;
+
+        // games of the day are still settled with it until the day is over
+        if day >= daily_seed_day(Clock::get()?.slot) {
+            return Err(RoshamboError::DailySeedActive.into());
+        }
+        if daily_seed_commitment(&secret) != daily_seed.commitment {
+            return Err(RoshamboError::InvalidDailySeed.into());
+        }
+
+        daily_seed.secret = COption::Some(secret);
+        DailySeed::pack(daily_seed, &mut daily_seed_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_daily_seed(
+        accounts: &[AccountInfo],
+        enabled: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        config_info.daily_seed = enabled;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_set_admin(
+        accounts: &[AccountInfo],
+        permissions: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // only the owner grants permissions, admins can't grant their own
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let admin = next_account_info(account_info_iter)?;
+        let admin_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (admin_pda, bump) = find_admin_address(config_account.key, admin.key, program_id);
+        if *admin_account.key != admin_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // setting the permissions of an existing admin only updates them
+        if admin_account.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                config_creator,
+                admin_account,
+                system_program_account,
+                Admin::LEN,
+                &[
+                    ADMIN_SEED,
+                    config_account.key.as_ref(),
+                    admin.key.as_ref(),
+                    &[bump],
+                ],
+                program_id,
+            )?;
+        } else {
+            assert_owned_by(admin_account, program_id)?;
+        }
+
+        Admin::pack(
+            Admin {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                admin_pubkey: *admin.key,
+                permissions,
+                bump,
+            },
+            &mut admin_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Checks that `signer` is the owner of the config, or an admin of it holding the
+    /// `ADMIN_CAN_*` bit `permission`. The admin PDA can be passed anywhere in the accounts
+    /// (see `instruction::with_admin`).
+    fn assert_admin(
+        accounts: &[AccountInfo],
+        signer: &Pubkey,
+        config_key: &Pubkey,
+        config_info: &Config,
+        permission: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        if config_info.owner_pubkey == *signer {
+            return Ok(());
+        }
+
+        let (admin_key, _) = find_admin_address(config_key, signer, program_id);
+        let admin_account = Self::find_account(accounts, &admin_key)
+            .map_err(|_| ProgramError::InvalidAccountOwner)?;
+        assert_owned_by(admin_account, program_id)?;
+        let admin = Admin::unpack(&admin_account.try_borrow_data()?)?;
+        if admin.permissions & permission == 0 {
+            return Err(RoshamboError::MissingPermission.into());
+        }
+        Ok(())
+    }
+
+    fn process_update_pause(
+        accounts: &[AccountInfo],
+        paused: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_PAUSE,
+            program_id,
+        )?;
+
+        // a tripped circuit breaker is lifted by the owner alone, starting a new window
+        if !paused && config_info.breaker_tripped {
+            if config_info.owner_pubkey != *config_creator.key {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+            config_info.breaker_tripped = false;
+            config_info.breaker_window_start = Clock::get()?.slot;
+            config_info.breaker_window_loss = 0;
+        }
+        config_info.paused = paused;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Checks bet limits against the magnitude of the mint, so limits meant in whole tokens
+    /// (or in the base units of a mint with other decimals) fail instead of being stored
+    fn assert_bet_limits(min_bet_amount: u64, max_bet_amount: u64, decimals: u8) -> ProgramResult {
+        let max_allowed = 10u64
+            .checked_pow(decimals as u32)
+            .and_then(|unit| unit.checked_mul(MAX_BET_WHOLE_TOKENS))
+            .unwrap_or(u64::MAX);
+        if min_bet_amount == 0 || min_bet_amount > max_bet_amount || max_bet_amount > max_allowed {
+            return Err(RoshamboError::InvalidBetLimits.into());
+        }
+        Ok(())
+    }
+
+    fn process_claim_deferred(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let game_creator = next_account_info(account_info_iter)?;
+        let game_account = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
+
+        let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+        if game_info.game_creator_pubkey != *game_creator.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        match game_info.status {
+            GameStatus::ResultPosted => {}
+            GameStatus::Disputed => return Err(RoshamboError::PayoutHeld.into()),
+            _ => return Err(RoshamboError::NoDeferredPayout.into()),
+        }
+        if Clock::get()?.slot < game_info.release_slot {
+            return Err(RoshamboError::PayoutHeld.into());
+        }
+        game_info.transition(GameStatus::Settled)?;
+
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if game_info.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+
+        let (pda, nonce) = find_house_authority(program_id);
+        let receiver = assert_token_account(
+            receiver_account,
+            &config_info.mint_token_pubkey,
+            Some(game_creator.key),
+        )?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            game_creator.key,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        // a held loss has nothing to pay, the game is only closed
+        let released = game_info.deferred_payout;
+        if released > 0 {
+            assert_not_frozen(&receiver)?;
+        }
+        // the released payout goes through the payout cap and the vesting threshold like one
+        // paid at settlement, the creator signing for the rent of the PDAs
+        let owed = Self::payout_over_cap(released, &config_info);
+        if owed > 0 {
+            assert_signer(game_creator)?;
+            Self::open_payable(
+                accounts,
+                game_creator,
+                &game_info,
+                receiver_account.key,
+                config_account.key,
+                &config_info,
+                owed,
+                program_id,
+            )?;
+        }
+        let payout = released - owed;
+        let vested = if Self::vests(payout, &config_info) {
+            assert_signer(game_creator)?;
+            Self::start_vesting(
+                accounts,
+                game_creator,
+                &game_info,
+                receiver_account.key,
+                config_account.key,
+                &config_info,
+                payout,
+                program_id,
+            )?;
+            payout
+        } else {
+            0
+        };
+        let payout = payout - vested;
+
+        if payout > 0 {
+            let payout_ix = spl_token::instruction::transfer_checked(
+                token_program.key,
+                house_token_account.key,
+                mint_account.key,
+                receiver_account.key,
+                &pda,
+                &[&pda],
+                payout,
+                decimals,
+            )?;
+
+            verbose_msg!("Pay the deferred payout...");
+            invoke_signed(
+                &payout_ix,
+                &[
+                    house_token_account.clone(),
+                    receiver_account.clone(),
+                    pda_program.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+            )?;
+        }
+
+        let game_pool = config_info.game_pool;
+        let counters = Self::config_counters(&config_info);
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_info,
+            counters,
+            -Self::signed_amount(payout)?,
+            0,
+            game_creator.key,
+            program_id,
+        )?;
+
+        Self::close_game(game_account, game_creator, config_account.key, game_pool)
+    }
+
+    fn process_update_dispute_window(
+        accounts: &[AccountInfo],
+        window_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // without a new arbiter the current one keeps resolving pending disputes
+        match account_info_iter.next() {
+            Some(arbiter) => config_info.arbiter = COption::Some(*arbiter.key),
+            None if window_slots > 0 && config_info.arbiter.is_none() => {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+            None => {}
+        }
+        config_info.dispute_window_slots = window_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_dispute(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let game_creator = next_account_info(account_info_iter)?;
+        assert_signer(game_creator)?;
+
+        let game_account = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+        if game_info.game_creator_pubkey != *game_creator.key
+            || game_info.config_pubkey != *config_account.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if game_info.status == GameStatus::Disputed {
+            return Err(RoshamboError::AlreadyDisputed.into());
+        }
+
+        // only a held settlement can be disputed, and only while someone can resolve it
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        if game_info.status != GameStatus::ResultPosted
+            || game_info.release_slot == 0
+            || Clock::get()?.slot >= game_info.release_slot
+            || config_info.arbiter.is_none()
+        {
+            return Err(RoshamboError::DisputeWindowClosed.into());
+        }
+
+        game_info.transition(GameStatus::Disputed)?;
+        Game::pack(game_info, &mut game_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_resolve_dispute(
+        accounts: &[AccountInfo],
+        uphold: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let arbiter = next_account_info(account_info_iter)?;
+        assert_signer(arbiter)?;
+
+        let game_account = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+        if game_info.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if game_info.status != GameStatus::Disputed {
+            return Err(RoshamboError::NotDisputed.into());
+        }
+
+        let mut config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        if config_info.arbiter != COption::Some(*arbiter.key) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // the payout can be claimed right away, either as settled or as a refund of the stake
+        game_info.transition(GameStatus::ResultPosted)?;
+        game_info.release_slot = Clock::get()?.slot;
+        if !uphold {
+            let counters = Self::config_counters(&config_info);
+            let refund_delta = Self::signed_amount(game_info.bet_amount)?
+                - Self::signed_amount(game_info.deferred_payout)?;
+            config_info.house_pnl = config_info
+                .house_pnl
+                .checked_sub(refund_delta)
+                .ok_or(RoshamboError::AmountOverflow)?;
+            Self::record_circuit_breaker(config_account.key, &mut config_info, -refund_delta)?;
+            Self::record_return_to_player(&mut config_info, 0, refund_delta)?;
+            game_info.deferred_payout = game_info.bet_amount;
+            Self::commit_config_counters(
+                accounts,
+                config_account,
+                config_info,
+                counters,
+                refund_delta,
+                0,
+                &game_info.game_creator_pubkey,
+                program_id,
+            )?;
+        }
+        Game::pack(game_info, &mut game_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_large_win_hold(
+        accounts: &[AccountInfo],
+        threshold: u64,
+        delay_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        config_info.large_win_threshold = threshold;
+        config_info.large_win_delay_slots = delay_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_cancel_fee(
+        accounts: &[AccountInfo],
+        fee_bps: u16,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        if fee_bps as u64 > BPS_DENOMINATOR {
+            return Err(RoshamboError::InvalidFeeConfig.into());
+        }
+
+        config_info.cancel_fee_bps = fee_bps;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_min_stake(
+        accounts: &[AccountInfo],
+        min_stake: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        // without a new stake mint the current one is kept
+        match account_info_iter.next() {
+            Some(stake_mint) => {
+                assert_owned_by(stake_mint, &spl_token::id())?;
+                Mint::unpack(&stake_mint.try_borrow_data()?)?;
+                config_info.stake_mint = COption::Some(*stake_mint.key);
+            }
+            None if min_stake > 0 && config_info.stake_mint.is_none() => {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+            None => {}
+        }
+        config_info.min_stake = min_stake;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_burn(
+        accounts: &[AccountInfo],
+        burn_bps: u16,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        // burned and partner shares come out of the same house take
+        if burn_bps as u64 + config_info.partner_share_bps as u64 > BPS_DENOMINATOR {
+            return Err(RoshamboError::InvalidFeeConfig.into());
+        }
+
+        config_info.burn_bps = burn_bps;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Counts a settled game in the player's net loss while the cashback promotion runs
+    fn record_cashback(
+        player_stats: &mut PlayerStats,
+        config_info: &Config,
+        result: Outcome,
+        game_info: &Game,
+    ) -> ProgramResult {
+        let slot = Clock::get()?.slot;
+        let tiered = config_info
+            .cashback_tiers
+            .iter()
+            .any(|tier| tier.cashback_bps > 0);
+        if (config_info.cashback_bps == 0 && !tiered)
+            || slot < config_info.cashback_start_slot
+            || slot >= config_info.cashback_end_slot
+        {
+            return Ok(());
+        }
+        if player_stats.cashback_promo != config_info.cashback_start_slot {
+            player_stats.cashback_promo = config_info.cashback_start_slot;
+            player_stats.cashback_net_loss = 0;
+            player_stats.cashback_volume = 0;
+            player_stats.cashback_accrued = 0;
+        }
+        player_stats.cashback_volume = player_stats
+            .cashback_volume
+            .checked_add(game_info.bet_amount)
+            .ok_or(RoshamboError::AmountOverflow)?;
+
+        let delta = match result {
+            Outcome::Draw => 0,
+            Outcome::Lose => Self::signed_amount(game_info.bet_amount)?,
+            Outcome::Win => {
+                let payout = win_payout(
+                    game_info.bet_amount,
+                    game_info.payout_multiplier_bps,
+                    game_info.fee_bps,
+                )
+                .ok_or(RoshamboError::AmountOverflow)?;
+                -Self::signed_amount(payout - game_info.bet_amount)?
+            }
+        };
+        player_stats.cashback_net_loss = player_stats
+            .cashback_net_loss
+            .checked_add(delta)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        // the tier is the one reached with this game, earlier games keep their rate
+        let rate = config_info.cashback_rate(player_stats.cashback_volume);
+        player_stats.cashback_accrued = player_stats
+            .cashback_accrued
+            .checked_add((delta as i128 * rate as i128 / BPS_DENOMINATOR as i128) as i64)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Ok(())
+    }
+
+    fn process_update_cashback(
+        accounts: &[AccountInfo],
+        cashback_bps: u16,
+        start_slot: u64,
+        end_slot: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if cashback_bps as u64 > BPS_DENOMINATOR || start_slot > end_slot {
+            return Err(RoshamboError::InvalidFeeConfig.into());
+        }
+
+        config_info.cashback_bps = cashback_bps;
+        config_info.cashback_start_slot = start_slot;
+        config_info.cashback_end_slot = end_slot;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_claim_cashback(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+        assert_signer(player)?;
+
+        let player_stats_account = next_account_info(account_info_iter)?;
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        let mut config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+
+        let mut player_stats = Self::load_player_stats(
+            player_stats_account,
+            player.key,
+            config_account.key,
+            program_id,
+        )?;
+
+        // the cashback of a promotion is paid once it ended
+        if player_stats.cashback_promo != config_info.cashback_start_slot
+            || Clock::get()?.slot < config_info.cashback_end_slot
+            || player_stats.cashback_accrued <= 0
+        {
+            return Err(RoshamboError::NoCashback.into());
+        }
+        let cashback = player_stats.cashback_accrued as u64;
+
+        let (pda, nonce) = find_house_authority(program_id);
+        let receiver = assert_token_account(
+            receiver_account,
+            &config_info.mint_token_pubkey,
+            Some(player.key),
+        )?;
+        assert_not_frozen(&receiver)?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            player.key,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        let cashback_ix = spl_token::instruction::transfer_checked(
+            token_program.key,
+            house_token_account.key,
+            mint_account.key,
+            receiver_account.key,
+            &pda,
+            &[&pda],
+            cashback,
+            decimals,
+        )?;
+
+        verbose_msg!("Pay the cashback...");
+        invoke_signed(
+            &cashback_ix,
+            &[
+                house_token_account.clone(),
+                receiver_account.clone(),
+                pda_program.clone(),
+                mint_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )?;
+
+        player_stats.cashback_net_loss = 0;
+        player_stats.cashback_accrued = 0;
+        PlayerStats::pack(
+            player_stats,
+            &mut player_stats_account.try_borrow_mut_data()?,
+        )?;
+
+        config_info.house_pnl = config_info
+            .house_pnl
+            .checked_sub(Self::signed_amount(cashback)?)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Restarts the daily and weekly quest counters of the player when a new period is seen
+    fn roll_player_quests(player_stats: &mut PlayerStats, slot: u64) {
+        let day = daily_seed_day(slot);
+        if player_stats.quest_day != day {
+            player_stats.quest_day = day;
+            player_stats.day_wins = 0;
+            player_stats.day_wagered = 0;
+            player_stats.day_quests_claimed = 0;
+        }
+        let week = slot / QUEST_WEEK_SLOTS;
+        if player_stats.quest_week != week {
+            player_stats.quest_week = week;
+            player_stats.week_wins = 0;
+            player_stats.week_wagered = 0;
+            player_stats.week_quests_claimed = 0;
+        }
+    }
+
+    /// Counts a settled game in the player's daily and weekly quest progress
+    fn record_quest_progress(
+        player_stats: &mut PlayerStats,
+        result: Outcome,
+        game_info: &Game,
+    ) -> ProgramResult {
+        Self::roll_player_quests(player_stats, Clock::get()?.slot);
+        if result == Outcome::Win {
+            player_stats.day_wins = player_stats.day_wins.saturating_add(1);
+            player_stats.week_wins = player_stats.week_wins.saturating_add(1);
+        }
+        player_stats.day_wagered = player_stats
+            .day_wagered
+            .saturating_add(game_info.bet_amount);
+        player_stats.week_wagered = player_stats
+            .week_wagered
+            .saturating_add(game_info.bet_amount);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn process_set_quest(
+        accounts: &[AccountInfo],
+        quest_id: u8,
+        kind: u8,
+        period: u8,
+        target: u64,
+        reward: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if quest_id >= MAX_QUESTS
+            || (kind != QUEST_KIND_WINS && kind != QUEST_KIND_WAGER)
+            || (period != QUEST_PERIOD_DAY && period != QUEST_PERIOD_WEEK)
+            || target == 0
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let quest_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (quest_pda, bump) = find_quest_address(config_account.key, quest_id, program_id);
+        if *quest_account.key != quest_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // setting an existing quest only updates it
+        if quest_account.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                config_creator,
+                quest_account,
+                system_program_account,
+                Quest::LEN,
+                &[
+                    QUEST_SEED,
+                    config_account.key.as_ref(),
+                    &[quest_id],
+                    &[bump],
+                ],
+                program_id,
+            )?;
+        } else {
+            assert_owned_by(quest_account, program_id)?;
+        }
+
+        Quest::pack(
+            Quest {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                quest_id,
+                kind,
+                period,
+                target,
+                reward,
+                bump,
+            },
+            &mut quest_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    fn process_claim_quest_reward(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+        assert_signer(player)?;
+
+        let player_stats_account = next_account_info(account_info_iter)?;
+        let quest_account = next_account_info(account_info_iter)?;
+        assert_owned_by(quest_account, program_id)?;
+        let quest = Quest::unpack(&quest_account.try_borrow_data()?)?;
+
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if quest.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+
+        let mut player_stats = Self::load_player_stats(
+            player_stats_account,
+            player.key,
+            config_account.key,
+            program_id,
+        )?;
+        Self::roll_player_quests(&mut player_stats, Clock::get()?.slot);
+
+        let (wins, wagered, claimed) = match quest.period {
+            QUEST_PERIOD_DAY => (
+                player_stats.day_wins,
+                player_stats.day_wagered,
+                &mut player_stats.day_quests_claimed,
+            ),
+            _ => (
+                player_stats.week_wins,
+                player_stats.week_wagered,
+                &mut player_stats.week_quests_claimed,
+            ),
+        };
+        let progress = match quest.kind {
+            QUEST_KIND_WINS => wins,
+            _ => wagered,
+        };
+        if quest.reward == 0 || progress < quest.target {
+            return Err(RoshamboError::QuestNotCompleted.into());
+        }
+        let bit = 1u64 << quest.quest_id;
+        if *claimed & bit != 0 {
+            return Err(RoshamboError::QuestAlreadyClaimed.into());
+        }
+        *claimed |= bit;
+
+        let (pda, nonce) = find_house_authority(program_id);
+        let receiver = assert_token_account(
+            receiver_account,
+            &config_info.mint_token_pubkey,
+            Some(player.key),
+        )?;
+        assert_not_frozen(&receiver)?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            player.key,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        let reward_ix = spl_token::instruction::transfer_checked(
+            token_program.key,
+            house_token_account.key,
+            mint_account.key,
+            receiver_account.key,
+            &pda,
+            &[&pda],
+            quest.reward,
+            decimals,
+        )?;
+
+        verbose_msg!("Pay the quest reward...");
+        invoke_signed(
+            &reward_ix,
+            &[
+                house_token_account.clone(),
+                receiver_account.clone(),
+                pda_program.clone(),
+                mint_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )?;
+
+        PlayerStats::pack(
+            player_stats,
+            &mut player_stats_account.try_borrow_mut_data()?,
+        )?;
+
+        config_info.house_pnl = config_info
+            .house_pnl
+            .checked_sub(Self::signed_amount(quest.reward)?)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Partner PDA of `config_key` among `accounts`, passed to `NewGame` by the frontend the
+    /// game is created through (see `instruction::with_partner`), never one registered by
+    /// `player`
+    fn find_partner(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        player: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<COption<Pubkey>, ProgramError> {
+        let partner = accounts
+            .iter()
+            .filter(|account| account.owner == program_id && account.data_len() == Partner::LEN)
+            .find_map(|account| {
+                Partner::unpack(&account.data.borrow())
+                    .ok()
+                    .filter(|partner| partner.config_pubkey == *config_key)
+                    .map(|partner| (*account.key, partner))
+            });
+        match partner {
+            // a player referring their own games would earn back a share of the house's take
+            Some((_, partner)) if partner.authority_pubkey == *player => {
+                Err(RoshamboError::SelfReferral.into())
+            }
+            Some((partner_key, _)) => Ok(COption::Some(partner_key)),
+            None => Ok(COption::None),
+        }
+    }
+
+    fn process_register_partner(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let authority = next_account_info(account_info_iter)?;
+        assert_signer(authority)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+
+        let partner_account = next_account_info(account_info_iter)?;
+        let partner_token_account = next_account_info(account_info_iter)?;
+        assert_token_account(partner_token_account, &config_info.mint_token_pubkey, None)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (partner_pda, bump) =
+            find_partner_address(config_account.key, authority.key, program_id);
+        if *partner_account.key != partner_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // registering again only moves the payouts to another token account
+        let partner = if partner_account.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                authority,
+                partner_account,
+                system_program_account,
+                Partner::LEN,
+                &[
+                    PARTNER_SEED,
+                    config_account.key.as_ref(),
+                    authority.key.as_ref(),
+                    &[bump],
+                ],
+                program_id,
+            )?;
+            Partner {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                authority_pubkey: *authority.key,
+                token_account: *partner_token_account.key,
+                referred_players: 0,
+                games: 0,
+                volume: 0,
+                fees_earned: 0,
+                bump,
+            }
+        } else {
+            assert_owned_by(partner_account, program_id)?;
+            Partner {
+                token_account: *partner_token_account.key,
+                ..Partner::unpack(&partner_account.try_borrow_data()?)?
+            }
+        };
+        Partner::pack(partner, &mut partner_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_partner_share(
+        accounts: &[AccountInfo],
+        share_bps: u16,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        // burned and partner shares come out of the same house take
+        if share_bps as u64 + config_info.burn_bps as u64 > BPS_DENOMINATOR {
+            return Err(RoshamboError::InvalidFeeConfig.into());
+        }
+
+        config_info.partner_share_bps = share_bps;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Resolves the payout of a game settled as `result` at `slot`. It is held as
+    /// `deferred_payout` for the dispute window, the large win delay or a frozen receiver,
+    /// otherwise it is returned to be paid now. A rebet win only pays the profit, the stake
+    /// backs the new game.
+    fn settle_payout(
+        game_info: &mut Game,
+        result: Outcome,
+        config_info: &Config,
+        slot: u64,
+        receiver_frozen: bool,
+        rebet: bool,
+    ) -> Result<u64, ProgramError> {
+        // while results can be disputed every payout is held for the window, losses included
+        if config_info.dispute_window_slots > 0 {
+            game_info.release_slot = slot
+                .checked_add(config_info.dispute_window_slots)
+                .ok_or(RoshamboError::AmountOverflow)?;
+        }
+
+        let payout = match result {
+            Outcome::Lose => return Ok(0),
+            Outcome::Draw => game_info.bet_amount,
+            Outcome::Win => {
+                let payout = win_payout(
+                    game_info.bet_amount,
+                    game_info.payout_multiplier_bps,
+                    game_info.fee_bps,
+                )
+                .ok_or(RoshamboError::AmountOverflow)?;
+                let payout = if rebet {
+                    payout - game_info.bet_amount
+                } else {
+                    payout
+                };
+
+                // large wins are held for the finality delay, smaller ones pay instantly
+                let threshold = config_info.large_win_threshold;
+                if threshold > 0 && payout > threshold {
+                    let release_slot = slot
+                        .checked_add(config_info.large_win_delay_slots)
+                        .ok_or(RoshamboError::AmountOverflow)?;
+                    game_info.release_slot = game_info.release_slot.max(release_slot);
+                }
+                payout
+            }
+        };
+
+        if receiver_frozen || game_info.release_slot > 0 {
+            game_info.deferred_payout = payout;
+            return Ok(0);
+        }
+        Ok(payout)
+    }
+
+    /// Whether a payout is above the vesting threshold of the config
+    fn vests(payout: u64, config_info: &Config) -> bool {
+        config_info.vesting_threshold > 0 && payout > config_info.vesting_threshold
+    }
+
+    /// Creates the vesting PDA streaming the `total` payout of a settled game over the
+    /// config's `vesting_slots`, funded by `payer`
+    #[allow(clippy::too_many_arguments)]
+    fn start_vesting<'a>(
+        accounts: &[AccountInfo<'a>],
+        payer: &AccountInfo<'a>,
+        game_info: &Game,
+        receiver: &Pubkey,
+        config_key: &Pubkey,
+        config_info: &Config,
+        total: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (vesting_key, bump) = find_vesting_address(config_key, game_info.game_id, program_id);
+        let vesting_account = Self::find_account(accounts, &vesting_key)?;
+        let system_program_account = Self::find_account(accounts, &system_program::id())?;
+        Self::create_pda_account(
+            payer,
+            vesting_account,
+            system_program_account,
+            Vesting::LEN,
+            &[
+                VESTING_SEED,
+                config_key.as_ref(),
+                &game_info.game_id.to_le_bytes(),
+                &[bump],
+            ],
+            program_id,
+        )?;
+
+        let start_slot = Clock::get()?.slot;
+        verbose_msg!("Vesting the payout...");
+        Vesting::pack(
+            Vesting {
+                is_initialized: true,
+                config_pubkey: *config_key,
+                game_id: game_info.game_id,
+                player_pubkey: game_info.game_creator_pubkey,
+                receiver: *receiver,
+                payer: *payer.key,
+                total,
+                released: 0,
+                start_slot,
+                end_slot: start_slot
+                    .checked_add(config_info.vesting_slots)
+                    .ok_or(RoshamboError::AmountOverflow)?,
+                bump,
+            },
+            &mut vesting_account.try_borrow_mut_data()?,
+        )
+    }
+
+    /// Part of a payout above the payout cap of the config
+    fn payout_over_cap(payout: u64, config_info: &Config) -> u64 {
+        if config_info.max_payout_per_game == 0 {
+            return 0;
+        }
+        payout.saturating_sub(config_info.max_payout_per_game)
+    }
+
+    /// Creates the payable PDA owing `owed` of the payout of a settled game, paid in
+    /// installments of the config's payout cap, funded by `payer`
+    #[allow(clippy::too_many_arguments)]
+    fn open_payable<'a>(
+        accounts: &[AccountInfo<'a>],
+        payer: &AccountInfo<'a>,
+        game_info: &Game,
+        receiver: &Pubkey,
+        config_key: &Pubkey,
+        config_info: &Config,
+        owed: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (payable_key, bump) = find_payable_address(config_key, game_info.game_id, program_id);
+        let payable_account = Self::find_account(accounts, &payable_key)?;
+        let system_program_account = Self::find_account(accounts, &system_program::id())?;
+        Self::create_pda_account(
+            payer,
+            payable_account,
+            system_program_account,
+            Payable::LEN,
+            &[
+                PAYABLE_SEED,
+                config_key.as_ref(),
+                &game_info.game_id.to_le_bytes(),
+                &[bump],
+            ],
+            program_id,
+        )?;
+
+        verbose_msg!("Owing the payout above the cap...");
+        Payable::pack(
+            Payable {
+                is_initialized: true,
+                config_pubkey: *config_key,
+                game_id: game_info.game_id,
+                player_pubkey: game_info.game_creator_pubkey,
+                receiver: *receiver,
+                payer: *payer.key,
+                owed,
+                paid: 0,
+                installment: config_info.max_payout_per_game,
+                installment_slots: config_info.installment_slots,
+                next_slot: Clock::get()?
+                    .slot
+                    .checked_add(config_info.installment_slots)
+                    .ok_or(RoshamboError::AmountOverflow)?,
+                bump,
+            },
+            &mut payable_account.try_borrow_mut_data()?,
+        )
+    }
+
+    /// What a settled game pays back to the player - the stake of a draw, the payout of a win
+    fn result_payout(result: Outcome, game_info: &Game) -> Result<u64, ProgramError> {
+        Ok(match result {
+            Outcome::Draw => game_info.bet_amount,
+            Outcome::Lose => 0,
+            Outcome::Win => win_payout(
+                game_info.bet_amount,
+                game_info.payout_multiplier_bps,
+                game_info.fee_bps,
+            )
+            .ok_or(RoshamboError::AmountOverflow)?,
+        })
+    }
+
+    /// The house's take of a settled game - the stake of a loss, the fee of a win
+    fn house_take(result: Outcome, game_info: &Game) -> Result<u64, ProgramError> {
+        Ok(match result {
+            Outcome::Draw => 0,
+            Outcome::Lose => game_info.bet_amount,
+            Outcome::Win => win_fee(
+                game_info.bet_amount,
+                game_info.payout_multiplier_bps,
+                game_info.fee_bps,
+            )
+            .ok_or(RoshamboError::AmountOverflow)?,
+        })
+    }
+
+    fn process_claim_rewards(
+        accounts: &[AccountInfo],
+        seeds: &[(u64, u64)],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        if seeds.is_empty() || seeds.len() > MAX_CLAIM_BATCH {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let account_info_iter = &mut accounts.iter();
+
+        let game_creator = next_account_info(account_info_iter)?;
+        let house_account = next_account_info(account_info_iter)?;
+        assert_signer(house_account)?;
+
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        let mut config_account_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_account_info)?;
+        Self::assert_admin(
+            accounts,
+            house_account.key,
+            config_account.key,
+            &config_account_info,
+            ADMIN_CAN_SETTLE,
+            program_id,
+        )?;
+        let counters = Self::config_counters(&config_account_info);
+
+        let (pda, nonce) = find_house_authority(program_id);
+        let receiver_frozen = assert_token_account(
+            receiver_account,
+            &config_account_info.mint_token_pubkey,
+            Some(game_creator.key),
+        )?
+        .is_frozen();
+        assert_token_account(
+            house_token_account,
+            &config_account_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_account_info,
+            game_creator.key,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        let player_stats_account = next_account_info(account_info_iter)?;
+        let mut player_stats = Self::load_player_stats(
+            player_stats_account,
+            game_creator.key,
+            config_account.key,
+            program_id,
+        )?;
+        Self::roll_player_season(&mut player_stats, config_account_info.season);
+
+        let slot = Clock::get()?.slot;
+        let mut exposure_delta = 0i64;
+        let mut payout = 0u64;
+        let mut burned = 0u64;
+        for (host_seed, public_seed) in seeds.iter().copied() {
+            let game_account = next_account_info(account_info_iter)?;
+            assert_owned_by(game_account, program_id)?;
+            let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+            if game_info.game_creator_pubkey != *game_creator.key
+                || game_info.config_pubkey != *config_account.key
+            {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if !game_info.status.is_open() {
+                return Err(RoshamboError::GameEnded.into());
+            }
+            // only games the house settles alone, without a partner share to pay, are batched
+            if game_info.partner.is_some() {
+                return Err(RoshamboError::NotBatchable.into());
+            }
+            let COption::Some(commitment) = game_info.host_seed_commitment else {
+                return Err(RoshamboError::NotBatchable.into());
+            };
+            if host_seed_commitment(game_account.key, host_seed) != commitment
+                || public_seed_commitment(game_account.key, public_seed, game_info.seed_slot)
+                    != game_info.public_seed_commitment
+            {
+                return Err(RoshamboError::InvalidSeedReveal.into());
+            }
+
+            if slot
+                <= game_info
+                    .created_slot
+                    .saturating_add(config_account_info.min_settle_delay_slots)
+            {
+                return Err(RoshamboError::SettlementTooEarly.into());
+            }
+            if game_info.settlement_expired(&config_account_info, slot) {
+                return Err(RoshamboError::SettlementExpired.into());
+            }
+            if config_account_info.daily_seed {
+                Self::assert_daily_seed(
+                    accounts,
+                    config_account.key,
+                    daily_seed_day(game_info.created_slot),
+                    game_info.game_id,
+                    host_seed,
+                    program_id,
+                )?;
+            }
+
+            // the settled game no longer backs a payout
+            exposure_delta -= Self::signed_amount(
+                win_payout(
+                    game_info.bet_amount,
+                    game_info.payout_multiplier_bps,
+                    game_info.fee_bps,
+                )
+                .ok_or(RoshamboError::AmountOverflow)?,
+            )?;
+
+            let result = outcome(host_seed, public_seed);
+            game_info.result = COption::Some(result as u8);
+            let game_payout = Self::settle_payout(
+                &mut game_info,
+                result,
+                &config_account_info,
+                slot,
+                receiver_frozen,
+                false,
+            )?;
+            if Self::vests(game_payout, &config_account_info)
+                || Self::payout_over_cap(game_payout, &config_account_info) > 0
+            {
+                return Err(RoshamboError::NotBatchable.into());
+            }
+            payout = payout
+                .checked_add(game_payout)
+                .ok_or(RoshamboError::AmountOverflow)?;
+            exposure_delta += Self::signed_amount(game_info.deferred_payout)?;
+
+            Self::record_settlement(
+                config_account.key,
+                &mut config_account_info,
+                result,
+                &game_info,
+            )?;
+            if config_account_info.hourly_metrics {
+                Self::record_hourly_metrics(
+                    accounts,
+                    config_account.key,
+                    result,
+                    &game_info,
+                    program_id,
+                )?;
+            }
+            burned = burned
+                .checked_add(
+                    (Self::house_take(result, &game_info)? as u128
+                        * config_account_info.burn_bps as u128
+                        / BPS_DENOMINATOR as u128) as u64,
+                )
+                .ok_or(RoshamboError::AmountOverflow)?;
+
+            Self::record_player_result(&mut player_stats, result, &game_info);
+            Self::record_cashback(&mut player_stats, &config_account_info, result, &game_info)?;
+            Self::record_quest_progress(&mut player_stats, result, &game_info)?;
+            if config_account_info.streak_leaderboard {
+                Self::record_streak(
+                    accounts,
+                    config_account.key,
+                    config_account_info.season,
+                    &player_stats,
+                    program_id,
+                )?;
+            }
+
+            RoshamboEvent::GameSettled {
+                game: *game_account.key,
+                player: *game_creator.key,
+                game_id: game_info.game_id,
+                result: result as u8,
+                frontend_id: game_info.frontend_id,
+            }
+            .emit();
+            structured_log(
+                "settle",
+                &[
+                    &game_info.game_id,
+                    &(result as u8),
+                    &Self::result_payout(result, &game_info)?,
+                    &game_info.frontend_id,
+                ],
+            );
+
+            if config_account_info.settlement_memo {
+                Self::emit_settlement_memo(accounts, game_account.key, result)?;
+            }
+            if let COption::Some(tree) = config_account_info.settlement_tree {
+                let leaf = settlement_leaf(
+                    game_account.key,
+                    game_creator.key,
+                    game_info.bet_amount,
+                    result,
+                    slot,
+                );
+                Self::append_settlement_leaf(
+                    accounts,
+                    &tree,
+                    config_account.key,
+                    leaf,
+                    program_id,
+                )?;
+            }
+            if config_account_info.audit_log {
+                let entry = audit_entry(game_account.key, host_seed, public_seed, result);
+                Self::append_audit_entry(accounts, config_account.key, &entry, program_id)?;
+            }
+
+            // the game stays open, settled, until its payout is claimed
+            if game_info.deferred_payout > 0 || game_info.release_slot > 0 {
+                game_info.transition(GameStatus::ResultPosted)?;
+                Game::pack(game_info, &mut game_account.try_borrow_mut_data()?)?;
+            } else {
+                game_info.transition(GameStatus::Settled)?;
+                Self::close_game(
+                    game_account,
+                    game_creator,
+                    config_account.key,
+                    config_account_info.game_pool,
+                )?;
+            }
+        }
+
+        PlayerStats::pack(
+            player_stats,
+            &mut player_stats_account.try_borrow_mut_data()?,
+        )?;
+
+        // every game is settled before the single aggregated transfer
+        if payout > 0 {
+            let payout_ix = spl_token::instruction::transfer_checked(
+                token_program.key,
+                house_token_account.key,
+                mint_account.key,
+                receiver_account.key,
+                &pda,
+                &[&pda],
+                payout,
+                decimals,
+            )?;
+
+            verbose_msg!("Pay out the settled games...");
+            invoke_signed(
+                &payout_ix,
+                &[
+                    house_token_account.clone(),
+                    receiver_account.clone(),
+                    pda_program.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+            )?;
+        }
+
+        if burned > 0 {
+            let burn_ix = spl_token::instruction::burn_checked(
+                token_program.key,
+                house_token_account.key,
+                mint_account.key,
+                &pda,
+                &[&pda],
+                burned,
+                decimals,
+            )?;
+
+            verbose_msg!("Burn the house share...");
+            invoke_signed(
+                &burn_ix,
+                &[
+                    house_token_account.clone(),
+                    mint_account.clone(),
+                    pda_program.clone(),
+                    token_program.clone(),
+                ],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+            )?;
+            config_account_info.house_pnl = config_account_info
+                .house_pnl
+                .checked_sub(Self::signed_amount(burned)?)
+                .ok_or(RoshamboError::AmountOverflow)?;
+        }
+
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_account_info,
+            counters,
+            exposure_delta,
+            -(seeds.len() as i64),
+            game_creator.key,
+            program_id,
+        )
+    }
+
+    fn process_update_swap_program(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        config_info.swap_program = match account_info_iter.next() {
+            Some(program) if ALLOWED_SWAP_PROGRAMS.contains(program.key) => {
+                COption::Some(*program.key)
+            }
+            Some(_) => return Err(RoshamboError::SwapProgramNotAllowed.into()),
+            None => COption::None,
+        };
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_rebalance(
+        accounts: &[AccountInfo],
+        amount_in: u64,
+        min_amount_out: u64,
+        swap_data: &[u8],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let source_config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(source_config_account, program_id)?;
+        let destination_config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(destination_config_account, program_id)?;
+
+        let source_config = Config::unpack(&source_config_account.try_borrow_data()?)?;
+        let destination_config = Config::unpack(&destination_config_account.try_borrow_data()?)?;
+
+        // Check if the signer has authority over the funds of both configs
+        if source_config.owner_pubkey != *config_creator.key
+            || destination_config.owner_pubkey != *config_creator.key
+        {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let source_token_account = next_account_info(account_info_iter)?;
+        let destination_token_account = next_account_info(account_info_iter)?;
+        if source_token_account.key == destination_token_account.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let pda_program = next_account_info(account_info_iter)?;
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+        let source_before = assert_token_account(
+            source_token_account,
+            &source_config.mint_token_pubkey,
+            Some(&pda),
+        )?
+        .amount;
+        assert_token_account(
+            destination_token_account,
+            &destination_config.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        let source_shard = Self::assert_config_vault(
+            source_token_account,
+            source_config_account.key,
+            &source_config,
+            program_id,
+        )?;
+        Self::assert_config_vault(
+            destination_token_account,
+            destination_config_account.key,
+            &destination_config,
+            program_id,
+        )?;
+
+        // the counter shards of the source config precede the accounts of the swap
+        for _ in 0..source_config.counter_shards {
+            next_account_info(account_info_iter)?;
+        }
+
+        // the source market stays capitalized for its open games
+        let floor = Self::withdraw_floor(
+            accounts,
+            source_config_account.key,
+            &source_config,
+            source_shard,
+            program_id,
+        )?;
+        if source_before.saturating_sub(amount_in) < floor {
+            return Err(RoshamboError::HouseInsolvent.into());
+        }
+
+        let (swapped_out, swapped_in) = Self::escrow_swap(
+            account_info_iter,
+            source_config_account.key,
+            &source_config,
+            source_token_account,
+            destination_token_account,
+            pda_program,
+            amount_in,
+            swap_data,
+            program_id,
+        )?;
+        if swapped_out > amount_in || swapped_in < min_amount_out {
+            return Err(RoshamboError::SwapSlippage.into());
+        }
+
+        log_code!(
+            LOG_REBALANCE,
+            [swapped_out, swapped_in],
+            "Rebalanced {} into {}",
+            swapped_out,
+            swapped_in
+        );
+
+        Ok(())
+    }
+
+    /// Swaps at most `amount_in` out of `house_vault` of the config through the escrows of its
+    /// swap authority: the house PDA funds the source escrow, the swap program runs signed by
+    /// the swap authority alone, then both escrows are emptied, the tokens bought into
+    /// `destination` and what the swap left back into the vault. Reads the swap accounts
+    /// (see `Rebalance`) from `account_info_iter` and returns the amounts swapped out and in.
+    #[allow(clippy::too_many_arguments)]
+    fn escrow_swap<'a>(
+        account_info_iter: &mut std::slice::Iter<AccountInfo<'a>>,
+        config_key: &Pubkey,
+        config_info: &Config,
+        house_vault: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        pda_program: &AccountInfo<'a>,
+        amount_in: u64,
+        swap_data: &[u8],
+        program_id: &Pubkey,
+    ) -> Result<(u64, u64), ProgramError> {
+        let swap_authority = next_account_info(account_info_iter)?;
+        let source_escrow = next_account_info(account_info_iter)?;
+        let destination_escrow = next_account_info(account_info_iter)?;
+        let source_mint = next_account_info(account_info_iter)?;
+        let destination_mint = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let swap_program = next_account_info(account_info_iter)?;
+        if *token_program.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if config_info.swap_program != COption::Some(*swap_program.key)
+            || !ALLOWED_SWAP_PROGRAMS.contains(swap_program.key)
+        {
+            return Err(RoshamboError::SwapProgramNotAllowed.into());
+        }
+
+        let (pda, nonce) = find_house_authority(program_id);
+        let (swap_pda, swap_bump) = find_swap_authority_address(config_key, program_id);
+        assert_pda(
+            swap_authority,
+            &[SWAP_AUTHORITY_SEED, config_key.as_ref()],
+            swap_bump,
+            program_id,
+        )?;
+        assert_token_account(house_vault, source_mint.key, Some(&pda))?;
+        assert_token_account(destination, destination_mint.key, None)?;
+        assert_token_account(source_escrow, source_mint.key, Some(&swap_pda))?;
+        assert_token_account(destination_escrow, destination_mint.key, Some(&swap_pda))?;
+        assert_owned_by(source_mint, &spl_token::id())?;
+        assert_owned_by(destination_mint, &spl_token::id())?;
+        let source_decimals = Mint::unpack(&source_mint.try_borrow_data()?)?.decimals;
+        let destination_decimals = Mint::unpack(&destination_mint.try_borrow_data()?)?.decimals;
+
+        // the swap only reaches the escrows among the token accounts of the house
+        let swap_accounts = account_info_iter.as_slice();
+        for account in swap_accounts {
+            if *account.key == pda {
+                return Err(RoshamboError::SwapAccountNotAllowed.into());
+            }
+            if account.key == source_escrow.key
+                || account.key == destination_escrow.key
+                || *account.owner != spl_token::id()
+                || account.data_len() != Account::LEN
+            {
+                continue;
+            }
+            let token_account = Account::unpack(&account.try_borrow_data()?)?;
+            if token_account.owner == pda || token_account.owner == swap_pda {
+                return Err(RoshamboError::SwapAccountNotAllowed.into());
+            }
+        }
+
+        let house_seeds: &[&[u8]] = &[HOUSE_AUTHORITY_SEED, &[nonce]];
+        let swap_seeds: &[&[u8]] = &[SWAP_AUTHORITY_SEED, config_key.as_ref(), &[swap_bump]];
+        Self::transfer_signed(
+            house_vault,
+            source_escrow,
+            source_mint,
+            source_decimals,
+            pda_program,
+            token_program,
+            amount_in,
+            house_seeds,
+        )?;
+        let source_before = Account::unpack(&source_escrow.try_borrow_data()?)?.amount;
+        let destination_before = Account::unpack(&destination_escrow.try_borrow_data()?)?.amount;
+
+        let swap_ix = Instruction {
+            program_id: *swap_program.key,
+            accounts: swap_accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: *account.key,
+                    is_signer: account.is_signer || *account.key == swap_pda,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: swap_data.to_vec(),
+        };
+        let mut account_infos = swap_accounts.to_vec();
+        account_infos.push(swap_program.clone());
+        verbose_msg!("Swap through the escrows...");
+        invoke_signed(&swap_ix, &account_infos, &[swap_seeds])?;
+
+        let source_after = Account::unpack(&source_escrow.try_borrow_data()?)?.amount;
+        let destination_after = Account::unpack(&destination_escrow.try_borrow_data()?)?.amount;
+        if destination_after > 0 {
+            Self::transfer_signed(
+                destination_escrow,
+                destination,
+                destination_mint,
+                destination_decimals,
+                swap_authority,
+                token_program,
+                destination_after,
+                swap_seeds,
+            )?;
+        }
+        if source_after > 0 {
+            Self::transfer_signed(
+                source_escrow,
+                house_vault,
+                source_mint,
+                source_decimals,
+                swap_authority,
+                token_program,
+                source_after,
+                swap_seeds,
+            )?;
+        }
+
+        Ok((
+            source_before.saturating_sub(source_after),
+            destination_after.saturating_sub(destination_before),
+        ))
+    }
+
+    /// Transfers `amount` out of `source`, `authority` being a PDA signing with `seeds`
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_signed<'a>(
+        source: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        mint_account: &AccountInfo<'a>,
+        decimals: u8,
+        authority: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        amount: u64,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let transfer_ix = spl_token::instruction::transfer_checked(
+            token_program.key,
+            source.key,
+            mint_account.key,
+            destination.key,
+            authority.key,
+            &[authority.key],
+            amount,
+            decimals,
+        )?;
+        invoke_signed(
+            &transfer_ix,
+            &[
+                source.clone(),
+                destination.clone(),
+                authority.clone(),
+                mint_account.clone(),
+                token_program.clone(),
+            ],
+            &[seeds],
+        )
+    }
+
+    fn process_update_treasury(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        (config_info.treasury, config_info.settlement_mint) = match account_info_iter.next() {
+            Some(treasury) => {
+                let settlement_mint = next_account_info(account_info_iter)?;
+                assert_owned_by(settlement_mint, &spl_token::id())?;
+                Mint::unpack(&settlement_mint.try_borrow_data()?)?;
+                if *settlement_mint.key == config_info.mint_token_pubkey {
+                    return Err(ProgramError::InvalidArgument);
+                }
+                // a treasury the program could sign for would hand the swapped profits back
+                let treasury_info = assert_token_account(treasury, settlement_mint.key, None)?;
+                let (pda, _) = find_house_authority(program_id);
+                let (swap_authority, _) =
+                    find_swap_authority_address(config_account.key, program_id);
+                if treasury_info.owner == pda || treasury_info.owner == swap_authority {
+                    return Err(ProgramError::IllegalOwner);
+                }
+                (
+                    COption::Some(*treasury.key),
+                    COption::Some(*settlement_mint.key),
+                )
+            }
+            None => (COption::None, COption::None),
+        };
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_sweep_profits(
+        accounts: &[AccountInfo],
+        amount_in: u64,
+        min_amount_out: u64,
+        swap_data: &[u8],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let house_token_account = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
+        let (COption::Some(treasury), COption::Some(settlement_mint)) =
+            (config_info.treasury, config_info.settlement_mint)
+        else {
+            return Err(ProgramError::InvalidArgument);
+        };
+        if treasury != *treasury_account.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let pda_program = next_account_info(account_info_iter)?;
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+        let house_before = assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?
+        .amount;
+        let vault_shard = Self::assert_config_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            program_id,
+        )?;
+        // the treasury holds the settlement currency, whose mint can't change under it
+        assert_token_account(treasury_account, &settlement_mint, None)?;
+
+        // the counter shards precede the accounts of the swap
+        for _ in 0..config_info.counter_shards {
+            next_account_info(account_info_iter)?;
+        }
+
+        // only the edge won so far leaves the bankroll
+        let (_, _, house_pnl, ..) =
+            Self::total_config_counters(accounts, config_account.key, &config_info, program_id)?;
+        let unswept = house_pnl.saturating_sub_unsigned(config_info.swept_profits);
+        if amount_in as i128 > unswept as i128 {
+            return Err(RoshamboError::SweepExceedsProfits.into());
+        }
+        let floor = Self::withdraw_floor(
+            accounts,
+            config_account.key,
+            &config_info,
+            vault_shard,
+            program_id,
+        )?;
+        if house_before.saturating_sub(amount_in) < floor {
+            return Err(RoshamboError::HouseInsolvent.into());
+        }
+
+        let (swept, received) = Self::escrow_swap(
+            account_info_iter,
+            config_account.key,
+            &config_info,
+            house_token_account,
+            treasury_account,
+            pda_program,
+            amount_in,
+            swap_data,
+            program_id,
+        )?;
+        if swept > amount_in || received < min_amount_out {
+            return Err(RoshamboError::SwapSlippage.into());
+        }
+
+        config_info.swept_profits = config_info
+            .swept_profits
+            .checked_add(swept)
+            .ok_or(RoshamboError::AmountOverflow)?;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        log_code!(
+            LOG_SWEEP,
+            [swept, received],
+            "Swept {} into {}",
+            swept,
+            received
+        );
+
+        Ok(())
+    }
+
+    fn process_update_vesting(
+        accounts: &[AccountInfo],
+        threshold: u64,
+        vesting_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        config_info.vesting_threshold = threshold;
+        config_info.vesting_slots = vesting_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_release_vested(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let vesting_account = next_account_info(account_info_iter)?;
+        assert_owned_by(vesting_account, program_id)?;
+        let mut vesting = Vesting::unpack(&vesting_account.try_borrow_data()?)?;
+
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if vesting.receiver != *receiver_account.key || vesting.config_pubkey != *config_account.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+
+        let (pda, nonce) = find_house_authority(program_id);
+        let receiver = assert_token_account(
+            receiver_account,
+            &config_info.mint_token_pubkey,
+            Some(&vesting.player_pubkey),
+        )?;
+        assert_not_frozen(&receiver)?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            &vesting.player_pubkey,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+        let payer = next_account_info(account_info_iter)?;
+        if vesting.payer != *payer.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // released linearly over the vesting slots
+        let slot = Clock::get()?.slot;
+        let vested = if slot >= vesting.end_slot {
+            vesting.total
+        } else {
+            (vesting.total as u128 * slot.saturating_sub(vesting.start_slot) as u128
+                / (vesting.end_slot - vesting.start_slot) as u128) as u64
+        };
+        let amount = vested.saturating_sub(vesting.released);
+        if amount == 0 {
+            return Err(RoshamboError::NothingVested.into());
+        }
+
+        let release_ix = spl_token::instruction::transfer_checked(
+            token_program.key,
+            house_token_account.key,
+            mint_account.key,
+            receiver_account.key,
+            &pda,
+            &[&pda],
+            amount,
+            decimals,
+        )?;
+
+        verbose_msg!("Release the vested payout...");
+        invoke_signed(
+            &release_ix,
+            &[
+                house_token_account.clone(),
+                receiver_account.clone(),
+                pda_program.clone(),
+                mint_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )?;
+        vesting.released = vested;
+
+        let counters = Self::config_counters(&config_info);
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_info,
+            counters,
+            -Self::signed_amount(amount)?,
+            0,
+            &vesting.player_pubkey,
+            program_id,
+        )?;
+
+        if vesting.released < vesting.total {
+            return Vesting::pack(vesting, &mut vesting_account.try_borrow_mut_data()?);
+        }
+
+        verbose_msg!("Closing the vesting account and refund fee back to payer...");
+        **payer.try_borrow_mut_lamports()? = payer
+            .lamports()
+            .checked_add(vesting_account.lamports())
+            .ok_or(RoshamboError::AmountOverflow)?;
+        **vesting_account.try_borrow_mut_lamports()? = 0;
+        *vesting_account.try_borrow_mut_data()? = &mut [];
+
+        Ok(())
+    }
+
+    fn process_update_payout_cap(
+        accounts: &[AccountInfo],
+        max_payout: u64,
+        installment_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        config_info.max_payout_per_game = max_payout;
+        config_info.installment_slots = installment_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_pay_installment(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let payable_account = next_account_info(account_info_iter)?;
+        assert_owned_by(payable_account, program_id)?;
+        let mut payable = Payable::unpack(&payable_account.try_borrow_data()?)?;
+
+        let receiver_account = next_account_info(account_info_iter)?;
+        let house_token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if payable.receiver != *receiver_account.key || payable.config_pubkey != *config_account.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+
+        let (pda, nonce) = find_house_authority(program_id);
+        let receiver = assert_token_account(
+            receiver_account,
+            &config_info.mint_token_pubkey,
+            Some(&payable.player_pubkey),
+        )?;
+        assert_not_frozen(&receiver)?;
+        assert_token_account(
+            house_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&pda),
+        )?;
+        Self::assert_house_vault(
+            house_token_account,
+            config_account.key,
+            &config_info,
+            &payable.player_pubkey,
+            program_id,
+        )?;
+
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+        let payer = next_account_info(account_info_iter)?;
+        if payable.payer != *payer.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let slot = Clock::get()?.slot;
+        if slot < payable.next_slot {
+            return Err(RoshamboError::InstallmentNotDue.into());
+        }
+        let amount = payable.installment.min(payable.owed - payable.paid);
+
+        let installment_ix = spl_token::instruction::transfer_checked(
+            token_program.key,
+            house_token_account.key,
+            mint_account.key,
+            receiver_account.key,
+            &pda,
+            &[&pda],
+            amount,
+            decimals,
+        )?;
+
+        verbose_msg!("Pay the installment...");
+        invoke_signed(
+            &installment_ix,
+            &[
+                house_token_account.clone(),
+                receiver_account.clone(),
+                pda_program.clone(),
+                mint_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )?;
+        payable.paid += amount;
+        payable.next_slot = slot
+            .checked_add(payable.installment_slots)
+            .ok_or(RoshamboError::AmountOverflow)?;
+
+        let counters = Self::config_counters(&config_info);
+        Self::commit_config_counters(
+            accounts,
+            config_account,
+            config_info,
+            counters,
+            -Self::signed_amount(amount)?,
+            0,
+            &payable.player_pubkey,
+            program_id,
+        )?;
+
+        if payable.paid < payable.owed {
+            return Payable::pack(payable, &mut payable_account.try_borrow_mut_data()?);
+        }
+
+        verbose_msg!("Closing the payable account and refund fee back to payer...");
+        **payer.try_borrow_mut_lamports()? = payer
+            .lamports()
+            .checked_add(payable_account.lamports())
+            .ok_or(RoshamboError::AmountOverflow)?;
+        **payable_account.try_borrow_mut_lamports()? = 0;
+        *payable_account.try_borrow_mut_data()? = &mut [];
+
+        Ok(())
+    }
+
+    fn process_update_config_cooldown(
+        accounts: &[AccountInfo],
+        cooldown_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+
+        config_info.config_cooldown_slots = cooldown_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_set_fee_exempt(
+        accounts: &[AccountInfo],
+        exempt: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // waiving the fee is a commercial decision, left to the owner alone
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let player = next_account_info(account_info_iter)?;
+        let fee_exemption_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (fee_exemption_pda, bump) =
+            find_fee_exemption_address(config_account.key, player.key, program_id);
+        if *fee_exemption_account.key != fee_exemption_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // revoking keeps the account, granting again only flips it back
+        if fee_exemption_account.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                config_creator,
+                fee_exemption_account,
+                system_program_account,
+                FeeExemption::LEN,
+                &[
+                    FEE_EXEMPTION_SEED,
+                    config_account.key.as_ref(),
+                    player.key.as_ref(),
+                    &[bump],
+                ],
+                program_id,
+            )?;
+        } else {
+            assert_owned_by(fee_exemption_account, program_id)?;
+        }
+
+        FeeExemption::pack(
+            FeeExemption {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                player_pubkey: *player.key,
+                exempt,
+                updated_slot: Clock::get()?.slot,
+                bump,
+            },
+            &mut fee_exemption_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Fee of the games of `player`: none if the player is exempt and its fee exemption PDA is
+    /// anywhere in the accounts (see `instruction::with_fee_exemption`), the fee of the config
+    /// otherwise
+    fn player_fee_bps(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &Config,
+        player: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<u16, ProgramError> {
+        let (fee_exemption_key, _) = find_fee_exemption_address(config_key, player, program_id);
+        let Ok(fee_exemption_account) = Self::find_account(accounts, &fee_exemption_key) else {
+            return Ok(config_info.fee_bps);
+        };
+        assert_owned_by(fee_exemption_account, program_id)?;
+        let fee_exemption = FeeExemption::unpack(&fee_exemption_account.try_borrow_data()?)?;
+        Ok(if fee_exemption.exempt {
+            0
+        } else {
+            config_info.fee_bps
+        })
+    }
+
+    fn process_update_heartbeat_max_age(
+        accounts: &[AccountInfo],
+        max_age_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // a stale heartbeat pauses betting, so it takes the permission to pause
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_PAUSE,
+            program_id,
+        )?;
+
+        config_info.heartbeat_max_age_slots = max_age_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_heartbeat(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let house_account = next_account_info(account_info_iter)?;
+        assert_signer(house_account)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // only the service settling the games of the config vouches for it
+        Self::assert_admin(
+            accounts,
+            house_account.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_SETTLE,
+            program_id,
+        )?;
+
+        let heartbeat_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (heartbeat_pda, bump) = find_heartbeat_address(config_account.key, program_id);
+        if *heartbeat_account.key != heartbeat_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let slot = Clock::get()?.slot;
+        let (first_slot, beats) = if heartbeat_account.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                house_account,
+                heartbeat_account,
+                system_program_account,
+                Heartbeat::LEN,
+                &[HEARTBEAT_SEED, config_account.key.as_ref(), &[bump]],
+                program_id,
+            )?;
+            (slot, 0)
+        } else {
+            assert_owned_by(heartbeat_account, program_id)?;
+            let heartbeat = Heartbeat::unpack(&heartbeat_account.try_borrow_data()?)?;
+            (heartbeat.first_slot, heartbeat.beats)
+        };
+
+        Heartbeat::pack(
+            Heartbeat {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                host_pubkey: *house_account.key,
+                first_slot,
+                last_slot: slot,
+                beats: beats.saturating_add(1),
+                bump,
+            },
+            &mut heartbeat_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Checks the settlement service of the config sent a heartbeat within its max age, if
+    /// the config requires one. The heartbeat PDA can be passed anywhere in the accounts (see
+    /// `instruction::with_heartbeat`).
+    fn assert_host_alive(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        config_info: &Config,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        if config_info.heartbeat_max_age_slots == 0 {
+            return Ok(());
+        }
+
+        let (heartbeat_key, _) = find_heartbeat_address(config_key, program_id);
+        let heartbeat_account = Self::find_account(accounts, &heartbeat_key)?;
+        assert_owned_by(heartbeat_account, program_id)?;
+        let heartbeat = Heartbeat::unpack(&heartbeat_account.try_borrow_data()?)?;
+        let age = Clock::get()?.slot.saturating_sub(heartbeat.last_slot);
+        if age > config_info.heartbeat_max_age_slots {
+            return Err(RoshamboError::HostOffline.into());
+        }
+        Ok(())
+    }
+
+    fn process_update_insurance_fund(
+        accounts: &[AccountInfo],
+        insurance_bps: u16,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let insurance_fund = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        if *token_program.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if config_info.mint_token_pubkey != *mint_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if insurance_bps as u64 > BPS_DENOMINATOR {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let (insurance_fund_key, bump) =
+            find_insurance_fund_address(config_account.key, program_id);
+        if *insurance_fund.key != insurance_fund_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let (pda, _nonce) = find_house_authority(program_id);
+        if insurance_fund.data_is_empty() {
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                config_creator,
+                insurance_fund,
+                system_program_account,
+                spl_token::state::Account::LEN,
+                &[INSURANCE_FUND_SEED, config_account.key.as_ref(), &[bump]],
+                &spl_token::id(),
+            )?;
+            invoke(
+                &spl_token::instruction::initialize_account3(
+                    token_program.key,
+                    insurance_fund.key,
+                    mint_account.key,
+                    &pda,
+                )?,
+                &[
+                    insurance_fund.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        } else {
+            assert_token_account(insurance_fund, mint_account.key, Some(&pda))?;
+        }
+
+        config_info.insurance_bps = insurance_bps;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_schedule_insurance_withdraw(
+        accounts: &[AccountInfo],
+        enabled: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        config_info.insurance_unlock_slot = if enabled {
+            COption::Some(
+                Clock::get()?
+                    .slot
+                    .checked_add(INSURANCE_WITHDRAW_DELAY_SLOTS)
+                    .ok_or(RoshamboError::AmountOverflow)?,
+            )
+        } else {
+            COption::None
+        };
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_withdraw_insurance(
+        accounts: &[AccountInfo],
+        amount: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // the fund protects players, admins can't draw from it
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // the timelock leaves players time to see the reserve go before it is gone
+        match config_info.insurance_unlock_slot {
+            COption::Some(slot) if Clock::get()?.slot >= slot => {}
+            _ => return Err(RoshamboError::InsuranceWithdrawLocked.into()),
+        }
+        config_info.insurance_unlock_slot = COption::None;
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        let insurance_fund = next_account_info(account_info_iter)?;
+        let destination = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        Self::transfer_insurance(
+            config_account.key,
+            insurance_fund,
+            destination,
+            mint_account,
+            decimals,
+            token_program,
+            pda_program,
+            amount,
+            program_id,
+        )
+    }
+
+    fn process_pay_insurance_claim(
+        accounts: &[AccountInfo],
+        amount: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let arbiter = next_account_info(account_info_iter)?;
+        assert_signer(arbiter)?;
+
+        let game_account = next_account_info(account_info_iter)?;
+        assert_owned_by(game_account, program_id)?;
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        let mut game_info = Game::unpack(&game_account.try_borrow_data()?)?;
+        if game_info.config_pubkey != *config_account.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if game_info.status != GameStatus::Disputed {
+            return Err(RoshamboError::NotDisputed.into());
+        }
+
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+        if config_info.arbiter != COption::Some(*arbiter.key) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        // the fund makes a player whole, it isn't a second payout
+        if amount > game_info.bet_amount {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (mint_account, decimals) = Self::config_mint(accounts, &config_info)?;
+
+        let insurance_fund = next_account_info(account_info_iter)?;
+        let creator_token_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        assert_token_account(
+            creator_token_account,
+            &config_info.mint_token_pubkey,
+            Some(&game_info.game_creator_pubkey),
+        )?;
+
+        // the compensation settles the dispute, the held result stands
+        game_info.transition(GameStatus::ResultPosted)?;
+        game_info.release_slot = Clock::get()?.slot;
+        Game::pack(game_info, &mut game_account.try_borrow_mut_data()?)?;
+
+        Self::transfer_insurance(
+            config_account.key,
+            insurance_fund,
+            creator_token_account,
+            mint_account,
+            decimals,
+            token_program,
+            pda_program,
+            amount,
+            program_id,
+        )
+    }
+
+    /// Pays `amount` out of the insurance fund of the config, signed by the house PDA
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_insurance<'a>(
+        config_key: &Pubkey,
+        insurance_fund: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        mint_account: &AccountInfo<'a>,
+        decimals: u8,
+        token_program: &AccountInfo<'a>,
+        pda_program: &AccountInfo<'a>,
+        amount: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (insurance_fund_key, _) = find_insurance_fund_address(config_key, program_id);
+        if *insurance_fund.key != insurance_fund_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        let transfer_ix = spl_token::instruction::transfer_checked(
+            token_program.key,
+            insurance_fund.key,
+            mint_account.key,
+            destination.key,
+            &pda,
+            &[&pda],
+            amount,
+            decimals,
+        )?;
+
+        verbose_msg!("Pay out of the insurance fund...");
+        invoke_signed(
+            &transfer_ix,
+            &[
+                insurance_fund.clone(),
+                destination.clone(),
+                pda_program.clone(),
+                mint_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )
+    }
+
+    fn process_update_cashback_tiers(
+        accounts: &[AccountInfo],
+        tiers: &[(u64, u16)],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // the rate lookup takes the last tier reached, so volumes must strictly increase
+        if tiers.len() > MAX_CASHBACK_TIERS
+            || tiers
+                .iter()
+                .any(|(_, cashback_bps)| *cashback_bps as u64 > BPS_DENOMINATOR)
+            || tiers
+                .first()
+                .is_some_and(|(min_volume, _)| *min_volume == 0)
+            || tiers.windows(2).any(|pair| pair[0].0 >= pair[1].0)
+        {
+            return Err(RoshamboError::InvalidFeeConfig.into());
+        }
+
+        config_info.cashback_tiers = [CashbackTier::default(); MAX_CASHBACK_TIERS];
+        for (tier, (min_volume, cashback_bps)) in config_info.cashback_tiers.iter_mut().zip(tiers) {
+            tier.min_volume = *min_volume;
+            tier.cashback_bps = *cashback_bps;
+        }
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_init_hourly_metrics(
+        accounts: &[AccountInfo],
+        hour: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let payer = next_account_info(account_info_iter)?;
+        assert_signer(payer)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        let metrics_account = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        Config::unpack(&config_account.try_borrow_data()?)?;
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let (metrics_key, bump) = find_hourly_metrics_address(config_account.key, hour, program_id);
+        if *metrics_account.key != metrics_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !metrics_account.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        Self::create_pda_account(
+            payer,
+            metrics_account,
+            system_program_account,
+            HourlyMetrics::LEN,
+            &[
+                HOURLY_METRICS_SEED,
+                config_account.key.as_ref(),
+                &hour.to_le_bytes(),
+                &[bump],
+            ],
+            program_id,
+        )?;
+
+        HourlyMetrics::pack(
+            HourlyMetrics {
+                is_initialized: true,
+                config_pubkey: *config_account.key,
+                hour,
+                games: 0,
+                volume: 0,
+                payouts: 0,
+                bump,
+            },
+            &mut metrics_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    fn process_update_hourly_metrics(
+        accounts: &[AccountInfo],
+        enabled: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        config_info.hourly_metrics = enabled;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Counts a settled game in the hourly metrics of the hour it was created in
+    fn record_hourly_metrics(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        result: Outcome,
+        game_info: &Game,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let hour = HourlyMetrics::hour(game_info.created_slot);
+        let (metrics_key, _) = find_hourly_metrics_address(config_key, hour, program_id);
+        let metrics_account = Self::find_account(accounts, &metrics_key)?;
+        assert_owned_by(metrics_account, program_id)?;
+        let mut metrics = HourlyMetrics::unpack(&metrics_account.try_borrow_data()?)?;
+
+        metrics.games = metrics.games.saturating_add(1);
+        metrics.volume = metrics.volume.saturating_add(game_info.bet_amount);
+        metrics.payouts = metrics
+            .payouts
+            .saturating_add(Self::result_payout(result, game_info)?);
+        HourlyMetrics::pack(metrics, &mut metrics_account.try_borrow_mut_data()?)
+    }
+
+    fn process_update_kelly_fraction(
+        accounts: &[AccountInfo],
+        fraction_bps: u16,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+        if fraction_bps as u64 > BPS_DENOMINATOR {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        config_info.kelly_fraction_bps = fraction_bps;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Checks the recovery authority, a program-level key: the house PDA is shared by every
+    /// config, no config owner can speak for what it holds. It is the key held by the
+    /// recovery authority PDA once rotated, the one the program is built with before.
+    fn assert_recovery_authority(
+        recovery_authority: &AccountInfo,
+        recovery_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        assert_signer(recovery_authority)?;
+        let (recovery_key, _) = find_recovery_authority_address(program_id);
+        if *recovery_account.key != recovery_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let authority = if recovery_account.data_is_empty() {
+            crate::recovery_authority::id()
+        } else {
+            assert_owned_by(recovery_account, program_id)?;
+            RecoveryAuthority::unpack(&recovery_account.try_borrow_data()?)?.authority
+        };
+        if *recovery_authority.key != authority {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(())
+    }
+
+    fn process_set_recovery_authority(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let recovery_authority = next_account_info(account_info_iter)?;
+        let recovery_account = next_account_info(account_info_iter)?;
+        Self::assert_recovery_authority(recovery_authority, recovery_account, program_id)?;
+
+        let new_recovery_authority = next_account_info(account_info_iter)?;
+        assert_signer(new_recovery_authority)?;
+
+        let (_, bump) = find_recovery_authority_address(program_id);
+        if recovery_account.data_is_empty() {
+            let system_program_account = next_account_info(account_info_iter)?;
+            if *system_program_account.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Self::create_pda_account(
+                recovery_authority,
+                recovery_account,
+                system_program_account,
+                RecoveryAuthority::LEN,
+                &[RECOVERY_AUTHORITY_SEED, &[bump]],
+                program_id,
+            )?;
+        }
+
+        RecoveryAuthority::pack(
+            RecoveryAuthority {
+                is_initialized: true,
+                authority: *new_recovery_authority.key,
+                bump,
+            },
+            &mut recovery_account.try_borrow_mut_data()?,
+        )
+    }
+
+    fn process_recover_tokens(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let recovery_authority = next_account_info(account_info_iter)?;
+        let source_account = next_account_info(account_info_iter)?;
+        let destination_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        let recovery_account = next_account_info(account_info_iter)?;
+        Self::assert_recovery_authority(recovery_authority, recovery_account, program_id)?;
+        if *token_program.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        // vaults, insurance funds and the other token accounts of a config are PDAs of this
+        // program, the associated token account never is
+        if *source_account.key != find_house_token_address(mint_account.key, program_id) {
+            return Err(RoshamboError::RecoveryAccountNotAllowed.into());
+        }
+
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+        let source = assert_token_account(source_account, mint_account.key, Some(&pda))?;
+        assert_token_account(destination_account, mint_account.key, None)?;
+        assert_owned_by(mint_account, &spl_token::id())?;
+        let mint = Mint::unpack(&mint_account.try_borrow_data()?)?;
+
+        if source.amount > 0 {
+            let transfer_ix = spl_token::instruction::transfer_checked(
+                token_program.key,
+                source_account.key,
+                mint_account.key,
+                destination_account.key,
+                &pda,
+                &[&pda],
+                source.amount,
+                mint.decimals,
+            )?;
+
+            verbose_msg!("Recover the tokens sent to the house...");
+            invoke_signed(
+                &transfer_ix,
+                &[
+                    source_account.clone(),
+                    destination_account.clone(),
+                    pda_program.clone(),
+                    mint_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+            )?;
+        }
+
+        let close_ix = spl_token::instruction::close_account(
+            token_program.key,
+            source_account.key,
+            recovery_authority.key,
+            &pda,
+            &[&pda],
+        )?;
+        invoke_signed(
+            &close_ix,
+            &[
+                source_account.clone(),
+                recovery_authority.clone(),
+                pda_program.clone(),
+                token_program.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )
+    }
+
+    fn process_recover_lamports(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let recovery_authority = next_account_info(account_info_iter)?;
+        let pda_program = next_account_info(account_info_iter)?;
+        let destination = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
+        let recovery_account = next_account_info(account_info_iter)?;
+        Self::assert_recovery_authority(recovery_authority, recovery_account, program_id)?;
+        if *system_program_account.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let (pda, nonce) = find_house_authority(program_id);
+        assert_pda(pda_program, &[HOUSE_AUTHORITY_SEED], nonce, program_id)?;
+
+        let lamports = pda_program.lamports();
+        if lamports == 0 {
+            return Ok(());
+        }
+        verbose_msg!("Recover the lamports sent to the house...");
+        invoke_signed(
+            &system_instruction::transfer(&pda, destination.key, lamports),
+            &[
+                pda_program.clone(),
+                destination.clone(),
+                system_program_account.clone(),
+            ],
+            &[&[HOUSE_AUTHORITY_SEED, &[nonce]]],
+        )
+    }
+
+    fn process_reclaim_rent(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+        let config_info = Config::unpack(&config_account.try_borrow_data()?)?;
+
+        // the rent goes to the treasury of the config, never to an account of the caller
+        let treasury_account = next_account_info(account_info_iter)?;
+        if config_info.treasury != COption::Some(*treasury_account.key) {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let slot = Clock::get()?.slot;
+        for account in account_info_iter {
+            assert_owned_by(account, program_id)?;
+            let mut data = account.try_borrow_mut_data()?;
+            // no creation slot to go by: the first call stamps one behind the state, an
+            // account is initialized once any of its state is written
+            let stamp_offset = data
+                .len()
+                .checked_sub(U64_LENGTH)
+                .filter(|offset| *offset > 0)
+                .ok_or(ProgramError::InvalidAccountData)?;
+            let (state, stamp) = data.split_at_mut(stamp_offset);
+            if state.iter().any(|byte| *byte != 0) {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            let stamped_slot = u64::from_le_bytes(
+                (&*stamp)
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidAccountData)?,
+            );
+            if stamped_slot == 0 {
+                stamp.copy_from_slice(&slot.to_le_bytes());
+                continue;
+            }
+            if !config_info.settle_age_passed(stamped_slot, slot) {
+                continue;
+            }
+
+            verbose_msg!("Closing an abandoned account and reclaim its rent...");
+            **treasury_account.try_borrow_mut_lamports()? = treasury_account
+                .lamports()
+                .checked_add(account.lamports())
+                .ok_or(RoshamboError::AmountOverflow)?;
+            **account.try_borrow_mut_lamports()? = 0;
+            *data = &mut [];
+        }
+
+        Ok(())
+    }
+
+    fn process_update_max_settle_age(
+        accounts: &[AccountInfo],
+        max_age_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::assert_admin(
+            accounts,
+            config_creator.key,
+            config_account.key,
+            &config_info,
+            ADMIN_CAN_UPDATE_LIMITS,
+            program_id,
+        )?;
+        // a bound within the settlement delay would leave no slot to settle in
+        if max_age_slots > 0 && max_age_slots <= config_info.min_settle_delay_slots {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        config_info.max_settle_age_slots = max_age_slots;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    fn process_update_exposure_tracking(
+        accounts: &[AccountInfo],
+        enabled: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // every game would write-lock the exposure account again, as the unsharded config
+        if enabled && config_info.counter_shards > 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if enabled {
+            let exposure_account = next_account_info(account_info_iter)?;
+            let system_program_account = next_account_info(account_info_iter)?;
+            let (exposure_key, bump) = find_exposure_address(config_account.key, program_id);
+            if *exposure_account.key != exposure_key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if exposure_account.data_is_empty() {
+                if *system_program_account.key != system_program::id() {
+                    return Err(ProgramError::IncorrectProgramId);
+                }
+                Self::create_pda_account(
+                    config_creator,
+                    exposure_account,
+                    system_program_account,
+                    Exposure::LEN,
+                    &[EXPOSURE_SEED, config_account.key.as_ref(), &[bump]],
+                    program_id,
+                )?;
+            } else {
+                assert_owned_by(exposure_account, program_id)?;
+            }
+
+            // it starts from what the open games already owe, their count isn't known
+            let potential_payout = Self::total_outstanding_exposure(
+                accounts,
+                config_account.key,
+                &config_info,
+                program_id,
+            )?;
+            Exposure::pack(
+                Exposure {
+                    is_initialized: true,
+                    config_pubkey: *config_account.key,
+                    open_games: 0,
+                    potential_payout,
+                    slot: Clock::get()?.slot,
+                    bump,
+                },
+                &mut exposure_account.try_borrow_mut_data()?,
+            )?;
+        }
+
+        config_info.exposure_tracking = enabled;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Moves the exposure account of the config by `exposure_delta` and `open_games_delta`.
+    /// Games opened before tracking started settle against it too, their count stops at 0.
+    fn record_exposure(
+        accounts: &[AccountInfo],
+        config_key: &Pubkey,
+        exposure_delta: i64,
+        open_games_delta: i64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let (exposure_key, _) = find_exposure_address(config_key, program_id);
+        let exposure_account = Self::find_account(accounts, &exposure_key)?;
+        assert_owned_by(exposure_account, program_id)?;
+        let mut exposure = Exposure::unpack(&exposure_account.try_borrow_data()?)?;
+
+        exposure.open_games = exposure.open_games.saturating_add_signed(open_games_delta);
+        exposure.potential_payout = exposure
+            .potential_payout
+            .saturating_add_signed(exposure_delta);
+        exposure.slot = Clock::get()?.slot;
+        Exposure::pack(exposure, &mut exposure_account.try_borrow_mut_data()?)
+    }
+
+    fn process_update_circuit_breaker(
+        accounts: &[AccountInfo],
+        max_loss: u64,
+        window_slots: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let config_creator = next_account_info(account_info_iter)?;
+        assert_signer(config_creator)?;
+
+        let config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(config_account, program_id)?;
+
+        // Check if this config account is already initialize
+        let mut config_info = Config::unpack_unchecked(&config_account.try_borrow_data()?)?;
+        if !config_info.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        // Check if the signer has authority to update the config
+        if config_info.owner_pubkey != *config_creator.key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // the settlements of a sharded config can't pause it, they don't write it
+        if max_loss > 0 && (window_slots == 0 || config_info.counter_shards > 0) {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        config_info.breaker_max_loss = max_loss;
+        config_info.breaker_window_slots = window_slots;
+        config_info.breaker_window_start = Clock::get()?.slot;
+        config_info.breaker_window_loss = 0;
+        Config::pack(config_info, &mut config_account.try_borrow_mut_data()?)?;
+
+        Ok(())
+    }
+
+    /// Counts the house result of a settlement, a closed session or an overturned dispute in
+    /// the circuit breaker window, pausing the config once the house lost more than the
+    /// breaker allows within it
+    fn record_circuit_breaker(
+        config_key: &Pubkey,
+        config_info: &mut Config,
+        house_result: i64,
+    ) -> ProgramResult {
+        if config_info.breaker_max_loss == 0 {
+            return Ok(());
+        }
+
+        let slot = Clock::get()?.slot;
+        if slot.saturating_sub(config_info.breaker_window_start) >= config_info.breaker_window_slots
+        {
+            config_info.breaker_window_start = slot;
+            config_info.breaker_window_loss = 0;
+        }
+        config_info.breaker_window_loss = config_info
+            .breaker_window_loss
+            .checked_sub(house_result)
+            .ok_or(RoshamboError::AmountOverflow)?;
+
+        let window_loss = config_info.breaker_window_loss.max(0) as u64;
+        if window_loss > config_info.breaker_max_loss && !config_info.breaker_tripped {
+            config_info.paused = true;
+            config_info.breaker_tripped = true;
+            RoshamboEvent::CircuitBreakerTripped {
+                config: *config_key,
+                window_loss,
+                slot,
+            }
+            .emit();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG_EXPOSURE: u64 = 1_000;
+
+    /// A config with `vault_shards` house vaults and as many counter shards if `sharded`
+    fn config(vault_shards: u8, sharded: bool) -> Config {
+        let mut config = Config::unpack_unchecked(&[0; Config::LEN]).unwrap();
+        config.is_initialized = true;
+        config.vault_shards = vault_shards;
+        config.counter_shards = if sharded { vault_shards } else { 0 };
+        config.outstanding_exposure = CONFIG_EXPOSURE;
+        config
+    }
+
+    /// Packed counter shard of `config_key` with `outstanding_exposure`
+    fn counter_data(config_key: &Pubkey, shard: u8, outstanding_exposure: i64) -> Vec<u8> {
+        let mut counter = Counter::unpack_unchecked(&[0; Counter::LEN]).unwrap();
+        counter.is_initialized = true;
+        counter.config_pubkey = *config_key;
+        counter.shard = shard;
+        counter.outstanding_exposure = outstanding_exposure;
+        let mut data = vec![0; Counter::LEN];
+        Counter::pack(counter, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn vault_exposure_is_the_config_and_the_vault_counter() {
+        let program_id = crate::id();
+        let config_key = Pubkey::new_unique();
+        let keys: Vec<Pubkey> = (0..2)
+            .map(|shard| find_counter_address(&config_key, shard, &program_id).0)
+            .collect();
+        let mut lamports = [0; 2];
+        let mut data = [
+            counter_data(&config_key, 0, 50),
+            counter_data(&config_key, 1, -200),
+        ];
+        let [lamports_0, lamports_1] = &mut lamports;
+        let [data_0, data_1] = &mut data;
+        let accounts = [
+            AccountInfo::new(
+                &keys[0],
+                false,
+                true,
+                lamports_0,
+                data_0,
+                &program_id,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &keys[1],
+                false,
+                true,
+                lamports_1,
+                data_1,
+                &program_id,
+                false,
+                0,
+            ),
+        ];
+        let config = config(2, true);
+
+        let vault_0 = Processor::vault_exposure(&accounts, &config_key, &config, 0, &program_id);
+        assert_eq!(vault_0, Ok(CONFIG_EXPOSURE + 50));
+        // a player of the second vault is checked against its games only
+        let player = Pubkey::new_from_array([1; 32]);
+        let player_exposure =
+            Processor::outstanding_exposure(&accounts, &config_key, &config, &player, &program_id);
+        assert_eq!(player_exposure, Ok(CONFIG_EXPOSURE - 200));
+
+        // the floor keeps the vault's exposure and half of the bond
+        let mut config = config;
+        config.withdraw_bond = 300;
+        let floor = Processor::withdraw_floor(&accounts, &config_key, &config, 0, &program_id);
+        assert_eq!(floor, Ok(CONFIG_EXPOSURE + 50 + 150));
+
+        // the counter shard of the vault must be passed
+        let exposure =
+            Processor::vault_exposure(&accounts[..1], &config_key, &config, 1, &program_id);
+        assert_eq!(exposure, Err(ProgramError::NotEnoughAccountKeys));
+    }
+
+    #[test]
+    fn every_vault_backs_the_exposure_of_an_unsharded_config() {
+        let program_id = crate::id();
+        let config_key = Pubkey::new_unique();
+        let mut config = config(4, false);
+        config.withdraw_bond = 400;
+
+        for shard in 0..4 {
+            let exposure = Processor::vault_exposure(&[], &config_key, &config, shard, &program_id);
+            assert_eq!(exposure, Ok(CONFIG_EXPOSURE));
+            let floor = Processor::withdraw_floor(&[], &config_key, &config, shard, &program_id);
+            assert_eq!(floor, Ok(CONFIG_EXPOSURE + 100));
+        }
+    }
+
+    /// A game of `bet_amount` paying twice the stake on a win, without fee
+    fn game(bet_amount: u64) -> Game {
+        let mut game = Game::unpack_unchecked(&[0; Game::LEN]).unwrap();
+        game.is_initialized = true;
+        game.bet_amount = bet_amount;
+        game.payout_multiplier_bps = 20_000;
+        game
+    }
+
+    #[test]
+    fn payout_over_cap_is_the_part_above_the_cap() {
+        let mut config = config(1, false);
+        assert_eq!(Processor::payout_over_cap(1_000, &config), 0);
+
+        config.max_payout_per_game = 300;
+        assert_eq!(Processor::payout_over_cap(1_000, &config), 700);
+        assert_eq!(Processor::payout_over_cap(300, &config), 0);
+        assert_eq!(Processor::payout_over_cap(100, &config), 0);
+    }
+
+    #[test]
+    fn settle_payout_pays_the_result_now() {
+        let config = config(1, false);
+        let settle = |result, rebet| {
+            let mut game = game(100);
+            let payout = Processor::settle_payout(&mut game, result, &config, 10, false, rebet);
+            (payout, game.deferred_payout, game.release_slot)
+        };
+
+        assert_eq!(settle(Outcome::Win, false), (Ok(200), 0, 0));
+        assert_eq!(settle(Outcome::Draw, false), (Ok(100), 0, 0));
+        assert_eq!(settle(Outcome::Lose, false), (Ok(0), 0, 0));
+        // the stake of a rebet win backs the next game
+        assert_eq!(settle(Outcome::Win, true), (Ok(100), 0, 0));
+    }
+
+    #[test]
+    fn settle_payout_holds_large_wins_whole() {
+        let mut config = config(1, false);
+        config.large_win_threshold = 150;
+        config.large_win_delay_slots = 20;
+        config.max_payout_per_game = 50;
+
+        // the held win is paid in full later, the cap applies when it is released
+        let mut won = game(100);
+        let payout = Processor::settle_payout(&mut won, Outcome::Win, &config, 10, false, false);
+        assert_eq!(payout, Ok(0));
+        assert_eq!(won.deferred_payout, 200);
+        assert_eq!(won.release_slot, 30);
+        assert_eq!(
+            Processor::payout_over_cap(won.deferred_payout, &config),
+            150
+        );
+
+        // a draw stays under the threshold
+        let mut drawn = game(100);
+        let payout = Processor::settle_payout(&mut drawn, Outcome::Draw, &config, 10, false, false);
+        assert_eq!(payout, Ok(100));
+        assert_eq!(drawn.release_slot, 0);
+
+        // a frozen receiver holds any payout until it is thawed
+        let mut frozen = game(100);
+        let payout = Processor::settle_payout(&mut frozen, Outcome::Draw, &config, 10, true, false);
+        assert_eq!(payout, Ok(0));
+        assert_eq!(frozen.deferred_payout, 100);
+    }
 }
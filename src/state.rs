@@ -1,19 +1,154 @@
 // program objects, (de)serializing state
 
+use std::fmt;
+
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::{
+    entrypoint::ProgramResult,
     program_error::ProgramError,
     program_option::COption,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
 };
 
+use crate::{
+    error::RoshamboError,
+    kelly::kelly_max_bet,
+    outcome::{BPS_DENOMINATOR, DAILY_SEED_SLOTS},
+};
+
+/// Serializes `COption` fields as plain `Option`s, `COption` has no serde support
+#[cfg(feature = "serde")]
+mod coption_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use solana_program::program_option::COption;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &COption<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            COption::Some(value) => serializer.serialize_some(value),
+            COption::None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<COption<T>, D::Error> {
+        Ok(Option::<T>::deserialize(deserializer)?.into())
+    }
+}
+
+/// Lifecycle of a game, `GameStatus::can_become` lists the legal transitions
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameStatus {
+    /// Opened by the player, waiting for the house
+    Created = 0,
+    /// The house committed its seed with `CommitHostSeed`
+    Committed = 1,
+    /// Result posted with its payout held, finalized by `ClaimDeferred`
+    ResultPosted = 2,
+    /// Result posted and paid
+    Settled = 3,
+    /// Declined by the house or cancelled by the player, the stake refunded
+    Cancelled = 4,
+    /// Result disputed by the player, held until the arbiter resolves it
+    Disputed = 5,
+    /// Refunded by `CancelGame` or `DeclineGame` once past its settlement deadline (see
+    /// `Game::settlement_expired`)
+    Expired = 6,
+}
+
+impl GameStatus {
+    /// Whether the game is still waiting for its result
+    pub fn is_open(self) -> bool {
+        matches!(self, GameStatus::Created | GameStatus::Committed)
+    }
+
+    /// Whether a game can move from this status to `next`
+    pub fn can_become(self, next: GameStatus) -> bool {
+        use GameStatus::*;
+        match self {
+            Created => matches!(
+                next,
+                Committed | ResultPosted | Settled | Cancelled | Expired
+            ),
+            Committed => matches!(next, ResultPosted | Settled | Cancelled | Expired),
+            ResultPosted => matches!(next, Settled | Disputed),
+            Disputed => next == ResultPosted,
+            Settled | Cancelled | Expired => false,
+        }
+    }
+}
+
+impl TryFrom<u8> for GameStatus {
+    type Error = ProgramError;
+
+    fn try_from(status: u8) -> Result<Self, Self::Error> {
+        match status {
+            0 => Ok(GameStatus::Created),
+            1 => Ok(GameStatus::Committed),
+            2 => Ok(GameStatus::ResultPosted),
+            3 => Ok(GameStatus::Settled),
+            4 => Ok(GameStatus::Cancelled),
+            5 => Ok(GameStatus::Disputed),
+            6 => Ok(GameStatus::Expired),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
 // Game
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     pub is_initialized: bool,
     pub bet_amount: u64,
     pub game_creator_pubkey: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
     pub result: COption<u8>,
+    pub created_slot: u64,
+    pub min_bet_amount: u64,
+    pub max_bet_amount: u64,
+    pub fee_bps: u16,
+    pub payout_multiplier_bps: u16,
+    pub public_seed_commitment: [u8; 32],
+    pub config_pubkey: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub host_seed_commitment: COption<[u8; 32]>,
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub revealed_public_seed: COption<u64>,
+    /// Sequential number of the game within its config, see `Processor::next_game_id`
+    pub game_id: u64,
+    /// Recent slot the public seed commitment is bound to, see `outcome::public_seed_commitment`
+    pub seed_slot: u64,
+    /// Payout of a game settled while the receiver token account was frozen or with its
+    /// payout held, paid by `ClaimDeferred`
+    pub deferred_payout: u64,
+    /// Slot from which the held settlement of the game can be finalized by `ClaimDeferred`,
+    /// 0 if it isn't held
+    pub release_slot: u64,
+    /// Where the game is in its lifecycle, moved only through `Game::transition`
+    pub status: GameStatus,
+    /// Partner PDA of the frontend the game was created through, earns a share of the house's
+    /// take at settlement
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub partner: COption<Pubkey>,
+    /// Frontend the game was opened through, logged with its settlement (0 for none)
+    pub frontend_id: u16,
+    /// Share of the stake in basis points paid to the insurance fund on creation, never
+    /// refunded as it didn't reach the house
+    pub insurance_bps: u16,
+    /// Settled game returned to the pool of its config, zeroed apart from `config_pubkey`
+    pub recycled: bool,
 }
 
 impl Sealed for Game {}
@@ -23,13 +158,76 @@ impl IsInitialized for Game {
     }
 }
 
+impl Game {
+    /// Moves the game to `next`, failing with `GameEnded` once its result is posted or it
+    /// is over, and with `InvalidGameStatus` for any other illegal transition
+    pub fn transition(&mut self, next: GameStatus) -> ProgramResult {
+        if !self.status.can_become(next) {
+            return Err(if self.status.is_open() {
+                RoshamboError::InvalidGameStatus.into()
+            } else {
+                RoshamboError::GameEnded.into()
+            });
+        }
+        self.status = next;
+        Ok(())
+    }
+
+    /// What the house received of the stake, the refund of a game cancelled or declined
+    pub fn house_stake(&self) -> u64 {
+        self.bet_amount
+            - (self.bet_amount as u128 * self.insurance_bps as u128 / BPS_DENOMINATOR as u128)
+                as u64
+    }
+
+    /// Whether the game is more than `max_settle_age_slots` of `config` old at `slot`: its
+    /// result can no longer be claimed, the stake can only be refunded. Without a bound, a game
+    /// whose host seed is fixed (committed, or a daily seed) still expires after
+    /// `DEFAULT_SETTLE_AGE_SLOTS`, as its player can't cancel it anymore.
+    pub fn settlement_expired(&self, config: &Config, slot: u64) -> bool {
+        match config.max_settle_age_slots {
+            0 if self.host_seed_commitment.is_none() && !config.daily_seed => false,
+            _ => config.settle_age_passed(self.created_slot, slot),
+        }
+    }
+}
+
 /// Initialization flag size for account state
 pub const INITIALIZED_BYTES: usize = 1;
+pub const BOOL_LENGTH: usize = 1;
+pub const U8_LENGTH: usize = 1;
+pub const U16_LENGTH: usize = 2;
+pub const U32_LENGTH: usize = 4;
 pub const U64_LENGTH: usize = 8;
+pub const I64_LENGTH: usize = 8;
 pub const PUBKEY_BYTES: usize = 32;
+pub const HASH_BYTES: usize = 32;
 pub const OPTIONAL_U8: usize = 5;
-pub const GAME_ACCOUNT_STATE_SPACE: usize =
-    INITIALIZED_BYTES + U64_LENGTH + PUBKEY_BYTES + OPTIONAL_U8;
+pub const OPTIONAL_PUBKEY: usize = 36;
+pub const OPTIONAL_U64: usize = 12;
+pub const OPTIONAL_HASH: usize = 36;
+pub const GAME_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + U64_LENGTH
+    + PUBKEY_BYTES
+    + OPTIONAL_U8
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U16_LENGTH
+    + U16_LENGTH
+    + HASH_BYTES
+    + PUBKEY_BYTES
+    + OPTIONAL_HASH
+    + OPTIONAL_U64
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U8_LENGTH
+    + OPTIONAL_PUBKEY
+    + U16_LENGTH
+    + U16_LENGTH
+    + BOOL_LENGTH;
 
 fn pack_coption_u8(src: &COption<u8>, dst: &mut [u8; OPTIONAL_U8]) {
     let (tag, body) = mut_array_refs![dst, 4, 1];
@@ -53,39 +251,210 @@ fn unpack_coption_u8(src: &[u8; OPTIONAL_U8]) -> Result<COption<u8>, ProgramErro
     }
 }
 
+fn pack_coption_u64(src: &COption<u64>, dst: &mut [u8; OPTIONAL_U64]) {
+    let (tag, body) = mut_array_refs![dst, 4, 8];
+    match src {
+        COption::Some(value) => {
+            *tag = [1, 0, 0, 0];
+            *body = value.to_le_bytes();
+        }
+        COption::None => {
+            *tag = [0; 4];
+        }
+    }
+}
+
+fn unpack_coption_u64(src: &[u8; OPTIONAL_U64]) -> Result<COption<u64>, ProgramError> {
+    let (tag, body) = array_refs![src, 4, 8];
+    match *tag {
+        [0, 0, 0, 0] => Ok(COption::None),
+        [1, 0, 0, 0] => Ok(COption::Some(u64::from_le_bytes(*body))),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn pack_coption_pubkey(src: &COption<Pubkey>, dst: &mut [u8; OPTIONAL_PUBKEY]) {
+    let (tag, body) = mut_array_refs![dst, 4, 32];
+    match src {
+        COption::Some(key) => {
+            *tag = [1, 0, 0, 0];
+            body.copy_from_slice(key.as_ref());
+        }
+        COption::None => {
+            *tag = [0; 4];
+        }
+    }
+}
+
+fn unpack_coption_pubkey(src: &[u8; OPTIONAL_PUBKEY]) -> Result<COption<Pubkey>, ProgramError> {
+    let (tag, body) = array_refs![src, 4, 32];
+    match *tag {
+        [0, 0, 0, 0] => Ok(COption::None),
+        [1, 0, 0, 0] => Ok(COption::Some(Pubkey::new_from_array(*body))),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn pack_coption_hash(src: &COption<[u8; 32]>, dst: &mut [u8; OPTIONAL_HASH]) {
+    let (tag, body) = mut_array_refs![dst, 4, 32];
+    match src {
+        COption::Some(hash) => {
+            *tag = [1, 0, 0, 0];
+            *body = *hash;
+        }
+        COption::None => {
+            *tag = [0; 4];
+        }
+    }
+}
+
+fn unpack_coption_hash(src: &[u8; OPTIONAL_HASH]) -> Result<COption<[u8; 32]>, ProgramError> {
+    let (tag, body) = array_refs![src, 4, 32];
+    match *tag {
+        [0, 0, 0, 0] => Ok(COption::None),
+        [1, 0, 0, 0] => Ok(COption::Some(*body)),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
 impl Pack for Game {
     const LEN: usize = GAME_ACCOUNT_STATE_SPACE;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, GAME_ACCOUNT_STATE_SPACE];
-        let (is_initialized, bet_amount, game_creator_pubkey, result) = array_refs![
+        let (
+            is_initialized,
+            bet_amount,
+            game_creator_pubkey,
+            result,
+            created_slot,
+            min_bet_amount,
+            max_bet_amount,
+            fee_bps,
+            payout_multiplier_bps,
+            public_seed_commitment,
+            config_pubkey,
+            host_seed_commitment,
+            revealed_public_seed,
+            game_id,
+            seed_slot,
+            deferred_payout,
+            release_slot,
+            status,
+            partner,
+            frontend_id,
+            insurance_bps,
+            recycled,
+        ) = array_refs![
             src,
             INITIALIZED_BYTES,
             U64_LENGTH,
             PUBKEY_BYTES,
-            OPTIONAL_U8
+            OPTIONAL_U8,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U16_LENGTH,
+            U16_LENGTH,
+            HASH_BYTES,
+            PUBKEY_BYTES,
+            OPTIONAL_HASH,
+            OPTIONAL_U64,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH,
+            OPTIONAL_PUBKEY,
+            U16_LENGTH,
+            U16_LENGTH,
+            BOOL_LENGTH
         ];
         let is_initialized = match is_initialized {
             [0] => false,
             [1] => true,
             _ => return Err(ProgramError::InvalidAccountData),
         };
+        let recycled = match recycled {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
 
         Ok(Game {
             is_initialized,
             bet_amount: u64::from_le_bytes(*bet_amount),
             game_creator_pubkey: Pubkey::new_from_array(*game_creator_pubkey),
             result: unpack_coption_u8(result)?,
+            created_slot: u64::from_le_bytes(*created_slot),
+            min_bet_amount: u64::from_le_bytes(*min_bet_amount),
+            max_bet_amount: u64::from_le_bytes(*max_bet_amount),
+            fee_bps: u16::from_le_bytes(*fee_bps),
+            payout_multiplier_bps: u16::from_le_bytes(*payout_multiplier_bps),
+            public_seed_commitment: *public_seed_commitment,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            host_seed_commitment: unpack_coption_hash(host_seed_commitment)?,
+            revealed_public_seed: unpack_coption_u64(revealed_public_seed)?,
+            game_id: u64::from_le_bytes(*game_id),
+            seed_slot: u64::from_le_bytes(*seed_slot),
+            deferred_payout: u64::from_le_bytes(*deferred_payout),
+            release_slot: u64::from_le_bytes(*release_slot),
+            status: GameStatus::try_from(status[0])?,
+            partner: unpack_coption_pubkey(partner)?,
+            frontend_id: u16::from_le_bytes(*frontend_id),
+            insurance_bps: u16::from_le_bytes(*insurance_bps),
+            recycled,
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, GAME_ACCOUNT_STATE_SPACE];
-        let (is_initialized_dst, bet_amount_dst, game_creator_pubkey_dst, result_dst) = mut_array_refs![
+        let (
+            is_initialized_dst,
+            bet_amount_dst,
+            game_creator_pubkey_dst,
+            result_dst,
+            created_slot_dst,
+            min_bet_amount_dst,
+            max_bet_amount_dst,
+            fee_bps_dst,
+            payout_multiplier_bps_dst,
+            public_seed_commitment_dst,
+            config_pubkey_dst,
+            host_seed_commitment_dst,
+            revealed_public_seed_dst,
+            game_id_dst,
+            seed_slot_dst,
+            deferred_payout_dst,
+            release_slot_dst,
+            status_dst,
+            partner_dst,
+            frontend_id_dst,
+            insurance_bps_dst,
+            recycled_dst,
+        ) = mut_array_refs![
             dst,
             INITIALIZED_BYTES,
             U64_LENGTH,
             PUBKEY_BYTES,
-            OPTIONAL_U8
+            OPTIONAL_U8,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U16_LENGTH,
+            U16_LENGTH,
+            HASH_BYTES,
+            PUBKEY_BYTES,
+            OPTIONAL_HASH,
+            OPTIONAL_U64,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH,
+            OPTIONAL_PUBKEY,
+            U16_LENGTH,
+            U16_LENGTH,
+            BOOL_LENGTH
         ];
 
         let Game {
@@ -93,16 +462,85 @@ impl Pack for Game {
             bet_amount,
             game_creator_pubkey,
             ref result,
+            created_slot,
+            min_bet_amount,
+            max_bet_amount,
+            fee_bps,
+            payout_multiplier_bps,
+            public_seed_commitment,
+            config_pubkey,
+            ref host_seed_commitment,
+            ref revealed_public_seed,
+            game_id,
+            seed_slot,
+            deferred_payout,
+            release_slot,
+            status,
+            ref partner,
+            frontend_id,
+            insurance_bps,
+            recycled,
         } = self;
 
         is_initialized_dst[0] = *is_initialized as u8;
         *bet_amount_dst = bet_amount.to_le_bytes();
         game_creator_pubkey_dst.copy_from_slice(game_creator_pubkey.as_ref());
         pack_coption_u8(result, result_dst);
+        *created_slot_dst = created_slot.to_le_bytes();
+        *min_bet_amount_dst = min_bet_amount.to_le_bytes();
+        *max_bet_amount_dst = max_bet_amount.to_le_bytes();
+        *fee_bps_dst = fee_bps.to_le_bytes();
+        *payout_multiplier_bps_dst = payout_multiplier_bps.to_le_bytes();
+        *public_seed_commitment_dst = *public_seed_commitment;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        pack_coption_hash(host_seed_commitment, host_seed_commitment_dst);
+        pack_coption_u64(revealed_public_seed, revealed_public_seed_dst);
+        *game_id_dst = game_id.to_le_bytes();
+        *seed_slot_dst = seed_slot.to_le_bytes();
+        *deferred_payout_dst = deferred_payout.to_le_bytes();
+        *release_slot_dst = release_slot.to_le_bytes();
+        status_dst[0] = *status as u8;
+        pack_coption_pubkey(partner, partner_dst);
+        *frontend_id_dst = frontend_id.to_le_bytes();
+        *insurance_bps_dst = insurance_bps.to_le_bytes();
+        recycled_dst[0] = *recycled as u8;
     }
 }
 
+/// Slots between scheduling an emergency withdrawal and being able to make it (~1 day)
+pub const EMERGENCY_WITHDRAW_DELAY_SLOTS: u64 = 216_000;
+
+/// Slots between scheduling an insurance fund withdrawal and being able to make it (~1 week)
+pub const INSURANCE_WITHDRAW_DELAY_SLOTS: u64 = 1_512_000;
+
+/// Age in slots past which a game with a fixed host seed can only be refunded on a config
+/// without `max_settle_age_slots` (~2 days)
+pub const DEFAULT_SETTLE_AGE_SLOTS: u64 = 432_000;
+
+/// Upper bound of `max_bet_amount`, in whole tokens of the mint
+pub const MAX_BET_WHOLE_TOKENS: u64 = 1_000_000_000;
+
+/// Maximum age of the seed slot of a new game, about the lifetime of a blockhash
+pub const MAX_SEED_SLOT_AGE: u64 = 150;
+
+/// Most games settled by one `ClaimRewards`, bound by the accounts of a transaction
+pub const MAX_CLAIM_BATCH: usize = 10;
+
+/// Cashback tiers of a config, see `CashbackTier`
+pub const MAX_CASHBACK_TIERS: usize = 4;
+pub const CASHBACK_TIER_LENGTH: usize = U64_LENGTH + U16_LENGTH;
+
+/// Cashback rate of the players who staked at least `min_volume` in settled games over the
+/// promotion, in place of `Config::cashback_bps`. An unused tier has a zero `min_volume`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Default)]
+pub struct CashbackTier {
+    pub min_volume: u64,
+    pub cashback_bps: u16,
+}
+
 // Config
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Config {
     pub is_initialized: bool,
     pub total_games: u64,
@@ -110,6 +548,135 @@ pub struct Config {
     pub max_bet_amount: u64,
     pub owner_pubkey: Pubkey,
     pub mint_token_pubkey: Pubkey,
+    pub max_games_per_window: u64,
+    pub rate_limit_window_slots: u64,
+    pub min_settle_delay_slots: u64,
+    pub fee_bps: u16,
+    pub payout_multiplier_bps: u16,
+    pub settlement_memo: bool,
+    pub total_volume: u64,
+    pub house_pnl: i64,
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub settlement_tree: COption<Pubkey>,
+    pub counter_shards: u8,
+    pub vault_shards: u8,
+    pub game_pool: bool,
+    /// Sum of the maximum payouts of all open games
+    pub outstanding_exposure: u64,
+    /// Amount kept in the house on top of the outstanding exposure by `Withdraw`
+    pub withdraw_bond: u64,
+    /// Slot from which a scheduled emergency withdrawal can be made
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub emergency_unlock_slot: COption<u64>,
+    pub season: u32,
+    /// Whether settlements update the streak leaderboard of the current season
+    pub streak_leaderboard: bool,
+    /// Whether the host seed of every game comes from the `DailySeed` of its day
+    pub daily_seed: bool,
+    /// Decimals of the mint, read at `Initialize`
+    pub decimals: u8,
+    /// Whether betting in the mint of the config is halted, open games still settle
+    pub paused: bool,
+    /// Whether settlements are chained into the config's `AuditLog`
+    pub audit_log: bool,
+    /// Key resolving disputed results with `ResolveDispute`
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub arbiter: COption<Pubkey>,
+    /// Slots after a settlement during which the player can `Dispute` it, payouts are held
+    /// meanwhile. 0 disables disputes.
+    pub dispute_window_slots: u64,
+    /// Wins paying more than this are held for `large_win_delay_slots` before they can be
+    /// claimed with `ClaimDeferred`, 0 pays every win instantly
+    pub large_win_threshold: u64,
+    pub large_win_delay_slots: u64,
+    /// Share of the stake kept by the house when a player cancels a pending game
+    pub cancel_fee_bps: u16,
+    /// Token players must hold at least `min_stake` of to create games, see `UpdateMinStake`
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub stake_mint: COption<Pubkey>,
+    pub min_stake: u64,
+    /// Share of the house's take of every settlement (the stake of a loss, the fee of a win)
+    /// burned instead of kept, see `UpdateBurn`
+    pub burn_bps: u16,
+    /// Share of a player's net loss over the cashback promotion rebated with `ClaimCashback`,
+    /// below the first of the `cashback_tiers`. See `UpdateCashback`.
+    pub cashback_bps: u16,
+    /// Slots the promotion runs in, games settled in `[cashback_start_slot, cashback_end_slot)`
+    /// count toward the cashback
+    pub cashback_start_slot: u64,
+    pub cashback_end_slot: u64,
+    /// Higher cashback rates by volume over the promotion, by increasing `min_volume`. See
+    /// `UpdateCashbackTiers`.
+    pub cashback_tiers: [CashbackTier; MAX_CASHBACK_TIERS],
+    /// Share of the house's take of a settlement paid to the partner of the game, see
+    /// `UpdatePartnerShare`
+    pub partner_share_bps: u16,
+    /// Swap program `Rebalance` may route house funds through, see `UpdateSwapProgram`
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub swap_program: COption<Pubkey>,
+    /// Token account receiving the profits swapped with `SweepProfits`, see `UpdateTreasury`
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub treasury: COption<Pubkey>,
+    /// House profits swept so far, in the config mint. `house_pnl` minus this is left to
+    /// sweep.
+    pub swept_profits: u64,
+    /// Wins paying more than this at settlement are streamed to the player over
+    /// `vesting_slots` through a `Vesting` PDA, 0 pays every win at once. See `UpdateVesting`.
+    pub vesting_threshold: u64,
+    pub vesting_slots: u64,
+    /// Most paid for a single game at settlement, the rest of a larger win is owed through a
+    /// `Payable` in installments of at most this every `installment_slots`, 0 disables the
+    /// cap. See `UpdatePayoutCap`.
+    pub max_payout_per_game: u64,
+    pub installment_slots: u64,
+    /// Stake of the settled games and what they paid back (stakes included), the realized
+    /// return to player is `total_paid_out / settled_volume`. Compared across `SnapshotStats`
+    /// snapshots they give the RTP of an epoch.
+    pub settled_volume: u64,
+    pub total_paid_out: u64,
+    /// Slots after a bet limits change (`UpdateConfig`) during which no game can be opened,
+    /// so no bet lands under limits changed after it was signed. See `UpdateConfigCooldown`.
+    pub config_cooldown_slots: u64,
+    /// Slot of the last bet limits change
+    pub limits_updated_slot: u64,
+    /// Slots the heartbeat of the settlement service can be behind before games are refused,
+    /// 0 doesn't require one. See `UpdateHeartbeatMaxAge`.
+    pub heartbeat_max_age_slots: u64,
+    /// Share of every stake moved to the insurance fund of the config, see
+    /// `UpdateInsuranceFund`
+    pub insurance_bps: u16,
+    /// Slot from which a scheduled insurance fund withdrawal can be made
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub insurance_unlock_slot: COption<u64>,
+    /// Whether settlements are counted in the `HourlyMetrics` of the hour of their game
+    pub hourly_metrics: bool,
+    /// Fraction of the Kelly criterion capping every stake, 0 doesn't cap them. See
+    /// `UpdateKellyFraction` and `kelly::kelly_max_bet`.
+    pub kelly_fraction_bps: u16,
+    /// Games refunded instead of settled, declined by the house or cancelled by their player
+    pub games_cancelled: u64,
+    /// Stakes refunded by those games
+    pub refunds_total: u64,
+    /// Age in slots past which a game can't be settled anymore, only refunded, 0 for no bound.
+    /// See `UpdateMaxSettleAge`.
+    pub max_settle_age_slots: u64,
+    /// Whether the `Exposure` account of the config follows every open and settlement, see
+    /// `UpdateExposureTracking`
+    pub exposure_tracking: bool,
+    /// Net house loss within `breaker_window_slots` that pauses the config, 0 for no circuit
+    /// breaker. See `UpdateCircuitBreaker`.
+    pub breaker_max_loss: u64,
+    pub breaker_window_slots: u64,
+    /// Slot the current circuit breaker window started at
+    pub breaker_window_start: u64,
+    /// Net house loss over the current window, negative when the house is ahead
+    pub breaker_window_loss: i64,
+    /// Whether the config was paused by the circuit breaker, only its owner can unpause it
+    pub breaker_tripped: bool,
+    /// Mint of the `treasury`, the currency `SweepProfits` swaps the profits into. See
+    /// `UpdateTreasury`.
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub settlement_mint: COption<Pubkey>,
 }
 
 impl Sealed for Config {}
@@ -119,8 +686,72 @@ impl IsInitialized for Config {
     }
 }
 
-pub const CONFIG_ACCOUNT_STATE_SPACE: usize =
-    INITIALIZED_BYTES + U64_LENGTH + U64_LENGTH + U64_LENGTH + PUBKEY_BYTES + PUBKEY_BYTES;
+pub const CONFIG_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U16_LENGTH
+    + U16_LENGTH
+    + BOOL_LENGTH
+    + U64_LENGTH
+    + I64_LENGTH
+    + OPTIONAL_PUBKEY
+    + U8_LENGTH
+    + U8_LENGTH
+    + BOOL_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + OPTIONAL_U64
+    + U32_LENGTH
+    + BOOL_LENGTH
+    + BOOL_LENGTH
+    + U8_LENGTH
+    + BOOL_LENGTH
+    + BOOL_LENGTH
+    + OPTIONAL_PUBKEY
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U16_LENGTH
+    + OPTIONAL_PUBKEY
+    + U64_LENGTH
+    + U16_LENGTH
+    + U16_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + CASHBACK_TIER_LENGTH * MAX_CASHBACK_TIERS
+    + U16_LENGTH
+    + OPTIONAL_PUBKEY
+    + OPTIONAL_PUBKEY
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U16_LENGTH
+    + OPTIONAL_U64
+    + BOOL_LENGTH
+    + U16_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + BOOL_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + I64_LENGTH
+    + BOOL_LENGTH
+    + OPTIONAL_PUBKEY;
 
 impl Pack for Config {
     const LEN: usize = CONFIG_ACCOUNT_STATE_SPACE;
@@ -133,6 +764,66 @@ impl Pack for Config {
             max_bet_amount,
             owner_pubkey,
             mint_token_pubkey,
+            max_games_per_window,
+            rate_limit_window_slots,
+            min_settle_delay_slots,
+            fee_bps,
+            payout_multiplier_bps,
+            settlement_memo,
+            total_volume,
+            house_pnl,
+            settlement_tree,
+            counter_shards,
+            vault_shards,
+            game_pool,
+            outstanding_exposure,
+            withdraw_bond,
+            emergency_unlock_slot,
+            season,
+            streak_leaderboard,
+            daily_seed,
+            decimals,
+            paused,
+            audit_log,
+            arbiter,
+            dispute_window_slots,
+            large_win_threshold,
+            large_win_delay_slots,
+            cancel_fee_bps,
+            stake_mint,
+            min_stake,
+            burn_bps,
+            cashback_bps,
+            cashback_start_slot,
+            cashback_end_slot,
+            cashback_tiers,
+            partner_share_bps,
+            swap_program,
+            treasury,
+            swept_profits,
+            vesting_threshold,
+            vesting_slots,
+            max_payout_per_game,
+            installment_slots,
+            settled_volume,
+            total_paid_out,
+            config_cooldown_slots,
+            limits_updated_slot,
+            heartbeat_max_age_slots,
+            insurance_bps,
+            insurance_unlock_slot,
+            hourly_metrics,
+            kelly_fraction_bps,
+            games_cancelled,
+            refunds_total,
+            max_settle_age_slots,
+            exposure_tracking,
+            breaker_max_loss,
+            breaker_window_slots,
+            breaker_window_start,
+            breaker_window_loss,
+            breaker_tripped,
+            settlement_mint,
         ) = array_refs![
             src,
             INITIALIZED_BYTES,
@@ -140,13 +831,118 @@ impl Pack for Config {
             U64_LENGTH,
             U64_LENGTH,
             PUBKEY_BYTES,
-            PUBKEY_BYTES
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U16_LENGTH,
+            U16_LENGTH,
+            BOOL_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            OPTIONAL_PUBKEY,
+            U8_LENGTH,
+            U8_LENGTH,
+            BOOL_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            OPTIONAL_U64,
+            U32_LENGTH,
+            BOOL_LENGTH,
+            BOOL_LENGTH,
+            U8_LENGTH,
+            BOOL_LENGTH,
+            BOOL_LENGTH,
+            OPTIONAL_PUBKEY,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U16_LENGTH,
+            OPTIONAL_PUBKEY,
+            U64_LENGTH,
+            U16_LENGTH,
+            U16_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            CASHBACK_TIER_LENGTH * MAX_CASHBACK_TIERS,
+            U16_LENGTH,
+            OPTIONAL_PUBKEY,
+            OPTIONAL_PUBKEY,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U16_LENGTH,
+            OPTIONAL_U64,
+            BOOL_LENGTH,
+            U16_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            BOOL_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            BOOL_LENGTH,
+            OPTIONAL_PUBKEY
         ];
         let is_initialized = match is_initialized {
             [0] => false,
             [1] => true,
             _ => return Err(ProgramError::InvalidAccountData),
         };
+        let settlement_memo = match settlement_memo {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let game_pool = match game_pool {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let streak_leaderboard = match streak_leaderboard {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let daily_seed = match daily_seed {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let paused = match paused {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let audit_log = match audit_log {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let hourly_metrics = match hourly_metrics {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let exposure_tracking = match exposure_tracking {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let breaker_tripped = match breaker_tripped {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
 
         Ok(Config {
             is_initialized,
@@ -155,6 +951,66 @@ impl Pack for Config {
             max_bet_amount: u64::from_le_bytes(*max_bet_amount),
             owner_pubkey: Pubkey::new_from_array(*owner_pubkey),
             mint_token_pubkey: Pubkey::new_from_array(*mint_token_pubkey),
+            max_games_per_window: u64::from_le_bytes(*max_games_per_window),
+            rate_limit_window_slots: u64::from_le_bytes(*rate_limit_window_slots),
+            min_settle_delay_slots: u64::from_le_bytes(*min_settle_delay_slots),
+            fee_bps: u16::from_le_bytes(*fee_bps),
+            payout_multiplier_bps: u16::from_le_bytes(*payout_multiplier_bps),
+            settlement_memo,
+            total_volume: u64::from_le_bytes(*total_volume),
+            house_pnl: i64::from_le_bytes(*house_pnl),
+            settlement_tree: unpack_coption_pubkey(settlement_tree)?,
+            counter_shards: counter_shards[0],
+            vault_shards: vault_shards[0],
+            game_pool,
+            outstanding_exposure: u64::from_le_bytes(*outstanding_exposure),
+            withdraw_bond: u64::from_le_bytes(*withdraw_bond),
+            emergency_unlock_slot: unpack_coption_u64(emergency_unlock_slot)?,
+            season: u32::from_le_bytes(*season),
+            streak_leaderboard,
+            daily_seed,
+            decimals: decimals[0],
+            paused,
+            audit_log,
+            arbiter: unpack_coption_pubkey(arbiter)?,
+            dispute_window_slots: u64::from_le_bytes(*dispute_window_slots),
+            large_win_threshold: u64::from_le_bytes(*large_win_threshold),
+            large_win_delay_slots: u64::from_le_bytes(*large_win_delay_slots),
+            cancel_fee_bps: u16::from_le_bytes(*cancel_fee_bps),
+            stake_mint: unpack_coption_pubkey(stake_mint)?,
+            min_stake: u64::from_le_bytes(*min_stake),
+            burn_bps: u16::from_le_bytes(*burn_bps),
+            cashback_bps: u16::from_le_bytes(*cashback_bps),
+            cashback_start_slot: u64::from_le_bytes(*cashback_start_slot),
+            cashback_end_slot: u64::from_le_bytes(*cashback_end_slot),
+            cashback_tiers: unpack_cashback_tiers(cashback_tiers),
+            partner_share_bps: u16::from_le_bytes(*partner_share_bps),
+            swap_program: unpack_coption_pubkey(swap_program)?,
+            treasury: unpack_coption_pubkey(treasury)?,
+            swept_profits: u64::from_le_bytes(*swept_profits),
+            vesting_threshold: u64::from_le_bytes(*vesting_threshold),
+            vesting_slots: u64::from_le_bytes(*vesting_slots),
+            max_payout_per_game: u64::from_le_bytes(*max_payout_per_game),
+            installment_slots: u64::from_le_bytes(*installment_slots),
+            settled_volume: u64::from_le_bytes(*settled_volume),
+            total_paid_out: u64::from_le_bytes(*total_paid_out),
+            config_cooldown_slots: u64::from_le_bytes(*config_cooldown_slots),
+            limits_updated_slot: u64::from_le_bytes(*limits_updated_slot),
+            heartbeat_max_age_slots: u64::from_le_bytes(*heartbeat_max_age_slots),
+            insurance_bps: u16::from_le_bytes(*insurance_bps),
+            insurance_unlock_slot: unpack_coption_u64(insurance_unlock_slot)?,
+            hourly_metrics,
+            kelly_fraction_bps: u16::from_le_bytes(*kelly_fraction_bps),
+            games_cancelled: u64::from_le_bytes(*games_cancelled),
+            refunds_total: u64::from_le_bytes(*refunds_total),
+            max_settle_age_slots: u64::from_le_bytes(*max_settle_age_slots),
+            exposure_tracking,
+            breaker_max_loss: u64::from_le_bytes(*breaker_max_loss),
+            breaker_window_slots: u64::from_le_bytes(*breaker_window_slots),
+            breaker_window_start: u64::from_le_bytes(*breaker_window_start),
+            breaker_window_loss: i64::from_le_bytes(*breaker_window_loss),
+            breaker_tripped,
+            settlement_mint: unpack_coption_pubkey(settlement_mint)?,
         })
     }
 
@@ -167,6 +1023,66 @@ impl Pack for Config {
             max_bet_amount_dst,
             owner_pubkey_dst,
             mint_token_pubkey_dst,
+            max_games_per_window_dst,
+            rate_limit_window_slots_dst,
+            min_settle_delay_slots_dst,
+            fee_bps_dst,
+            payout_multiplier_bps_dst,
+            settlement_memo_dst,
+            total_volume_dst,
+            house_pnl_dst,
+            settlement_tree_dst,
+            counter_shards_dst,
+            vault_shards_dst,
+            game_pool_dst,
+            outstanding_exposure_dst,
+            withdraw_bond_dst,
+            emergency_unlock_slot_dst,
+            season_dst,
+            streak_leaderboard_dst,
+            daily_seed_dst,
+            decimals_dst,
+            paused_dst,
+            audit_log_dst,
+            arbiter_dst,
+            dispute_window_slots_dst,
+            large_win_threshold_dst,
+            large_win_delay_slots_dst,
+            cancel_fee_bps_dst,
+            stake_mint_dst,
+            min_stake_dst,
+            burn_bps_dst,
+            cashback_bps_dst,
+            cashback_start_slot_dst,
+            cashback_end_slot_dst,
+            cashback_tiers_dst,
+            partner_share_bps_dst,
+            swap_program_dst,
+            treasury_dst,
+            swept_profits_dst,
+            vesting_threshold_dst,
+            vesting_slots_dst,
+            max_payout_per_game_dst,
+            installment_slots_dst,
+            settled_volume_dst,
+            total_paid_out_dst,
+            config_cooldown_slots_dst,
+            limits_updated_slot_dst,
+            heartbeat_max_age_slots_dst,
+            insurance_bps_dst,
+            insurance_unlock_slot_dst,
+            hourly_metrics_dst,
+            kelly_fraction_bps_dst,
+            games_cancelled_dst,
+            refunds_total_dst,
+            max_settle_age_slots_dst,
+            exposure_tracking_dst,
+            breaker_max_loss_dst,
+            breaker_window_slots_dst,
+            breaker_window_start_dst,
+            breaker_window_loss_dst,
+            breaker_tripped_dst,
+            settlement_mint_dst,
         ) = mut_array_refs![
             dst,
             INITIALIZED_BYTES,
@@ -174,7 +1090,67 @@ impl Pack for Config {
             U64_LENGTH,
             U64_LENGTH,
             PUBKEY_BYTES,
-            PUBKEY_BYTES
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U16_LENGTH,
+            U16_LENGTH,
+            BOOL_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            OPTIONAL_PUBKEY,
+            U8_LENGTH,
+            U8_LENGTH,
+            BOOL_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            OPTIONAL_U64,
+            U32_LENGTH,
+            BOOL_LENGTH,
+            BOOL_LENGTH,
+            U8_LENGTH,
+            BOOL_LENGTH,
+            BOOL_LENGTH,
+            OPTIONAL_PUBKEY,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U16_LENGTH,
+            OPTIONAL_PUBKEY,
+            U64_LENGTH,
+            U16_LENGTH,
+            U16_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            CASHBACK_TIER_LENGTH * MAX_CASHBACK_TIERS,
+            U16_LENGTH,
+            OPTIONAL_PUBKEY,
+            OPTIONAL_PUBKEY,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U16_LENGTH,
+            OPTIONAL_U64,
+            BOOL_LENGTH,
+            U16_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            BOOL_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            BOOL_LENGTH,
+            OPTIONAL_PUBKEY
         ];
 
         let Config {
@@ -184,6 +1160,66 @@ impl Pack for Config {
             max_bet_amount,
             owner_pubkey,
             mint_token_pubkey,
+            max_games_per_window,
+            rate_limit_window_slots,
+            min_settle_delay_slots,
+            fee_bps,
+            payout_multiplier_bps,
+            settlement_memo,
+            total_volume,
+            house_pnl,
+            ref settlement_tree,
+            counter_shards,
+            vault_shards,
+            game_pool,
+            outstanding_exposure,
+            withdraw_bond,
+            ref emergency_unlock_slot,
+            season,
+            streak_leaderboard,
+            daily_seed,
+            decimals,
+            paused,
+            audit_log,
+            ref arbiter,
+            dispute_window_slots,
+            large_win_threshold,
+            large_win_delay_slots,
+            cancel_fee_bps,
+            ref stake_mint,
+            min_stake,
+            burn_bps,
+            cashback_bps,
+            cashback_start_slot,
+            cashback_end_slot,
+            cashback_tiers,
+            partner_share_bps,
+            ref swap_program,
+            ref treasury,
+            swept_profits,
+            vesting_threshold,
+            vesting_slots,
+            max_payout_per_game,
+            installment_slots,
+            settled_volume,
+            total_paid_out,
+            config_cooldown_slots,
+            limits_updated_slot,
+            heartbeat_max_age_slots,
+            insurance_bps,
+            ref insurance_unlock_slot,
+            hourly_metrics,
+            kelly_fraction_bps,
+            games_cancelled,
+            refunds_total,
+            max_settle_age_slots,
+            exposure_tracking,
+            breaker_max_loss,
+            breaker_window_slots,
+            breaker_window_start,
+            breaker_window_loss,
+            breaker_tripped,
+            ref settlement_mint,
         } = self;
 
         is_initialized_dst[0] = *is_initialized as u8;
@@ -192,5 +1228,3168 @@ impl Pack for Config {
         *max_bet_amount_dst = max_bet_amount.to_le_bytes();
         owner_pubkey_dst.copy_from_slice(owner_pubkey.as_ref());
         mint_token_pubkey_dst.copy_from_slice(mint_token_pubkey.as_ref());
+        *max_games_per_window_dst = max_games_per_window.to_le_bytes();
+        *rate_limit_window_slots_dst = rate_limit_window_slots.to_le_bytes();
+        *min_settle_delay_slots_dst = min_settle_delay_slots.to_le_bytes();
+        *fee_bps_dst = fee_bps.to_le_bytes();
+        *payout_multiplier_bps_dst = payout_multiplier_bps.to_le_bytes();
+        settlement_memo_dst[0] = *settlement_memo as u8;
+        *total_volume_dst = total_volume.to_le_bytes();
+        *house_pnl_dst = house_pnl.to_le_bytes();
+        pack_coption_pubkey(settlement_tree, settlement_tree_dst);
+        counter_shards_dst[0] = *counter_shards;
+        vault_shards_dst[0] = *vault_shards;
+        game_pool_dst[0] = *game_pool as u8;
+        *outstanding_exposure_dst = outstanding_exposure.to_le_bytes();
+        *withdraw_bond_dst = withdraw_bond.to_le_bytes();
+        pack_coption_u64(emergency_unlock_slot, emergency_unlock_slot_dst);
+        *season_dst = season.to_le_bytes();
+        streak_leaderboard_dst[0] = *streak_leaderboard as u8;
+        daily_seed_dst[0] = *daily_seed as u8;
+        decimals_dst[0] = *decimals;
+        paused_dst[0] = *paused as u8;
+        audit_log_dst[0] = *audit_log as u8;
+        pack_coption_pubkey(arbiter, arbiter_dst);
+        *dispute_window_slots_dst = dispute_window_slots.to_le_bytes();
+        *large_win_threshold_dst = large_win_threshold.to_le_bytes();
+        *large_win_delay_slots_dst = large_win_delay_slots.to_le_bytes();
+        *cancel_fee_bps_dst = cancel_fee_bps.to_le_bytes();
+        pack_coption_pubkey(stake_mint, stake_mint_dst);
+        *min_stake_dst = min_stake.to_le_bytes();
+        *burn_bps_dst = burn_bps.to_le_bytes();
+        *cashback_bps_dst = cashback_bps.to_le_bytes();
+        *cashback_start_slot_dst = cashback_start_slot.to_le_bytes();
+        *cashback_end_slot_dst = cashback_end_slot.to_le_bytes();
+        pack_cashback_tiers(cashback_tiers, cashback_tiers_dst);
+        *partner_share_bps_dst = partner_share_bps.to_le_bytes();
+        pack_coption_pubkey(swap_program, swap_program_dst);
+        pack_coption_pubkey(treasury, treasury_dst);
+        *swept_profits_dst = swept_profits.to_le_bytes();
+        *vesting_threshold_dst = vesting_threshold.to_le_bytes();
+        *vesting_slots_dst = vesting_slots.to_le_bytes();
+        *max_payout_per_game_dst = max_payout_per_game.to_le_bytes();
+        *installment_slots_dst = installment_slots.to_le_bytes();
+        *settled_volume_dst = settled_volume.to_le_bytes();
+        *total_paid_out_dst = total_paid_out.to_le_bytes();
+        *config_cooldown_slots_dst = config_cooldown_slots.to_le_bytes();
+        *limits_updated_slot_dst = limits_updated_slot.to_le_bytes();
+        *heartbeat_max_age_slots_dst = heartbeat_max_age_slots.to_le_bytes();
+        *insurance_bps_dst = insurance_bps.to_le_bytes();
+        pack_coption_u64(insurance_unlock_slot, insurance_unlock_slot_dst);
+        hourly_metrics_dst[0] = *hourly_metrics as u8;
+        *kelly_fraction_bps_dst = kelly_fraction_bps.to_le_bytes();
+        *games_cancelled_dst = games_cancelled.to_le_bytes();
+        *refunds_total_dst = refunds_total.to_le_bytes();
+        *max_settle_age_slots_dst = max_settle_age_slots.to_le_bytes();
+        exposure_tracking_dst[0] = *exposure_tracking as u8;
+        *breaker_max_loss_dst = breaker_max_loss.to_le_bytes();
+        *breaker_window_slots_dst = breaker_window_slots.to_le_bytes();
+        *breaker_window_start_dst = breaker_window_start.to_le_bytes();
+        *breaker_window_loss_dst = breaker_window_loss.to_le_bytes();
+        breaker_tripped_dst[0] = *breaker_tripped as u8;
+        pack_coption_pubkey(settlement_mint, settlement_mint_dst);
+    }
+}
+
+impl Config {
+    /// Whether more than `max_settle_age_slots` have passed from `since_slot` to `slot`, or
+    /// `DEFAULT_SETTLE_AGE_SLOTS` without a bound
+    pub fn settle_age_passed(&self, since_slot: u64, slot: u64) -> bool {
+        let max_age_slots = match self.max_settle_age_slots {
+            0 => DEFAULT_SETTLE_AGE_SLOTS,
+            max_age_slots => max_age_slots,
+        };
+        slot > since_slot.saturating_add(max_age_slots)
+    }
+
+    /// Cashback rate of a player who staked `volume` over the promotion: the rate of the
+    /// highest tier reached, the base `cashback_bps` below every tier
+    pub fn cashback_rate(&self, volume: u64) -> u16 {
+        self.cashback_tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.min_volume > 0 && tier.min_volume <= volume)
+            .map(|tier| tier.cashback_bps)
+            .unwrap_or(self.cashback_bps)
+    }
+}
+
+/// Largest stake `NewGame` accepts under `config` from a house token account holding
+/// `house_balance` and backing `outstanding_exposure` of open games (the ones of its vault
+/// with house vaults): the house must be able to pay the win on top of every open game. The win
+/// is counted without fee, as a fee-exempt player gets it, so the limit is the same for every
+/// player. With a Kelly fraction the stake is also capped by the Kelly criterion over what is
+/// left of the house balance, at the config's fee. 0 if not even the min bet fits.
+pub fn max_allowed_bet(config: &Config, house_balance: u64, outstanding_exposure: u64) -> u64 {
+    let available = house_balance.saturating_sub(outstanding_exposure);
+    let multiplier = (config.payout_multiplier_bps as u128).max(1);
+    // the largest stake whose win, rounded down like `outcome::win_payout`, fits
+    let max_bet = ((available as u128 + 1) * BPS_DENOMINATOR as u128 - 1) / multiplier;
+    let mut max_bet = u64::try_from(max_bet)
+        .unwrap_or(u64::MAX)
+        .min(config.max_bet_amount);
+    if config.kelly_fraction_bps > 0 {
+        max_bet = max_bet.min(kelly_max_bet(
+            available,
+            config.payout_multiplier_bps,
+            config.fee_bps,
+            config.kelly_fraction_bps,
+        ));
+    }
+    if max_bet < config.min_bet_amount {
+        return 0;
+    }
+    max_bet
+}
+
+fn unpack_cashback_tiers(src: &[u8]) -> [CashbackTier; MAX_CASHBACK_TIERS] {
+    let mut tiers = [CashbackTier::default(); MAX_CASHBACK_TIERS];
+    for (tier, tier_src) in tiers.iter_mut().zip(src.chunks_exact(CASHBACK_TIER_LENGTH)) {
+        let tier_src = array_ref![tier_src, 0, CASHBACK_TIER_LENGTH];
+        let (min_volume, cashback_bps) = array_refs![tier_src, U64_LENGTH, U16_LENGTH];
+        tier.min_volume = u64::from_le_bytes(*min_volume);
+        tier.cashback_bps = u16::from_le_bytes(*cashback_bps);
+    }
+    tiers
+}
+
+fn pack_cashback_tiers(tiers: &[CashbackTier; MAX_CASHBACK_TIERS], dst: &mut [u8]) {
+    for (tier, tier_dst) in tiers.iter().zip(dst.chunks_exact_mut(CASHBACK_TIER_LENGTH)) {
+        let tier_dst = array_mut_ref![tier_dst, 0, CASHBACK_TIER_LENGTH];
+        let (min_volume_dst, cashback_bps_dst) = mut_array_refs![tier_dst, U64_LENGTH, U16_LENGTH];
+        *min_volume_dst = tier.min_volume.to_le_bytes();
+        *cashback_bps_dst = tier.cashback_bps.to_le_bytes();
+    }
+}
+
+// Session
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Session {
+    pub is_initialized: bool,
+    pub player_pubkey: Pubkey,
+    pub config_pubkey: Pubkey,
+    pub balance: u64,
+    pub games_played: u64,
+    /// Tokens deposited by `OpenSession`, the house's net result is what is left of it
+    pub deposit: u64,
+    /// Total stake of the session games
+    pub wagered: u64,
+    /// Commitment to the host seed of the next session game, see `CommitSessionSeed`
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub host_seed_commitment: COption<[u8; 32]>,
+    /// Stake and public seed of the session game played against that commitment, waiting for
+    /// `RevealSessionSeed`. No game is pending while the stake is 0.
+    pub pending_amount: u64,
+    pub pending_public_seed: u64,
+}
+
+impl Sealed for Session {}
+impl IsInitialized for Session {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const SESSION_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + OPTIONAL_HASH
+    + U64_LENGTH
+    + U64_LENGTH;
+
+impl Pack for Session {
+    const LEN: usize = SESSION_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, SESSION_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized,
+            player_pubkey,
+            config_pubkey,
+            balance,
+            games_played,
+            deposit,
+            wagered,
+            host_seed_commitment,
+            pending_amount,
+            pending_public_seed,
+        ) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            OPTIONAL_HASH,
+            U64_LENGTH,
+            U64_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Session {
+            is_initialized,
+            player_pubkey: Pubkey::new_from_array(*player_pubkey),
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            balance: u64::from_le_bytes(*balance),
+            games_played: u64::from_le_bytes(*games_played),
+            deposit: u64::from_le_bytes(*deposit),
+            wagered: u64::from_le_bytes(*wagered),
+            host_seed_commitment: unpack_coption_hash(host_seed_commitment)?,
+            pending_amount: u64::from_le_bytes(*pending_amount),
+            pending_public_seed: u64::from_le_bytes(*pending_public_seed),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, SESSION_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            player_pubkey_dst,
+            config_pubkey_dst,
+            balance_dst,
+            games_played_dst,
+            deposit_dst,
+            wagered_dst,
+            host_seed_commitment_dst,
+            pending_amount_dst,
+            pending_public_seed_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            OPTIONAL_HASH,
+            U64_LENGTH,
+            U64_LENGTH
+        ];
+
+        let Session {
+            is_initialized,
+            player_pubkey,
+            config_pubkey,
+            balance,
+            games_played,
+            deposit,
+            wagered,
+            ref host_seed_commitment,
+            pending_amount,
+            pending_public_seed,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        player_pubkey_dst.copy_from_slice(player_pubkey.as_ref());
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *balance_dst = balance.to_le_bytes();
+        *games_played_dst = games_played.to_le_bytes();
+        *deposit_dst = deposit.to_le_bytes();
+        *wagered_dst = wagered.to_le_bytes();
+        pack_coption_hash(host_seed_commitment, host_seed_commitment_dst);
+        *pending_amount_dst = pending_amount.to_le_bytes();
+        *pending_public_seed_dst = pending_public_seed.to_le_bytes();
+    }
+}
+
+// PlayerStats
+
+/// Won a game
+pub const ACHIEVEMENT_FIRST_WIN: u64 = 1 << 0;
+/// Drew a game
+pub const ACHIEVEMENT_FIRST_DRAW: u64 = 1 << 1;
+/// Played 100 games
+pub const ACHIEVEMENT_100_GAMES: u64 = 1 << 2;
+/// Played 1000 games
+pub const ACHIEVEMENT_1000_GAMES: u64 = 1 << 3;
+/// Won 5 games in a row
+pub const ACHIEVEMENT_WIN_STREAK_5: u64 = 1 << 4;
+/// Won a game bet at the maximum bet amount
+pub const ACHIEVEMENT_MAX_BET_WIN: u64 = 1 << 5;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerStats {
+    pub is_initialized: bool,
+    pub player_pubkey: Pubkey,
+    pub config_pubkey: Pubkey,
+    pub games_played: u64,
+    pub window_start_slot: u64,
+    pub games_in_window: u64,
+    pub wins: u64,
+    /// Wins in a row, reset by a loss, draws don't break it
+    pub win_streak: u64,
+    /// `ACHIEVEMENT_*` bits earned so far
+    pub achievements: u64,
+    /// Season the `season_*` counters belong to, they restart when a new season is seen
+    pub season: u32,
+    pub season_games: u64,
+    pub season_wins: u64,
+    /// Cashback promotion (its start slot) the net loss belongs to, it restarts when a new
+    /// promotion is seen
+    pub cashback_promo: u64,
+    /// Stakes lost minus profits won over the promotion, zeroed once the cashback is claimed
+    pub cashback_net_loss: i64,
+    /// Stakes of the games settled over the promotion, picking the cashback tier
+    pub cashback_volume: u64,
+    /// Cashback of the net loss, each game at the rate of the tier reached when it settled
+    pub cashback_accrued: i64,
+    /// Day (see `daily_seed_day`) the `day_*` quest counters belong to, they restart when a
+    /// new day is seen
+    pub quest_day: u64,
+    pub day_wins: u64,
+    pub day_wagered: u64,
+    /// Bits of the daily quests (by `quest_id`) claimed this day
+    pub day_quests_claimed: u64,
+    /// Week (see `QUEST_WEEK_SLOTS`) the `week_*` quest counters belong to
+    pub quest_week: u64,
+    pub week_wins: u64,
+    pub week_wagered: u64,
+    /// Bits of the weekly quests (by `quest_id`) claimed this week
+    pub week_quests_claimed: u64,
+    /// Partner PDA that referred the player, the partner of their first settled partner game
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub referrer: COption<Pubkey>,
+    /// Games of the player refunded instead of settled, declined or cancelled
+    pub games_cancelled: u64,
+    /// Stakes refunded by those games
+    pub refunds_total: u64,
+}
+
+impl Sealed for PlayerStats {}
+impl IsInitialized for PlayerStats {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const PLAYER_STATS_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U32_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + I64_LENGTH
+    + U64_LENGTH
+    + I64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + OPTIONAL_PUBKEY
+    + U64_LENGTH
+    + U64_LENGTH;
+
+impl Pack for PlayerStats {
+    const LEN: usize = PLAYER_STATS_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, PLAYER_STATS_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized,
+            player_pubkey,
+            config_pubkey,
+            games_played,
+            window_start_slot,
+            games_in_window,
+            wins,
+            win_streak,
+            achievements,
+            season,
+            season_games,
+            season_wins,
+            cashback_promo,
+            cashback_net_loss,
+            cashback_volume,
+            cashback_accrued,
+            quest_day,
+            day_wins,
+            day_wagered,
+            day_quests_claimed,
+            quest_week,
+            week_wins,
+            week_wagered,
+            week_quests_claimed,
+            referrer,
+            games_cancelled,
+            refunds_total,
+        ) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U32_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            OPTIONAL_PUBKEY,
+            U64_LENGTH,
+            U64_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(PlayerStats {
+            is_initialized,
+            player_pubkey: Pubkey::new_from_array(*player_pubkey),
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            games_played: u64::from_le_bytes(*games_played),
+            window_start_slot: u64::from_le_bytes(*window_start_slot),
+            games_in_window: u64::from_le_bytes(*games_in_window),
+            wins: u64::from_le_bytes(*wins),
+            win_streak: u64::from_le_bytes(*win_streak),
+            achievements: u64::from_le_bytes(*achievements),
+            season: u32::from_le_bytes(*season),
+            season_games: u64::from_le_bytes(*season_games),
+            season_wins: u64::from_le_bytes(*season_wins),
+            cashback_promo: u64::from_le_bytes(*cashback_promo),
+            cashback_net_loss: i64::from_le_bytes(*cashback_net_loss),
+            cashback_volume: u64::from_le_bytes(*cashback_volume),
+            cashback_accrued: i64::from_le_bytes(*cashback_accrued),
+            quest_day: u64::from_le_bytes(*quest_day),
+            day_wins: u64::from_le_bytes(*day_wins),
+            day_wagered: u64::from_le_bytes(*day_wagered),
+            day_quests_claimed: u64::from_le_bytes(*day_quests_claimed),
+            quest_week: u64::from_le_bytes(*quest_week),
+            week_wins: u64::from_le_bytes(*week_wins),
+            week_wagered: u64::from_le_bytes(*week_wagered),
+            week_quests_claimed: u64::from_le_bytes(*week_quests_claimed),
+            referrer: unpack_coption_pubkey(referrer)?,
+            games_cancelled: u64::from_le_bytes(*games_cancelled),
+            refunds_total: u64::from_le_bytes(*refunds_total),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, PLAYER_STATS_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            player_pubkey_dst,
+            config_pubkey_dst,
+            games_played_dst,
+            window_start_slot_dst,
+            games_in_window_dst,
+            wins_dst,
+            win_streak_dst,
+            achievements_dst,
+            season_dst,
+            season_games_dst,
+            season_wins_dst,
+            cashback_promo_dst,
+            cashback_net_loss_dst,
+            cashback_volume_dst,
+            cashback_accrued_dst,
+            quest_day_dst,
+            day_wins_dst,
+            day_wagered_dst,
+            day_quests_claimed_dst,
+            quest_week_dst,
+            week_wins_dst,
+            week_wagered_dst,
+            week_quests_claimed_dst,
+            referrer_dst,
+            games_cancelled_dst,
+            refunds_total_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U32_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            OPTIONAL_PUBKEY,
+            U64_LENGTH,
+            U64_LENGTH
+        ];
+
+        let PlayerStats {
+            is_initialized,
+            player_pubkey,
+            config_pubkey,
+            games_played,
+            window_start_slot,
+            games_in_window,
+            wins,
+            win_streak,
+            achievements,
+            season,
+            season_games,
+            season_wins,
+            cashback_promo,
+            cashback_net_loss,
+            cashback_volume,
+            cashback_accrued,
+            quest_day,
+            day_wins,
+            day_wagered,
+            day_quests_claimed,
+            quest_week,
+            week_wins,
+            week_wagered,
+            week_quests_claimed,
+            ref referrer,
+            games_cancelled,
+            refunds_total,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        player_pubkey_dst.copy_from_slice(player_pubkey.as_ref());
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *games_played_dst = games_played.to_le_bytes();
+        *window_start_slot_dst = window_start_slot.to_le_bytes();
+        *games_in_window_dst = games_in_window.to_le_bytes();
+        *wins_dst = wins.to_le_bytes();
+        *win_streak_dst = win_streak.to_le_bytes();
+        *achievements_dst = achievements.to_le_bytes();
+        *season_dst = season.to_le_bytes();
+        *season_games_dst = season_games.to_le_bytes();
+        *season_wins_dst = season_wins.to_le_bytes();
+        *cashback_promo_dst = cashback_promo.to_le_bytes();
+        *cashback_net_loss_dst = cashback_net_loss.to_le_bytes();
+        *cashback_volume_dst = cashback_volume.to_le_bytes();
+        *cashback_accrued_dst = cashback_accrued.to_le_bytes();
+        *quest_day_dst = quest_day.to_le_bytes();
+        *day_wins_dst = day_wins.to_le_bytes();
+        *day_wagered_dst = day_wagered.to_le_bytes();
+        *day_quests_claimed_dst = day_quests_claimed.to_le_bytes();
+        *quest_week_dst = quest_week.to_le_bytes();
+        *week_wins_dst = week_wins.to_le_bytes();
+        *week_wagered_dst = week_wagered.to_le_bytes();
+        *week_quests_claimed_dst = week_quests_claimed.to_le_bytes();
+        pack_coption_pubkey(referrer, referrer_dst);
+        *games_cancelled_dst = games_cancelled.to_le_bytes();
+        *refunds_total_dst = refunds_total.to_le_bytes();
+    }
+}
+
+// SessionAuthority
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionAuthority {
+    pub is_initialized: bool,
+    pub player_pubkey: Pubkey,
+    pub session_key_pubkey: Pubkey,
+    pub expiry_slot: u64,
+    pub max_wager: u64,
+    pub bump: u8,
+}
+
+impl Sealed for SessionAuthority {}
+impl IsInitialized for SessionAuthority {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const SESSION_AUTHORITY_ACCOUNT_STATE_SPACE: usize =
+    INITIALIZED_BYTES + PUBKEY_BYTES + PUBKEY_BYTES + U64_LENGTH + U64_LENGTH + U8_LENGTH;
+
+impl Pack for SessionAuthority {
+    const LEN: usize = SESSION_AUTHORITY_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, SESSION_AUTHORITY_ACCOUNT_STATE_SPACE];
+        let (is_initialized, player_pubkey, session_key_pubkey, expiry_slot, max_wager, bump) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(SessionAuthority {
+            is_initialized,
+            player_pubkey: Pubkey::new_from_array(*player_pubkey),
+            session_key_pubkey: Pubkey::new_from_array(*session_key_pubkey),
+            expiry_slot: u64::from_le_bytes(*expiry_slot),
+            max_wager: u64::from_le_bytes(*max_wager),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, SESSION_AUTHORITY_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            player_pubkey_dst,
+            session_key_pubkey_dst,
+            expiry_slot_dst,
+            max_wager_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let SessionAuthority {
+            is_initialized,
+            player_pubkey,
+            session_key_pubkey,
+            expiry_slot,
+            max_wager,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        player_pubkey_dst.copy_from_slice(player_pubkey.as_ref());
+        session_key_pubkey_dst.copy_from_slice(session_key_pubkey.as_ref());
+        *expiry_slot_dst = expiry_slot.to_le_bytes();
+        *max_wager_dst = max_wager.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// StatsSnapshot
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatsSnapshot {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub epoch: u64,
+    pub slot: u64,
+    pub total_games: u64,
+    pub total_volume: u64,
+    pub house_pnl: i64,
+    pub settled_volume: u64,
+    pub total_paid_out: u64,
+}
+
+impl Sealed for StatsSnapshot {}
+impl IsInitialized for StatsSnapshot {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const STATS_SNAPSHOT_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + I64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH;
+
+impl Pack for StatsSnapshot {
+    const LEN: usize = STATS_SNAPSHOT_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, STATS_SNAPSHOT_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized,
+            config_pubkey,
+            epoch,
+            slot,
+            total_games,
+            total_volume,
+            house_pnl,
+            settled_volume,
+            total_paid_out,
+        ) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(StatsSnapshot {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            epoch: u64::from_le_bytes(*epoch),
+            slot: u64::from_le_bytes(*slot),
+            total_games: u64::from_le_bytes(*total_games),
+            total_volume: u64::from_le_bytes(*total_volume),
+            house_pnl: i64::from_le_bytes(*house_pnl),
+            settled_volume: u64::from_le_bytes(*settled_volume),
+            total_paid_out: u64::from_le_bytes(*total_paid_out),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, STATS_SNAPSHOT_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            epoch_dst,
+            slot_dst,
+            total_games_dst,
+            total_volume_dst,
+            house_pnl_dst,
+            settled_volume_dst,
+            total_paid_out_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH
+        ];
+
+        let StatsSnapshot {
+            is_initialized,
+            config_pubkey,
+            epoch,
+            slot,
+            total_games,
+            total_volume,
+            house_pnl,
+            settled_volume,
+            total_paid_out,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *epoch_dst = epoch.to_le_bytes();
+        *slot_dst = slot.to_le_bytes();
+        *total_games_dst = total_games.to_le_bytes();
+        *total_volume_dst = total_volume.to_le_bytes();
+        *house_pnl_dst = house_pnl.to_le_bytes();
+        *settled_volume_dst = settled_volume.to_le_bytes();
+        *total_paid_out_dst = total_paid_out.to_le_bytes();
+    }
+}
+
+// Counter
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Counter {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub shard: u8,
+    pub total_games: u64,
+    pub total_volume: u64,
+    pub house_pnl: i64,
+    /// Change of the outstanding exposure of the house vault of the same index, negative when
+    /// games opened before the config was sharded are settled through this shard
+    pub outstanding_exposure: i64,
+    pub settled_volume: u64,
+    pub total_paid_out: u64,
+    pub games_cancelled: u64,
+    pub refunds_total: u64,
+    pub bump: u8,
+}
+
+impl Sealed for Counter {}
+impl IsInitialized for Counter {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const COUNTER_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + U8_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + I64_LENGTH
+    + I64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U8_LENGTH;
+
+impl Pack for Counter {
+    const LEN: usize = COUNTER_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, COUNTER_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized,
+            config_pubkey,
+            shard,
+            total_games,
+            total_volume,
+            house_pnl,
+            outstanding_exposure,
+            settled_volume,
+            total_paid_out,
+            games_cancelled,
+            refunds_total,
+            bump,
+        ) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U8_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            I64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Counter {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            shard: shard[0],
+            total_games: u64::from_le_bytes(*total_games),
+            total_volume: u64::from_le_bytes(*total_volume),
+            house_pnl: i64::from_le_bytes(*house_pnl),
+            outstanding_exposure: i64::from_le_bytes(*outstanding_exposure),
+            settled_volume: u64::from_le_bytes(*settled_volume),
+            total_paid_out: u64::from_le_bytes(*total_paid_out),
+            games_cancelled: u64::from_le_bytes(*games_cancelled),
+            refunds_total: u64::from_le_bytes(*refunds_total),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, COUNTER_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            shard_dst,
+            total_games_dst,
+            total_volume_dst,
+            house_pnl_dst,
+            outstanding_exposure_dst,
+            settled_volume_dst,
+            total_paid_out_dst,
+            games_cancelled_dst,
+            refunds_total_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U8_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            I64_LENGTH,
+            I64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let Counter {
+            is_initialized,
+            config_pubkey,
+            shard,
+            total_games,
+            total_volume,
+            house_pnl,
+            outstanding_exposure,
+            settled_volume,
+            total_paid_out,
+            games_cancelled,
+            refunds_total,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        shard_dst[0] = *shard;
+        *total_games_dst = total_games.to_le_bytes();
+        *total_volume_dst = total_volume.to_le_bytes();
+        *house_pnl_dst = house_pnl.to_le_bytes();
+        *outstanding_exposure_dst = outstanding_exposure.to_le_bytes();
+        *settled_volume_dst = settled_volume.to_le_bytes();
+        *total_paid_out_dst = total_paid_out.to_le_bytes();
+        *games_cancelled_dst = games_cancelled.to_le_bytes();
+        *refunds_total_dst = refunds_total.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// StreakLeaderboard
+
+/// Number of players ranked on a streak leaderboard
+pub const STREAK_LEADERBOARD_SIZE: usize = 10;
+pub const STREAK_ENTRY_LENGTH: usize = PUBKEY_BYTES + U64_LENGTH;
+
+/// A player and their best win streak, an unused slot has a zero streak
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Default)]
+pub struct StreakEntry {
+    pub player_pubkey: Pubkey,
+    pub streak: u64,
+}
+
+/// Best win streaks of a season, longest first
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreakLeaderboard {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub season: u32,
+    pub entries: [StreakEntry; STREAK_LEADERBOARD_SIZE],
+}
+
+impl Sealed for StreakLeaderboard {}
+impl IsInitialized for StreakLeaderboard {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const STREAK_LEADERBOARD_ACCOUNT_STATE_SPACE: usize =
+    INITIALIZED_BYTES + PUBKEY_BYTES + U32_LENGTH + STREAK_ENTRY_LENGTH * STREAK_LEADERBOARD_SIZE;
+
+impl Pack for StreakLeaderboard {
+    const LEN: usize = STREAK_LEADERBOARD_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, STREAK_LEADERBOARD_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, season, entries_src) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U32_LENGTH,
+            STREAK_ENTRY_LENGTH * STREAK_LEADERBOARD_SIZE
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(StreakLeaderboard {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            season: u32::from_le_bytes(*season),
+            entries: unpack_streak_entries(entries_src),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, STREAK_LEADERBOARD_ACCOUNT_STATE_SPACE];
+        let (is_initialized_dst, config_pubkey_dst, season_dst, entries_dst) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U32_LENGTH,
+            STREAK_ENTRY_LENGTH * STREAK_LEADERBOARD_SIZE
+        ];
+
+        let StreakLeaderboard {
+            is_initialized,
+            config_pubkey,
+            season,
+            entries,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *season_dst = season.to_le_bytes();
+        pack_streak_entries(entries, entries_dst);
+    }
+}
+
+fn unpack_streak_entries(src: &[u8]) -> [StreakEntry; STREAK_LEADERBOARD_SIZE] {
+    let mut entries = [StreakEntry::default(); STREAK_LEADERBOARD_SIZE];
+    for (entry, entry_src) in entries
+        .iter_mut()
+        .zip(src.chunks_exact(STREAK_ENTRY_LENGTH))
+    {
+        let entry_src = array_ref![entry_src, 0, STREAK_ENTRY_LENGTH];
+        let (player_pubkey, streak) = array_refs![entry_src, PUBKEY_BYTES, U64_LENGTH];
+        entry.player_pubkey = Pubkey::new_from_array(*player_pubkey);
+        entry.streak = u64::from_le_bytes(*streak);
+    }
+    entries
+}
+
+fn pack_streak_entries(entries: &[StreakEntry; STREAK_LEADERBOARD_SIZE], dst: &mut [u8]) {
+    for (entry, entry_dst) in entries
+        .iter()
+        .zip(dst.chunks_exact_mut(STREAK_ENTRY_LENGTH))
+    {
+        let entry_dst = array_mut_ref![entry_dst, 0, STREAK_ENTRY_LENGTH];
+        let (player_pubkey_dst, streak_dst) = mut_array_refs![entry_dst, PUBKEY_BYTES, U64_LENGTH];
+        player_pubkey_dst.copy_from_slice(entry.player_pubkey.as_ref());
+        *streak_dst = entry.streak.to_le_bytes();
+    }
+}
+
+// SeasonResult
+/// Final streak ranking of an ended season, never modified once written
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeasonResult {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub season: u32,
+    pub end_slot: u64,
+    pub entries: [StreakEntry; STREAK_LEADERBOARD_SIZE],
+}
+
+impl Sealed for SeasonResult {}
+impl IsInitialized for SeasonResult {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const SEASON_RESULT_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + U32_LENGTH
+    + U64_LENGTH
+    + STREAK_ENTRY_LENGTH * STREAK_LEADERBOARD_SIZE;
+
+impl Pack for SeasonResult {
+    const LEN: usize = SEASON_RESULT_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, SEASON_RESULT_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, season, end_slot, entries_src) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U32_LENGTH,
+            U64_LENGTH,
+            STREAK_ENTRY_LENGTH * STREAK_LEADERBOARD_SIZE
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(SeasonResult {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            season: u32::from_le_bytes(*season),
+            end_slot: u64::from_le_bytes(*end_slot),
+            entries: unpack_streak_entries(entries_src),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, SEASON_RESULT_ACCOUNT_STATE_SPACE];
+        let (is_initialized_dst, config_pubkey_dst, season_dst, end_slot_dst, entries_dst) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U32_LENGTH,
+            U64_LENGTH,
+            STREAK_ENTRY_LENGTH * STREAK_LEADERBOARD_SIZE
+        ];
+
+        let SeasonResult {
+            is_initialized,
+            config_pubkey,
+            season,
+            end_slot,
+            entries,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *season_dst = season.to_le_bytes();
+        *end_slot_dst = end_slot.to_le_bytes();
+        pack_streak_entries(entries, entries_dst);
+    }
+}
+
+// DailySeed
+/// House seed commitment of a day (see `outcome::daily_seed_day`), posted before the day
+/// starts and revealed once it is over
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DailySeed {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub day: u64,
+    pub commitment: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "coption_serde"))]
+    pub secret: COption<[u8; 32]>,
+}
+
+impl Sealed for DailySeed {}
+impl IsInitialized for DailySeed {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const DAILY_SEED_ACCOUNT_STATE_SPACE: usize =
+    INITIALIZED_BYTES + PUBKEY_BYTES + U64_LENGTH + HASH_BYTES + OPTIONAL_HASH;
+
+impl Pack for DailySeed {
+    const LEN: usize = DAILY_SEED_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, DAILY_SEED_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, day, commitment, secret) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            HASH_BYTES,
+            OPTIONAL_HASH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(DailySeed {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            day: u64::from_le_bytes(*day),
+            commitment: *commitment,
+            secret: unpack_coption_hash(secret)?,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, DAILY_SEED_ACCOUNT_STATE_SPACE];
+        let (is_initialized_dst, config_pubkey_dst, day_dst, commitment_dst, secret_dst) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            HASH_BYTES,
+            OPTIONAL_HASH
+        ];
+
+        let DailySeed {
+            is_initialized,
+            config_pubkey,
+            day,
+            commitment,
+            ref secret,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *day_dst = day.to_le_bytes();
+        *commitment_dst = *commitment;
+        pack_coption_hash(secret, secret_dst);
+    }
+}
+
+// AuditLog
+/// Append-only hash chain of every seed reveal of a config, see `outcome::audit_log_head`.
+/// Kept apart from the game accounts, which are closed once settled.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuditLog {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    /// Reveals chained so far
+    pub entries: u64,
+    /// Head of the chain, all zeroes before the first reveal
+    pub head: [u8; 32],
+    pub bump: u8,
+}
+
+impl Sealed for AuditLog {}
+impl IsInitialized for AuditLog {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const AUDIT_LOG_ACCOUNT_STATE_SPACE: usize =
+    INITIALIZED_BYTES + PUBKEY_BYTES + U64_LENGTH + HASH_BYTES + U8_LENGTH;
+
+impl Pack for AuditLog {
+    const LEN: usize = AUDIT_LOG_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, AUDIT_LOG_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, entries, head, bump) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            HASH_BYTES,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(AuditLog {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            entries: u64::from_le_bytes(*entries),
+            head: *head,
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, AUDIT_LOG_ACCOUNT_STATE_SPACE];
+        let (is_initialized_dst, config_pubkey_dst, entries_dst, head_dst, bump_dst) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            HASH_BYTES,
+            U8_LENGTH
+        ];
+
+        let AuditLog {
+            is_initialized,
+            config_pubkey,
+            entries,
+            head,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *entries_dst = entries.to_le_bytes();
+        *head_dst = *head;
+        bump_dst[0] = *bump;
+    }
+}
+
+// Admin
+/// Can pause and resume betting (`UpdatePause`)
+pub const ADMIN_CAN_PAUSE: u8 = 1 << 0;
+/// Can update bet limits, fees, rate limits and the settle delay
+pub const ADMIN_CAN_UPDATE_LIMITS: u8 = 1 << 1;
+/// Can withdraw from the house (`Withdraw`, `Airdrop`)
+pub const ADMIN_CAN_WITHDRAW: u8 = 1 << 2;
+/// Can settle as the house (host seeds, claims, session games, declines)
+pub const ADMIN_CAN_SETTLE: u8 = 1 << 3;
+
+/// Permissions of an admin key over a config, granted by its owner with `SetAdmin`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Admin {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub admin_pubkey: Pubkey,
+    /// `ADMIN_CAN_*` bits
+    pub permissions: u8,
+    pub bump: u8,
+}
+
+impl Sealed for Admin {}
+impl IsInitialized for Admin {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const ADMIN_ACCOUNT_STATE_SPACE: usize =
+    INITIALIZED_BYTES + PUBKEY_BYTES + PUBKEY_BYTES + U8_LENGTH + U8_LENGTH;
+
+impl Pack for Admin {
+    const LEN: usize = ADMIN_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, ADMIN_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, admin_pubkey, permissions, bump) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U8_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Admin {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            admin_pubkey: Pubkey::new_from_array(*admin_pubkey),
+            permissions: permissions[0],
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, ADMIN_ACCOUNT_STATE_SPACE];
+        let (is_initialized_dst, config_pubkey_dst, admin_pubkey_dst, permissions_dst, bump_dst) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U8_LENGTH,
+            U8_LENGTH
+        ];
+
+        let Admin {
+            is_initialized,
+            config_pubkey,
+            admin_pubkey,
+            permissions,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        admin_pubkey_dst.copy_from_slice(admin_pubkey.as_ref());
+        permissions_dst[0] = *permissions;
+        bump_dst[0] = *bump;
+    }
+}
+
+// Quest
+/// Goal of a quest: games won in its period
+pub const QUEST_KIND_WINS: u8 = 0;
+/// Goal of a quest: tokens wagered in settled games of its period
+pub const QUEST_KIND_WAGER: u8 = 1;
+/// Quest progress restarts every day (see `daily_seed_day`)
+pub const QUEST_PERIOD_DAY: u8 = 0;
+/// Quest progress restarts every week
+pub const QUEST_PERIOD_WEEK: u8 = 1;
+/// Slots of a quest week (~7 days)
+pub const QUEST_WEEK_SLOTS: u64 = 7 * DAILY_SEED_SLOTS;
+/// Quest ids are bits of the claimed quests of a player, see `PlayerStats.day_quests_claimed`
+pub const MAX_QUESTS: u8 = 64;
+
+/// A quest defined by the owner with `SetQuest`, e.g. "win 3 games today", rewarding players
+/// once per period through `ClaimQuestReward`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quest {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub quest_id: u8,
+    /// `QUEST_KIND_*`
+    pub kind: u8,
+    /// `QUEST_PERIOD_*`
+    pub period: u8,
+    /// Wins or wagered tokens to reach in a period
+    pub target: u64,
+    /// Paid from the house on completion, 0 disables the quest
+    pub reward: u64,
+    pub bump: u8,
+}
+
+impl Sealed for Quest {}
+impl IsInitialized for Quest {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const QUEST_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + U8_LENGTH
+    + U8_LENGTH
+    + U8_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U8_LENGTH;
+
+impl Pack for Quest {
+    const LEN: usize = QUEST_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, QUEST_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, quest_id, kind, period, target, reward, bump) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U8_LENGTH,
+            U8_LENGTH,
+            U8_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Quest {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            quest_id: quest_id[0],
+            kind: kind[0],
+            period: period[0],
+            target: u64::from_le_bytes(*target),
+            reward: u64::from_le_bytes(*reward),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, QUEST_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            quest_id_dst,
+            kind_dst,
+            period_dst,
+            target_dst,
+            reward_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U8_LENGTH,
+            U8_LENGTH,
+            U8_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let Quest {
+            is_initialized,
+            config_pubkey,
+            quest_id,
+            kind,
+            period,
+            target,
+            reward,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        quest_id_dst[0] = *quest_id;
+        kind_dst[0] = *kind;
+        period_dst[0] = *period;
+        *target_dst = target.to_le_bytes();
+        *reward_dst = reward.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// Partner
+/// A frontend registered with `RegisterPartner`, earning `Config.partner_share_bps` of the
+/// house's take of the games created through it
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Partner {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub authority_pubkey: Pubkey,
+    /// Token account of the config mint receiving the partner's share
+    pub token_account: Pubkey,
+    /// Players whose first settled partner game was created through the partner, see
+    /// `PlayerStats.referrer`
+    pub referred_players: u64,
+    /// Settled games created through the partner
+    pub games: u64,
+    /// Total stake of those games
+    pub volume: u64,
+    /// Total share paid to the partner
+    pub fees_earned: u64,
+    pub bump: u8,
+}
+
+impl Sealed for Partner {}
+impl IsInitialized for Partner {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const PARTNER_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U8_LENGTH;
+
+impl Pack for Partner {
+    const LEN: usize = PARTNER_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, PARTNER_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized,
+            config_pubkey,
+            authority_pubkey,
+            token_account,
+            referred_players,
+            games,
+            volume,
+            fees_earned,
+            bump,
+        ) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Partner {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            authority_pubkey: Pubkey::new_from_array(*authority_pubkey),
+            token_account: Pubkey::new_from_array(*token_account),
+            referred_players: u64::from_le_bytes(*referred_players),
+            games: u64::from_le_bytes(*games),
+            volume: u64::from_le_bytes(*volume),
+            fees_earned: u64::from_le_bytes(*fees_earned),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, PARTNER_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            authority_pubkey_dst,
+            token_account_dst,
+            referred_players_dst,
+            games_dst,
+            volume_dst,
+            fees_earned_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let Partner {
+            is_initialized,
+            config_pubkey,
+            authority_pubkey,
+            token_account,
+            referred_players,
+            games,
+            volume,
+            fees_earned,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        authority_pubkey_dst.copy_from_slice(authority_pubkey.as_ref());
+        token_account_dst.copy_from_slice(token_account.as_ref());
+        *referred_players_dst = referred_players.to_le_bytes();
+        *games_dst = games.to_le_bytes();
+        *volume_dst = volume.to_le_bytes();
+        *fees_earned_dst = fees_earned.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// Vesting
+/// Payout of a win above `Config.vesting_threshold`, released linearly from `start_slot` to
+/// `end_slot` with `ReleaseVested`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vesting {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    /// Id of the settled game paying out
+    pub game_id: u64,
+    pub player_pubkey: Pubkey,
+    /// Token account of the player receiving the releases
+    pub receiver: Pubkey,
+    /// Account that paid the rent, refunded once everything is released
+    pub payer: Pubkey,
+    pub total: u64,
+    pub released: u64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub bump: u8,
+}
+
+impl Sealed for Vesting {}
+impl IsInitialized for Vesting {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const VESTING_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U8_LENGTH;
+
+impl Pack for Vesting {
+    const LEN: usize = VESTING_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, VESTING_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized,
+            config_pubkey,
+            game_id,
+            player_pubkey,
+            receiver,
+            payer,
+            total,
+            released,
+            start_slot,
+            end_slot,
+            bump,
+        ) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Vesting {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            game_id: u64::from_le_bytes(*game_id),
+            player_pubkey: Pubkey::new_from_array(*player_pubkey),
+            receiver: Pubkey::new_from_array(*receiver),
+            payer: Pubkey::new_from_array(*payer),
+            total: u64::from_le_bytes(*total),
+            released: u64::from_le_bytes(*released),
+            start_slot: u64::from_le_bytes(*start_slot),
+            end_slot: u64::from_le_bytes(*end_slot),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, VESTING_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            game_id_dst,
+            player_pubkey_dst,
+            receiver_dst,
+            payer_dst,
+            total_dst,
+            released_dst,
+            start_slot_dst,
+            end_slot_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let Vesting {
+            is_initialized,
+            config_pubkey,
+            game_id,
+            player_pubkey,
+            receiver,
+            payer,
+            total,
+            released,
+            start_slot,
+            end_slot,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *game_id_dst = game_id.to_le_bytes();
+        player_pubkey_dst.copy_from_slice(player_pubkey.as_ref());
+        receiver_dst.copy_from_slice(receiver.as_ref());
+        payer_dst.copy_from_slice(payer.as_ref());
+        *total_dst = total.to_le_bytes();
+        *released_dst = released.to_le_bytes();
+        *start_slot_dst = start_slot.to_le_bytes();
+        *end_slot_dst = end_slot.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// Payable
+/// Part of a win above `Config.max_payout_per_game` owed to the player, paid in installments
+/// with `PayInstallment`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Payable {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    /// Id of the settled game paying out
+    pub game_id: u64,
+    pub player_pubkey: Pubkey,
+    /// Token account of the player receiving the installments
+    pub receiver: Pubkey,
+    /// Account that paid the rent, refunded once everything is paid
+    pub payer: Pubkey,
+    pub owed: u64,
+    pub paid: u64,
+    /// Most paid by one installment, and the slots between two of them
+    pub installment: u64,
+    pub installment_slots: u64,
+    /// Slot from which the next installment can be paid
+    pub next_slot: u64,
+    pub bump: u8,
+}
+
+impl Sealed for Payable {}
+impl IsInitialized for Payable {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const PAYABLE_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U8_LENGTH;
+
+impl Pack for Payable {
+    const LEN: usize = PAYABLE_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, PAYABLE_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized,
+            config_pubkey,
+            game_id,
+            player_pubkey,
+            receiver,
+            payer,
+            owed,
+            paid,
+            installment,
+            installment_slots,
+            next_slot,
+            bump,
+        ) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Payable {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            game_id: u64::from_le_bytes(*game_id),
+            player_pubkey: Pubkey::new_from_array(*player_pubkey),
+            receiver: Pubkey::new_from_array(*receiver),
+            payer: Pubkey::new_from_array(*payer),
+            owed: u64::from_le_bytes(*owed),
+            paid: u64::from_le_bytes(*paid),
+            installment: u64::from_le_bytes(*installment),
+            installment_slots: u64::from_le_bytes(*installment_slots),
+            next_slot: u64::from_le_bytes(*next_slot),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, PAYABLE_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            game_id_dst,
+            player_pubkey_dst,
+            receiver_dst,
+            payer_dst,
+            owed_dst,
+            paid_dst,
+            installment_dst,
+            installment_slots_dst,
+            next_slot_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let Payable {
+            is_initialized,
+            config_pubkey,
+            game_id,
+            player_pubkey,
+            receiver,
+            payer,
+            owed,
+            paid,
+            installment,
+            installment_slots,
+            next_slot,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *game_id_dst = game_id.to_le_bytes();
+        player_pubkey_dst.copy_from_slice(player_pubkey.as_ref());
+        receiver_dst.copy_from_slice(receiver.as_ref());
+        payer_dst.copy_from_slice(payer.as_ref());
+        *owed_dst = owed.to_le_bytes();
+        *paid_dst = paid.to_le_bytes();
+        *installment_dst = installment.to_le_bytes();
+        *installment_slots_dst = installment_slots.to_le_bytes();
+        *next_slot_dst = next_slot.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// FeeExemption
+/// Player of a config whose games are settled without the fee, granted by the owner with
+/// `SetFeeExempt` (market makers, partners, promotional accounts)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeExemption {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    pub player_pubkey: Pubkey,
+    /// Cleared to revoke the exemption, the account is kept
+    pub exempt: bool,
+    /// Slot of the last grant or revocation
+    pub updated_slot: u64,
+    pub bump: u8,
+}
+
+impl Sealed for FeeExemption {}
+impl IsInitialized for FeeExemption {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const FEE_EXEMPTION_ACCOUNT_STATE_SPACE: usize =
+    INITIALIZED_BYTES + PUBKEY_BYTES + PUBKEY_BYTES + BOOL_LENGTH + U64_LENGTH + U8_LENGTH;
+impl Pack for FeeExemption {
+    const LEN: usize = FEE_EXEMPTION_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, FEE_EXEMPTION_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, player_pubkey, exempt, updated_slot, bump) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            BOOL_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let exempt = match exempt {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(FeeExemption {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            player_pubkey: Pubkey::new_from_array(*player_pubkey),
+            exempt,
+            updated_slot: u64::from_le_bytes(*updated_slot),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, FEE_EXEMPTION_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            player_pubkey_dst,
+            exempt_dst,
+            updated_slot_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            BOOL_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let FeeExemption {
+            is_initialized,
+            config_pubkey,
+            player_pubkey,
+            exempt,
+            updated_slot,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        player_pubkey_dst.copy_from_slice(player_pubkey.as_ref());
+        exempt_dst[0] = *exempt as u8;
+        *updated_slot_dst = updated_slot.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// Heartbeat
+/// Liveness of the settlement service of a config, refreshed by `Heartbeat`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heartbeat {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    /// House key that sent the last heartbeat
+    pub host_pubkey: Pubkey,
+    /// Slot of the first heartbeat
+    pub first_slot: u64,
+    pub last_slot: u64,
+    /// Heartbeats sent since the first one
+    pub beats: u64,
+    pub bump: u8,
+}
+
+impl Sealed for Heartbeat {}
+impl IsInitialized for Heartbeat {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const HEARTBEAT_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U8_LENGTH;
+impl Pack for Heartbeat {
+    const LEN: usize = HEARTBEAT_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, HEARTBEAT_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, host_pubkey, first_slot, last_slot, beats, bump) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Heartbeat {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            host_pubkey: Pubkey::new_from_array(*host_pubkey),
+            first_slot: u64::from_le_bytes(*first_slot),
+            last_slot: u64::from_le_bytes(*last_slot),
+            beats: u64::from_le_bytes(*beats),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, HEARTBEAT_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            host_pubkey_dst,
+            first_slot_dst,
+            last_slot_dst,
+            beats_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let Heartbeat {
+            is_initialized,
+            config_pubkey,
+            host_pubkey,
+            first_slot,
+            last_slot,
+            beats,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        host_pubkey_dst.copy_from_slice(host_pubkey.as_ref());
+        *first_slot_dst = first_slot.to_le_bytes();
+        *last_slot_dst = last_slot.to_le_bytes();
+        *beats_dst = beats.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// HourlyMetrics
+/// Slots of an hourly metrics bucket (~1 hour)
+pub const METRICS_HOUR_SLOTS: u64 = 9_000;
+
+/// Settlements of the games of a config opened in an hour bucket, for dashboards polling a
+/// single account instead of replaying the logs
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HourlyMetrics {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    /// Bucket of the games counted, see `HourlyMetrics::hour`
+    pub hour: u64,
+    /// Games settled
+    pub games: u64,
+    /// Stakes of the games settled
+    pub volume: u64,
+    /// Paid out to the players, stakes of draws and wins included
+    pub payouts: u64,
+    pub bump: u8,
+}
+
+impl Sealed for HourlyMetrics {}
+impl IsInitialized for HourlyMetrics {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl HourlyMetrics {
+    /// Hour bucket of `slot`, a game is counted in the bucket of the slot it was created in
+    pub fn hour(slot: u64) -> u64 {
+        slot / METRICS_HOUR_SLOTS
+    }
+}
+
+pub const HOURLY_METRICS_ACCOUNT_STATE_SPACE: usize = INITIALIZED_BYTES
+    + PUBKEY_BYTES
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U64_LENGTH
+    + U8_LENGTH;
+impl Pack for HourlyMetrics {
+    const LEN: usize = HOURLY_METRICS_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, HOURLY_METRICS_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, hour, games, volume, payouts, bump) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(HourlyMetrics {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            hour: u64::from_le_bytes(*hour),
+            games: u64::from_le_bytes(*games),
+            volume: u64::from_le_bytes(*volume),
+            payouts: u64::from_le_bytes(*payouts),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, HOURLY_METRICS_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            hour_dst,
+            games_dst,
+            volume_dst,
+            payouts_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let HourlyMetrics {
+            is_initialized,
+            config_pubkey,
+            hour,
+            games,
+            volume,
+            payouts,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *hour_dst = hour.to_le_bytes();
+        *games_dst = games.to_le_bytes();
+        *volume_dst = volume.to_le_bytes();
+        *payouts_dst = payouts.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// Display
+
+// Exposure
+/// Live exposure of the house on a config, written by every instruction opening or settling
+/// a game, for risk monitors to poll a single small account
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Exposure {
+    pub is_initialized: bool,
+    pub config_pubkey: Pubkey,
+    /// Games opened and not settled yet
+    pub open_games: u64,
+    /// Payouts the house owes if every open game is won, deferred payouts included
+    pub potential_payout: u64,
+    /// Slot of the last update
+    pub slot: u64,
+    pub bump: u8,
+}
+
+impl Sealed for Exposure {}
+impl IsInitialized for Exposure {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const EXPOSURE_ACCOUNT_STATE_SPACE: usize =
+    INITIALIZED_BYTES + PUBKEY_BYTES + U64_LENGTH + U64_LENGTH + U64_LENGTH + U8_LENGTH;
+impl Pack for Exposure {
+    const LEN: usize = EXPOSURE_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, EXPOSURE_ACCOUNT_STATE_SPACE];
+        let (is_initialized, config_pubkey, open_games, potential_payout, slot, bump) = array_refs![
+            src,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Exposure {
+            is_initialized,
+            config_pubkey: Pubkey::new_from_array(*config_pubkey),
+            open_games: u64::from_le_bytes(*open_games),
+            potential_payout: u64::from_le_bytes(*potential_payout),
+            slot: u64::from_le_bytes(*slot),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, EXPOSURE_ACCOUNT_STATE_SPACE];
+        let (
+            is_initialized_dst,
+            config_pubkey_dst,
+            open_games_dst,
+            potential_payout_dst,
+            slot_dst,
+            bump_dst,
+        ) = mut_array_refs![
+            dst,
+            INITIALIZED_BYTES,
+            PUBKEY_BYTES,
+            U64_LENGTH,
+            U64_LENGTH,
+            U64_LENGTH,
+            U8_LENGTH
+        ];
+
+        let Exposure {
+            is_initialized,
+            config_pubkey,
+            open_games,
+            potential_payout,
+            slot,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        config_pubkey_dst.copy_from_slice(config_pubkey.as_ref());
+        *open_games_dst = open_games.to_le_bytes();
+        *potential_payout_dst = potential_payout.to_le_bytes();
+        *slot_dst = slot.to_le_bytes();
+        bump_dst[0] = *bump;
+    }
+}
+
+// RecoveryAuthority
+/// Program-level authority of the recovery instructions once rotated from the
+/// `recovery_authority` the program is built with, see `SetRecoveryAuthority`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecoveryAuthority {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+impl Sealed for RecoveryAuthority {}
+impl IsInitialized for RecoveryAuthority {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub const RECOVERY_AUTHORITY_ACCOUNT_STATE_SPACE: usize =
+    INITIALIZED_BYTES + PUBKEY_BYTES + U8_LENGTH;
+impl Pack for RecoveryAuthority {
+    const LEN: usize = RECOVERY_AUTHORITY_ACCOUNT_STATE_SPACE;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, RECOVERY_AUTHORITY_ACCOUNT_STATE_SPACE];
+        let (is_initialized, authority, bump) =
+            array_refs![src, INITIALIZED_BYTES, PUBKEY_BYTES, U8_LENGTH];
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(RecoveryAuthority {
+            is_initialized,
+            authority: Pubkey::new_from_array(*authority),
+            bump: bump[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, RECOVERY_AUTHORITY_ACCOUNT_STATE_SPACE];
+        let (is_initialized_dst, authority_dst, bump_dst) =
+            mut_array_refs![dst, INITIALIZED_BYTES, PUBKEY_BYTES, U8_LENGTH];
+
+        let RecoveryAuthority {
+            is_initialized,
+            authority,
+            bump,
+        } = self;
+
+        is_initialized_dst[0] = *is_initialized as u8;
+        authority_dst.copy_from_slice(authority.as_ref());
+        bump_dst[0] = *bump;
+    }
+}
+
+/// Pretty-prints a program account for debugging. Accounts carry no discriminator, every
+/// account type has a distinct size so the type is detected from the data length.
+pub fn dump_account(data: &[u8]) -> String {
+    let dump = match data.len() {
+        Game::LEN => Game::unpack_unchecked(data).map(|game| game.to_string()),
+        Config::LEN => Config::unpack_unchecked(data).map(|config| config.to_string()),
+        Session::LEN => Session::unpack_unchecked(data).map(|session| session.to_string()),
+        PlayerStats::LEN => PlayerStats::unpack_unchecked(data).map(|stats| stats.to_string()),
+        SessionAuthority::LEN => {
+            SessionAuthority::unpack_unchecked(data).map(|authority| authority.to_string())
+        }
+        Counter::LEN => Counter::unpack_unchecked(data).map(|counter| counter.to_string()),
+        StreakLeaderboard::LEN => {
+            StreakLeaderboard::unpack_unchecked(data).map(|board| board.to_string())
+        }
+        SeasonResult::LEN => SeasonResult::unpack_unchecked(data).map(|result| result.to_string()),
+        DailySeed::LEN => DailySeed::unpack_unchecked(data).map(|seed| seed.to_string()),
+        Admin::LEN => Admin::unpack_unchecked(data).map(|admin| admin.to_string()),
+        AuditLog::LEN => AuditLog::unpack_unchecked(data).map(|log| log.to_string()),
+        Quest::LEN => Quest::unpack_unchecked(data).map(|quest| quest.to_string()),
+        Partner::LEN => Partner::unpack_unchecked(data).map(|partner| partner.to_string()),
+        Vesting::LEN => Vesting::unpack_unchecked(data).map(|vesting| vesting.to_string()),
+        Payable::LEN => Payable::unpack_unchecked(data).map(|payable| payable.to_string()),
+        StatsSnapshot::LEN => {
+            StatsSnapshot::unpack_unchecked(data).map(|snapshot| snapshot.to_string())
+        }
+        FeeExemption::LEN => {
+            FeeExemption::unpack_unchecked(data).map(|exemption| exemption.to_string())
+        }
+        Heartbeat::LEN => Heartbeat::unpack_unchecked(data).map(|heartbeat| heartbeat.to_string()),
+        HourlyMetrics::LEN => {
+            HourlyMetrics::unpack_unchecked(data).map(|metrics| metrics.to_string())
+        }
+        Exposure::LEN => Exposure::unpack_unchecked(data).map(|exposure| exposure.to_string()),
+        RecoveryAuthority::LEN => {
+            RecoveryAuthority::unpack_unchecked(data).map(|authority| authority.to_string())
+        }
+        len => return format!("unknown account ({} bytes)", len),
+    };
+    dump.unwrap_or_else(|err| format!("invalid account data: {}", err))
+}
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+struct CashbackTiers<'a>(&'a [CashbackTier]);
+
+impl fmt::Display for CashbackTiers<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut tiers = self.0.iter().filter(|tier| tier.min_volume > 0);
+        match tiers.next() {
+            Some(tier) => write!(f, "{}: {}bps", tier.min_volume, tier.cashback_bps)?,
+            None => return write!(f, "none"),
+        }
+        tiers.try_for_each(|tier| write!(f, ", {}: {}bps", tier.min_volume, tier.cashback_bps))
+    }
+}
+
+fn display_coption<T: fmt::Display>(value: COption<T>) -> String {
+    match value {
+        COption::Some(value) => value.to_string(),
+        COption::None => "none".to_string(),
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Game")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  bet_amount: {}", self.bet_amount)?;
+        writeln!(f, "  game_creator_pubkey: {}", self.game_creator_pubkey)?;
+        writeln!(f, "  result: {}", display_coption(self.result))?;
+        writeln!(f, "  created_slot: {}", self.created_slot)?;
+        writeln!(f, "  min_bet_amount: {}", self.min_bet_amount)?;
+        writeln!(f, "  max_bet_amount: {}", self.max_bet_amount)?;
+        writeln!(f, "  fee_bps: {}", self.fee_bps)?;
+        writeln!(f, "  payout_multiplier_bps: {}", self.payout_multiplier_bps)?;
+        writeln!(
+            f,
+            "  public_seed_commitment: {}",
+            Hex(&self.public_seed_commitment)
+        )?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(
+            f,
+            "  host_seed_commitment: {}",
+            display_coption(self.host_seed_commitment.as_ref().map(|hash| Hex(hash)))
+        )?;
+        writeln!(
+            f,
+            "  revealed_public_seed: {}",
+            display_coption(self.revealed_public_seed)
+        )?;
+        writeln!(f, "  game_id: {}", self.game_id)?;
+        writeln!(f, "  seed_slot: {}", self.seed_slot)?;
+        writeln!(f, "  deferred_payout: {}", self.deferred_payout)?;
+        writeln!(f, "  release_slot: {}", self.release_slot)?;
+        writeln!(f, "  status: {}", self.status)?;
+        writeln!(f, "  partner: {}", display_coption(self.partner))?;
+        writeln!(f, "  frontend_id: {}", self.frontend_id)?;
+        writeln!(f, "  insurance_bps: {}", self.insurance_bps)?;
+        write!(f, "  recycled: {}", self.recycled)
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Config")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  total_games: {}", self.total_games)?;
+        writeln!(f, "  min_bet_amount: {}", self.min_bet_amount)?;
+        writeln!(f, "  max_bet_amount: {}", self.max_bet_amount)?;
+        writeln!(f, "  owner_pubkey: {}", self.owner_pubkey)?;
+        writeln!(f, "  mint_token_pubkey: {}", self.mint_token_pubkey)?;
+        writeln!(f, "  max_games_per_window: {}", self.max_games_per_window)?;
+        writeln!(
+            f,
+            "  rate_limit_window_slots: {}",
+            self.rate_limit_window_slots
+        )?;
+        writeln!(
+            f,
+            "  min_settle_delay_slots: {}",
+            self.min_settle_delay_slots
+        )?;
+        writeln!(f, "  fee_bps: {}", self.fee_bps)?;
+        writeln!(f, "  payout_multiplier_bps: {}", self.payout_multiplier_bps)?;
+        writeln!(f, "  settlement_memo: {}", self.settlement_memo)?;
+        writeln!(f, "  total_volume: {}", self.total_volume)?;
+        writeln!(f, "  house_pnl: {}", self.house_pnl)?;
+        writeln!(
+            f,
+            "  settlement_tree: {}",
+            display_coption(self.settlement_tree)
+        )?;
+        writeln!(f, "  counter_shards: {}", self.counter_shards)?;
+        writeln!(f, "  vault_shards: {}", self.vault_shards)?;
+        writeln!(f, "  game_pool: {}", self.game_pool)?;
+        writeln!(f, "  outstanding_exposure: {}", self.outstanding_exposure)?;
+        writeln!(f, "  withdraw_bond: {}", self.withdraw_bond)?;
+        writeln!(
+            f,
+            "  emergency_unlock_slot: {}",
+            display_coption(self.emergency_unlock_slot)
+        )?;
+        writeln!(f, "  season: {}", self.season)?;
+        writeln!(f, "  streak_leaderboard: {}", self.streak_leaderboard)?;
+        writeln!(f, "  daily_seed: {}", self.daily_seed)?;
+        writeln!(f, "  decimals: {}", self.decimals)?;
+        writeln!(f, "  paused: {}", self.paused)?;
+        writeln!(f, "  audit_log: {}", self.audit_log)?;
+        writeln!(f, "  arbiter: {}", display_coption(self.arbiter))?;
+        writeln!(f, "  dispute_window_slots: {}", self.dispute_window_slots)?;
+        writeln!(f, "  large_win_threshold: {}", self.large_win_threshold)?;
+        writeln!(f, "  large_win_delay_slots: {}", self.large_win_delay_slots)?;
+        writeln!(f, "  cancel_fee_bps: {}", self.cancel_fee_bps)?;
+        writeln!(f, "  stake_mint: {}", display_coption(self.stake_mint))?;
+        writeln!(f, "  min_stake: {}", self.min_stake)?;
+        writeln!(f, "  burn_bps: {}", self.burn_bps)?;
+        writeln!(f, "  cashback_bps: {}", self.cashback_bps)?;
+        writeln!(f, "  cashback_start_slot: {}", self.cashback_start_slot)?;
+        writeln!(f, "  cashback_end_slot: {}", self.cashback_end_slot)?;
+        writeln!(
+            f,
+            "  cashback_tiers: {}",
+            CashbackTiers(&self.cashback_tiers)
+        )?;
+        writeln!(f, "  partner_share_bps: {}", self.partner_share_bps)?;
+        writeln!(f, "  swap_program: {}", display_coption(self.swap_program))?;
+        writeln!(f, "  treasury: {}", display_coption(self.treasury))?;
+        writeln!(f, "  swept_profits: {}", self.swept_profits)?;
+        writeln!(f, "  vesting_threshold: {}", self.vesting_threshold)?;
+        writeln!(f, "  vesting_slots: {}", self.vesting_slots)?;
+        writeln!(f, "  max_payout_per_game: {}", self.max_payout_per_game)?;
+        writeln!(f, "  installment_slots: {}", self.installment_slots)?;
+        writeln!(f, "  settled_volume: {}", self.settled_volume)?;
+        writeln!(f, "  total_paid_out: {}", self.total_paid_out)?;
+        writeln!(f, "  config_cooldown_slots: {}", self.config_cooldown_slots)?;
+        writeln!(f, "  limits_updated_slot: {}", self.limits_updated_slot)?;
+        writeln!(
+            f,
+            "  heartbeat_max_age_slots: {}",
+            self.heartbeat_max_age_slots
+        )?;
+        writeln!(f, "  insurance_bps: {}", self.insurance_bps)?;
+        writeln!(
+            f,
+            "  insurance_unlock_slot: {}",
+            display_coption(self.insurance_unlock_slot)
+        )?;
+        writeln!(f, "  hourly_metrics: {}", self.hourly_metrics)?;
+        writeln!(f, "  kelly_fraction_bps: {}", self.kelly_fraction_bps)?;
+        writeln!(f, "  games_cancelled: {}", self.games_cancelled)?;
+        writeln!(f, "  refunds_total: {}", self.refunds_total)?;
+        writeln!(f, "  max_settle_age_slots: {}", self.max_settle_age_slots)?;
+        writeln!(f, "  exposure_tracking: {}", self.exposure_tracking)?;
+        writeln!(f, "  breaker_max_loss: {}", self.breaker_max_loss)?;
+        writeln!(f, "  breaker_window_slots: {}", self.breaker_window_slots)?;
+        writeln!(f, "  breaker_window_start: {}", self.breaker_window_start)?;
+        writeln!(f, "  breaker_window_loss: {}", self.breaker_window_loss)?;
+        writeln!(f, "  breaker_tripped: {}", self.breaker_tripped)?;
+        write!(
+            f,
+            "  settlement_mint: {}",
+            display_coption(self.settlement_mint)
+        )
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Session")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  player_pubkey: {}", self.player_pubkey)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  balance: {}", self.balance)?;
+        writeln!(f, "  games_played: {}", self.games_played)?;
+        writeln!(f, "  deposit: {}", self.deposit)?;
+        writeln!(f, "  wagered: {}", self.wagered)?;
+        writeln!(
+            f,
+            "  host_seed_commitment: {}",
+            display_coption(self.host_seed_commitment.as_ref().map(|hash| Hex(hash)))
+        )?;
+        writeln!(f, "  pending_amount: {}", self.pending_amount)?;
+        write!(f, "  pending_public_seed: {}", self.pending_public_seed)
+    }
+}
+
+impl fmt::Display for PlayerStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "PlayerStats")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  player_pubkey: {}", self.player_pubkey)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  games_played: {}", self.games_played)?;
+        writeln!(f, "  window_start_slot: {}", self.window_start_slot)?;
+        writeln!(f, "  games_in_window: {}", self.games_in_window)?;
+        writeln!(f, "  wins: {}", self.wins)?;
+        writeln!(f, "  win_streak: {}", self.win_streak)?;
+        writeln!(f, "  achievements: {}", self.achievements)?;
+        writeln!(f, "  season: {}", self.season)?;
+        writeln!(f, "  season_games: {}", self.season_games)?;
+        writeln!(f, "  season_wins: {}", self.season_wins)?;
+        writeln!(f, "  cashback_promo: {}", self.cashback_promo)?;
+        writeln!(f, "  cashback_net_loss: {}", self.cashback_net_loss)?;
+        writeln!(f, "  cashback_volume: {}", self.cashback_volume)?;
+        writeln!(f, "  cashback_accrued: {}", self.cashback_accrued)?;
+        writeln!(f, "  quest_day: {}", self.quest_day)?;
+        writeln!(f, "  day_wins: {}", self.day_wins)?;
+        writeln!(f, "  day_wagered: {}", self.day_wagered)?;
+        writeln!(f, "  day_quests_claimed: {}", self.day_quests_claimed)?;
+        writeln!(f, "  quest_week: {}", self.quest_week)?;
+        writeln!(f, "  week_wins: {}", self.week_wins)?;
+        writeln!(f, "  week_wagered: {}", self.week_wagered)?;
+        writeln!(f, "  week_quests_claimed: {}", self.week_quests_claimed)?;
+        writeln!(f, "  referrer: {}", display_coption(self.referrer))?;
+        writeln!(f, "  games_cancelled: {}", self.games_cancelled)?;
+        write!(f, "  refunds_total: {}", self.refunds_total)
+    }
+}
+
+impl fmt::Display for SessionAuthority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "SessionAuthority")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  player_pubkey: {}", self.player_pubkey)?;
+        writeln!(f, "  session_key_pubkey: {}", self.session_key_pubkey)?;
+        writeln!(f, "  expiry_slot: {}", self.expiry_slot)?;
+        writeln!(f, "  max_wager: {}", self.max_wager)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for StatsSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "StatsSnapshot")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  epoch: {}", self.epoch)?;
+        writeln!(f, "  slot: {}", self.slot)?;
+        writeln!(f, "  total_games: {}", self.total_games)?;
+        writeln!(f, "  total_volume: {}", self.total_volume)?;
+        writeln!(f, "  house_pnl: {}", self.house_pnl)?;
+        writeln!(f, "  settled_volume: {}", self.settled_volume)?;
+        write!(f, "  total_paid_out: {}", self.total_paid_out)
+    }
+}
+
+impl fmt::Display for Counter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Counter")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  shard: {}", self.shard)?;
+        writeln!(f, "  total_games: {}", self.total_games)?;
+        writeln!(f, "  total_volume: {}", self.total_volume)?;
+        writeln!(f, "  house_pnl: {}", self.house_pnl)?;
+        writeln!(f, "  outstanding_exposure: {}", self.outstanding_exposure)?;
+        writeln!(f, "  settled_volume: {}", self.settled_volume)?;
+        writeln!(f, "  total_paid_out: {}", self.total_paid_out)?;
+        writeln!(f, "  games_cancelled: {}", self.games_cancelled)?;
+        writeln!(f, "  refunds_total: {}", self.refunds_total)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for StreakLeaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "StreakLeaderboard")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        write!(f, "  season: {}", self.season)?;
+        for (rank, entry) in self.entries.iter().enumerate() {
+            if entry.streak > 0 {
+                write!(
+                    f,
+                    "\n  #{}: {} ({})",
+                    rank + 1,
+                    entry.player_pubkey,
+                    entry.streak
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SeasonResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "SeasonResult")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  season: {}", self.season)?;
+        write!(f, "  end_slot: {}", self.end_slot)?;
+        for (rank, entry) in self.entries.iter().enumerate() {
+            if entry.streak > 0 {
+                write!(
+                    f,
+                    "\n  #{}: {} ({})",
+                    rank + 1,
+                    entry.player_pubkey,
+                    entry.streak
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DailySeed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "DailySeed")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  day: {}", self.day)?;
+        writeln!(f, "  commitment: {}", Hex(&self.commitment))?;
+        write!(
+            f,
+            "  secret: {}",
+            display_coption(self.secret.as_ref().map(|hash| Hex(hash)))
+        )
+    }
+}
+
+impl fmt::Display for Admin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Admin")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  admin_pubkey: {}", self.admin_pubkey)?;
+        writeln!(f, "  permissions: {}", self.permissions)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "AuditLog")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  entries: {}", self.entries)?;
+        writeln!(f, "  head: {}", Hex(&self.head))?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for Quest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Quest")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  quest_id: {}", self.quest_id)?;
+        writeln!(f, "  kind: {}", self.kind)?;
+        writeln!(f, "  period: {}", self.period)?;
+        writeln!(f, "  target: {}", self.target)?;
+        writeln!(f, "  reward: {}", self.reward)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for Partner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Partner")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  authority_pubkey: {}", self.authority_pubkey)?;
+        writeln!(f, "  token_account: {}", self.token_account)?;
+        writeln!(f, "  referred_players: {}", self.referred_players)?;
+        writeln!(f, "  games: {}", self.games)?;
+        writeln!(f, "  volume: {}", self.volume)?;
+        writeln!(f, "  fees_earned: {}", self.fees_earned)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for Vesting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Vesting")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  game_id: {}", self.game_id)?;
+        writeln!(f, "  player_pubkey: {}", self.player_pubkey)?;
+        writeln!(f, "  receiver: {}", self.receiver)?;
+        writeln!(f, "  payer: {}", self.payer)?;
+        writeln!(f, "  total: {}", self.total)?;
+        writeln!(f, "  released: {}", self.released)?;
+        writeln!(f, "  start_slot: {}", self.start_slot)?;
+        writeln!(f, "  end_slot: {}", self.end_slot)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for Payable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Payable")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  game_id: {}", self.game_id)?;
+        writeln!(f, "  player_pubkey: {}", self.player_pubkey)?;
+        writeln!(f, "  receiver: {}", self.receiver)?;
+        writeln!(f, "  payer: {}", self.payer)?;
+        writeln!(f, "  owed: {}", self.owed)?;
+        writeln!(f, "  paid: {}", self.paid)?;
+        writeln!(f, "  installment: {}", self.installment)?;
+        writeln!(f, "  installment_slots: {}", self.installment_slots)?;
+        writeln!(f, "  next_slot: {}", self.next_slot)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for FeeExemption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "FeeExemption")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  player_pubkey: {}", self.player_pubkey)?;
+        writeln!(f, "  exempt: {}", self.exempt)?;
+        writeln!(f, "  updated_slot: {}", self.updated_slot)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for Heartbeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Heartbeat")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  host_pubkey: {}", self.host_pubkey)?;
+        writeln!(f, "  first_slot: {}", self.first_slot)?;
+        writeln!(f, "  last_slot: {}", self.last_slot)?;
+        writeln!(f, "  beats: {}", self.beats)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for HourlyMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "HourlyMetrics")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  hour: {}", self.hour)?;
+        writeln!(f, "  games: {}", self.games)?;
+        writeln!(f, "  volume: {}", self.volume)?;
+        writeln!(f, "  payouts: {}", self.payouts)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for Exposure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Exposure")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  config_pubkey: {}", self.config_pubkey)?;
+        writeln!(f, "  open_games: {}", self.open_games)?;
+        writeln!(f, "  potential_payout: {}", self.potential_payout)?;
+        writeln!(f, "  slot: {}", self.slot)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+impl fmt::Display for RecoveryAuthority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "RecoveryAuthority")?;
+        writeln!(f, "  is_initialized: {}", self.is_initialized)?;
+        writeln!(f, "  authority: {}", self.authority)?;
+        write!(f, "  bump: {}", self.bump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outcome::Outcome;
+
+    /// Offset of `Game.result`, after `is_initialized`, `bet_amount` and `game_creator_pubkey`
+    const GAME_RESULT_OFFSET: usize = INITIALIZED_BYTES + U64_LENGTH + PUBKEY_BYTES;
+
+    fn packed_game(result: COption<u8>, status: GameStatus) -> Vec<u8> {
+        let mut game = Game::unpack_unchecked(&[0; Game::LEN]).unwrap();
+        game.is_initialized = true;
+        game.bet_amount = 1_000;
+        game.game_creator_pubkey = Pubkey::new_unique();
+        game.result = result;
+        game.status = status;
+        let mut data = vec![0; Game::LEN];
+        Game::pack(game, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn coption_u8_round_trips_every_value() {
+        let mut data = [0xff; OPTIONAL_U8];
+        pack_coption_u8(&COption::None, &mut data);
+        assert_eq!(unpack_coption_u8(&data), Ok(COption::None));
+
+        for value in 0..=u8::MAX {
+            pack_coption_u8(&COption::Some(value), &mut data);
+            assert_eq!(data, [1, 0, 0, 0, value]);
+            assert_eq!(unpack_coption_u8(&data), Ok(COption::Some(value)));
+        }
+    }
+
+    #[test]
+    fn coption_u8_rejects_invalid_tags() {
+        for tag in [
+            [2, 0, 0, 0],
+            [0, 0, 0, 1],
+            [1, 1, 0, 0],
+            [0, 1, 0, 0],
+            [0xff; 4],
+        ] {
+            let mut data = [0; OPTIONAL_U8];
+            data[..4].copy_from_slice(&tag);
+            assert_eq!(
+                unpack_coption_u8(&data),
+                Err(ProgramError::InvalidAccountData),
+                "tag {:?}",
+                tag
+            );
+        }
+    }
+
+    #[test]
+    fn coption_none_ignores_the_body() {
+        assert_eq!(unpack_coption_u8(&[0, 0, 0, 0, 7]), Ok(COption::None));
+        assert_eq!(
+            unpack_coption_u64(&[0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8]),
+            Ok(COption::None)
+        );
+    }
+
+    #[test]
+    fn wider_coptions_reject_invalid_tags() {
+        let mut u64_data = [0; OPTIONAL_U64];
+        u64_data[0] = 2;
+        assert_eq!(
+            unpack_coption_u64(&u64_data),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let mut pubkey_data = [0; OPTIONAL_PUBKEY];
+        pubkey_data[3] = 1;
+        assert_eq!(
+            unpack_coption_pubkey(&pubkey_data),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let mut hash_data = [0; OPTIONAL_HASH];
+        hash_data[..4].copy_from_slice(&[1, 0, 0, 1]);
+        assert_eq!(
+            unpack_coption_hash(&hash_data),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn game_round_trips_every_result() {
+        for result in [Outcome::Win, Outcome::Lose, Outcome::Draw] {
+            let data = packed_game(COption::Some(result as u8), GameStatus::Settled);
+            let game = Game::unpack(&data).unwrap();
+            assert_eq!(game.result, COption::Some(result as u8));
+            assert_eq!(game.status, GameStatus::Settled);
+        }
+        let game = Game::unpack(&packed_game(COption::None, GameStatus::Created)).unwrap();
+        assert_eq!(game.result, COption::None);
+    }
+
+    #[test]
+    fn game_unpack_rejects_bad_result_tag() {
+        let mut data = packed_game(COption::Some(Outcome::Win as u8), GameStatus::Settled);
+        assert_eq!(
+            data[GAME_RESULT_OFFSET..GAME_RESULT_OFFSET + 4],
+            [1, 0, 0, 0]
+        );
+        data[GAME_RESULT_OFFSET] = 2;
+        assert_eq!(
+            Game::unpack(&data).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn game_unpack_rejects_bad_is_initialized() {
+        let mut data = packed_game(COption::None, GameStatus::Created);
+        for flag in [2, 0x80, 0xff] {
+            data[0] = flag;
+            assert_eq!(
+                Game::unpack(&data).err(),
+                Some(ProgramError::InvalidAccountData)
+            );
+            assert_eq!(
+                Game::unpack_unchecked(&data).err(),
+                Some(ProgramError::InvalidAccountData)
+            );
+        }
+
+        data[0] = 0;
+        assert_eq!(
+            Game::unpack(&data).err(),
+            Some(ProgramError::UninitializedAccount)
+        );
+        assert!(!Game::unpack_unchecked(&data).unwrap().is_initialized());
+    }
+
+    #[test]
+    fn game_unpack_rejects_truncated_and_oversized_buffers() {
+        let data = packed_game(COption::None, GameStatus::Created);
+        for len in [0, 1, GAME_RESULT_OFFSET, Game::LEN - 1] {
+            assert_eq!(
+                Game::unpack(&data[..len]).err(),
+                Some(ProgramError::InvalidAccountData),
+                "length {}",
+                len
+            );
+        }
+
+        let mut oversized = data.clone();
+        oversized.push(0);
+        assert_eq!(
+            Game::unpack(&oversized).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn game_unpack_rejects_unknown_status() {
+        let created = packed_game(COption::None, GameStatus::Created);
+        let disputed = packed_game(COption::None, GameStatus::Disputed);
+        let offset = (0..Game::LEN)
+            .find(|&index| created[index] != disputed[index] && disputed[index] == 5)
+            .unwrap();
+
+        let mut data = created;
+        for status in [7, 0xff] {
+            data[offset] = status;
+            assert_eq!(
+                Game::unpack(&data).err(),
+                Some(ProgramError::InvalidAccountData)
+            );
+        }
+    }
+
+    #[test]
+    fn game_status_rejects_skipped_and_reopening_transitions() {
+        use GameStatus::*;
+        let all = [
+            Created,
+            Committed,
+            ResultPosted,
+            Settled,
+            Cancelled,
+            Disputed,
+            Expired,
+        ];
+        let rejected = [
+            (Created, Created),
+            (Created, Disputed),
+            (Committed, Created),
+            (Committed, Committed),
+            (Committed, Disputed),
+            (ResultPosted, Created),
+            (ResultPosted, Committed),
+            (ResultPosted, Cancelled),
+            (ResultPosted, Expired),
+            (Disputed, Settled),
+            (Disputed, Cancelled),
+        ];
+        for (status, next) in rejected {
+            assert!(!status.can_become(next), "{} -> {}", status, next);
+        }
+        // ended games stay ended
+        for status in [Settled, Cancelled, Expired] {
+            for next in all {
+                assert!(!status.can_become(next), "{} -> {}", status, next);
+            }
+        }
+
+        let mut game = Game::unpack(&packed_game(COption::None, Created)).unwrap();
+        assert_eq!(
+            game.transition(Disputed),
+            Err(RoshamboError::InvalidGameStatus.into())
+        );
+        game.transition(Expired).unwrap();
+        assert_eq!(
+            game.transition(Cancelled),
+            Err(RoshamboError::GameEnded.into())
+        );
+    }
+
+    #[test]
+    fn max_allowed_bet_is_the_largest_covered_stake() {
+        let mut config = Config::unpack_unchecked(&[0; Config::LEN]).unwrap();
+        config.min_bet_amount = 10;
+        config.max_bet_amount = 1_000;
+        config.payout_multiplier_bps = 19_500;
+
+        for (house_balance, exposure) in [(1_000, 400), (5_000, 0), (1_000, 990), (0, 0)] {
+            let max_bet = max_allowed_bet(&config, house_balance, exposure);
+            let available = house_balance - exposure;
+            let covered = |bet| crate::outcome::win_payout(bet, 19_500, 0).unwrap() <= available;
+            if max_bet == 0 {
+                assert!(!covered(config.min_bet_amount));
+            } else {
+                assert!(covered(max_bet));
+                assert!(max_bet == config.max_bet_amount || !covered(max_bet + 1));
+            }
+        }
+        assert_eq!(max_allowed_bet(&config, 5_000, 0), 1_000);
+        assert_eq!(max_allowed_bet(&config, 1_000, 990), 0);
     }
 }
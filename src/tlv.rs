@@ -0,0 +1,65 @@
+// length-prefixed fields of the v2 instruction payloads: a type byte, the length of the value
+// as a little-endian u16 and the value
+
+use std::collections::BTreeMap;
+
+use solana_program::program_error::ProgramError;
+
+use crate::error::RoshamboError::InvalidInstruction;
+
+/// Bytes before the value of a field, its type and length
+pub const FIELD_HEADER_LENGTH: usize = 3;
+
+/// Appends a field of type `field_type` holding `value`
+pub fn write_field(buf: &mut Vec<u8>, field_type: u8, value: &[u8]) {
+    buf.push(field_type);
+    buf.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    buf.extend_from_slice(value);
+}
+
+/// Fields of a payload by type, in any order
+pub struct Fields<'a>(BTreeMap<u8, &'a [u8]>);
+
+impl<'a> Fields<'a> {
+    /// Splits `input` into its fields, failing on a truncated field or a type given twice
+    pub fn read(mut input: &'a [u8]) -> Result<Self, ProgramError> {
+        let mut fields = BTreeMap::new();
+        while !input.is_empty() {
+            let header = input.get(..FIELD_HEADER_LENGTH).ok_or(InvalidInstruction)?;
+            let length = u16::from_le_bytes([header[1], header[2]]) as usize;
+            let value = input
+                .get(FIELD_HEADER_LENGTH..FIELD_HEADER_LENGTH + length)
+                .ok_or(InvalidInstruction)?;
+            if fields.insert(header[0], value).is_some() {
+                return Err(InvalidInstruction.into());
+            }
+            input = &input[FIELD_HEADER_LENGTH + length..];
+        }
+        Ok(Self(fields))
+    }
+
+    /// Fails on a field of a type not in `known`: an older program must not silently drop a
+    /// field a newer client relies on
+    pub fn only(self, known: &[u8]) -> Result<Self, ProgramError> {
+        if self.0.keys().any(|field_type| !known.contains(field_type)) {
+            return Err(InvalidInstruction.into());
+        }
+        Ok(self)
+    }
+
+    /// Value of the optional field `field_type`, of exactly `N` bytes
+    pub fn optional<const N: usize>(
+        &self,
+        field_type: u8,
+    ) -> Result<Option<[u8; N]>, ProgramError> {
+        self.0
+            .get(&field_type)
+            .map(|value| (*value).try_into().map_err(|_| InvalidInstruction.into()))
+            .transpose()
+    }
+
+    /// Value of the field `field_type`, of exactly `N` bytes
+    pub fn required<const N: usize>(&self, field_type: u8) -> Result<[u8; N], ProgramError> {
+        self.optional(field_type)?.ok_or(InvalidInstruction.into())
+    }
+}
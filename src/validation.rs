@@ -0,0 +1,89 @@
+// shared account checks used by the processor
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    program_option::COption, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+};
+use spl_token::state::Account as TokenAccount;
+
+use crate::error::RoshamboError;
+
+/// Fails unless the account signed the transaction
+pub fn assert_signer(account: &AccountInfo) -> ProgramResult {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// Fails unless the account is owned by the given program
+pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if account.owner != owner {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Fails unless the account holds enough lamports to be rent exempt
+pub fn assert_rent_exempt(rent: &Rent, account: &AccountInfo) -> ProgramResult {
+    if !rent.is_exempt(account.lamports(), account.data_len()) {
+        return Err(RoshamboError::NotRentExempt.into());
+    }
+    Ok(())
+}
+
+/// Unpacks an SPL token account, checking its mint and (optionally) its owner
+pub fn assert_token_account(
+    account: &AccountInfo,
+    mint: &Pubkey,
+    owner: Option<&Pubkey>,
+) -> Result<TokenAccount, ProgramError> {
+    assert_owned_by(account, &spl_token::id())?;
+
+    let token_account = TokenAccount::unpack(&account.try_borrow_data()?)?;
+    if token_account.mint != *mint {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if let Some(owner) = owner {
+        if token_account.owner != *owner {
+            return Err(ProgramError::IllegalOwner);
+        }
+    }
+
+    Ok(token_account)
+}
+
+/// Fails if the token account was frozen by its mint's freeze authority
+pub fn assert_not_frozen(token_account: &TokenAccount) -> ProgramResult {
+    if token_account.is_frozen() {
+        return Err(RoshamboError::TokenAccountFrozen.into());
+    }
+    Ok(())
+}
+
+/// Fails if anyone but the owner can close the token account, which for a native (wrapped
+/// SOL) account means taking its balance
+pub fn assert_owner_closes(token_account: &TokenAccount) -> ProgramResult {
+    match token_account.close_authority {
+        COption::Some(authority) if authority != token_account.owner => {
+            Err(RoshamboError::ForeignCloseAuthority.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fails unless the account is the program address derived from `seeds` and `bump`
+pub fn assert_pda(
+    account: &AccountInfo,
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let bump = [bump];
+    let seeds_with_bump = [seeds, &[&bump[..]]].concat();
+    let expected = Pubkey::create_program_address(&seeds_with_bump, program_id)?;
+    if *account.key != expected {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+// browser bindings of the instruction builders, PDA helpers and outcome, for frontends to
+// reuse instead of re-implementing them
+//
+// `Pubkey` and `Instruction` are the wasm-bindgen classes of solana-program, the instructions
+// returned convert to web3.js ones through their accounts and data. Build with
+// `wasm-pack build --target web -- --features wasm`.
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use wasm_bindgen::prelude::*;
+
+use crate::{instruction, outcome, pda};
+
+/// Resolves a game from the host and player seeds, an `outcome::Outcome` as a number
+#[wasm_bindgen(js_name = gameOutcome)]
+pub fn game_outcome(host_seed: u64, public_seed: u64) -> u8 {
+    outcome::outcome(host_seed, public_seed) as u8
+}
+
+/// Amount paid to a winning player, stake included, undefined if the multiplier is below 1x
+#[wasm_bindgen(js_name = winPayout)]
+pub fn win_payout(bet_amount: u64, payout_multiplier_bps: u16, fee_bps: u16) -> Option<u64> {
+    outcome::win_payout(bet_amount, payout_multiplier_bps, fee_bps)
+}
+
+/// Commitment to the public seed a `NewGame` carries, revealed by `RevealSeed`
+#[wasm_bindgen(js_name = publicSeedCommitment)]
+pub fn public_seed_commitment(game: &Pubkey, public_seed: u64, seed_slot: u64) -> Vec<u8> {
+    outcome::public_seed_commitment(game, public_seed, seed_slot).to_vec()
+}
+
+/// House authority PDA, owning the house and bet token accounts
+#[wasm_bindgen(js_name = houseAuthority)]
+pub fn house_authority(program_id: &Pubkey) -> Pubkey {
+    pda::find_house_authority(program_id).0
+}
+
+/// Stats PDA of `player` under `config`
+#[wasm_bindgen(js_name = playerStatsAddress)]
+pub fn player_stats_address(config: &Pubkey, player: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    pda::find_player_stats_address(config, player, program_id).0
+}
+
+/// Shard of `player` on a config with `shards` counter shards
+#[wasm_bindgen(js_name = playerShard)]
+pub fn player_shard(player: &Pubkey, shards: u8) -> u8 {
+    pda::player_shard(player, shards)
+}
+
+/// Counter shard PDA of a sharded config
+#[wasm_bindgen(js_name = counterAddress)]
+pub fn counter_address(config: &Pubkey, shard: u8, program_id: &Pubkey) -> Pubkey {
+    pda::find_counter_address(config, shard, program_id).0
+}
+
+/// Exposure PDA of a config tracking its exposure
+#[wasm_bindgen(js_name = exposureAddress)]
+pub fn exposure_address(config: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    pda::find_exposure_address(config, program_id).0
+}
+
+/// `instruction::new_game`, `public_seed_commitment` being the 32 bytes of
+/// `publicSeedCommitment`
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = newGame)]
+pub fn new_game(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    creator_token_account: &Pubkey,
+    game_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    player_stats_account: &Pubkey,
+    amount: u64,
+    public_seed_commitment: &[u8],
+    seed_slot: u64,
+) -> Result<Instruction, JsError> {
+    let public_seed_commitment = public_seed_commitment
+        .try_into()
+        .map_err(|_| JsError::new("the public seed commitment must be 32 bytes"))?;
+    Ok(instruction::new_game(
+        program_id,
+        game_creator,
+        creator_token_account,
+        game_account,
+        house_token_account,
+        config_account,
+        player_stats_account,
+        amount,
+        public_seed_commitment,
+        seed_slot,
+    ))
+}
+
+/// `instruction::reveal_seed`
+#[wasm_bindgen(js_name = revealSeed)]
+pub fn reveal_seed(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    game_account: &Pubkey,
+    public_seed: u64,
+) -> Instruction {
+    instruction::reveal_seed(program_id, game_creator, game_account, public_seed)
+}
+
+/// `instruction::cancel_game`
+#[wasm_bindgen(js_name = cancelGame)]
+pub fn cancel_game(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    game_account: &Pubkey,
+    receiver_token_account: &Pubkey,
+    house_token_account: &Pubkey,
+    config_account: &Pubkey,
+    house_authority: &Pubkey,
+) -> Instruction {
+    instruction::cancel_game(
+        program_id,
+        game_creator,
+        game_account,
+        receiver_token_account,
+        house_token_account,
+        config_account,
+        house_authority,
+    )
+}
+
+/// `instruction::dispute`
+#[wasm_bindgen]
+pub fn dispute(
+    program_id: &Pubkey,
+    game_creator: &Pubkey,
+    game_account: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    instruction::dispute(program_id, game_creator, game_account, config_account)
+}
+
+/// `instruction::with_counter_shard`
+#[wasm_bindgen(js_name = withCounterShard)]
+pub fn with_counter_shard(
+    instruction: Instruction,
+    config_account: &Pubkey,
+    counter: &Pubkey,
+) -> Instruction {
+    instruction::with_counter_shard(instruction, config_account, counter)
+}
+
+/// `instruction::with_mint`
+#[wasm_bindgen(js_name = withMint)]
+pub fn with_mint(instruction: Instruction, mint: &Pubkey) -> Instruction {
+    instruction::with_mint(instruction, mint)
+}
+
+/// `instruction::with_stake_account`
+#[wasm_bindgen(js_name = withStakeAccount)]
+pub fn with_stake_account(instruction: Instruction, stake_account: &Pubkey) -> Instruction {
+    instruction::with_stake_account(instruction, stake_account)
+}
+
+/// `instruction::with_heartbeat`
+#[wasm_bindgen(js_name = withHeartbeat)]
+pub fn with_heartbeat(
+    instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    instruction::with_heartbeat(instruction, program_id, config_account)
+}
+
+/// `instruction::with_insurance_fund`
+#[wasm_bindgen(js_name = withInsuranceFund)]
+pub fn with_insurance_fund(
+    instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    instruction::with_insurance_fund(instruction, program_id, config_account)
+}
+
+/// `instruction::with_exposure`
+#[wasm_bindgen(js_name = withExposure)]
+pub fn with_exposure(
+    instruction: Instruction,
+    program_id: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    instruction::with_exposure(instruction, program_id, config_account)
+}
+
+/// `instruction::from_game_pool`
+#[wasm_bindgen(js_name = fromGamePool)]
+pub fn from_game_pool(instruction: Instruction, game_account: &Pubkey) -> Instruction {
+    instruction::from_game_pool(instruction, game_account)
+}
@@ -0,0 +1,1199 @@
+// end to end checks of the instructions moving funds, through solana-program-test with the
+// native processor: the token balances they leave behind and the accounts they close
+
+use roshambo_sol::{
+    error::RoshamboError,
+    instruction,
+    outcome::{
+        host_seed_commitment, outcome, public_seed_commitment, session_seed_commitment, win_payout,
+        Outcome, HANDS,
+    },
+    pda::{
+        find_associated_token_address, find_counter_address, find_exposure_address,
+        find_house_authority, find_house_token_address, find_house_vault_address,
+        find_insurance_fund_address, find_payable_address, find_player_stats_address,
+        find_recovery_authority_address, find_vesting_address,
+    },
+    processor::Processor,
+    state::{
+        Config, Exposure, Game, Payable, RecoveryAuthority, Session, Vesting,
+        INSURANCE_WITHDRAW_DELAY_SLOTS,
+    },
+};
+use solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{Instruction, InstructionError},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    sysvar::clock::Clock,
+    transaction::{Transaction, TransactionError},
+};
+
+const DECIMALS: u8 = 6;
+const BET_AMOUNT: u64 = 1_000_000;
+const BANKROLL: u64 = 1_000_000_000_000;
+
+/// A config with a single house vault and a player, both funded at genesis, along with
+/// `games` game accounts and a session account. The recovery authority is already rotated to
+/// `recovery_authority`, with tokens sent by mistake to the house PDA.
+struct Harness {
+    context: ProgramTestContext,
+    program_id: Pubkey,
+    config: Pubkey,
+    mint: Pubkey,
+    house_authority: Pubkey,
+    house_vault: Pubkey,
+    player: Keypair,
+    player_token_account: Pubkey,
+    player_stats: Pubkey,
+    games: Vec<Keypair>,
+    session: Pubkey,
+    settlement_mint: Pubkey,
+    treasury: Pubkey,
+    treasury_owner: Keypair,
+    player_reward_account: Pubkey,
+    recovery_authority: Keypair,
+}
+
+impl Harness {
+    async fn start(games: usize) -> Self {
+        let program_id = roshambo_sol::id();
+        let mut program_test =
+            ProgramTest::new("roshambo_sol", program_id, processor!(Processor::process));
+        let rent = Rent::default();
+
+        let config = Pubkey::new_unique();
+        program_test.add_account(config, program_account(&rent, Config::LEN, &program_id));
+        let games: Vec<Keypair> = (0..games).map(|_| Keypair::new()).collect();
+        for game in &games {
+            program_test.add_account(
+                game.pubkey(),
+                program_account(&rent, Game::LEN, &program_id),
+            );
+        }
+        let session = Pubkey::new_unique();
+        program_test.add_account(session, program_account(&rent, Session::LEN, &program_id));
+
+        let mint = Pubkey::new_unique();
+        let mint_state = spl_token::state::Mint {
+            mint_authority: COption::None,
+            supply: 4 * BANKROLL,
+            decimals: DECIMALS,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        program_test.add_account(mint, packed_account(&rent, mint_state, &spl_token::id()));
+
+        let (house_authority, _) = find_house_authority(&program_id);
+        let (house_vault, _) = find_house_vault_address(&config, 0, &program_id);
+        add_token_account(
+            &mut program_test,
+            &rent,
+            house_vault,
+            &mint,
+            &house_authority,
+        );
+
+        let player = Keypair::new();
+        let player_token_account = Pubkey::new_unique();
+        add_token_account(
+            &mut program_test,
+            &rent,
+            player_token_account,
+            &mint,
+            &player.pubkey(),
+        );
+        program_test.add_account(
+            player.pubkey(),
+            Account {
+                lamports: 10_000_000_000,
+                ..Account::default()
+            },
+        );
+        let (player_stats, _) = find_player_stats_address(&config, &player.pubkey(), &program_id);
+
+        // a treasury of the house in another mint, along with a token account of the player
+        let settlement_mint = Pubkey::new_unique();
+        program_test.add_account(
+            settlement_mint,
+            packed_account(&rent, mint_state, &spl_token::id()),
+        );
+        let treasury = Pubkey::new_unique();
+        let treasury_owner = Keypair::new();
+        add_token_account(
+            &mut program_test,
+            &rent,
+            treasury,
+            &settlement_mint,
+            &treasury_owner.pubkey(),
+        );
+        // where the cancellation fees of a config with this treasury go
+        add_token_account(
+            &mut program_test,
+            &rent,
+            find_associated_token_address(&treasury_owner.pubkey(), &mint),
+            &mint,
+            &treasury_owner.pubkey(),
+        );
+        let player_reward_account = Pubkey::new_unique();
+        add_token_account(
+            &mut program_test,
+            &rent,
+            player_reward_account,
+            &settlement_mint,
+            &player.pubkey(),
+        );
+
+        // the compiled-in authority has no keypair here, start from an already rotated one
+        let recovery_authority = Keypair::new();
+        let (recovery_account, bump) = find_recovery_authority_address(&program_id);
+        let recovery_state = RecoveryAuthority {
+            is_initialized: true,
+            authority: recovery_authority.pubkey(),
+            bump,
+        };
+        program_test.add_account(
+            recovery_account,
+            packed_account(&rent, recovery_state, &program_id),
+        );
+        add_token_account(
+            &mut program_test,
+            &rent,
+            find_house_token_address(&mint, &program_id),
+            &mint,
+            &house_authority,
+        );
+
+        let mut harness = Self {
+            context: program_test.start_with_context().await,
+            program_id,
+            config,
+            mint,
+            house_authority,
+            house_vault,
+            player,
+            player_token_account,
+            player_stats,
+            games,
+            session,
+            settlement_mint,
+            treasury,
+            treasury_owner,
+            player_reward_account,
+            recovery_authority,
+        };
+        let house = harness.house();
+        harness
+            .execute(
+                instruction::initialize(
+                    &program_id,
+                    &house,
+                    &config,
+                    &mint,
+                    BET_AMOUNT,
+                    100 * BET_AMOUNT,
+                ),
+                &[],
+            )
+            .await
+            .unwrap();
+        harness
+            .execute(
+                instruction::init_house_vaults(
+                    &program_id,
+                    &house,
+                    &config,
+                    &mint,
+                    &[house_vault],
+                    1,
+                ),
+                &[],
+            )
+            .await
+            .unwrap();
+        harness
+    }
+
+    fn house(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    fn game(&self, index: usize) -> Pubkey {
+        self.games[index].pubkey()
+    }
+
+    /// Runs `instruction` alone in a transaction paid and signed by the house
+    async fn execute(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let payer = self.context.payer.insecure_clone();
+        let mut all_signers = vec![&payer];
+        all_signers.extend(signers);
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    async fn warp(&mut self, slots: u64) {
+        let clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        self.context.warp_to_slot(clock.slot + slots).unwrap();
+    }
+
+    async fn balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+            .unwrap();
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    async fn exists(&mut self, address: &Pubkey) -> bool {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    async fn state<T: Pack>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap();
+        T::unpack_unchecked(&account.data).unwrap()
+    }
+
+    /// Opens game `index` for `BET_AMOUNT` with `public_seed`
+    async fn new_game(&mut self, index: usize, public_seed: u64) -> Result<(), BanksClientError> {
+        let game = self.games[index].insecure_clone();
+        let clock = self.context.banks_client.get_sysvar::<Clock>().await?;
+        let commitment = public_seed_commitment(&game.pubkey(), public_seed, clock.slot);
+        let mut new_game = instruction::new_game(
+            &self.program_id,
+            &self.player.pubkey(),
+            &self.player_token_account,
+            &game.pubkey(),
+            &self.house_vault,
+            &self.config,
+            &self.player_stats,
+            BET_AMOUNT,
+            commitment,
+            clock.slot,
+        );
+        new_game = instruction::with_mint(new_game, &self.mint);
+        let config: Config = self.state(&self.config.clone()).await;
+        if config.insurance_bps > 0 {
+            new_game = instruction::with_insurance_fund(new_game, &self.program_id, &self.config);
+        }
+        let player = self.player.insecure_clone();
+        self.execute(new_game, &[&player, &game]).await?;
+
+        // settlements land after the slot of the game
+        self.warp(2).await;
+        Ok(())
+    }
+
+    /// Commits `host_seed` for game `index` and reveals its `public_seed`
+    async fn reveal(&mut self, index: usize, host_seed: u64, public_seed: u64) {
+        let game = self.game(index);
+        let commit = instruction::commit_host_seed(
+            &self.program_id,
+            &self.house(),
+            &game,
+            &self.config,
+            host_seed_commitment(&game, host_seed),
+        );
+        self.execute(commit, &[]).await.unwrap();
+        let reveal =
+            instruction::reveal_seed(&self.program_id, &self.player.pubkey(), &game, public_seed);
+        let player = self.player.insecure_clone();
+        self.execute(reveal, &[&player]).await.unwrap();
+    }
+
+    /// Opens and reveals game `index`, its seeds giving `result` to the player
+    async fn play(&mut self, index: usize, result: Outcome) -> (u64, u64) {
+        let public_seed = index as u64;
+        let host_seed = host_seed_for(result, public_seed);
+        self.new_game(index, public_seed).await.unwrap();
+        self.reveal(index, host_seed, public_seed).await;
+        (host_seed, public_seed)
+    }
+
+    fn claim(&self, index: usize, host_seed: u64, public_seed: u64) -> Instruction {
+        let claim = instruction::claim_reward(
+            &self.program_id,
+            &self.player.pubkey(),
+            &self.house(),
+            &self.game(index),
+            &self.player_token_account,
+            &self.house_vault,
+            &self.config,
+            &self.house_authority,
+            host_seed,
+            public_seed,
+        );
+        let claim = instruction::with_player_stats(claim, &self.player_stats);
+        instruction::with_mint(claim, &self.mint)
+    }
+
+    fn claim_deferred(&self, index: usize) -> Instruction {
+        let claim = instruction::claim_deferred(
+            &self.program_id,
+            &self.player.pubkey(),
+            &self.game(index),
+            &self.player_token_account,
+            &self.house_vault,
+            &self.config,
+            &self.house_authority,
+        );
+        instruction::with_mint(claim, &self.mint)
+    }
+
+    async fn win_payout(&mut self, index: usize) -> u64 {
+        let game: Game = self.state(&self.game(index)).await;
+        win_payout(game.bet_amount, game.payout_multiplier_bps, game.fee_bps).unwrap()
+    }
+}
+
+/// Host seed giving `result` to the player against `public_seed`
+fn host_seed_for(result: Outcome, public_seed: u64) -> u64 {
+    (0..HANDS)
+        .find(|host_seed| outcome(*host_seed, public_seed) == result)
+        .unwrap()
+}
+
+fn program_account(rent: &Rent, len: usize, program_id: &Pubkey) -> Account {
+    Account {
+        lamports: rent.minimum_balance(len),
+        data: vec![0; len],
+        owner: *program_id,
+        ..Account::default()
+    }
+}
+
+fn add_token_account(
+    program_test: &mut ProgramTest,
+    rent: &Rent,
+    address: Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) {
+    let account = spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount: BANKROLL,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    };
+    program_test.add_account(address, packed_account(rent, account, &spl_token::id()));
+}
+
+fn packed_account<T: Pack>(rent: &Rent, state: T, owner: &Pubkey) -> Account {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    Account {
+        lamports: rent.minimum_balance(T::LEN),
+        data,
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+/// The custom program error a failed transaction ended with
+fn program_error(err: BanksClientError) -> u32 {
+    match err.unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => code,
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[tokio::test]
+async fn claim_pays_wins_and_draws() {
+    let mut h = Harness::start(3).await;
+    let results = [Outcome::Win, Outcome::Lose, Outcome::Draw];
+    for (index, result) in results.into_iter().enumerate() {
+        let (host_seed, public_seed) = h.play(index, result).await;
+        let expected = match result {
+            Outcome::Win => h.win_payout(index).await,
+            Outcome::Lose => 0,
+            Outcome::Draw => BET_AMOUNT,
+        };
+        let player_before = h.balance(&h.player_token_account.clone()).await;
+        let vault_before = h.balance(&h.house_vault.clone()).await;
+
+        h.execute(h.claim(index, host_seed, public_seed), &[])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            h.balance(&h.player_token_account.clone()).await,
+            player_before + expected
+        );
+        assert_eq!(
+            h.balance(&h.house_vault.clone()).await,
+            vault_before - expected
+        );
+        assert!(!h.exists(&h.game(index)).await);
+    }
+}
+
+#[tokio::test]
+async fn claim_and_rebet_pays_the_profit_and_stakes_again() {
+    let mut h = Harness::start(2).await;
+    let (host_seed, public_seed) = h.play(0, Outcome::Win).await;
+    let profit = h.win_payout(0).await - BET_AMOUNT;
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+    let vault_before = h.balance(&h.house_vault.clone()).await;
+
+    let next_game = h.games[1].insecure_clone();
+    let clock = h.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let rebet = instruction::claim_and_rebet(
+        &h.program_id,
+        &h.player.pubkey(),
+        &h.house(),
+        &h.game(0),
+        &h.player_token_account,
+        &h.house_vault,
+        &h.config,
+        &h.house_authority,
+        &next_game.pubkey(),
+        &h.player_stats,
+        host_seed,
+        public_seed,
+        public_seed_commitment(&next_game.pubkey(), 7, clock.slot),
+        clock.slot,
+    );
+    let rebet = instruction::with_mint(rebet, &h.mint);
+    let player = h.player.insecure_clone();
+    h.execute(rebet, &[&player, &next_game]).await.unwrap();
+
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before + profit
+    );
+    assert_eq!(
+        h.balance(&h.house_vault.clone()).await,
+        vault_before - profit
+    );
+    let next: Game = h.state(&next_game.pubkey()).await;
+    assert!(next.is_initialized);
+    assert_eq!(next.bet_amount, BET_AMOUNT);
+    assert_eq!(next.game_creator_pubkey, h.player.pubkey());
+}
+
+#[tokio::test]
+async fn session_games_count_against_the_rate_limit() {
+    let mut h = Harness::start(0).await;
+    let (program_id, house, config, session) = (h.program_id, h.house(), h.config, h.session);
+    h.execute(
+        instruction::update_rate_limit(&program_id, &house, &config, 1, 1_000),
+        &[],
+    )
+    .await
+    .unwrap();
+    let player = h.player.insecure_clone();
+    let open = instruction::open_session(
+        &program_id,
+        &player.pubkey(),
+        &h.player_token_account,
+        &session,
+        &h.house_vault,
+        &config,
+        3 * BET_AMOUNT,
+    );
+    let open = instruction::with_mint(open, &h.mint);
+    h.execute(open, &[&player]).await.unwrap();
+
+    let play = instruction::play_session(
+        &program_id,
+        &player.pubkey(),
+        &session,
+        &config,
+        &h.house_vault,
+        &h.player_stats,
+        BET_AMOUNT,
+        0,
+    );
+    let commit = |games_played| {
+        instruction::commit_session_seed(
+            &program_id,
+            &house,
+            &session,
+            &config,
+            session_seed_commitment(&session, games_played, 0),
+        )
+    };
+    h.execute(commit(0), &[]).await.unwrap();
+    h.execute(play.clone(), &[&player]).await.unwrap();
+    let reveal = instruction::reveal_session_seed(&program_id, &house, &session, &config, 0);
+    h.execute(reveal, &[]).await.unwrap();
+    h.execute(commit(1), &[]).await.unwrap();
+
+    // the second game of the window is refused like a second `NewGame` would be
+    let err = h.execute(play, &[&player]).await.unwrap_err();
+    assert_eq!(program_error(err), RoshamboError::RateLimited as u32);
+    let session_info: Session = h.state(&session).await;
+    assert_eq!(session_info.games_played, 1);
+    assert_eq!(session_info.pending_amount, 0);
+}
+
+#[tokio::test]
+async fn exposure_tracking_starts_from_the_open_games() {
+    let mut h = Harness::start(1).await;
+    let (program_id, house, config) = (h.program_id, h.house(), h.config);
+    let public_seed = 0;
+    let host_seed = host_seed_for(Outcome::Win, public_seed);
+    h.new_game(0, public_seed).await.unwrap();
+    let payout = h.win_payout(0).await;
+
+    h.execute(
+        instruction::update_exposure_tracking(&program_id, &house, &config, true),
+        &[],
+    )
+    .await
+    .unwrap();
+    let (exposure, _) = find_exposure_address(&config, &program_id);
+    let exposure_info: Exposure = h.state(&exposure).await;
+    assert_eq!(exposure_info.potential_payout, payout);
+
+    // a sharded config would write-lock the exposure account from every game again
+    let (counter, _) = find_counter_address(&config, 0, &program_id);
+    let err = h
+        .execute(
+            instruction::set_counter_shards(&program_id, &house, &config, &[counter], 1),
+            &[],
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.unwrap(),
+        TransactionError::InstructionError(_, InstructionError::InvalidArgument)
+    ));
+
+    h.reveal(0, host_seed, public_seed).await;
+    let claim = h.claim(0, host_seed, public_seed);
+    let claim = instruction::with_exposure(claim, &program_id, &config);
+    h.execute(claim, &[]).await.unwrap();
+    let exposure_info: Exposure = h.state(&exposure).await;
+    assert_eq!(exposure_info.potential_payout, 0);
+}
+
+#[tokio::test]
+async fn airdrop_pays_from_the_treasury() {
+    let mut h = Harness::start(0).await;
+    let update_treasury = instruction::update_treasury(
+        &h.program_id,
+        &h.house(),
+        &h.config,
+        Some((&h.treasury, &h.settlement_mint)),
+    );
+    h.execute(update_treasury, &[]).await.unwrap();
+    let approve = spl_token::instruction::approve(
+        &spl_token::id(),
+        &h.treasury,
+        &h.house(),
+        &h.treasury_owner.pubkey(),
+        &[],
+        BET_AMOUNT,
+    )
+    .unwrap();
+    let treasury_owner = h.treasury_owner.insecure_clone();
+    h.execute(approve, &[&treasury_owner]).await.unwrap();
+    let vault_before = h.balance(&h.house_vault.clone()).await;
+
+    let airdrop = instruction::airdrop(
+        &h.program_id,
+        &h.house(),
+        &h.config,
+        &h.treasury,
+        &[(h.player_reward_account, BET_AMOUNT)],
+    );
+    h.execute(instruction::with_mint(airdrop, &h.settlement_mint), &[])
+        .await
+        .unwrap();
+
+    assert_eq!(h.balance(&h.treasury.clone()).await, BANKROLL - BET_AMOUNT);
+    assert_eq!(
+        h.balance(&h.player_reward_account.clone()).await,
+        BANKROLL + BET_AMOUNT
+    );
+    assert_eq!(h.balance(&h.house_vault.clone()).await, vault_before);
+}
+
+#[tokio::test]
+async fn cancel_fee_goes_to_the_treasury() {
+    let mut h = Harness::start(1).await;
+    let fee_bps = 100;
+    let update_fee = instruction::update_cancel_fee(&h.program_id, &h.house(), &h.config, fee_bps);
+    h.execute(update_fee, &[]).await.unwrap();
+    let update_treasury = instruction::update_treasury(
+        &h.program_id,
+        &h.house(),
+        &h.config,
+        Some((&h.treasury, &h.settlement_mint)),
+    );
+    h.execute(update_treasury, &[]).await.unwrap();
+    let fee_account = find_associated_token_address(&h.treasury_owner.pubkey(), &h.mint);
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+    let vault_before = h.balance(&h.house_vault.clone()).await;
+
+    h.new_game(0, 0).await.unwrap();
+    let cancel = instruction::cancel_game(
+        &h.program_id,
+        &h.player.pubkey(),
+        &h.game(0),
+        &h.player_token_account,
+        &h.house_vault,
+        &h.config,
+        &h.house_authority,
+    );
+    let cancel = instruction::with_player_stats(cancel, &h.player_stats);
+    let cancel = instruction::with_mint(cancel, &h.mint);
+    let cancel = instruction::with_treasury_fee_account(
+        cancel,
+        &h.treasury,
+        &h.treasury_owner.pubkey(),
+        &h.mint,
+    );
+    let player = h.player.insecure_clone();
+    h.execute(cancel, &[&player]).await.unwrap();
+
+    let fee = BET_AMOUNT * fee_bps as u64 / 10_000;
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before - fee
+    );
+    assert_eq!(h.balance(&fee_account).await, BANKROLL + fee);
+    assert_eq!(h.balance(&h.house_vault.clone()).await, vault_before);
+    assert!(!h.exists(&h.game(0)).await);
+}
+
+#[tokio::test]
+async fn claim_rewards_pays_a_batch_at_once() {
+    let mut h = Harness::start(3).await;
+    let mut games = Vec::new();
+    let mut expected = 0;
+    for (index, result) in [Outcome::Win, Outcome::Lose, Outcome::Draw]
+        .into_iter()
+        .enumerate()
+    {
+        let (host_seed, public_seed) = h.play(index, result).await;
+        expected += match result {
+            Outcome::Win => h.win_payout(index).await,
+            Outcome::Lose => 0,
+            Outcome::Draw => BET_AMOUNT,
+        };
+        games.push((h.game(index), host_seed, public_seed));
+    }
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+    let vault_before = h.balance(&h.house_vault.clone()).await;
+
+    let claim = instruction::claim_rewards(
+        &h.program_id,
+        &h.player.pubkey(),
+        &h.house(),
+        &h.player_token_account,
+        &h.house_vault,
+        &h.config,
+        &h.house_authority,
+        &h.player_stats,
+        &games,
+    );
+    let claim = instruction::with_mint(claim, &h.mint);
+    h.execute(claim, &[]).await.unwrap();
+
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before + expected
+    );
+    assert_eq!(
+        h.balance(&h.house_vault.clone()).await,
+        vault_before - expected
+    );
+    for (game, _, _) in games {
+        assert!(!h.exists(&game).await);
+    }
+}
+
+#[tokio::test]
+async fn payout_cap_pays_the_rest_in_installments() {
+    let mut h = Harness::start(1).await;
+    let (program_id, house, config) = (h.program_id, h.house(), h.config);
+    h.execute(
+        instruction::update_payout_cap(&program_id, &house, &config, BET_AMOUNT, 10),
+        &[],
+    )
+    .await
+    .unwrap();
+    let (host_seed, public_seed) = h.play(0, Outcome::Win).await;
+    let payout = h.win_payout(0).await;
+    let game: Game = h.state(&h.game(0)).await;
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+
+    let claim = h.claim(0, host_seed, public_seed);
+    let claim = instruction::with_payable(claim, &program_id, &config, game.game_id);
+    h.execute(claim, &[]).await.unwrap();
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before + BET_AMOUNT
+    );
+
+    let (payable, _) = find_payable_address(&config, game.game_id, &program_id);
+    let installment = instruction::pay_installment(
+        &program_id,
+        &payable,
+        &h.player_token_account,
+        &h.house_vault,
+        &config,
+        &h.house_authority,
+        &house,
+    );
+    let installment = instruction::with_mint(installment, &h.mint);
+    let err = h.execute(installment.clone(), &[]).await.unwrap_err();
+    assert_eq!(program_error(err), RoshamboError::InstallmentNotDue as u32);
+
+    h.warp(11).await;
+    h.execute(installment, &[]).await.unwrap();
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before + payout
+    );
+    assert!(!h.exists(&payable).await);
+}
+
+#[tokio::test]
+async fn held_payout_above_the_cap_pays_the_rest_in_installments() {
+    let mut h = Harness::start(1).await;
+    let (program_id, house, config) = (h.program_id, h.house(), h.config);
+    h.execute(
+        instruction::update_payout_cap(&program_id, &house, &config, BET_AMOUNT, 10),
+        &[],
+    )
+    .await
+    .unwrap();
+    h.execute(
+        instruction::update_large_win_hold(&program_id, &house, &config, BET_AMOUNT, 5),
+        &[],
+    )
+    .await
+    .unwrap();
+    let (host_seed, public_seed) = h.play(0, Outcome::Win).await;
+    let payout = h.win_payout(0).await;
+    let game: Game = h.state(&h.game(0)).await;
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+
+    // the win is held whole, nothing is paid at settlement
+    h.execute(h.claim(0, host_seed, public_seed), &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before
+    );
+
+    h.warp(6).await;
+    let claim = h.claim_deferred(0);
+    let claim = instruction::with_payable(claim, &program_id, &config, game.game_id);
+    let claim = instruction::with_creator_signature(claim);
+    let player = h.player.insecure_clone();
+    h.execute(claim, &[&player]).await.unwrap();
+
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before + BET_AMOUNT
+    );
+    let (payable, _) = find_payable_address(&config, game.game_id, &program_id);
+    let payable: Payable = h.state(&payable).await;
+    assert_eq!(payable.owed, payout - BET_AMOUNT);
+    assert_eq!(payable.payer, h.player.pubkey());
+    assert!(!h.exists(&h.game(0)).await);
+}
+
+#[tokio::test]
+async fn vesting_releases_a_large_win_over_time() {
+    let mut h = Harness::start(1).await;
+    let (program_id, house, config) = (h.program_id, h.house(), h.config);
+    h.execute(
+        instruction::update_vesting(&program_id, &house, &config, BET_AMOUNT, 100),
+        &[],
+    )
+    .await
+    .unwrap();
+    let (host_seed, public_seed) = h.play(0, Outcome::Win).await;
+    let payout = h.win_payout(0).await;
+    let game: Game = h.state(&h.game(0)).await;
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+
+    let claim = h.claim(0, host_seed, public_seed);
+    let claim = instruction::with_vesting(claim, &program_id, &config, game.game_id);
+    h.execute(claim, &[]).await.unwrap();
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before
+    );
+
+    h.warp(101).await;
+    let (vesting, _) = find_vesting_address(&config, game.game_id, &program_id);
+    let release = instruction::release_vested(
+        &program_id,
+        &vesting,
+        &h.player_token_account,
+        &h.house_vault,
+        &config,
+        &h.house_authority,
+        &house,
+    );
+    let release = instruction::with_mint(release, &h.mint);
+    h.execute(release, &[]).await.unwrap();
+
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before + payout
+    );
+    assert!(!h.exists(&vesting).await);
+}
+
+#[tokio::test]
+async fn held_large_win_vests_when_it_is_released() {
+    let mut h = Harness::start(1).await;
+    let (program_id, house, config) = (h.program_id, h.house(), h.config);
+    h.execute(
+        instruction::update_vesting(&program_id, &house, &config, BET_AMOUNT, 100),
+        &[],
+    )
+    .await
+    .unwrap();
+    h.execute(
+        instruction::update_large_win_hold(&program_id, &house, &config, BET_AMOUNT, 5),
+        &[],
+    )
+    .await
+    .unwrap();
+    let (host_seed, public_seed) = h.play(0, Outcome::Win).await;
+    let payout = h.win_payout(0).await;
+    let game: Game = h.state(&h.game(0)).await;
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+    h.execute(h.claim(0, host_seed, public_seed), &[])
+        .await
+        .unwrap();
+
+    h.warp(6).await;
+    let claim = h.claim_deferred(0);
+    let claim = instruction::with_vesting(claim, &program_id, &config, game.game_id);
+    let claim = instruction::with_creator_signature(claim);
+    let player = h.player.insecure_clone();
+    h.execute(claim, &[&player]).await.unwrap();
+
+    // nothing is paid at once, the whole win streams out of the vesting
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before
+    );
+    let (vesting, _) = find_vesting_address(&config, game.game_id, &program_id);
+    let vesting: Vesting = h.state(&vesting).await;
+    assert_eq!(vesting.total, payout);
+    assert_eq!(vesting.payer, h.player.pubkey());
+    assert!(!h.exists(&h.game(0)).await);
+}
+
+#[tokio::test]
+async fn insurance_cut_stays_out_of_the_bankroll() {
+    let mut h = Harness::start(1).await;
+    let (program_id, house, config, mint) = (h.program_id, h.house(), h.config, h.mint);
+    h.execute(
+        instruction::update_insurance_fund(&program_id, &house, &config, &mint, 100),
+        &[],
+    )
+    .await
+    .unwrap();
+    let (insurance_fund, _) = find_insurance_fund_address(&config, &program_id);
+    let destination = find_associated_token_address(&h.treasury_owner.pubkey(), &mint);
+    let cut = BET_AMOUNT / 100;
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+    let vault_before = h.balance(&h.house_vault.clone()).await;
+
+    h.new_game(0, 0).await.unwrap();
+    assert_eq!(h.balance(&insurance_fund).await, cut);
+    assert_eq!(
+        h.balance(&h.house_vault.clone()).await,
+        vault_before + BET_AMOUNT - cut
+    );
+
+    // a declined game refunds what the house received, the cut stays insured
+    let decline = instruction::decline_game(
+        &program_id,
+        &house,
+        &h.game(0),
+        &h.player.pubkey(),
+        &h.player_token_account,
+        &h.house_vault,
+        &config,
+        &h.house_authority,
+    );
+    let decline = instruction::with_player_stats(decline, &h.player_stats);
+    let decline = instruction::with_mint(decline, &mint);
+    h.execute(decline, &[]).await.unwrap();
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before - cut
+    );
+    assert_eq!(h.balance(&h.house_vault.clone()).await, vault_before);
+
+    // the owner only withdraws the fund once the scheduled withdrawal is unlocked
+    let withdraw = instruction::withdraw_insurance(
+        &program_id,
+        &house,
+        &config,
+        &destination,
+        &h.house_authority,
+        cut,
+    );
+    let withdraw = instruction::with_mint(withdraw, &mint);
+    h.execute(
+        instruction::schedule_insurance_withdraw(&program_id, &house, &config, true),
+        &[],
+    )
+    .await
+    .unwrap();
+    let err = h.execute(withdraw.clone(), &[]).await.unwrap_err();
+    assert_eq!(
+        program_error(err),
+        RoshamboError::InsuranceWithdrawLocked as u32
+    );
+
+    h.warp(INSURANCE_WITHDRAW_DELAY_SLOTS + 1).await;
+    let destination_before = h.balance(&destination).await;
+    h.execute(withdraw, &[]).await.unwrap();
+    assert_eq!(h.balance(&insurance_fund).await, 0);
+    assert_eq!(h.balance(&destination).await, destination_before + cut);
+}
+
+#[tokio::test]
+async fn recovery_moves_the_tokens_sent_to_the_house() {
+    let mut h = Harness::start(0).await;
+    let (program_id, mint, house_authority) = (h.program_id, h.mint, h.house_authority);
+    let house_token_account = find_house_token_address(&mint, &program_id);
+    let destination = h.player_token_account;
+    let before = h.balance(&destination).await;
+    let recover = |authority: &Pubkey| {
+        instruction::recover_tokens(
+            &program_id,
+            authority,
+            &house_token_account,
+            &destination,
+            &mint,
+            &house_authority,
+        )
+    };
+
+    // any other key is turned away
+    let impostor = h.player.insecure_clone();
+    let err = h
+        .execute(recover(&impostor.pubkey()), &[&impostor])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.unwrap(),
+        TransactionError::InstructionError(_, InstructionError::InvalidAccountOwner)
+    ));
+    assert_eq!(h.balance(&house_token_account).await, BANKROLL);
+
+    let authority = h.recovery_authority.insecure_clone();
+    h.execute(recover(&authority.pubkey()), &[&authority])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(&destination).await, before + BANKROLL);
+    assert!(!h.exists(&house_token_account).await);
+
+    // rotated again, only the new authority recovers
+    let next_authority = Keypair::new();
+    h.execute(
+        instruction::set_recovery_authority(
+            &program_id,
+            &authority.pubkey(),
+            &next_authority.pubkey(),
+        ),
+        &[&authority, &next_authority],
+    )
+    .await
+    .unwrap();
+    let recover = |authority: &Keypair| {
+        instruction::recover_lamports(
+            &program_id,
+            &authority.pubkey(),
+            &house_authority,
+            &authority.pubkey(),
+        )
+    };
+    assert!(h.execute(recover(&authority), &[&authority]).await.is_err());
+    h.execute(recover(&next_authority), &[&next_authority])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn reclaim_rent_closes_abandoned_accounts_into_the_treasury() {
+    let mut h = Harness::start(1).await;
+    let (program_id, config, treasury) = (h.program_id, h.config, h.treasury);
+    let update_treasury = instruction::update_treasury(
+        &program_id,
+        &h.house(),
+        &config,
+        Some((&treasury, &h.settlement_mint)),
+    );
+    h.execute(update_treasury, &[]).await.unwrap();
+    let max_age_slots = 100;
+    let update_age =
+        instruction::update_max_settle_age(&program_id, &h.house(), &config, max_age_slots);
+    h.execute(update_age, &[]).await.unwrap();
+
+    // the rent only goes to the treasury of the config, and no initialized account closes
+    let abandoned = h.game(0);
+    let elsewhere = h.player_reward_account;
+    let reclaim = instruction::reclaim_rent(&program_id, &config, &elsewhere, &[abandoned]);
+    assert!(h.execute(reclaim, &[]).await.is_err());
+    let reclaim = instruction::reclaim_rent(&program_id, &config, &treasury, &[config]);
+    assert!(h.execute(reclaim, &[]).await.is_err());
+
+    let treasury_before = h.context.banks_client.get_balance(treasury).await.unwrap();
+    let rent = h.context.banks_client.get_balance(abandoned).await.unwrap();
+    let reclaim = || instruction::reclaim_rent(&program_id, &config, &treasury, &[abandoned]);
+    // the first call only stamps the account, still open before the settlement age
+    h.execute(reclaim(), &[]).await.unwrap();
+    h.warp(max_age_slots / 2).await;
+    h.execute(reclaim(), &[]).await.unwrap();
+    assert!(h.exists(&abandoned).await);
+
+    h.warp(max_age_slots).await;
+    h.execute(reclaim(), &[]).await.unwrap();
+    assert!(!h.exists(&abandoned).await);
+    assert_eq!(
+        h.context.banks_client.get_balance(treasury).await.unwrap(),
+        treasury_before + rent
+    );
+    assert_eq!(h.balance(&treasury).await, BANKROLL);
+}
+
+#[tokio::test]
+async fn circuit_breaker_pauses_the_config_after_a_loss() {
+    let mut h = Harness::start(2).await;
+    let (program_id, house, config) = (h.program_id, h.house(), h.config);
+    h.execute(
+        instruction::update_circuit_breaker(&program_id, &house, &config, BET_AMOUNT / 2, 1_000),
+        &[],
+    )
+    .await
+    .unwrap();
+    let (host_seed, public_seed) = h.play(0, Outcome::Win).await;
+    let payout = h.win_payout(0).await;
+    let player_before = h.balance(&h.player_token_account.clone()).await;
+
+    // the settlement tripping the breaker still pays
+    h.execute(h.claim(0, host_seed, public_seed), &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        h.balance(&h.player_token_account.clone()).await,
+        player_before + payout
+    );
+    let config_info: Config = h.state(&config).await;
+    assert!(config_info.paused);
+    assert!(config_info.breaker_tripped);
+
+    let err = h.new_game(1, 1).await.unwrap_err();
+    assert_eq!(program_error(err), RoshamboError::BettingPaused as u32);
+}
+
+#[tokio::test]
+async fn circuit_breaker_counts_the_net_loss_of_a_session() {
+    let mut h = Harness::start(0).await;
+    let (program_id, house, config, session) = (h.program_id, h.house(), h.config, h.session);
+    h.execute(
+        instruction::update_circuit_breaker(&program_id, &house, &config, BET_AMOUNT / 2, 1_000),
+        &[],
+    )
+    .await
+    .unwrap();
+    let player = h.player.insecure_clone();
+    let open = instruction::open_session(
+        &program_id,
+        &player.pubkey(),
+        &h.player_token_account,
+        &session,
+        &h.house_vault,
+        &config,
+        3 * BET_AMOUNT,
+    );
+    let open = instruction::with_mint(open, &h.mint);
+    h.execute(open, &[&player]).await.unwrap();
+
+    let host_seed = host_seed_for(Outcome::Win, 0);
+    let commit = instruction::commit_session_seed(
+        &program_id,
+        &house,
+        &session,
+        &config,
+        session_seed_commitment(&session, 0, host_seed),
+    );
+    h.execute(commit, &[]).await.unwrap();
+    let play = instruction::play_session(
+        &program_id,
+        &player.pubkey(),
+        &session,
+        &config,
+        &h.house_vault,
+        &h.player_stats,
+        BET_AMOUNT,
+        0,
+    );
+    h.execute(play, &[&player]).await.unwrap();
+    let reveal =
+        instruction::reveal_session_seed(&program_id, &house, &session, &config, host_seed);
+    h.execute(reveal, &[]).await.unwrap();
+    let session_info: Session = h.state(&session).await;
+    assert!(session_info.balance > 3 * BET_AMOUNT + BET_AMOUNT / 2);
+
+    // the win of the session is only booked when it closes
+    let close = instruction::close_session(
+        &program_id,
+        &player.pubkey(),
+        &session,
+        &h.player_token_account,
+        &h.house_vault,
+        &config,
+        &h.house_authority,
+    );
+    let close = instruction::with_mint(close, &h.mint);
+    h.execute(close, &[&player]).await.unwrap();
+    let config_info: Config = h.state(&config).await;
+    assert!(config_info.paused);
+    assert!(config_info.breaker_tripped);
+}